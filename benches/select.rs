@@ -0,0 +1,67 @@
+use std::str::FromStr;
+use chrono::{TimeDelta, Utc};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use todo_list::query::reflect::Value;
+use todo_list::query::{Query, ResultSet};
+use todo_list::storage::Storage;
+use todo_list::task::{Status, Task};
+
+const TASKS: usize = 10_000;
+
+fn tasks() -> Vec<Task> {
+    let now = Utc::now();
+    (0..TASKS)
+        .map(|idx| Task {
+            name: format!("task {idx}"),
+            description: "description".to_string(),
+            date: now + TimeDelta::minutes(idx as i64),
+            category: ["work", "home", "study"][idx % 3].to_string(),
+            status: if idx % 2 == 0 { Status::On } else { Status::Off },
+            owner: None,
+            assignee: None,
+            external_refs: Vec::new(),
+        })
+        .collect()
+}
+
+/// Bytes of the string values of the result set, counting every shared string once.
+fn string_bytes(result_set: &ResultSet) -> usize {
+    let mut seen = std::collections::HashSet::new();
+    result_set
+        .rows()
+        .flatten()
+        .filter_map(|value| match value {
+            Value::String(string) if seen.insert(string.as_ptr()) => Some(string.len()),
+            _ => None,
+        })
+        .sum()
+}
+
+fn select(c: &mut Criterion) {
+    let tasks = tasks();
+    let query = Query::from_str("SELECT * WHERE status = 'on' OR category = 'work'").unwrap();
+    let result_set = query.execute(&tasks).unwrap();
+    println!("string bytes of {} rows: {}", result_set.rows().count(), string_bytes(&result_set));
+
+    c.bench_function("select *", |b| b.iter(|| query.execute(&tasks).unwrap()));
+    // Parts of `select *`: the predicate alone, and the projection of every task without the predicate.
+    let predicate = query.predicate.clone().unwrap();
+    c.bench_function("where", |b| b.iter(|| predicate.matches_indices(&tasks).unwrap()));
+    let project = Query::from_str("SELECT *").unwrap();
+    c.bench_function("project *", |b| b.iter(|| project.execute(&tasks).unwrap()));
+    // Tasks are decoded from the storage, before any query over the stored tasks is evaluated.
+    let tempdir = tempfile::tempdir().unwrap();
+    let storage = Storage::<Task>::open(&tempdir).unwrap();
+    for task in &tasks {
+        storage.insert(&task.name, task).unwrap();
+    }
+    c.bench_function("decode tasks", |b| {
+        b.iter(|| storage.iter().map(|entry| entry.unwrap().1).collect::<Vec<_>>())
+    });
+    c.bench_function("clone rows", |b| {
+        b.iter_batched(|| (), |_| result_set.rows().map(<[Value]>::to_vec).collect::<Vec<_>>(), BatchSize::SmallInput)
+    });
+}
+
+criterion_group!(benches, select);
+criterion_main!(benches);
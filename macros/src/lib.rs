@@ -0,0 +1,246 @@
+//! Compile-time checked queries of the todo-list query language and derive of the reflection of the queried types.
+
+mod reflect;
+
+use std::fmt::Debug;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use std::str::FromStr;
+use syn::{parse_macro_input, DeriveInput, LitStr};
+use todo_list::query::ast::expression::{
+    Expression, FunctionCall, Identifier, InSubquery, Literal, Number, Operation, Subquery,
+};
+use todo_list::query::ast::{Collation, Field, Predicate, Query};
+
+/// Parse the query at compile time and expand to its [`Query`] AST.
+///
+/// Syntax errors of the query are reported as compile errors.
+///
+/// ```ignore
+/// use todo_list_macros::query;
+///
+/// let query = query!("SELECT name WHERE status = 'off' AND date < TODAY");
+/// ```
+#[proc_macro]
+pub fn query(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let text = parse_macro_input!(input as LitStr);
+    match Query::from_str(&text.value()) {
+        Ok(query) => query_tokens(&query).into(),
+        Err(err) => syn::Error::new(text.span(), err.to_string()).to_compile_error().into(),
+    }
+}
+
+/// Derive `Reflectable` for the struct with named fields, so its fields can be queried.
+///
+/// Fields are reflected as values of their `ToValue` implementations. Field attributes:
+/// * `#[reflect(rename = "name")]` - reflect the field under another name;
+/// * `#[reflect(nested)]` - field is a nested `Reflectable` value, that is read by dotted paths, e.g. `repeat.days`;
+/// * `#[reflect(extra)]` - field is a map of the dynamic fields, e.g. `HashMap<String, Value<'static>>` of the custom
+///   fields, which are reflected after the fields of the struct;
+/// * `#[reflect(skip)]` - do not reflect the field.
+///
+/// ```ignore
+/// use todo_list_macros::Reflectable;
+///
+/// #[derive(Reflectable)]
+/// struct Note {
+///     #[reflect(rename = "title")]
+///     name: String,
+///     created: DateTime<Utc>,
+///     #[reflect(skip)]
+///     cache: Vec<u8>,
+/// }
+/// ```
+#[proc_macro_derive(Reflectable, attributes(reflect))]
+pub fn derive_reflectable(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    reflect::derive(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Path of the query AST in the todo-list crate.
+fn ast() -> TokenStream {
+    quote!(::todo_list::query::ast)
+}
+
+/// Name of the fieldless enum variant, that is the same as its debug representation.
+fn variant(value: &impl Debug) -> Ident {
+    Ident::new(&format!("{value:?}"), Span::call_site())
+}
+
+fn query_tokens(query: &Query) -> TokenStream {
+    let ast = ast();
+    let fields = query.fields_projection.0.iter().map(field_tokens);
+    let from = option_tokens(query.from.as_ref(), identifier_tokens);
+    let predicate = option_tokens(query.predicate.as_ref(), predicate_tokens);
+    let group_by = query.group_by.iter().map(expression_tokens);
+    let collation = option_tokens(query.collation.as_ref(), collation_tokens);
+
+    quote! {
+        #ast::Query {
+            fields_projection: #ast::FieldsProjection(::std::vec![#(#fields),*]),
+            from: #from,
+            predicate: #predicate,
+            group_by: ::std::vec![#(#group_by),*],
+            collation: #collation,
+        }
+    }
+}
+
+fn collation_tokens(collation: &Collation) -> TokenStream {
+    let ast = ast();
+    let collation = variant(collation);
+    quote!(#ast::Collation::#collation)
+}
+
+fn option_tokens<T>(value: Option<&T>, tokens: fn(&T) -> TokenStream) -> TokenStream {
+    match value {
+        Some(value) => {
+            let value = tokens(value);
+            quote!(::std::option::Option::Some(#value))
+        }
+        None => quote!(::std::option::Option::None),
+    }
+}
+
+fn field_tokens(field: &Field) -> TokenStream {
+    let ast = ast();
+    match field {
+        Field::Asterisk => quote!(#ast::Field::Asterisk),
+        Field::Expression { expression, name } => {
+            let expression = expression_tokens(expression);
+            quote!(#ast::Field::Expression { expression: #expression, name: ::std::string::String::from(#name) })
+        }
+    }
+}
+
+fn predicate_tokens(predicate: &Predicate) -> TokenStream {
+    let ast = ast();
+    let expr = expression_tokens(&predicate.expr);
+
+    quote!(#ast::Predicate { expr: #expr })
+}
+
+fn identifier_tokens(identifier: &Identifier) -> TokenStream {
+    let ast = ast();
+    let name = &identifier.0;
+
+    quote!(#ast::expression::Identifier(::std::string::String::from(#name)))
+}
+
+fn expression_tokens(expression: &Expression) -> TokenStream {
+    let ast = ast();
+    match expression {
+        Expression::Identifier(identifier) => {
+            let identifier = identifier_tokens(identifier);
+            quote!(#ast::expression::Expression::Identifier(#identifier))
+        }
+        Expression::Literal(literal) => {
+            let literal = literal_tokens(literal);
+            quote!(#ast::expression::Expression::Literal(#literal))
+        }
+        Expression::Operation(operation) => {
+            let operation = operation_tokens(operation);
+            quote!(#ast::expression::Expression::Operation(::std::boxed::Box::new(#operation)))
+        }
+        Expression::FunctionCall(call) => {
+            let call = function_call_tokens(call);
+            quote!(#ast::expression::Expression::FunctionCall(#call))
+        }
+        Expression::RelativeDate(relative_date) => {
+            let variant = variant(relative_date);
+            quote!(#ast::expression::Expression::RelativeDate(#ast::expression::RelativeDate::#variant))
+        }
+        Expression::In(in_subquery) => {
+            let in_subquery = in_subquery_tokens(in_subquery);
+            quote!(#ast::expression::Expression::In(::std::boxed::Box::new(#in_subquery)))
+        }
+        Expression::List(items) => {
+            let items = items.iter().map(expression_tokens);
+            quote!(#ast::expression::Expression::List(::std::vec![#(#items),*]))
+        }
+    }
+}
+
+fn operation_tokens(operation: &Operation) -> TokenStream {
+    let ast = ast();
+    match operation {
+        Operation::Unary(operation) => {
+            let expression = expression_tokens(&operation.expression);
+            let op = variant(&operation.op);
+            quote! {
+                #ast::expression::Operation::Unary(#ast::expression::UnaryOperation {
+                    expression: #expression,
+                    op: #ast::expression::UnaryOp::#op,
+                })
+            }
+        }
+        Operation::Binary(operation) => {
+            let left = expression_tokens(&operation.left_expression);
+            let right = expression_tokens(&operation.right_expression);
+            let op = variant(&operation.op);
+            quote! {
+                #ast::expression::Operation::Binary(#ast::expression::BinaryOperation {
+                    left_expression: #left,
+                    op: #ast::expression::BinaryOp::#op,
+                    right_expression: #right,
+                })
+            }
+        }
+    }
+}
+
+fn function_call_tokens(call: &FunctionCall) -> TokenStream {
+    let ast = ast();
+    let name = identifier_tokens(&call.name);
+    let arguments = call.arguments.iter().map(expression_tokens);
+
+    quote!(#ast::expression::FunctionCall { name: #name, arguments: ::std::vec![#(#arguments),*] })
+}
+
+fn in_subquery_tokens(in_subquery: &InSubquery) -> TokenStream {
+    let ast = ast();
+    let expression = expression_tokens(&in_subquery.expression);
+    let subquery = match &in_subquery.subquery {
+        Subquery::Query(query) => {
+            let query = query_tokens(query);
+            quote!(#ast::expression::Subquery::Query(#query))
+        }
+        Subquery::Values(expression) => {
+            let expression = expression_tokens(expression);
+            quote!(#ast::expression::Subquery::Values(#expression))
+        }
+        Subquery::Materialized(_) => unreachable!("parsed subqueries are not materialized"),
+    };
+
+    quote!(#ast::expression::InSubquery { expression: #expression, subquery: #subquery })
+}
+
+fn literal_tokens(literal: &Literal) -> TokenStream {
+    let ast = ast();
+    let chrono = quote!(::todo_list::__private::chrono);
+    let literal = match literal {
+        Literal::Number(Number::Int(int)) => quote!(Number(#ast::expression::Number::Int(#int))),
+        Literal::Number(Number::Float(float)) => {
+            let bits = float.to_bits();
+            quote!(Number(#ast::expression::Number::Float(f64::from_bits(#bits))))
+        }
+        Literal::Number(Number::Decimal(decimal)) => {
+            let (mantissa, scale) = (decimal.mantissa(), decimal.scale());
+            let decimal = quote!(::todo_list::query::evaluator::value::decimal::Decimal::new(#mantissa, #scale).expect("decimal is parsed by the macro"));
+            quote!(Number(#ast::expression::Number::Decimal(#decimal)))
+        }
+        Literal::String(string) => quote!(String(::std::string::String::from(#string))),
+        Literal::Bool(bool) => quote!(Bool(#bool)),
+        Literal::DateTime(date_time) => {
+            let (secs, nanos) = (date_time.timestamp(), date_time.timestamp_subsec_nanos());
+            quote!(DateTime(#chrono::DateTime::from_timestamp(#secs, #nanos).expect("date is parsed by the macro")))
+        }
+        Literal::Duration(duration) => {
+            let (secs, nanos) = (duration.num_seconds(), duration.subsec_nanos());
+            quote!(Duration(#chrono::TimeDelta::seconds(#secs) + #chrono::TimeDelta::nanoseconds(#nanos as i64)))
+        }
+        Literal::Null => quote!(Null),
+    };
+
+    quote!(#ast::expression::Literal::#literal)
+}
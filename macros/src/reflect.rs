@@ -0,0 +1,132 @@
+//! Derive of the `Reflectable` trait from the struct fields.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr};
+
+/// Reflected field of the struct.
+struct Field {
+    ident: syn::Ident,
+    ty: syn::Type,
+    /// Name of the field in queries.
+    name: String,
+    /// Field is a nested `Reflectable` value, that is read by dotted paths.
+    nested: bool,
+}
+
+/// Returns the `Reflectable` implementation of the struct with named fields.
+///
+/// Each field is reflected with its `ToValue` implementation under its own name, or the name of
+/// `#[reflect(rename = "...")]`. Fields with `#[reflect(nested)]` are `Reflectable` values of `get_nested`, and fields
+/// with `#[reflect(skip)]` are not reflected. The only field with `#[reflect(extra)]` is a `Reflectable` map of the
+/// dynamic fields, which are read, when the struct has no field with the name.
+pub fn derive(input: DeriveInput) -> syn::Result<TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(&input.ident, "Reflectable can be derived only for structs"));
+    };
+    let Fields::Named(named) = &data.fields else {
+        return Err(syn::Error::new_spanned(&input.ident, "Reflectable can be derived only for structs with named fields"));
+    };
+
+    let mut fields = Vec::new();
+    let mut extra = None;
+    for field in &named.named {
+        let ident = field.ident.clone().expect("fields are named");
+        let mut name = ident.to_string().trim_start_matches("r#").to_string();
+        let mut skip = false;
+        let mut nested = false;
+        let mut is_extra = false;
+        for attribute in field.attrs.iter().filter(|attribute| attribute.path().is_ident("reflect")) {
+            attribute.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("nested") {
+                    nested = true;
+                    Ok(())
+                } else if meta.path.is_ident("extra") {
+                    is_extra = true;
+                    Ok(())
+                } else if meta.path.is_ident("rename") {
+                    name = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown reflect attribute, expected `rename`, `nested`, `extra` or `skip`"))
+                }
+            })?;
+        }
+        if is_extra {
+            if extra.replace(ident.clone()).is_some() {
+                return Err(syn::Error::new_spanned(&ident, "only one field can have dynamic extra fields"));
+            }
+            continue;
+        }
+        if let Some(duplicate) = fields.iter().find(|field: &&Field| field.name == name) {
+            return Err(syn::Error::new_spanned(
+                &ident,
+                format!("field `{name}` is already reflected by `{}`", duplicate.ident),
+            ));
+        }
+        if !skip {
+            fields.push(Field { ident, ty: field.ty.clone(), name, nested });
+        }
+    }
+
+    let reflect = quote!(::todo_list::query::reflect);
+    let cow = quote!(::std::borrow::Cow);
+    let (nested, fields): (Vec<_>, Vec<_>) = fields.iter().partition(|field| field.nested);
+    let (idents, names): (Vec<_>, Vec<_>) = fields.iter().map(|field| (&field.ident, &field.name)).unzip();
+    let (nested_idents, nested_names): (Vec<_>, Vec<_>) = nested.iter().map(|field| (&field.ident, &field.name)).unzip();
+    let types = fields.iter().map(|field| &field.ty);
+    let len = fields.len();
+    let (read_extra, extra_fields, has_dynamic_fields) = match &extra {
+        Some(extra) => (
+            quote!(#reflect::Reflectable::get_field(&self.#extra, field)),
+            quote!(.chain(#reflect::Reflectable::fields(&self.#extra))),
+            quote!(true),
+        ),
+        None => (
+            quote!(::std::result::Result::Err(#reflect::ReflectError::NoField(::std::string::ToString::to_string(field)))),
+            quote!(),
+            quote!(false),
+        ),
+    };
+    let ty = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #reflect::Reflectable for #ty #ty_generics #where_clause {
+            fn get_field(&self, field: &str) -> ::std::result::Result<#reflect::Value<'_>, #reflect::ReflectError> {
+                match field {
+                    #(#names => ::std::result::Result::Ok(#reflect::ToValue::to_value(&self.#idents)),)*
+                    field => #read_extra,
+                }
+            }
+
+            fn fields(&self) -> #reflect::FieldsIterator<'_> {
+                let fields: [(#cow<'static, str>, #reflect::Value<'_>); #len] =
+                    [#((#cow::Borrowed(#names), #reflect::ToValue::to_value(&self.#idents))),*];
+                ::std::boxed::Box::new(::std::iter::IntoIterator::into_iter(fields)#extra_fields)
+            }
+
+            fn field_names() -> #cow<'static, [#cow<'static, str>]> {
+                #cow::Borrowed(&[#(#cow::Borrowed(#names)),*])
+            }
+
+            fn field_types() -> #cow<'static, [(#cow<'static, str>, #reflect::Type)]> {
+                #cow::Borrowed(&[#((#cow::Borrowed(#names), <#types as #reflect::ToValue>::TYPE)),*])
+            }
+
+            fn has_dynamic_fields() -> bool {
+                #has_dynamic_fields
+            }
+
+            fn get_nested(&self, field: &str) -> ::std::result::Result<&dyn #reflect::Reflectable, #reflect::ReflectError> {
+                match field {
+                    #(#nested_names => ::std::result::Result::Ok(&self.#nested_idents),)*
+                    field => ::std::result::Result::Err(#reflect::ReflectError::NoField(::std::string::ToString::to_string(field))),
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,20 @@
+use std::str::FromStr;
+use todo_list::query::Query;
+use todo_list_macros::query;
+
+#[test]
+fn expand_query() {
+    assert_eq!(query!("SELECT * WHERE status = 'on'"), Query::from_str("SELECT * WHERE status = 'on'").unwrap());
+    assert_eq!(
+        query!("SELECT name, -1.5 + 2 AS score FROM archive WHERE NOT date < TODAY AND date > DATETIME '2024-12-12 20:20' OR category IN (SELECT category WHERE name LIKE '%a%')"),
+        Query::from_str("SELECT name, -1.5 + 2 AS score FROM archive WHERE NOT date < TODAY AND date > DATETIME '2024-12-12 20:20' OR category IN (SELECT category WHERE name LIKE '%a%')").unwrap()
+    );
+    assert_eq!(
+        query!("SELECT upper(name) WHERE description = NULL OR date > TODAY - INTERVAL '1 day 2 hours'"),
+        Query::from_str("SELECT upper(name) WHERE description = NULL OR date > TODAY - INTERVAL '1 day 2 hours'").unwrap()
+    );
+    assert_eq!(
+        query!("SELECT [1, [name]] WHERE category IN ('work', 'home')"),
+        Query::from_str("SELECT [1, [name]] WHERE category IN ('work', 'home')").unwrap()
+    );
+}
@@ -0,0 +1,96 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::str::FromStr;
+use todo_list::__private::chrono::{DateTime, Utc};
+use todo_list::query::reflect::{Reflectable, ReflectError, Type, Value};
+use todo_list::query::Query;
+use todo_list_macros::Reflectable;
+
+#[derive(Reflectable)]
+struct Recurrence {
+    days: i64,
+}
+
+#[derive(Reflectable)]
+struct Note {
+    #[reflect(rename = "title")]
+    name: String,
+    priority: i64,
+    due: Option<DateTime<Utc>>,
+    tags: Vec<String>,
+    #[reflect(nested)]
+    repeat: Option<Recurrence>,
+    #[reflect(skip)]
+    #[allow(dead_code)]
+    cache: Vec<u8>,
+}
+
+#[test]
+fn derive_reflectable() {
+    let note = Note { name: "Write docs".to_string(), priority: 2, due: None, tags: vec!["urgent".to_string()], repeat: None, cache: Vec::new() };
+
+    assert_eq!(*Note::field_names(), [Cow::Borrowed("title"), Cow::Borrowed("priority"), Cow::Borrowed("due"), Cow::Borrowed("tags")]);
+    assert_eq!(note.get_field("title").unwrap(), Value::String("Write docs".into()));
+    assert_eq!(note.get_field("due").unwrap(), Value::Null);
+    assert_eq!(Note::field_types()[2], (Cow::Borrowed("due"), Type::DateTime));
+    assert!(matches!(note.get_field("name"), Err(ReflectError::NoField(_))));
+    assert!(matches!(note.get_field("cache"), Err(ReflectError::NoField(_))));
+    assert_eq!(
+        note.fields().collect::<Vec<_>>(),
+        [
+            (Cow::Borrowed("title"), Value::String("Write docs".into())),
+            (Cow::Borrowed("priority"), Value::Number(2.into())),
+            (Cow::Borrowed("due"), Value::Null),
+            (Cow::Borrowed("tags"), Value::List(vec![Value::String("urgent".into())])),
+        ]
+    );
+
+    let result_set = Query::from_str("SELECT title WHERE priority > 1").unwrap().execute([&note]).unwrap();
+    assert_eq!(result_set.rows().count(), 1);
+    let result_set = Query::from_str("SELECT title WHERE 'urgent' IN tags AND tags STARTSWITH 'ur' AND NOT tags LIKE 'home'")
+        .unwrap()
+        .execute([&note])
+        .unwrap();
+    assert_eq!(result_set.rows().count(), 1);
+}
+
+#[test]
+fn derive_nested() {
+    let note = |days: Option<i64>| Note {
+        name: "Water plants".to_string(),
+        priority: 1,
+        due: None,
+        tags: Vec::new(),
+        repeat: days.map(|days| Recurrence { days }),
+        cache: Vec::new(),
+    };
+    let notes = [note(Some(7)), note(Some(1)), note(None)];
+
+    assert!(matches!(notes[0].get_field("repeat"), Err(ReflectError::NoField(_))));
+    let result_set = Query::from_str("SELECT repeat.days").unwrap().execute(&notes).unwrap();
+    assert_eq!(result_set.rows().collect::<Vec<_>>(), [[Value::Number(7.into())], [Value::Number(1.into())], [Value::Null]]);
+    let result_set = Query::from_str("SELECT repeat.days WHERE repeat.days > 1").unwrap().execute(&notes[..2]).unwrap();
+    assert_eq!(result_set.rows().collect::<Vec<_>>(), [[Value::Number(7.into())]]);
+}
+
+#[derive(Reflectable)]
+struct Card {
+    name: String,
+    #[reflect(extra)]
+    custom: HashMap<String, Value<'static>>,
+}
+
+#[test]
+fn derive_extra() {
+    let cards = [
+        Card { name: "a".to_string(), custom: HashMap::from([("points".to_string(), Value::Number(3.into()))]) },
+        Card { name: "b".to_string(), custom: HashMap::new() },
+    ];
+
+    assert_eq!(cards[0].get_field("points").unwrap(), Value::Number(3.into()));
+    assert_eq!(cards[1].get_field("points").unwrap(), Value::Null);
+    assert!(Card::has_dynamic_fields());
+
+    let result_set = Query::from_str("SELECT * WHERE points = 3").unwrap().execute(&cards[..1]).unwrap();
+    assert!(result_set.columns().eq(["name", "points"]));
+}
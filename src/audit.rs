@@ -0,0 +1,63 @@
+use std::borrow::Cow;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::query::reflect::{FieldsIterator, ReflectError, Reflectable, ToValue, Value};
+use crate::storage::{Storage, StorageError};
+
+/// Name of the storage tree, where audit log of API mutations is kept.
+pub const AUDIT_TREE: &str = "audit";
+
+/// Represents entry of the audit log of mutations, that were requested through HTTP API.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuditEntry {
+    pub time: DateTime<Utc>,
+    pub client: String,
+    pub token: String,
+    pub request: String,
+    pub status: u16,
+}
+
+impl AuditEntry {
+    /// Record `entry` to the `audit` log.
+    pub fn record(audit: &Storage<AuditEntry>, entry: &AuditEntry) -> Result<(), StorageError> {
+        audit.insert(audit.generate_id()?.to_be_bytes(), entry)?;
+
+        Ok(())
+    }
+}
+
+/// Reflectable implementation to be able to use audit entries in select queries.
+impl Reflectable for AuditEntry {
+    fn get_field(&self, field: &str) -> Result<Value<'_>, ReflectError> {
+        let value = match field {
+            "time" => self.time.to_value(),
+            "client" => self.client.to_value(),
+            "token" => self.token.to_value(),
+            "request" => self.request.to_value(),
+            "status" => Value::Number((self.status as i64).into()),
+            field => return Err(ReflectError::NoField(field.to_string())),
+        };
+
+        Ok(value)
+    }
+
+    fn fields(&self) -> FieldsIterator<'_> {
+        Box::new([
+            ("time".into(), self.time.to_value()),
+            ("client".into(), self.client.to_value()),
+            ("token".into(), self.token.to_value()),
+            ("request".into(), self.request.to_value()),
+            ("status".into(), Value::Number((self.status as i64).into())),
+        ].into_iter())
+    }
+
+    fn field_names() -> Cow<'static, [Cow<'static, str>]> {
+        (&[
+            Cow::Borrowed("time"),
+            Cow::Borrowed("client"),
+            Cow::Borrowed("token"),
+            Cow::Borrowed("request"),
+            Cow::Borrowed("status"),
+        ]).into()
+    }
+}
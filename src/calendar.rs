@@ -0,0 +1,84 @@
+use chrono::{DateTime, Utc};
+use crate::task::Task;
+
+/// Maximum length of the iCalendar content line in octets, excluding line break.
+const MAX_LINE_LENGTH: usize = 75;
+
+/// Render `tasks` as an iCalendar feed with one event per task, dated at the task date.
+///
+/// `now` is used as a timestamp of the events.
+pub fn to_ics<'a>(tasks: impl IntoIterator<Item = &'a Task>, now: DateTime<Utc>) -> String {
+    let mut ics = String::new();
+    push_line(&mut ics, "BEGIN:VCALENDAR");
+    push_line(&mut ics, "VERSION:2.0");
+    push_line(&mut ics, "PRODID:-//todo-list//EN");
+    for task in tasks {
+        push_line(&mut ics, "BEGIN:VEVENT");
+        push_line(&mut ics, &format!("UID:{}@todo-list", escape(&task.name)));
+        push_line(&mut ics, &format!("DTSTAMP:{}", format_date_time(now)));
+        push_line(&mut ics, &format!("DTSTART:{}", format_date_time(task.date)));
+        push_line(&mut ics, &format!("SUMMARY:{}", escape(&task.name)));
+        push_line(&mut ics, &format!("DESCRIPTION:{}", escape(&task.description)));
+        push_line(&mut ics, &format!("CATEGORIES:{}", escape(&task.category)));
+        push_line(&mut ics, "END:VEVENT");
+    }
+    push_line(&mut ics, "END:VCALENDAR");
+
+    ics
+}
+
+fn format_date_time(date_time: DateTime<Utc>) -> String {
+    date_time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape text value according to RFC 5545.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Push content line, folding it to the lines of at most [`MAX_LINE_LENGTH`] octets.
+fn push_line(ics: &mut String, line: &str) {
+    let mut length = 0;
+    for char in line.chars() {
+        if length + char.len_utf8() > MAX_LINE_LENGTH {
+            ics.push_str("\r\n ");
+            length = 1;
+        }
+        ics.push(char);
+        length += char.len_utf8();
+    }
+    ics.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+    use crate::task::Status;
+    use super::*;
+
+    #[test]
+    fn render_ics() {
+        let date = NaiveDateTime::parse_from_str("2024-12-12 20:20", "%Y-%m-%d %H:%M").unwrap().and_utc();
+        let task = Task {
+            name: "Buy milk, bread".to_string(),
+            description: "d".repeat(80),
+            date,
+            category: "home".to_string(),
+            status: Status::Off,
+            owner: None,
+            assignee: None,
+            external_refs: Vec::new(),
+        };
+
+        let ics = to_ics([&task], date);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.contains("\r\nDTSTART:20241212T202000Z\r\n"));
+        assert!(ics.contains("\r\nSUMMARY:Buy milk\\, bread\r\n"));
+        assert!(ics.contains(&format!("\r\nDESCRIPTION:{}\r\n {}\r\n", "d".repeat(63), "d".repeat(17))));
+        assert!(ics.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+    }
+}
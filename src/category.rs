@@ -0,0 +1,90 @@
+use clap::{Args, Subcommand};
+use crate::history::{Action, HistoryEntry, HISTORY_TREE};
+use crate::journal::{JournalEntry, JOURNAL_TREE};
+use crate::query::ast::expression::Literal;
+use crate::query::ast::Predicate;
+use crate::storage::{Storage, StorageError};
+use crate::task::Task;
+
+/// Commands to manage task categories.
+///
+/// * `CategoryCommand::Rename` - Rename category of all its tasks. Rename is undone by `undo`;
+#[derive(Debug, Subcommand, PartialEq)]
+pub enum CategoryCommand {
+    #[command(about = "Rename category of all its tasks")]
+    Rename { old: String, new: String },
+}
+
+/// Repeatable category filter of the reports. Tasks must match any of the categories. Tasks have no tags, so
+/// `--tag` is an alias of `--category`.
+#[derive(Debug, Clone, Default, Args, PartialEq)]
+pub struct CategoryFilter {
+    #[arg(long = "category", visible_alias = "tag", value_name = "CATEGORY", help = "Only tasks of the category. May be repeated")]
+    pub categories: Vec<String>,
+}
+
+impl CategoryFilter {
+    /// Returns predicate, that tests that the category is any of the filter ones, or `None` if the filter is empty.
+    pub fn predicate(&self) -> Option<Predicate> {
+        Predicate::any_of("category", self.categories.iter().cloned().map(Literal::String))
+    }
+}
+
+/// Rename category `old` to `new` of all tasks in the `storage` at once and record it to the journal as a single
+/// undoable change. Returns the number of renamed tasks.
+pub fn rename(storage: &Storage<Task>, old: &str, new: &str) -> Result<usize, StorageError> {
+    let prev_tasks = storage.update_where(|task| task.category == old, |task| task.category = new.to_string())?;
+    if prev_tasks.is_empty() {
+        return Ok(0);
+    }
+    let history = storage.open_tree(HISTORY_TREE)?;
+    for task in &prev_tasks {
+        HistoryEntry::record(&history, Action::Update, &task.name)?;
+    }
+    let count = prev_tasks.len();
+    JournalEntry::record(
+        &storage.open_tree(JOURNAL_TREE)?,
+        format!("rename category '{old}' to '{new}'"),
+        prev_tasks,
+    )?;
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+    use tempfile::tempdir;
+    use crate::task::Status;
+    use super::*;
+
+    #[test]
+    fn rename_and_undo() {
+        let tempdir = tempdir().unwrap();
+        let storage = Storage::<Task>::open(&tempdir).unwrap();
+        let journal = storage.open_tree::<JournalEntry>(JOURNAL_TREE).unwrap();
+        let task = |name: &str, category: &str| Task {
+            name: name.to_string(),
+            description: "description".to_string(),
+            date: NaiveDateTime::parse_from_str("2024-12-12 20:20", "%Y-%m-%d %H:%M").unwrap().and_utc(),
+            category: category.to_string(),
+            status: Status::Off,
+            owner: None,
+            assignee: None,
+            external_refs: Vec::new(),
+        };
+        let tasks = [task("first", "work"), task("second", "home"), task("third", "work")];
+        storage.insert_batch(tasks.iter().map(|task| (&task.name, task))).unwrap();
+        let categories = || storage.iter().map(|entry| entry.unwrap().1.category).collect::<Vec<_>>();
+
+        assert_eq!(rename(&storage, "work", "job").unwrap(), 2);
+        assert_eq!(rename(&storage, "no category", "job").unwrap(), 0);
+        assert_eq!(categories(), ["job", "home", "job"]);
+
+        let entry = JournalEntry::undo(&storage, &journal).unwrap().unwrap();
+
+        assert_eq!(entry.description, "rename category 'work' to 'job'");
+        assert_eq!(categories(), ["work", "home", "work"]);
+        assert!(JournalEntry::undo(&storage, &journal).unwrap().is_none());
+    }
+}
@@ -1,21 +1,96 @@
+use std::io::{stdout, IsTerminal};
 use std::iter::once;
+use std::time::Duration;
 use crate::command::CommandError;
 use crate::query::Query;
+use crate::query::help::{Schema, SyntaxHelp};
+use crate::query::reflect::Reflectable;
+use crate::query::ast::Predicate;
+use crate::query::ast::expression::Literal;
 use crate::task::Task;
+use crate::query::evaluator::limits::Limits;
+use crate::query::evaluator::cancel::CancellationToken;
+use crate::query::evaluator::context::ExecutionContext;
+use crate::query::evaluator::result_set::{SortOrder, TableLayout};
+use chrono_tz::Tz;
 use clap::builder::ValueParser;
 use clap::{
-    Arg, ArgAction, ArgMatches, Args, Error, FromArgMatches, Id, Parser,
+    Arg, ArgAction, ArgMatches, Args, Error, FromArgMatches, Id, Parser, Subcommand, ValueEnum,
 };
-use std::str::FromStr;
 use inquire::InquireError;
 use crate::storage::Storage;
+use crate::retention::RetentionPolicy;
+use crate::import::ImportSource;
+use crate::format::{exporter_names, importer_names};
+use crate::notify::Notify;
+use crate::server::Serve;
+use crate::remote::RemoteStore;
+use crate::store::Backend;
+use crate::migration;
+use crate::interrupt;
+use crate::view::ViewCommand;
+use crate::category::CategoryCommand;
+use crate::dedupe::Dedupe;
+use crate::validator::TaskValidator;
+use crate::key::KeyStrategy;
+use crate::diff::Diff;
+use crate::generate::Generate;
+use crate::report::ReportCommand;
+use crate::pick::Pick;
+use crate::sort::SortSpec;
 
-const TODO_FILE_STORAGE: &str = "todo";
+/// Path of the local storage.
+pub const TODO_FILE_STORAGE: &str = "todo";
 
-/// Cli command. May be specific command or read-eval-print-loop.
+/// Cli arguments.
+///
+/// Commands operate on the local storage, unless the todo-list server is specified with `--remote`.
 #[derive(Debug, Parser, PartialEq)]
-#[command(about = "Simple todo-list command-line app")]
-pub enum Cli {
+#[command(about = "Simple todo-list command-line app", long_about = None)]
+pub struct Cli {
+    #[arg(long, env = "TODO_LIST_REMOTE", value_name = "URL", help = "Operate on tasks of the todo-list server, e.g. http://host:8080")]
+    pub remote: Option<String>,
+    #[arg(long, env = "TODO_LIST_TOKEN", requires = "remote", help = "Access token of the todo-list server")]
+    pub token: Option<String>,
+    #[arg(long, env = "TODO_LIST_USER", help = "Current user. Owns added tasks and is used by 'list --mine' and 'select --mine'")]
+    pub user: Option<String>,
+    #[arg(long, global = true, env = "TODO_LIST_KEYS", value_enum, value_name = "STRATEGY", help = "Key strategy of the local storage. New storage takes it, existing one must be re-keyed with 'rekey'")]
+    pub keys: Option<KeyStrategy>,
+    #[arg(long = "date-format", global = true, env = "TODO_LIST_DATE_FORMATS", value_name = "FORMAT", value_delimiter = ',', help = "Format of the date times in queries, e.g. '%m/%d/%Y', that is tried before the built-in ones. May be repeated or comma-separated")]
+    pub date_formats: Vec<String>,
+    #[arg(long, global = true, env = "TODO_LIST_TIMEZONE", value_name = "TZ", help = "Time zone of the date times in queries and their results, e.g. 'Europe/Berlin'. Defaults to UTC")]
+    pub timezone: Option<Tz>,
+    #[arg(long = "field", global = true, env = "TODO_LIST_FIELDS", value_name = "NAME=EXPRESSION", value_delimiter = ';', help = "Computed field of the tasks, e.g. 'overdue=date < NOW()', that is queried as the stored ones. May be repeated or semicolon-separated")]
+    pub fields: Vec<String>,
+    #[arg(long = "trace-eval", global = true, help = "Print values of the expression nodes for the task, which query evaluation fails. May be toggled in the REPL by '.trace on' and '.trace off'")]
+    pub trace_eval: bool,
+    #[arg(long, global = true, env = "TODO_LIST_MAX_SCANNED_ROWS", value_name = "ROWS", help = "Maximal number of the tasks, that a query scans")]
+    pub max_scanned_rows: Option<usize>,
+    #[arg(long, global = true, env = "TODO_LIST_MAX_RESULT_ROWS", value_name = "ROWS", help = "Maximal number of the rows of a query result")]
+    pub max_result_rows: Option<usize>,
+    #[arg(long, global = true, env = "TODO_LIST_MAX_QUERY_TIME", value_name = "MILLIS", help = "Maximal time of a query execution in milliseconds")]
+    pub max_query_time: Option<u64>,
+    #[arg(long, global = true, env = "TODO_LIST_FLOAT_EPSILON", value_name = "EPSILON", help = "Tolerance of the comparison of the fractional numbers in queries, e.g. '1e-9'. Defaults to exact comparison")]
+    pub float_epsilon: Option<f64>,
+    #[arg(long, global = true, env = "TODO_LIST_PAGE_SIZE", value_name = "ROWS", help = "Rows per page of the query results in the terminal. Results are piped through $PAGER, if it is set, otherwise the next page is printed on Enter")]
+    pub page_size: Option<usize>,
+    #[arg(long, global = true, env = "TODO_LIST_MAX_COLUMN_WIDTH", value_name = "CHARS", help = "Maximal width of the columns of the query results. Wider values are truncated with ellipsis")]
+    pub max_column_width: Option<usize>,
+    #[arg(long = "column-width", global = true, env = "TODO_LIST_COLUMN_WIDTHS", value_name = "NAME=CHARS", value_delimiter = ',', value_parser = parse_column_width, help = "Maximal width of the column of the query results, e.g. 'description=40'. May be repeated or comma-separated")]
+    pub column_widths: Vec<(String, usize)>,
+    #[arg(long, global = true, env = "TODO_LIST_WRAP", help = "Wrap the values, that are wider than their column, instead of the truncation")]
+    pub wrap: bool,
+    #[arg(long, global = true, env = "TODO_LIST_TOTALS", help = "Show totals of the columns and the row count under the query results")]
+    pub totals: bool,
+    #[command(flatten)]
+    pub validator: TaskValidator,
+    #[command(subcommand)]
+    pub mode: Mode,
+}
+
+/// Cli mode. May be specific command or read-eval-print-loop.
+#[derive(Debug, Subcommand, PartialEq)]
+pub enum Mode {
     #[command(flatten)]
     Command(Command),
     #[command(about = "Run app in repl mode")]
@@ -25,75 +100,293 @@ pub enum Cli {
 /// Possible commands.
 ///
 /// * `Command::Add` - Add task to list;
+/// * `Command::Show` - Show task with its external references;
 /// * `Command::Done` - Mark task as completed;
 /// * `Command::Update` - Interactively update task;
 /// * `Command::Delete` - Delete task;
+/// * `Command::List` - List tasks in the sort order;
 /// * `Command::Select` - Select tasks that satisfy query;
+/// * `Command::Insert` - Add tasks with insert statement;
+/// * `Command::Pick` - Print tasks for fuzzy finders or apply action to the picked tasks;
+/// * `Command::QueryHelp` - Print reference of the query language;
+/// * `Command::Category` - Manage task categories;
+/// * `Command::Dedupe` - Find and merge duplicate tasks;
+/// * `Command::Undo` - Undo the latest batch change, such as category rename;
+/// * `Command::Rekey` - Re-key stored tasks with the key strategy;
+/// * `Command::Archive` - Archive and purge old completed tasks;
+/// * `Command::Diff` - Compare query results with the previous run or snapshot;
+/// * `Command::History` - Select entries of the task changes history that satisfy query;
+/// * `Command::Notify` - Post digest of overdue and due soon tasks to the channel;
+/// * `Command::Serve` - Serve tasks over HTTP;
+/// * `Command::Audit` - Select entries of the audit log of HTTP API mutations that satisfy query;
+/// * `Command::Import` - Import tasks from external source;
+/// * `Command::Export` - Write all tasks to stdout;
+/// * `Command::View` - Manage saved views;
+/// * `Command::Share` - Print read-only link to the saved view;
+/// * `Command::Report` - Manage and run reports;
+/// * `Command::Status` - Print version, schema version and storage status;
+/// * `Command::Generate` - Add random tasks or write them to the file;
 #[derive(Debug, Parser, PartialEq)]
 #[command(name = "", about = "Todo list commands")]
 pub enum Command {
     #[command(alias = "ADD", about  = "Add task to list")]
-    Add(Task),
+    Add(Add),
+    #[command(alias = "SHOW", about  = "Show task with its external references")]
+    Show { task_name: String },
     #[command(alias = "DONE", about  = "Mark task as completed")]
     Done { task_name: String },
     #[command(alias = "UPDATE", about  = "Update task")]
     Update { task_name: String },
     #[command(alias = "DELETE", about  = "Delete task")]
     Delete { task_name: String },
+    #[command(alias = "LIST", about  = "List tasks")]
+    List {
+        #[arg(long, env = "TODO_LIST_SORT", value_name = "SPEC", default_value_t = SortSpec::default(), help = "Comma-separated sort keys: 'overdue' or task field, prefixed by '-' for descending order. Empty fields go last, unless the field is followed by 'nulls first'")]
+        sort: SortSpec,
+        #[arg(long, help = "List only tasks owned by or assigned to the current user")]
+        mine: bool,
+    },
     #[command(alias = "SELECT", about  = "Select tasks")]
     Select(Select),
+    #[command(alias = "INSERT", about  = "Add tasks with insert statement: (<field>, ...) VALUES (<value>, ...), ...")]
+    Insert {
+        #[arg(value_name = "STATEMENT", required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        statement: Vec<String>,
+    },
+    #[command(alias = "PICK", about  = "Print tasks for fuzzy finders or apply action to the picked tasks", long_about = None)]
+    Pick(Pick),
+    #[command(alias = "QUERY-HELP", about  = "Print reference of the query language")]
+    QueryHelp,
+    #[command(alias = "CATEGORY", about  = "Manage task categories", subcommand)]
+    Category(CategoryCommand),
+    #[command(alias = "DEDUPE", about  = "Find duplicate tasks and merge them", long_about = None)]
+    Dedupe(Dedupe),
+    #[command(alias = "UNDO", about  = "Undo the latest batch change, such as category rename")]
+    Undo,
+    #[command(alias = "REKEY", about  = "Re-key stored tasks with the key strategy")]
+    Rekey {
+        #[arg(value_enum)]
+        strategy: KeyStrategy,
+    },
+    #[command(alias = "ARCHIVE", about  = "Archive old completed tasks", long_about = None)]
+    Archive(RetentionPolicy),
+    #[command(alias = "DIFF", about  = "Compare query results with the previous run or snapshot", long_about = None)]
+    Diff(Diff),
+    #[command(alias = "HISTORY", about  = "Select entries of task changes history")]
+    History {
+        #[arg(long, value_parser = Query::parse_cached, default_value = "SELECT *", help = "Query over history entries with fields 'time', 'action' and 'task'")]
+        query: Query
+    },
+    #[command(alias = "NOTIFY", about  = "Post overdue and due soon tasks to Slack or Matrix")]
+    Notify(Notify),
+    #[command(alias = "SERVE", about  = "Serve tasks over HTTP", long_about = None)]
+    Serve(Serve),
+    #[command(alias = "AUDIT", about  = "Select entries of audit log of HTTP API mutations")]
+    Audit {
+        #[arg(long, value_parser = Query::parse_cached, default_value = "SELECT *", help = "Query over audit entries with fields 'time', 'client', 'token', 'request' and 'status'")]
+        query: Query
+    },
+    #[command(alias = "IMPORT", about  = "Import tasks from external source", subcommand)]
+    Import(ImportSource),
+    #[command(alias = "EXPORT", about  = "Write all tasks to stdout")]
+    Export {
+        #[arg(long, value_parser = exporter_names(), default_value = "jsonl", help = "Format of written tasks")]
+        format: String,
+    },
+    #[command(alias = "VIEW", about  = "Manage saved views", subcommand)]
+    View(ViewCommand),
+    #[command(alias = "SHARE", about  = "Print read-only link to the saved view")]
+    Share {
+        view: String,
+        #[arg(long, value_name = "DAYS", value_parser = clap::value_parser!(i64).range(1..), help = "Number of days the link is valid. Link never expires if not specified")]
+        expires_in: Option<i64>,
+        #[arg(long, value_name = "URL", default_value = "http://127.0.0.1:8080", help = "Base URL of the todo-list server")]
+        base_url: String,
+    },
+    #[command(alias = "REPORT", about  = "Manage and run reports", subcommand)]
+    Report(ReportCommand),
+    #[command(alias = "STATUS", about  = "Print version, schema version and storage status")]
+    Status {
+        #[cfg(feature = "self-update")]
+        #[arg(long, help = "Check GitHub for the newer release")]
+        check_update: bool,
+    },
+    #[command(alias = "GENERATE", about  = "Add random tasks for benchmarks and demos, or write them to the file")]
+    Generate(Generate),
+}
+
+/// Add single task from arguments or multiple tasks from stdin.
+#[derive(Debug, Args, PartialEq)]
+pub struct Add {
+    #[command(flatten)]
+    pub task: Option<Task>,
+    #[arg(long, conflicts_with = "name", help = "Read tasks from stdin, one per line")]
+    pub stdin: bool,
+    #[arg(long, value_parser = importer_names(), default_value = "jsonl", requires = "stdin", help = "Format of tasks read from stdin")]
+    pub format: String,
 }
 
+/// Select query. With `mine` only tasks owned by or assigned to the current user are selected. Result is sorted by
+/// the `sort` columns and printed in the `output` format.
 #[derive(Debug, Clone, PartialEq)]
-pub struct Select(pub Query);
+pub struct Select {
+    pub query: Query,
+    pub mine: bool,
+    pub sort: Vec<(String, SortOrder)>,
+    pub output: Output,
+}
+
+/// Formats of the query result.
+#[derive(Debug, Clone, Copy, Default, ValueEnum, PartialEq)]
+pub enum Output {
+    #[default]
+    Table,
+    /// Array of objects, that are keyed by the column names, e.g. to be piped to `jq`.
+    Json,
+    /// Pipe table, e.g. to be pasted into GitHub issues and notes.
+    Markdown,
+}
 
 impl Cli {
     /// Runs the command or read-eval-print-loop
     pub fn run(self) -> Result<(), CommandError> {
-        let storage = Storage::open(TODO_FILE_STORAGE)?;
-        match self {
-            Cli::Command(command) => command.run(&storage),
-            Cli::Repl => loop {
-                let line =  match repl::readline() {
-                    Ok(value) => value,
-                    Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => return Ok(()),
-                    Err(err) => {
-                        eprintln!("{}", CommandError::Readline(err));
-                        continue;
+        let context = self.context()?;
+        context.scope(|| self.run_mode())
+    }
+
+    /// Returns the context of the queries and the printed results, that is set up by the arguments.
+    fn context(&self) -> Result<ExecutionContext, CommandError> {
+        let mut context = ExecutionContext {
+            timezone: self.timezone.unwrap_or_default(),
+            float_epsilon: self.float_epsilon.unwrap_or_default(),
+            limits: Limits {
+                max_scanned_rows: self.max_scanned_rows,
+                max_result_rows: self.max_result_rows,
+                max_duration: self.max_query_time.map(Duration::from_millis),
+            },
+            trace: self.trace_eval,
+            page_size: self.page_size,
+            table_layout: TableLayout {
+                max_column_width: self.max_column_width,
+                column_widths: self.column_widths.clone(),
+                // Tables, that are printed to the terminal, fit its width.
+                max_width: stdout().is_terminal().then(crossterm::terminal::size).and_then(Result::ok).map(|(columns, _)| columns.into()),
+                wrap: self.wrap,
+                totals: self.totals,
+            },
+            ..ExecutionContext::default()
+        };
+        for format in self.date_formats.iter().rev() {
+            context.register_datetime_format(format).map_err(CommandError::DateFormat)?;
+        }
+        for field in &self.fields {
+            context.register_definition::<Task>(field)?;
+        }
+
+        Ok(context)
+    }
+
+    fn run_mode(mut self) -> Result<(), CommandError> {
+        // Picked lines are read before the storage is opened, as it is locked by 'pick', that lists tasks to the picker.
+        if let Mode::Command(Command::Pick(pick)) = &mut self.mode {
+            pick.read_stdin()?;
+        }
+        let (backend, _interrupt) = match self.remote {
+            Some(url) => (Backend::Remote(RemoteStore::new(&url, self.token)), interrupt::install(None)?),
+            None => {
+                let storage = Storage::open(TODO_FILE_STORAGE)?;
+                let interrupt = interrupt::install(Some(&storage))?;
+                // Status is reported before the migration, so it shows pending migrations.
+                if !matches!(self.mode, Mode::Command(Command::Status { .. })) {
+                    let _section = interrupt::critical_section();
+                    migration::migrate(&storage)?;
+                }
+                match self.keys {
+                    Some(keys) if !matches!(self.mode, Mode::Command(Command::Rekey { .. } | Command::Status { .. })) => {
+                        KeyStrategy::configure(&storage, keys)?;
                     }
-                };
-                let line = line.trim();
-                if line.is_empty(){
-                    continue;
+                    _ => {}
                 }
-                let command = match repl::parse(line) {
-                    Ok(command) => command,
-                    Err(err) => {
-                        eprintln!("{err}");
+                (Backend::Local(storage), interrupt)
+            }
+        };
+        let user = self.user.as_deref();
+        let result = match self.mode {
+            Mode::Command(command) => command.run(&backend, user, &self.validator),
+            Mode::Repl => {
+                // Tracing is switched on and off by the REPL, so commands run in its own copy of the context.
+                let mut context = ExecutionContext::current();
+                loop {
+                    let line =  match repl::readline() {
+                        Ok(value) => value,
+                        Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => break Ok(()),
+                        Err(err) => {
+                            eprintln!("{}", CommandError::Readline(err));
+                            continue;
+                        }
+                    };
+                    let line = line.trim();
+                    if line.is_empty(){
                         continue;
                     }
-                };
-
-                match command.run(&storage) {
-                    Ok(_) => continue,
-                    Err(err) => {
-                        eprintln!("{err}");
+                    if line == ".syntax" {
+                        println!("{}", SyntaxHelp::new(&Task::field_names()));
+                        continue;
+                    }
+                    if line == ".schema" {
+                        println!("{}", Schema::new(&Task::field_types()));
+                        continue;
+                    }
+                    if let Some(mode) = line.strip_prefix(".trace") {
+                        match mode.trim() {
+                            "on" => context.trace = true,
+                            "off" => context.trace = false,
+                            _ => eprintln!("Usage: .trace on|off"),
+                        }
                         continue;
                     }
+                    let command = match repl::parse(line) {
+                        Ok(command) => command,
+                        Err(err) => {
+                            eprintln!("{err}");
+                            continue;
+                        }
+                    };
+
+                    // Ctrl-C cancels the running query, but not the REPL.
+                    let token = CancellationToken::new();
+                    let _cancel = interrupt::cancel_on_interrupt(&token);
+                    match context.scope(|| token.scope(|| command.run(&backend, user, &self.validator))) {
+                        Ok(_) => continue,
+                        Err(err) => {
+                            eprintln!("{err}");
+                            continue;
+                        }
+                    }
                 }
-            },
+            }
+        };
+        // Changes are flushed explicitly, so they are not lost, if the storage outlives the command.
+        if let Backend::Local(storage) = &backend {
+            storage.flush()?;
         }
+
+        result
     }
 }
 
 mod repl {
     use clap::Parser;
+    use inquire::autocompletion::{Autocomplete, Replacement};
     use inquire::ui::{Color, RenderConfig, Styled};
-    use inquire::{InquireError, Text};
+    use inquire::{CustomUserError, InquireError, Text};
     use crate::cli::Command;
+    use crate::query::{complete, Suggestion};
+    use crate::task::Task;
 
     pub fn readline() -> Result<String, InquireError> {
         Text::new("")
+            .with_autocomplete(QueryCompletion)
             .with_render_config(
                 RenderConfig::default()
                     .with_prompt_prefix(Styled::new("<<").with_fg(Color::DarkBlue))
@@ -103,14 +396,46 @@ mod repl {
     }
 
     pub fn parse(line: &str) -> Result<Command, clap::Error> {
-        let args = if line.starts_with("SELECT") || line.starts_with("select"){
+        let is_statement = ["SELECT", "select", "INSERT", "insert"].iter().any(|keyword| line.starts_with(keyword));
+        let args = if is_statement {
             line.split_whitespace().map(ToString::to_string).collect()
         } else {
-            shlex::split(line).unwrap_or(Vec::new())
+            shlex::split(line).unwrap_or_default()
         };
 
         Command::try_parse_from(std::iter::once(String::new()).chain(args))
     }
+
+    /// Completes select queries at the end of the line.
+    #[derive(Clone)]
+    struct QueryCompletion;
+
+    impl QueryCompletion {
+        fn suggestions(line: &str) -> Vec<Suggestion> {
+            let is_select = line.get(..7).is_some_and(|command| command.eq_ignore_ascii_case("SELECT "));
+            if is_select {
+                complete::<Task>(line, line.len())
+            } else {
+                Vec::new()
+            }
+        }
+    }
+
+    impl Autocomplete for QueryCompletion {
+        fn get_suggestions(&mut self, input: &str) -> Result<Vec<String>, CustomUserError> {
+            Ok(Self::suggestions(input).into_iter().map(|suggestion| suggestion.text).collect())
+        }
+
+        fn get_completion(&mut self, input: &str, highlighted: Option<String>) -> Result<Replacement, CustomUserError> {
+            let suggestions = Self::suggestions(input);
+            let suggestion = match highlighted {
+                Some(text) => suggestions.iter().find(|suggestion| suggestion.text == text),
+                None => suggestions.first(),
+            };
+
+            Ok(suggestion.map(|suggestion| suggestion.apply(input)))
+        }
+    }
 }
 
 /// Parse query from command line arguments
@@ -122,12 +447,34 @@ impl FromArgMatches for Select {
         let query = arg_matches
             .remove_many::<String>("query")
             .map(|v| once("SELECT".to_string()).chain(v).collect::<Vec<_>>())
-            .unwrap_or_else(Vec::new)
+            .unwrap_or_default()
             .join(" ");
+        let categories = arg_matches
+            .remove_many::<String>("category")
+            .into_iter()
+            .flatten()
+            .map(Literal::String);
 
-        Query::from_str(&query)
-            .map(Select)
-            .map_err(|err| clap::Error::raw(clap::error::ErrorKind::InvalidValue, err))
+        let mut query = Query::parse_cached(&query)
+            .map_err(|err| clap::Error::raw(clap::error::ErrorKind::InvalidValue, err))?;
+        if let Some(filter) = Predicate::any_of("category", categories) {
+            query.and_where(filter);
+        }
+        let mine = arg_matches.remove_one::<bool>("mine").unwrap_or_default();
+        let sort = arg_matches
+            .remove_one::<String>("sort")
+            .iter()
+            .flat_map(|columns| columns.split(','))
+            .map(str::trim)
+            .filter(|column| !column.is_empty())
+            .map(|column| match column.strip_prefix('-') {
+                Some(column) => (column.to_string(), SortOrder::Descending),
+                None => (column.to_string(), SortOrder::Ascending),
+            })
+            .collect();
+        let output = arg_matches.remove_one::<Output>("output").unwrap_or_default();
+
+        Ok(Select { query, mine, sort, output })
     }
     fn update_from_arg_matches(&mut self, arg_matches: &ArgMatches) -> Result<(), Error> {
         self.update_from_arg_matches_mut(&mut arg_matches.clone())
@@ -144,7 +491,7 @@ impl Args for Select {
         Some(Id::from("Select"))
     }
     fn augment_args<'b>(app: clap::Command) -> clap::Command {
-        app.arg(
+        app.arg(category_arg()).arg(mine_arg()).arg(sort_arg()).arg(output_arg()).arg(
             Arg::new("query")
                 .value_name("QUERY")
                 .value_parser(ValueParser::string())
@@ -155,7 +502,7 @@ impl Args for Select {
         )
     }
     fn augment_args_for_update<'b>(app: clap::Command) -> clap::Command {
-        app.arg(
+        app.arg(category_arg()).arg(mine_arg()).arg(sort_arg()).arg(output_arg()).arg(
             Arg::new("query")
                 .value_name("QUERY")
                 .value_parser(ValueParser::string())
@@ -167,47 +514,260 @@ impl Args for Select {
     }
 }
 
+/// Repeatable category filter. Selected tasks must match any of the categories. `--tag` is its alias, the same as
+/// of the [`CategoryFilter`](crate::category::CategoryFilter) of the reports.
+fn category_arg() -> Arg {
+    Arg::new("category")
+        .long("category")
+        .visible_alias("tag")
+        .value_name("CATEGORY")
+        .help("Select only tasks of the category. May be repeated")
+        .value_parser(ValueParser::string())
+        .action(ArgAction::Append)
+}
+
+/// Filter of the tasks owned by or assigned to the current user.
+fn mine_arg() -> Arg {
+    Arg::new("mine")
+        .long("mine")
+        .help("Select only tasks owned by or assigned to the current user")
+        .action(ArgAction::SetTrue)
+}
+
+/// Parse maximal width of the column `<name>=<chars>`, e.g. `description=40`.
+fn parse_column_width(width: &str) -> Result<(String, usize), String> {
+    match width.split_once('=').map(|(name, chars)| (name.trim(), chars.trim().parse::<usize>())) {
+        Some((name, Ok(chars))) if !name.is_empty() => Ok((name.to_string(), chars)),
+        _ => Err(format!("Column width must be defined as '<name>=<chars>', but it is '{width}'")),
+    }
+}
+
+/// Columns, that the query result is sorted by.
+fn sort_arg() -> Arg {
+    Arg::new("sort")
+        .long("sort")
+        .value_name("COLUMNS")
+        .help("Comma-separated columns of the query result to sort it by, prefixed by '-' for descending order. Empty values go last")
+        .value_parser(ValueParser::string())
+}
+
+/// Format of the printed query result.
+fn output_arg() -> Arg {
+    Arg::new("output")
+        .long("output")
+        .value_name("FORMAT")
+        .help("Format of the query result")
+        .value_parser(clap::value_parser!(Output))
+        .default_value("table")
+}
+
 
 #[cfg(test)]
 mod tests {
     use chrono::NaiveDateTime;
-    use crate::query::ast::{Field, FieldsProjection, Predicate};
-    use crate::query::ast::expression::{BinaryOp, BinaryOperation, Expression, Identifier, Literal, Operation};
+    use crate::query::ast::{Field, FieldsProjection};
+    use crate::query::ast::expression::{BinaryOp, BinaryOperation, Expression, Identifier, Operation};
     use crate::query::ast::expression::Number;
     use crate::task::Status;
     use super::*;
+    fn command_cli(command: Command) -> Cli {
+        Cli {
+            remote: None,
+            token: None,
+            user: None,
+            keys: None,
+            date_formats: Vec::new(),
+            timezone: None,
+            fields: Vec::new(),
+            trace_eval: false,
+            max_scanned_rows: None,
+            max_result_rows: None,
+            max_query_time: None,
+            float_epsilon: None,
+            page_size: None,
+            max_column_width: None,
+            column_widths: Vec::new(),
+            wrap: false,
+            totals: false,
+            validator: TaskValidator::default(),
+            mode: Mode::Command(command),
+        }
+    }
+
     #[test]
     fn select_command() {
         let cmd = shlex::split("todo-list select * where predicate = 10").unwrap_or_default();
         let command = Cli::try_parse_from(cmd).unwrap();
-        let expected = Cli::Command(Command::Select(Select(Query{
+        let expected = command_cli(Command::Select(Select{ mine: false, sort: Vec::new(), output: Output::Table, query: Query{
             fields_projection: FieldsProjection(Vec::from([Field::Asterisk])),
+            from: None,
             predicate: Some(Predicate{
                 expr: Expression::Operation(Box::new(Operation::Binary(BinaryOperation{
                     left_expression: Expression::Identifier(Identifier("predicate".to_string())),
                     right_expression: Expression::Literal(Literal::Number(Number::Int(10))),
                     op: BinaryOp::Eq
                 })))
-            })
+            }),
+            group_by: Vec::new(),
+            collation: None
+        }}));
+
+        assert_eq!(command, expected)
+    }
+
+    #[test]
+    fn select_command_with_categories() {
+        let cmd = shlex::split("todo-list select --category work --tag home *").unwrap_or_default();
+        let command = Cli::try_parse_from(cmd).unwrap();
+        let category = |name: &str| Expression::Operation(Box::new(Operation::Binary(BinaryOperation{
+            left_expression: Expression::Identifier(Identifier("category".to_string())),
+            right_expression: Expression::Literal(Literal::String(name.to_string())),
+            op: BinaryOp::Eq
         })));
+        let expected = command_cli(Command::Select(Select{ mine: false, sort: Vec::new(), output: Output::Table, query: Query{
+            fields_projection: FieldsProjection(Vec::from([Field::Asterisk])),
+            from: None,
+            predicate: Some(Predicate{
+                expr: Expression::Operation(Box::new(Operation::Binary(BinaryOperation{
+                    left_expression: category("work"),
+                    right_expression: category("home"),
+                    op: BinaryOp::Or
+                })))
+            }),
+            group_by: Vec::new(),
+            collation: None
+        }}));
 
         assert_eq!(command, expected)
     }
 
+    #[test]
+    fn select_command_with_sort() {
+        let cmd = shlex::split("todo-list select --sort '-date, name' --output json name, date").unwrap_or_default();
+        let Mode::Command(Command::Select(select)) = Cli::try_parse_from(cmd).unwrap().mode else {
+            panic!("select command is expected");
+        };
+
+        assert_eq!(select.sort, [("date".to_string(), SortOrder::Descending), ("name".to_string(), SortOrder::Ascending)]);
+        assert_eq!(select.output, Output::Json);
+    }
+
+    #[test]
+    fn notify_command_requires_webhook_unless_dry_run() {
+        let parse = |line: &str| Cli::try_parse_from(shlex::split(line).unwrap_or_default());
+        let Mode::Command(Command::Notify(notify)) = parse("todo-list notify --channel slack --dry-run").unwrap().mode else {
+            panic!("notify command is expected");
+        };
+
+        assert_eq!(notify.webhook, None);
+        assert!(parse("todo-list notify --channel slack").is_err());
+        assert!(parse("todo-list notify --channel slack --webhook https://example.com").is_ok());
+    }
+
+    #[test]
+    fn list_command_mine() {
+        let parse = |line: &str| Cli::try_parse_from(shlex::split(line).unwrap_or_default()).unwrap().mode;
+
+        assert!(matches!(parse("todo-list --user alice list --mine"), Mode::Command(Command::List { mine: true, .. })));
+        assert!(matches!(parse("todo-list list"), Mode::Command(Command::List { mine: false, .. })));
+    }
+
+    #[test]
+    fn report_commands_with_categories() {
+        let parse = |line: &str| Cli::try_parse_from(shlex::split(line).unwrap_or_default()).unwrap().mode;
+        let expected = "SELECT * WHERE category = 'work' OR category = 'home'".parse::<Query>().unwrap().predicate;
+
+        let Mode::Command(Command::Report(ReportCommand::Run { name, filter })) =
+            parse("todo-list report run --category work --tag home")
+        else {
+            panic!("report run command is expected");
+        };
+        assert_eq!(name, None);
+        assert_eq!(filter.predicate(), expected);
+
+        let Mode::Command(Command::Notify(notify)) =
+            parse("todo-list notify --channel slack --dry-run --tag work --category home")
+        else {
+            panic!("notify command is expected");
+        };
+        assert_eq!(notify.filter.predicate(), expected);
+    }
+
     #[test]
     fn add_command() {
         let cmd = shlex::split("todo-list add name description \"2020-12-12 20:20\" category off").unwrap_or_default();
         let command = Cli::try_parse_from(cmd).unwrap();
-        let expected = Cli::Command(Command::Add(Task{
-            name: "name".to_string(),
-            description: "description".to_string(),
-            date: NaiveDateTime::parse_from_str("2020-12-12 20:20", "%Y-%m-%d %H:%M")
-                .unwrap()
-                .and_utc(),
-            category: "category".to_string(),
-            status: Status::Off
+        let expected = command_cli(Command::Add(Add{
+            task: Some(Task{
+                name: "name".to_string(),
+                description: "description".to_string(),
+                date: NaiveDateTime::parse_from_str("2020-12-12 20:20", "%Y-%m-%d %H:%M")
+                    .unwrap()
+                    .and_utc(),
+                category: "category".to_string(),
+                status: Status::Off,
+                owner: None,
+                assignee: None,
+                external_refs: Vec::new(),
+            }),
+            stdin: false,
+            format: "jsonl".to_string()
         }));
 
         assert_eq!(command, expected)
     }
+
+    #[test]
+    fn add_stdin_command() {
+        let cmd = shlex::split("todo-list add --stdin --format jsonl").unwrap_or_default();
+        let command = Cli::try_parse_from(cmd).unwrap();
+        let expected = command_cli(Command::Add(Add{
+            task: None,
+            stdin: true,
+            format: "jsonl".to_string()
+        }));
+
+        assert_eq!(command, expected);
+
+        let cmd = shlex::split("todo-list add name --stdin").unwrap_or_default();
+
+        assert!(Cli::try_parse_from(cmd).is_err());
+
+        let cmd = shlex::split("todo-list add").unwrap_or_default();
+
+        assert!(Cli::try_parse_from(cmd).is_err());
+    }
+
+    #[test]
+    fn remote_option() {
+        let cmd = shlex::split("todo-list --remote http://host:8080 --token secret done name").unwrap_or_default();
+        let command = Cli::try_parse_from(cmd).unwrap();
+        let expected = Cli {
+            remote: Some("http://host:8080".to_string()),
+            token: Some("secret".to_string()),
+            user: None,
+            keys: None,
+            date_formats: Vec::new(),
+            timezone: None,
+            fields: Vec::new(),
+            trace_eval: false,
+            max_scanned_rows: None,
+            max_result_rows: None,
+            max_query_time: None,
+            float_epsilon: None,
+            page_size: None,
+            max_column_width: None,
+            column_widths: Vec::new(),
+            wrap: false,
+            totals: false,
+            validator: TaskValidator::default(),
+            mode: Mode::Command(Command::Done { task_name: "name".to_string() }),
+        };
+
+        assert_eq!(command, expected);
+
+        let cmd = shlex::split("todo-list --token secret done name").unwrap_or_default();
+
+        assert!(Cli::try_parse_from(cmd).is_err());
+    }
 }
\ No newline at end of file
@@ -1,54 +1,377 @@
-use crate::cli::Command;
+use crate::cli::{Add, Command, Output, Select, TODO_FILE_STORAGE};
+use crate::query::ast::{Field, FieldsProjection, Insert, ParseError, Predicate};
+use crate::query::Query;
+use crate::query::help::SyntaxHelp;
+use crate::query::reflect::Reflectable;
+use crate::share::{ShareClaims, ShareError, ShareKey};
+use crate::view::{View, ViewCommand, VIEWS_TREE};
+use crate::category::{self, CategoryCommand};
+use crate::journal::{JournalEntry, JOURNAL_TREE};
+use crate::key::{self, KeyError};
+use crate::diff::DiffError;
+use crate::report::{Destination, Report, ReportCommand, ReportError, ReportSource, REPORTS_TREE};
+use crate::query::ast::expression::Literal;
+use crate::import::{ImportError, ImportSource};
+use crate::query::evaluator::computed::ComputedFieldError;
+use crate::pick::{self, Pick, PickAction};
+use crate::sort::SortSpec;
+use crate::interrupt::{self, InterruptError};
+use crate::pager;
+use crate::status::{StatusError, StatusReport};
+use tabled::Table;
+use tabled::settings::Style;
+use crate::format::{self, ExportError};
+use crate::notify::{Digest, Notify, NotifyError};
+use crate::server::ServerError;
+use crate::audit::{AuditEntry, AUDIT_TREE};
 use crate::query::EvaluationError;
+use crate::history::{HistoryEntry, HISTORY_TREE};
 use crate::storage::{Storage, StorageError};
+use crate::store::{self, Backend, StoreError, TaskStore};
+use crate::validator::{FieldError, TaskValidator, ValidatingStore};
 use crate::task::{Status, Task};
-use chrono::NaiveDateTime;
-use inquire::{CustomType, InquireError, Select, Text};
+use chrono::{NaiveDateTime, TimeDelta, Utc};
+use inquire::{CustomType, InquireError, Text};
 use std::fmt::{Debug, Display, Formatter};
+use std::fs::File;
+use std::io::{stdin, stdout, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
 use inquire::validator::ValueRequiredValidator;
 use thiserror::Error;
 
 impl Command {
 
-    /// Runs the command
-    pub fn run(self, storage: &Storage<Task>) -> Result<(), CommandError> {
+    /// Runs the command against `backend` on behalf of the `user`.
+    ///
+    /// Commands, that manage storage itself, such as `archive` or `serve`, require the local backend.
+    /// Added, updated and imported tasks are checked by the `validator`.
+    pub fn run(self, backend: &Backend, user: Option<&str>, validator: &TaskValidator) -> Result<(), CommandError> {
+        let store: &dyn TaskStore = &ValidatingStore { store: backend.store(), validator };
+        let with_owner = |mut task: Task| {
+            if task.owner.is_none() {
+                task.owner = user.map(ToString::to_string);
+            }
+            task
+        };
 
         match self {
-            Command::Add(task) => {
-                if let Some(prev_task) = storage.insert(&task.name, &task)? {
+            Command::Add(Add { task: Some(task), .. }) => {
+                if let Some(prev_task) = store.insert(&with_owner(task))? {
                     println!("Replaced task: \n{prev_task}");
                 };
             }
+            Command::Add(Add { task: None, format, .. }) => {
+                let importer = format::importer(&format).map_err(ImportError::from)?;
+                let tasks = importer.read_tasks(&mut stdin().lock())?.into_iter().map(with_owner).collect::<Vec<_>>();
+                store.insert_batch(&tasks)?;
+                println!("Added {} tasks", tasks.len());
+            }
+            Command::Insert { statement } => {
+                let insert = Insert::from_str(&format!("INSERT {}", statement.join(" ")))?;
+                let tasks = insert.values::<Task>()?.into_iter().map(with_owner).collect::<Vec<_>>();
+                store.insert_batch(&tasks)?;
+                println!("Added {} tasks", tasks.len());
+            }
+            Command::Import(ImportSource::Github(import)) => {
+                let tasks = import.fetch().map_err(ImportError::from)?;
+                let imported = {
+                    let _section = interrupt::critical_section();
+                    store.import(tasks)?
+                };
+                println!("Imported {} tasks, updated {} tasks", imported.added, imported.updated);
+            }
+            Command::Generate(generate) => {
+                let seed = generate.seed();
+                let tasks = generate.tasks(seed, Utc::now());
+                match &generate.output {
+                    Some(path) => {
+                        let exporter = format::exporter(&generate.format).map_err(ExportError::from)?;
+                        let mut file = BufWriter::new(File::create(path).map_err(ExportError::from)?);
+                        exporter.write_tasks(&tasks, &mut file)?;
+                        file.flush().map_err(ExportError::from)?;
+                        println!("Wrote {} tasks to {} (seed {seed})", tasks.len(), path.display());
+                    }
+                    None => {
+                        {
+                            let _section = interrupt::critical_section();
+                            store.insert_batch(&tasks)?;
+                        }
+                        println!("Added {} tasks (seed {seed})", tasks.len());
+                    }
+                }
+            }
+            Command::Export { format } => {
+                let exporter = format::exporter(&format).map_err(ExportError::from)?;
+                exporter.write_tasks(&store.tasks()?, &mut stdout().lock())?;
+            }
+            Command::Show { task_name } => match store.get(&task_name)? {
+                Some(task) => Self::show(&task),
+                None => println!("Task not found"),
+            },
+            Command::List { sort, mine } => {
+                let mut tasks = store.tasks()?;
+                if mine {
+                    tasks = Self::mine(user)?.filter(tasks.iter())?.into_iter().cloned().collect();
+                }
+                if !sort.is_name_order() {
+                    sort.sort(&mut tasks, Utc::now());
+                }
+                println!("{}", Table::new(&tasks).with(Style::modern_rounded()));
+            }
+            Command::Pick(Pick { query, apply: None, .. }) => {
+                let mut tasks = store.tasks()?;
+                SortSpec::default().sort(&mut tasks, Utc::now());
+                let tasks = match &query {
+                    Some(predicate) => {
+                        predicate.validate::<Task>().map_err(EvaluationError::from)?;
+                        predicate.filter(tasks.iter())?
+                    }
+                    None => tasks.iter().collect(),
+                };
+                for task in tasks {
+                    println!("{}", pick::line(task));
+                }
+            }
+            Command::Pick(Pick { apply: Some(action), lines, .. }) => {
+                let tasks = store.tasks()?;
+                for line in lines.iter().filter(|line| !line.trim().is_empty()) {
+                    let Some(task) = pick::find(&tasks, line) else {
+                        println!("Task not found: {line}");
+                        continue;
+                    };
+                    match action {
+                        PickAction::Done => {
+                            store.done(&task.name)?;
+                        }
+                        PickAction::Delete => {
+                            store.delete(&task.name)?;
+                        }
+                        PickAction::Show => Self::show(task),
+                    }
+                }
+            }
             Command::Done { task_name } => {
-                let is_updated = storage.update(&task_name, |task| task.status = Status::On)?;
-                if !is_updated {
+                if !store.done(&task_name)? {
                     println!("Task not found");
                 }
             }
             Command::Update { task_name } => {
-                let task = storage.get(&task_name)?;
+                let task = store.get(&task_name)?;
                 if let Some(task) = task {
                     let updated_task = Self::interactive_update(task)?;
-                    let prev_task = storage.insert(&updated_task.name, &updated_task)?;
-                    if updated_task.name != task_name {
-                        storage.delete(&task_name)?;
-                        if let Some(prev_task) = prev_task {
-                            println!("Replaced task: \n{prev_task}")
-                        }
+                    if let Some(prev_task) = store.update(&task_name, &updated_task)? {
+                        println!("Replaced task: \n{prev_task}")
                     }
                 } else {
                     println!("Task not found");
                 }
             }
             Command::Delete { task_name } => {
-                if let None = storage.delete(&task_name)?{
+                if store.delete(&task_name)?.is_none() {
                     println!("Task not found");
                 }
             }
-            Command::Select(query) => {
-                let result_set = storage.select(query.0)?;
-                println!("{result_set}");
+            Command::Select(Select { mut query, mine, sort, output }) => {
+                if mine {
+                    query.and_where(Self::mine(user)?);
+                }
+                let tasks = match &query.from {
+                    None => store.candidates(&query)?,
+                    Some(list) => store::list_tasks(backend.local().ok_or(CommandError::LocalOnly)?, Some(list))?,
+                };
+                let mut result_set = query.execute(tasks.iter())?;
+                if let Some((column, _)) = sort.iter().find(|(column, _)| !result_set.columns().any(|name| name == column)) {
+                    return Err(CommandError::NoColumn(column.clone()));
+                }
+                result_set.sort_by_columns(sort.iter().map(|(column, order)| (column.as_str(), *order)));
+                match output {
+                    Output::Table => pager::print(&result_set).map_err(CommandError::Pager)?,
+                    Output::Json => {
+                        result_set.write_json(stdout().lock())?;
+                        println!();
+                    }
+                    Output::Markdown => print!("{}", result_set.to_markdown()),
+                }
+            }
+            Command::QueryHelp => println!("{}", SyntaxHelp::new(&Task::field_names())),
+            Command::Notify(Notify { channel, webhook, within, dry_run, filter }) => {
+                let mut tasks = store.tasks()?;
+                if let Some(predicate) = filter.predicate() {
+                    tasks = predicate.filter(tasks.iter())?.into_iter().cloned().collect();
+                }
+                let digest = Digest::collect(tasks, Utc::now(), TimeDelta::days(within));
+                if digest.is_empty() {
+                    println!("Nothing to notify about");
+                } else if dry_run {
+                    println!("{digest}");
+                } else {
+                    channel.post(webhook.as_deref().ok_or(CommandError::NoWebhook)?, &digest)?;
+                }
+            }
+            command => {
+                let storage = backend.local().ok_or(CommandError::LocalOnly)?;
+                command.run_local(storage, validator)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns predicate, that tests that the task is owned by or assigned to the `user`.
+    fn mine(user: Option<&str>) -> Result<Predicate, CommandError> {
+        let user = user.ok_or(CommandError::NoUser)?;
+        let user = || Literal::String(user.to_string());
+
+        Ok(Predicate::eq("owner", user()).or(Predicate::eq("assignee", user())))
+    }
+
+    /// Print the task with its external references.
+    fn show(task: &Task) {
+        println!("{task}");
+        for external_ref in &task.external_refs {
+            println!("• {external_ref} {}", external_ref.url);
+        }
+    }
+
+    /// Runs the command, that manages the local `storage`.
+    fn run_local(self, storage: &Storage<Task>, validator: &TaskValidator) -> Result<(), CommandError> {
+        let history = storage.open_tree::<HistoryEntry>(HISTORY_TREE)?;
+
+        match self {
+            Command::Archive(policy) => {
+                for action in policy.run(storage, Utc::now())? {
+                    println!("{action}");
+                }
+            }
+            Command::Category(CategoryCommand::Rename { old, new }) => {
+                if let Some(error) = validator.validate_category(&new) {
+                    return Err(CommandError::InvalidCategory(error));
+                }
+                println!("Renamed category of {} tasks", category::rename(storage, &old, &new)?);
+            }
+            Command::Dedupe(dedupe) => dedupe.run(storage)?,
+            Command::Undo => match JournalEntry::undo(storage, &storage.open_tree(JOURNAL_TREE)?)? {
+                Some(entry) => println!("Undone: {} ({} tasks)", entry.description, entry.prev_tasks.len()),
+                None => println!("Nothing to undo"),
+            },
+            Command::Status {
+                #[cfg(feature = "self-update")]
+                check_update,
+            } => {
+                println!("{}", StatusReport::collect(storage, Path::new(TODO_FILE_STORAGE))?);
+                #[cfg(feature = "self-update")]
+                if check_update {
+                    match crate::status::check_update()? {
+                        Some(latest) => println!("Update available: {latest}. Run 'cargo install --git https://github.com/Rastler3D/todo-list.git'"),
+                        None => println!("Up to date"),
+                    }
+                }
+            }
+            Command::Rekey { strategy } => {
+                let _section = interrupt::critical_section();
+                println!("Re-keyed {} tasks with {strategy} keys", key::rekey(storage, strategy)?);
+            }
+            Command::Diff(diff) => match diff.run(storage, Utc::now())? {
+                None => println!("No previous results of the query. Current results are kept for the next run"),
+                Some(changes) if changes.is_empty() => println!("No changes"),
+                Some(changes) => changes.iter().for_each(|change| println!("{change}")),
+            },
+            Command::History { query } => {
+                let result_set = history.select(query)?;
+                pager::print(&result_set).map_err(CommandError::Pager)?;
+            }
+            Command::Serve(serve) => serve.run(storage)?,
+            Command::View(command) => {
+                let views = storage.open_tree::<View>(VIEWS_TREE)?;
+                match command {
+                    ViewCommand::Save { name, query } => View::new(&name, &query)?.save(&views)?,
+                    ViewCommand::List => {
+                        let result_set = views.select(Query {
+                            fields_projection: FieldsProjection(Vec::from([Field::Asterisk])),
+                            from: None,
+                            predicate: None,
+                            group_by: Vec::new(),
+                            collation: None,
+                        })?;
+                        println!("{result_set}");
+                    }
+                    ViewCommand::Show { name } => match views.get(&name)? {
+                        Some(view) => {
+                            let query = view.parse()?;
+                            let tasks = store::list_tasks(storage, query.from.as_ref())?;
+                            println!("{}", query.execute(tasks.iter())?);
+                        }
+                        None => println!("View not found"),
+                    },
+                    ViewCommand::Delete { name } => {
+                        if views.delete(&name)?.is_none() {
+                            println!("View not found");
+                        }
+                    }
+                }
             }
+            Command::Share { view, expires_in, base_url } => {
+                let Some(view) = storage.open_tree::<View>(VIEWS_TREE)?.get(&view)? else {
+                    println!("View not found");
+                    return Ok(());
+                };
+                let expires = expires_in
+                    .map(|days| TimeDelta::try_days(days).and_then(|days| Utc::now().checked_add_signed(days)))
+                    .map(|expires| expires.ok_or(CommandError::OutOfRange))
+                    .transpose()?;
+                let token = ShareKey::load_or_create(storage)?.mint(&ShareClaims::new(&view, expires));
+                println!("{}/shared/{token}", base_url.trim_end_matches('/'));
+            }
+            Command::Report(command) => {
+                let reports = storage.open_tree::<Report>(REPORTS_TREE)?;
+                match command {
+                    ReportCommand::Save { name, view, query, format, output, webhook, channel } => {
+                        let source = match view {
+                            Some(view) => ReportSource::View(view),
+                            None => ReportSource::Query(Query::from_str(&format!("SELECT {}", query.join(" ")))?.to_string()),
+                        };
+                        let destination = match (output, webhook, channel) {
+                            (Some(path), _, _) => Destination::File(path),
+                            (_, Some(url), Some(channel)) => Destination::Webhook { channel, url },
+                            _ => Destination::Stdout,
+                        };
+                        Report { name, source, format, destination }.save(&reports)?;
+                    }
+                    ReportCommand::List => {
+                        let result_set = reports.select(Query::from_str("SELECT *")?)?;
+                        println!("{result_set}");
+                    }
+                    ReportCommand::Run { name: Some(name), filter } => match reports.get(&name)? {
+                        Some(report) => report.run(storage, filter.predicate().as_ref())?,
+                        None => println!("Report not found"),
+                    },
+                    ReportCommand::Run { name: None, filter } => {
+                        let filter = filter.predicate();
+                        let mut failed = 0;
+                        for entry in reports.iter() {
+                            let (_, report) = entry?;
+                            if let Err(err) = report.run(storage, filter.as_ref()) {
+                                eprintln!("Report '{}' failed. {err}", report.name);
+                                failed += 1;
+                            }
+                        }
+                        if failed > 0 {
+                            return Err(ReportError::Failed(failed).into());
+                        }
+                    }
+                    ReportCommand::Delete { name } => {
+                        if reports.delete(&name)?.is_none() {
+                            println!("Report not found");
+                        }
+                    }
+                }
+            }
+            Command::Audit { query } => {
+                let result_set = storage.open_tree::<AuditEntry>(AUDIT_TREE)?.select(query)?;
+                pager::print(&result_set).map_err(CommandError::Pager)?;
+            }
+            command => unreachable!("{command:?} is run against any backend"),
         }
 
         Ok(())
@@ -82,10 +405,20 @@ impl Command {
             .with_validator(ValueRequiredValidator::new("This field is required"))
             .with_default(&task.category)
             .prompt()?;
-        task.status = Select::new("Status: ", Vec::from([Status::On, Status::Off]))
+        task.status = inquire::Select::new("Status: ", Vec::from([Status::On, Status::Off]))
             .with_starting_cursor(if task.status == Status::On { 0 } else { 1 })
             .prompt()?;
 
+        task.owner = Some(Text::new("Owner: ")
+            .with_default(task.owner.as_deref().unwrap_or_default())
+            .prompt()?)
+            .filter(|owner| !owner.is_empty());
+
+        task.assignee = Some(Text::new("Assignee: ")
+            .with_default(task.assignee.as_deref().unwrap_or_default())
+            .prompt()?)
+            .filter(|assignee| !assignee.is_empty());
+
         Ok(task)
     }
 }
@@ -98,11 +431,75 @@ pub enum CommandError {
     #[error("Failed to execute query. {0}")]
     QueryEvaluation(#[from] EvaluationError),
     #[error("Failed to read line. \nReason: {0}")]
-    Readline(#[from] InquireError)
+    Readline(#[from] InquireError),
+    #[error("Failed to add tasks. {0}")]
+    Import(#[from] ImportError),
+    #[error(transparent)]
+    Export(#[from] ExportError),
+    #[error("Failed to notify. {0}")]
+    Notify(#[from] NotifyError),
+    #[error("Server failed. {0}")]
+    Server(#[from] ServerError),
+    #[error("Failed to read/write task. {0}")]
+    Store(#[from] StoreError),
+    #[error("Command is not available with remote storage")]
+    LocalOnly,
+    #[error("Invalid query. {0}")]
+    Query(#[from] ParseError),
+    #[error("Failed to share view. {0}")]
+    Share(#[from] ShareError),
+    #[error(transparent)]
+    Key(#[from] KeyError),
+    #[error(transparent)]
+    Diff(#[from] DiffError),
+    #[error("Report failed. {0}")]
+    Report(#[from] ReportError),
+    #[error(transparent)]
+    Interrupt(#[from] InterruptError),
+    #[error(transparent)]
+    Status(#[from] StatusError),
+    #[error("Failed to rename category. {0}")]
+    InvalidCategory(FieldError),
+    #[error("Date is out-of-range")]
+    OutOfRange,
+    #[error("{0}")]
+    DateFormat(String),
+    #[error(transparent)]
+    ComputedField(#[from] ComputedFieldError),
+    #[error("Failed to read stdin. \nReason: {0}")]
+    Stdin(#[from] std::io::Error),
+    #[error("Failed to write query result. \nReason: {0}")]
+    Output(#[from] serde_json::Error),
+    #[error("Failed to page query result. \nReason: {0}")]
+    Pager(std::io::Error),
+    #[error("Query result has no column '{0}' to sort by")]
+    NoColumn(String),
+    #[error("Current user is not specified. Use '--user' option or 'TODO_LIST_USER' environment variable")]
+    NoUser,
+    #[error("Webhook is not specified. Use '--webhook' option or 'TODO_LIST_WEBHOOK' environment variable")]
+    NoWebhook,
 }
 
 impl Debug for CommandError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         Display::fmt(self, f)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use crate::task::tests::task;
+    use super::*;
+
+    #[test]
+    fn mine_tasks() {
+        let owned = Task { owner: Some("alice".to_string()), ..task("owned", "2024-12-12 20:20", Status::Off) };
+        let assigned = Task { assignee: Some("alice".to_string()), ..task("assigned", "2024-12-12 20:20", Status::Off) };
+        let other = Task { owner: Some("bob".to_string()), ..task("other", "2024-12-12 20:20", Status::Off) };
+        let tasks = [owned.clone(), assigned.clone(), other, task("nobody", "2024-12-12 20:20", Status::Off)];
+
+        let mine = Command::mine(Some("alice")).unwrap().filter(tasks.iter()).unwrap();
+
+        assert_eq!(mine, [&owned, &assigned]);
+        assert!(matches!(Command::mine(None), Err(CommandError::NoUser)));
+    }
+}
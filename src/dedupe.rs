@@ -0,0 +1,224 @@
+use std::iter::once;
+use clap::{Args, ValueEnum};
+use inquire::{Confirm, InquireError, Select};
+use tabled::builder::Builder;
+use tabled::settings::Style;
+use tabled::Table;
+use crate::command::CommandError;
+use crate::history::{Action, HistoryEntry, HISTORY_TREE};
+use crate::journal::{JournalEntry, JOURNAL_TREE};
+use crate::key::TaskKeys;
+use crate::query::evaluator::validate::edit_distance;
+use crate::query::reflect::Reflectable;
+use crate::storage::Storage;
+use crate::store::StoreError;
+use crate::task::Task;
+
+/// Find likely duplicate tasks and show them side by side. With `merge` each group of duplicates is interactively
+/// merged into one task.
+#[derive(Debug, Clone, Args, PartialEq)]
+pub struct Dedupe {
+    #[arg(long, value_enum, default_value = "name", help = "Tasks are duplicates if they have similar names, or similar names and the same due date")]
+    pub by: DedupeKey,
+    #[arg(long, help = "Interactively merge fields of the duplicates into one task")]
+    pub merge: bool,
+}
+
+/// Possible keys of the duplicates.
+///
+/// * `DedupeKey::Name` - similar names;
+/// * `DedupeKey::NameDate` - similar names and the same due date;
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq)]
+pub enum DedupeKey {
+    Name,
+    #[value(name = "name+date")]
+    NameDate,
+}
+
+impl Dedupe {
+    /// Show duplicates of the tasks in the `storage`, merging them if requested.
+    ///
+    /// Merged task replaces its duplicates atomically. Merge is recorded to the journal, so it is undone by `undo`.
+    pub fn run(&self, storage: &Storage<Task>) -> Result<(), CommandError> {
+        let tasks = storage
+            .iter()
+            .map(|entry| entry.map(|(_, task)| task))
+            .collect::<Result<Vec<_>, _>>()?;
+        let groups = duplicates(&tasks, self.by);
+        if groups.is_empty() {
+            println!("No duplicates found");
+            return Ok(());
+        }
+
+        for group in groups {
+            println!("{}", side_by_side(&group));
+            if !self.merge || !Confirm::new("Merge these tasks?").with_default(false).prompt()? {
+                continue;
+            }
+            let merged = merge(&group, |field, values| {
+                Select::new(&format!("{field}: "), values.to_vec()).raw_prompt().map(|value| value.index)
+            })?;
+            let removed = group.iter().map(|task| &task.name).filter(|name| **name != merged.name);
+            let keys = TaskKeys::load(storage)?;
+            let key = |name: &str| keys.get(name).ok_or_else(|| StoreError::NoTask(name.to_string()));
+            let removed_keys = removed.clone().map(|name| key(name)).collect::<Result<Vec<_>, _>>()?;
+            storage.apply_batch(once((key(&merged.name)?, &merged)), removed_keys)?;
+
+            let history = storage.open_tree(HISTORY_TREE)?;
+            HistoryEntry::record(&history, Action::Update, &merged.name)?;
+            for name in removed {
+                HistoryEntry::record(&history, Action::Delete, name)?;
+            }
+            JournalEntry::record(
+                &storage.open_tree(JOURNAL_TREE)?,
+                format!("merge duplicates into '{}'", merged.name),
+                group.iter().map(|&task| task.clone()).collect(),
+            )?;
+            println!("Merged {} tasks into '{}'", group.len(), merged.name);
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns groups of the duplicate `tasks`.
+///
+/// Names are similar if they are equal ignoring case, punctuation and whitespace, or differ by one edit
+/// per four characters, e.g. a typo. Similarity is transitive, so a group may contain less similar names.
+pub fn duplicates(tasks: &[Task], by: DedupeKey) -> Vec<Vec<&Task>> {
+    let names = tasks.iter().map(|task| normalize(&task.name)).collect::<Vec<_>>();
+    let is_duplicate = |left: usize, right: usize| {
+        let max_len = names[left].chars().count().max(names[right].chars().count());
+        let is_similar = edit_distance(&names[left], &names[right]) * 4 <= max_len;
+        match by {
+            DedupeKey::Name => is_similar,
+            DedupeKey::NameDate => is_similar && tasks[left].date == tasks[right].date,
+        }
+    };
+
+    // Every task is assigned to the group of its first duplicate.
+    let mut group_of = (0..tasks.len()).collect::<Vec<_>>();
+    for right in 0..tasks.len() {
+        for left in 0..right {
+            if is_duplicate(left, right) {
+                let (from, to) = (group_of[right], group_of[left]);
+                group_of.iter_mut().filter(|group| **group == from).for_each(|group| *group = to);
+            }
+        }
+    }
+
+    (0..tasks.len())
+        .map(|group| (0..tasks.len()).filter(|idx| group_of[*idx] == group).map(|idx| &tasks[idx]).collect::<Vec<_>>())
+        .filter(|group| group.len() > 1)
+        .collect()
+}
+
+/// Merge `group` of the duplicates into one task. Value of the field, that differs between the duplicates, is taken
+/// from the duplicate, which index is returned by `choose` for the field name and the values. External references
+/// of all duplicates are kept.
+pub fn merge(
+    group: &[&Task],
+    mut choose: impl FnMut(&str, &[String]) -> Result<usize, InquireError>,
+) -> Result<Task, InquireError> {
+    let mut merged = group[0].clone();
+    for field in Task::field_names().iter().filter(|field| *field != "external_refs") {
+        let values = group
+            .iter()
+            .map(|task| task.get_field(field).map(|value| value.to_string()).unwrap_or_default())
+            .collect::<Vec<_>>();
+        if values.iter().all(|value| *value == values[0]) {
+            continue;
+        }
+        let source = group[choose(field, &values)?];
+        match &**field {
+            "name" => merged.name = source.name.clone(),
+            "description" => merged.description = source.description.clone(),
+            "date" => merged.date = source.date,
+            "category" => merged.category = source.category.clone(),
+            "status" => merged.status = source.status,
+            "owner" => merged.owner = source.owner.clone(),
+            "assignee" => merged.assignee = source.assignee.clone(),
+            _ => {}
+        }
+    }
+    for task in &group[1..] {
+        for external_ref in &task.external_refs {
+            if !merged.external_refs.iter().any(|merged_ref| merged_ref.same_as(external_ref)) {
+                merged.external_refs.push(external_ref.clone());
+            }
+        }
+    }
+
+    Ok(merged)
+}
+
+/// Returns the name in lower case with words separated by single space and punctuation removed.
+fn normalize(name: &str) -> String {
+    name.to_lowercase()
+        .split(|char: char| !char.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Returns table of the fields, where every duplicate is a column.
+fn side_by_side(group: &[&Task]) -> Table {
+    let mut table = Builder::new();
+    for field in Task::field_names().iter() {
+        let values = group.iter().map(|task| task.get_field(field).map(|value| value.to_string()).unwrap_or_default());
+        table.push_record(once(field.to_string()).chain(values));
+    }
+
+    table.build().with(Style::modern_rounded()).to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDateTime, TimeDelta};
+    use crate::task::{ExternalRef, Status};
+    use super::*;
+
+    #[test]
+    fn find_and_merge_duplicates() {
+        let date = NaiveDateTime::parse_from_str("2024-12-12 20:20", "%Y-%m-%d %H:%M").unwrap().and_utc();
+        let task = |name: &str, days: i64, external_id: Option<&str>| Task {
+            name: name.to_string(),
+            description: format!("{name} description"),
+            date: date + TimeDelta::days(days),
+            category: "category".to_string(),
+            status: Status::Off,
+            owner: None,
+            assignee: None,
+            external_refs: external_id.into_iter().map(|id| ExternalRef {
+                source: "github".to_string(),
+                id: id.to_string(),
+                url: format!("https://github.com/{id}"),
+            }).collect(),
+        };
+        let tasks = [
+            task("Buy milk", 0, Some("1")),
+            task("Call mom", 0, None),
+            task("buy milk!", 1, Some("2")),
+            task("Buy mlik", 0, None),
+            task("Buy bread", 0, None),
+        ];
+        let names = |groups: Vec<Vec<&Task>>| groups
+            .into_iter()
+            .map(|group| group.into_iter().map(|task| task.name.clone()).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        assert_eq!(names(duplicates(&tasks, DedupeKey::Name)), [["Buy milk", "buy milk!", "Buy mlik"]]);
+        assert_eq!(names(duplicates(&tasks, DedupeKey::NameDate)), [["Buy milk", "Buy mlik"]]);
+
+        let mut asked = Vec::new();
+        let merged = merge(&[&tasks[0], &tasks[2]], |field, values| {
+            asked.push(field.to_string());
+            Ok(values.len() - 1)
+        }).unwrap();
+
+        assert_eq!(asked, ["name", "description", "date"]);
+        assert_eq!(merged.name, "buy milk!");
+        assert_eq!(merged.date, tasks[2].date);
+        assert_eq!(merged.external_refs.len(), 2);
+    }
+}
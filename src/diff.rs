@@ -0,0 +1,244 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use chrono::{DateTime, TimeDelta, Utc};
+use clap::Args;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use crate::query::evaluator::value::conversion::parse_duration;
+use crate::query::{EvaluationError, Query, ResultSet};
+use crate::storage::{Storage, StorageError};
+use crate::store::{list_tasks, StoreError};
+use crate::task::Task;
+
+/// Name of the storage tree, where results of the previous `diff` runs are kept.
+pub const RUNS_TREE: &str = "runs";
+
+/// Name of the storage tree, where named snapshots of the query results are kept.
+pub const SNAPSHOTS_TREE: &str = "snapshots";
+
+/// Compare current results of the query with the results of the previous run, and print added, removed and changed
+/// rows. Every run is kept, so the next run is compared with it.
+#[derive(Debug, Clone, Args, PartialEq)]
+pub struct Diff {
+    #[arg(long, value_parser = Query::from_str, default_value = "SELECT *", help = "Query over tasks, e.g. \"SELECT name, status WHERE category = 'work'\"")]
+    pub query: Query,
+    #[arg(long, value_name = "SNAPSHOT|DURATION", help = "Compare with the snapshot, or with the latest run at least DURATION ago, e.g. '1 day'. Previous run by default")]
+    pub since: Option<Since>,
+    #[arg(long, value_name = "SNAPSHOT", help = "Save current results as the named snapshot")]
+    pub save: Option<String>,
+}
+
+/// Results, that current results are compared with.
+///
+/// * `Since::Snapshot` - named snapshot;
+/// * `Since::Duration` - latest run of the same query at least the duration ago;
+#[derive(Debug, Clone, PartialEq)]
+pub enum Since {
+    Snapshot(String),
+    Duration(TimeDelta),
+}
+
+impl FromStr for Since {
+    type Err = String;
+
+    fn from_str(since: &str) -> Result<Self, Self::Err> {
+        Ok(parse_duration(since).map(Since::Duration).unwrap_or_else(|_| Since::Snapshot(since.to_string())))
+    }
+}
+
+/// Stored results of the query.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Snapshot {
+    pub time: DateTime<Utc>,
+    pub query: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Snapshot {
+    /// Create snapshot of the `result_set` of the `query` at the moment `time`.
+    pub fn new(query: &Query, result_set: &ResultSet, time: DateTime<Utc>) -> Snapshot {
+        Snapshot {
+            time,
+            query: query.to_string(),
+            columns: result_set.columns().map(ToString::to_string).collect(),
+            rows: result_set.rows().map(|row| row.iter().map(ToString::to_string).collect()).collect(),
+        }
+    }
+
+    /// Returns the index of the column, that identifies rows: `name` if it is selected, the first column otherwise.
+    fn key_column(&self) -> usize {
+        self.columns.iter().position(|column| column == "name").unwrap_or(0)
+    }
+}
+
+/// Change of the row between two snapshots.
+///
+/// * `RowChange::Added` - row is present only in the current snapshot;
+/// * `RowChange::Removed` - row is present only in the previous snapshot;
+/// * `RowChange::Changed` - row with the same key has different values of the listed columns;
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowChange {
+    Added(Vec<String>),
+    Removed(Vec<String>),
+    Changed {
+        key: String,
+        columns: Vec<(String, String, String)>,
+    },
+}
+
+/// Returns changes of the rows from the `previous` snapshot to the `current` one.
+///
+/// Rows are matched by the key column of the current snapshot. Columns, that are not present in both snapshots,
+/// are not compared.
+pub fn diff(previous: &Snapshot, current: &Snapshot) -> Vec<RowChange> {
+    let key_column = current.key_column();
+    let previous_key_column = previous.columns.iter().position(|column| *column == current.columns[key_column]);
+    let previous_row = |key: &str| {
+        previous_key_column.and_then(|idx| previous.rows.iter().find(|row| row[idx] == key))
+    };
+    let mut changes = Vec::new();
+
+    for row in &current.rows {
+        let Some(previous_row) = previous_row(&row[key_column]) else {
+            changes.push(RowChange::Added(row.clone()));
+            continue;
+        };
+        let columns = current
+            .columns
+            .iter()
+            .zip(row)
+            .filter_map(|(column, value)| {
+                let idx = previous.columns.iter().position(|previous_column| previous_column == column)?;
+                (previous_row[idx] != *value).then(|| (column.clone(), previous_row[idx].clone(), value.clone()))
+            })
+            .collect::<Vec<_>>();
+        if !columns.is_empty() {
+            changes.push(RowChange::Changed { key: row[key_column].clone(), columns });
+        }
+    }
+    for row in &previous.rows {
+        let is_removed = previous_key_column
+            .is_none_or(|idx| !current.rows.iter().any(|current_row| current_row[key_column] == row[idx]));
+        if is_removed {
+            changes.push(RowChange::Removed(row.clone()));
+        }
+    }
+
+    changes
+}
+
+impl Display for RowChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RowChange::Added(row) => write!(f, "+ {}", row.join(" | ")),
+            RowChange::Removed(row) => write!(f, "- {}", row.join(" | ")),
+            RowChange::Changed { key, columns } => {
+                let columns = columns
+                    .iter()
+                    .map(|(column, previous, current)| format!("{column}: '{previous}' -> '{current}'"))
+                    .collect::<Vec<_>>();
+                write!(f, "~ {key}: {}", columns.join(", "))
+            }
+        }
+    }
+}
+
+impl Diff {
+    /// Compare results of the query over tasks of the `storage` at the moment `now` with the previous results.
+    /// Returns the changes or `None` if there are no previous results of the query.
+    pub fn run(&self, storage: &Storage<Task>, now: DateTime<Utc>) -> Result<Option<Vec<RowChange>>, DiffError> {
+        let tasks = list_tasks(storage, self.query.from.as_ref())?;
+        let current = Snapshot::new(&self.query, &self.query.clone().execute(tasks.iter())?, now);
+        let runs = storage.open_tree::<Snapshot>(RUNS_TREE)?;
+        let snapshots = storage.open_tree::<Snapshot>(SNAPSHOTS_TREE)?;
+
+        let previous = match &self.since {
+            Some(Since::Snapshot(name)) => Some(snapshots.get(name)?.ok_or_else(|| DiffError::NoSnapshot(name.clone()))?),
+            since => {
+                let before = match since {
+                    Some(Since::Duration(duration)) => now - *duration,
+                    _ => now,
+                };
+                let mut previous = None;
+                for entry in runs.iter() {
+                    let (_, run) = entry?;
+                    if run.query == current.query && run.time <= before {
+                        previous = Some(run);
+                    }
+                }
+                previous
+            }
+        };
+
+        runs.insert(runs.generate_id()?.to_be_bytes(), &current)?;
+        if let Some(name) = &self.save {
+            snapshots.insert(name, &current)?;
+        }
+
+        Ok(previous.map(|previous| diff(&previous, &current)))
+    }
+}
+
+/// Represents possible errors of the query results diff.
+#[derive(Error, Debug)]
+pub enum DiffError {
+    #[error("Failed to read/write results. \nReason: {0}")]
+    Storage(#[from] StorageError),
+    #[error(transparent)]
+    Store(#[from] StoreError),
+    #[error("Failed to execute query. {0}")]
+    QueryEvaluation(#[from] EvaluationError),
+    #[error("Snapshot '{0}' not found")]
+    NoSnapshot(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+    use tempfile::tempdir;
+    use crate::task::Status;
+    use super::*;
+
+    #[test]
+    fn diff_runs() {
+        let tempdir = tempdir().unwrap();
+        let storage = Storage::<Task>::open(&tempdir).unwrap();
+        let now = NaiveDateTime::parse_from_str("2024-12-12 20:20", "%Y-%m-%d %H:%M").unwrap().and_utc();
+        let task = |name: &str, status: Status| Task {
+            name: name.to_string(),
+            description: "description".to_string(),
+            date: now,
+            category: "work".to_string(),
+            status,
+            owner: None,
+            assignee: None,
+            external_refs: Vec::new(),
+        };
+        let insert = |task: Task| storage.insert(&task.name, &task).unwrap();
+        let command = |since: Option<&str>, save: Option<&str>| Diff {
+            query: Query::from_str("SELECT name, status").unwrap(),
+            since: since.map(|since| Since::from_str(since).unwrap()),
+            save: save.map(ToString::to_string),
+        };
+        let changes = |changes: Vec<RowChange>| changes.iter().map(ToString::to_string).collect::<Vec<_>>();
+        insert(task("first", Status::Off));
+        insert(task("second", Status::Off));
+
+        assert_eq!(command(None, Some("standup")).run(&storage, now).unwrap(), None);
+
+        storage.delete("second").unwrap();
+        insert(task("first", Status::On));
+        insert(task("third", Status::Off));
+
+        assert_eq!(changes(command(None, None).run(&storage, now + TimeDelta::hours(1)).unwrap().unwrap()), [
+            "~ first: status: 'off' -> 'on'",
+            "+ third | off",
+            "- second | off",
+        ]);
+        assert!(command(None, None).run(&storage, now + TimeDelta::hours(2)).unwrap().unwrap().is_empty());
+        assert_eq!(command(Some("standup"), None).run(&storage, now + TimeDelta::hours(3)).unwrap().unwrap().len(), 3);
+        assert_eq!(command(Some("3 hours"), None).run(&storage, now + TimeDelta::hours(3)).unwrap().unwrap().len(), 3);
+        assert!(matches!(command(Some("retro"), None).run(&storage, now), Err(DiffError::NoSnapshot(_))));
+    }
+}
@@ -0,0 +1,191 @@
+use std::fmt::{Display, Formatter};
+use crate::query::ast::ParseError;
+use crate::query::EvaluationError;
+use crate::storage::StorageError;
+#[cfg(feature = "cli")]
+use crate::{command::CommandError, diff::DiffError, key::KeyError, report::ReportError, status::StatusError, store::StoreError};
+
+/// Underlying error, that is available through [`std::error::Error::source`].
+pub type Source = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Result of the library operations.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Error of the library operations.
+///
+/// Variants are stable categories of the failures, so downstream code matches on them without depending on the
+/// errors of the storage, the query language or the HTTP client. The underlying error is the source of the
+/// category and is used for the details, so the error prints as `<category>: <source>`. New categories may be added.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Storage could not be opened, read or written.
+    Storage(Source),
+    /// Query or statement could not be parsed or evaluated.
+    Query(Source),
+    /// Task does not satisfy the validation rules.
+    InvalidTask(Source),
+    /// Requested list, view, snapshot or report does not exist.
+    NotFound(Source),
+    /// Tasks could not be imported or exported in the format.
+    Format(Source),
+    /// Request to the todo-list server, the webhook or the external source failed.
+    Network(Source),
+    /// Input or output failed.
+    Io(Source),
+    /// Operation is not possible with the given options, e.g. with the remote storage.
+    Usage(Source),
+}
+
+impl Error {
+    /// Returns the underlying error.
+    pub fn source_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+        match self {
+            Error::Storage(source)
+            | Error::Query(source)
+            | Error::InvalidTask(source)
+            | Error::NotFound(source)
+            | Error::Format(source)
+            | Error::Network(source)
+            | Error::Io(source)
+            | Error::Usage(source) => &**source,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let category = match self {
+            Error::Storage(_) => "Storage error",
+            Error::Query(_) => "Query error",
+            Error::InvalidTask(_) => "Invalid task",
+            Error::NotFound(_) => "Not found",
+            Error::Format(_) => "Format error",
+            Error::Network(_) => "Network error",
+            Error::Io(_) => "I/O error",
+            Error::Usage(_) => "Usage error",
+        };
+
+        write!(f, "{category}: {}", self.source_error())
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source_error())
+    }
+}
+
+/// Constructor of the error category.
+type Category = fn(Source) -> Error;
+
+#[cfg(feature = "cli")]
+fn store_category(err: &StoreError) -> Category {
+    match err {
+        StoreError::Storage(_) | StoreError::Key(_) => Error::Storage,
+        StoreError::Remote(_) => Error::Network,
+        StoreError::Invalid(_) => Error::InvalidTask,
+        StoreError::NoList(_) | StoreError::NoTask(_) => Error::NotFound,
+    }
+}
+
+fn evaluation_category(_: &EvaluationError) -> Category {
+    Error::Query
+}
+
+#[cfg(feature = "cli")]
+fn diff_category(err: &DiffError) -> Category {
+    match err {
+        DiffError::Storage(_) => Error::Storage,
+        DiffError::Store(err) => store_category(err),
+        DiffError::QueryEvaluation(err) => evaluation_category(err),
+        DiffError::NoSnapshot(_) => Error::NotFound,
+    }
+}
+
+#[cfg(feature = "cli")]
+fn report_category(err: &ReportError) -> Category {
+    match err {
+        ReportError::Storage(_) => Error::Storage,
+        ReportError::Store(err) => store_category(err),
+        ReportError::NoView(_) => Error::NotFound,
+        ReportError::Query(_) | ReportError::QueryEvaluation(_) => Error::Query,
+        ReportError::Format(_) | ReportError::Export(_) => Error::Format,
+        ReportError::Io(_) => Error::Io,
+        ReportError::Notify(_) => Error::Network,
+        ReportError::Failed(_) => Error::Usage,
+    }
+}
+
+#[cfg(feature = "cli")]
+fn status_category(err: &StatusError) -> Category {
+    match err {
+        StatusError::Storage(_) => Error::Storage,
+        #[cfg(feature = "self-update")]
+        StatusError::Request(_) | StatusError::Response(_) => Error::Network,
+    }
+}
+
+#[cfg(feature = "cli")]
+fn command_category(err: &CommandError) -> Category {
+    match err {
+        CommandError::Storage(_) | CommandError::Key(_) | CommandError::Server(_) | CommandError::Share(_) => Error::Storage,
+        CommandError::QueryEvaluation(_) | CommandError::Query(_) => Error::Query,
+        CommandError::Readline(_) | CommandError::Stdin(_) | CommandError::Interrupt(_) | CommandError::Output(_) | CommandError::Pager(_) => Error::Io,
+        CommandError::Import(_) | CommandError::Export(_) => Error::Format,
+        CommandError::Notify(_) => Error::Network,
+        CommandError::Store(err) => store_category(err),
+        CommandError::Diff(err) => diff_category(err),
+        CommandError::Report(err) => report_category(err),
+        CommandError::Status(err) => status_category(err),
+        CommandError::InvalidCategory(_) => Error::InvalidTask,
+        CommandError::LocalOnly | CommandError::OutOfRange | CommandError::NoUser | CommandError::NoWebhook | CommandError::NoColumn(_) | CommandError::DateFormat(_) | CommandError::ComputedField(_) => Error::Usage,
+    }
+}
+
+macro_rules! impl_from {
+    ($($error:ty => $category:expr),* $(,)?) => {
+        $(
+            impl From<$error> for Error {
+                fn from(err: $error) -> Self {
+                    let category: Category = $category(&err);
+                    category(Box::new(err))
+                }
+            }
+        )*
+    };
+}
+
+impl_from! {
+    StorageError => |_: &StorageError| Error::Storage,
+    ParseError => |_: &ParseError| Error::Query,
+    EvaluationError => evaluation_category,
+}
+
+#[cfg(feature = "cli")]
+impl_from! {
+    CommandError => command_category,
+    StoreError => store_category,
+    KeyError => |_: &KeyError| Error::Storage,
+    DiffError => diff_category,
+    ReportError => report_category,
+}
+
+#[cfg(all(test, feature = "cli"))]
+mod tests {
+    use std::error::Error as _;
+    use super::*;
+
+    #[test]
+    fn categorize_errors() {
+        let err = Error::from(CommandError::Store(StoreError::NoList("work".to_string())));
+
+        assert!(matches!(err, Error::NotFound(_)));
+        assert_eq!(err.to_string(), "Not found: Failed to read/write task. No list 'work'. Lists are 'tasks' and 'archive'");
+        assert_eq!(err.source().unwrap().to_string(), "Failed to read/write task. No list 'work'. Lists are 'tasks' and 'archive'");
+        assert_eq!(err.source().unwrap().source().unwrap().to_string(), "No list 'work'. Lists are 'tasks' and 'archive'");
+        assert!(matches!(Error::from(CommandError::LocalOnly), Error::Usage(_)));
+        assert_eq!(Error::from(CommandError::LocalOnly).to_string(), format!("Usage error: {}", CommandError::LocalOnly));
+        assert!(err.source_error().downcast_ref::<CommandError>().is_some());
+    }
+}
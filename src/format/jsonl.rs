@@ -0,0 +1,157 @@
+use std::io::{BufRead, Write};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use crate::format::{read_lines, ExportError, Exporter, Importer};
+use crate::import::ImportError;
+use crate::task::{parse_date_time, ExternalRef, Status, Task};
+
+/// One JSON object per line.
+pub struct Jsonl;
+
+/// Task record as it appears in the imported and exported data.
+///
+/// Date is expected in format `%Y-%m-%d %H:%M`, status is one of the `on` or `off`.
+#[derive(Serialize, Deserialize)]
+struct TaskRecord {
+    name: String,
+    description: String,
+    #[serde(serialize_with = "serialize_date_time", deserialize_with = "deserialize_date_time")]
+    date: DateTime<Utc>,
+    category: String,
+    #[serde(serialize_with = "serialize_status", deserialize_with = "deserialize_status")]
+    status: Status,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    owner: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    assignee: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    external_refs: Vec<ExternalRef>,
+}
+
+impl Importer for Jsonl {
+    fn name(&self) -> &'static str {
+        "jsonl"
+    }
+
+    fn read_tasks(&self, reader: &mut dyn BufRead) -> Result<Vec<Task>, ImportError> {
+        read_lines(reader, |line| {
+            let record = serde_json::from_str::<TaskRecord>(line).map_err(|err| err.to_string())?;
+            for (field, value) in [("name", &record.name), ("description", &record.description), ("category", &record.category)] {
+                if value.trim().is_empty() {
+                    return Err(format!("Field '{field}' is required"));
+                }
+            }
+
+            Ok(Task::from(record))
+        })
+    }
+}
+
+impl Exporter for Jsonl {
+    fn name(&self) -> &'static str {
+        "jsonl"
+    }
+
+    fn write_tasks(&self, tasks: &[Task], writer: &mut dyn Write) -> Result<(), ExportError> {
+        for task in tasks {
+            let line = serde_json::to_string(&TaskRecord::from(task.clone()))
+                .map_err(|err| ExportError::Serialize(err.to_string()))?;
+            writeln!(writer, "{line}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<TaskRecord> for Task {
+    fn from(record: TaskRecord) -> Self {
+        Task {
+            name: record.name,
+            description: record.description,
+            date: record.date,
+            category: record.category,
+            status: record.status,
+            owner: record.owner,
+            assignee: record.assignee,
+            external_refs: record.external_refs,
+        }
+    }
+}
+
+impl From<Task> for TaskRecord {
+    fn from(task: Task) -> Self {
+        TaskRecord {
+            name: task.name,
+            description: task.description,
+            date: task.date,
+            category: task.category,
+            status: task.status,
+            owner: task.owner,
+            assignee: task.assignee,
+            external_refs: task.external_refs,
+        }
+    }
+}
+
+fn serialize_date_time<S: Serializer>(date: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&date.format("%Y-%m-%d %H:%M"))
+}
+
+fn deserialize_date_time<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<Utc>, D::Error> {
+    let date = String::deserialize(deserializer)?;
+
+    parse_date_time(&date).map_err(serde::de::Error::custom)
+}
+
+fn serialize_status<S: Serializer>(status: &Status, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(status)
+}
+
+fn deserialize_status<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Status, D::Error> {
+    let status = String::deserialize(deserializer)?;
+
+    status.parse().map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_jsonl() {
+        let input = r#"
+            {"name": "first", "description": "description", "date": "2024-10-10 20:10", "category": "work", "status": "on"}
+            {"name": "second", "description": "description", "date": "2024-10-11 20:10", "category": "home", "status": "Off"}
+        "#;
+
+        let tasks = Jsonl.read_tasks(&mut input.as_bytes()).unwrap();
+
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].name, "first");
+        assert_eq!(tasks[1].status, Status::Off);
+    }
+
+    #[test]
+    fn invalid_lines() {
+        let input = [
+            r#"{"name": "first", "description": "description", "date": "2024-10-10 20:10", "category": "work", "status": "on"}"#,
+            r#"{"name": "second", "description": "description", "date": "10.10.2024", "category": "work", "status": "on"}"#,
+            r#"{"name": "", "description": "description", "date": "2024-10-10 20:10", "category": "work", "status": "on"}"#,
+        ].join("\n");
+
+        let result = Jsonl.read_tasks(&mut input.as_bytes());
+
+        assert!(matches!(result, Err(ImportError::InvalidLines(errors)) if errors.iter().map(|err| err.line).eq([2, 3])));
+    }
+
+    #[test]
+    fn write_jsonl() {
+        let input = r#"{"name":"first","description":"description","date":"2024-10-10 20:10","category":"work","status":"on","owner":"alice"}"#;
+        let tasks = Jsonl.read_tasks(&mut input.as_bytes()).unwrap();
+
+        let mut output = Vec::new();
+        Jsonl.write_tasks(&tasks, &mut output).unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), format!("{input}\n"));
+    }
+}
@@ -0,0 +1,118 @@
+use std::io::{BufRead, Write};
+use clap::builder::PossibleValuesParser;
+use thiserror::Error;
+use crate::import::{ImportError, LineError};
+use crate::task::Task;
+
+mod jsonl;
+mod org;
+
+pub use jsonl::Jsonl;
+pub use org::Org;
+
+/// Reader of tasks in specific format.
+pub trait Importer: Sync {
+    /// Name of the format, that is used as `--format` value.
+    fn name(&self) -> &'static str;
+    /// Read tasks from `reader`.
+    fn read_tasks(&self, reader: &mut dyn BufRead) -> Result<Vec<Task>, ImportError>;
+}
+
+/// Writer of tasks in specific format.
+pub trait Exporter: Sync {
+    /// Name of the format, that is used as `--format` value.
+    fn name(&self) -> &'static str;
+    /// Write `tasks` to `writer`.
+    fn write_tasks(&self, tasks: &[Task], writer: &mut dyn Write) -> Result<(), ExportError>;
+}
+
+/// Registered importers. New formats are added here, possibly behind a feature flag.
+pub static IMPORTERS: &[&dyn Importer] = &[&Jsonl, &Org];
+
+/// Registered exporters. New formats are added here, possibly behind a feature flag.
+pub static EXPORTERS: &[&dyn Exporter] = &[&Jsonl, &Org];
+
+/// Returns the importer of the format `name`.
+pub fn importer(name: &str) -> Result<&'static dyn Importer, FormatError> {
+    IMPORTERS
+        .iter()
+        .copied()
+        .find(|importer| importer.name() == name)
+        .ok_or_else(|| FormatError(name.to_string()))
+}
+
+/// Returns the exporter of the format `name`.
+pub fn exporter(name: &str) -> Result<&'static dyn Exporter, FormatError> {
+    EXPORTERS
+        .iter()
+        .copied()
+        .find(|exporter| exporter.name() == name)
+        .ok_or_else(|| FormatError(name.to_string()))
+}
+
+/// Parser of `--format` value, that accepts names of the registered importers.
+pub fn importer_names() -> PossibleValuesParser {
+    PossibleValuesParser::new(IMPORTERS.iter().map(|importer| importer.name()))
+}
+
+/// Parser of `--format` value, that accepts names of the registered exporters.
+pub fn exporter_names() -> PossibleValuesParser {
+    PossibleValuesParser::new(EXPORTERS.iter().map(|exporter| exporter.name()))
+}
+
+/// Read line-oriented input, parsing every non-blank line with `parse_line`.
+///
+/// All lines are read and validated. If some of them are invalid, the errors of all invalid lines will be returned.
+pub fn read_lines(
+    reader: &mut dyn BufRead,
+    parse_line: impl Fn(&str) -> Result<Task, String>,
+) -> Result<Vec<Task>, ImportError> {
+    let mut tasks = Vec::new();
+    let mut errors = Vec::new();
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line.map_err(ImportError::Io)?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse_line(&line) {
+            Ok(task) => tasks.push(task),
+            Err(reason) => errors.push(LineError { line: idx + 1, reason }),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(tasks)
+    } else {
+        Err(ImportError::InvalidLines(errors))
+    }
+}
+
+/// Format is not registered.
+#[derive(Error, Debug)]
+#[error("Unknown format '{0}'")]
+pub struct FormatError(pub String);
+
+/// Represents possible errors of tasks export.
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error("Failed to write output. \nReason: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to serialize task. \nReason: {0}")]
+    Serialize(String),
+    #[error(transparent)]
+    Format(#[from] FormatError),
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::builder::TypedValueParser;
+    use super::*;
+
+    #[test]
+    fn registry() {
+        assert_eq!(importer("jsonl").unwrap().name(), "jsonl");
+        assert_eq!(exporter("jsonl").unwrap().name(), "jsonl");
+        assert!(importer("opml").is_err());
+        assert!(importer_names().parse_ref(&clap::Command::new(""), None, "jsonl".as_ref()).is_ok());
+    }
+}
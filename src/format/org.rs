@@ -0,0 +1,344 @@
+use std::io::{BufRead, Write};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use crate::format::{ExportError, Exporter, Importer};
+use crate::import::{ImportError, LineError};
+use crate::task::{ExternalRef, Status, Task};
+
+/// Emacs org-mode TODO headings.
+///
+/// ```org
+/// * TODO Task name :category:
+///   DEADLINE: <2024-10-10 Thu 20:10>
+///   :PROPERTIES:
+///   :OWNER: alice
+///   :END:
+///   Description
+/// ```
+///
+/// Heading keyword is the task status, the first tag (own or inherited from the parent headings) - the task category,
+/// `DEADLINE` or, if there is none, `SCHEDULED` timestamp - the task date. Headings without TODO keyword are skipped.
+/// Task without description is described by its name.
+pub struct Org;
+
+const TODO_KEYWORDS: &[&str] = &["TODO", "NEXT", "WAITING"];
+const DONE_KEYWORDS: &[&str] = &["DONE", "CANCELLED"];
+
+/// Heading of the org outline.
+struct Heading {
+    level: usize,
+    status: Option<Status>,
+    title: String,
+    tags: Vec<String>,
+}
+
+/// TODO heading with its section.
+struct Entry {
+    line: usize,
+    status: Status,
+    name: String,
+    category: Option<String>,
+    deadline: Option<DateTime<Utc>>,
+    scheduled: Option<DateTime<Utc>>,
+    properties: Vec<(String, String)>,
+    description: Vec<String>,
+    in_drawer: bool,
+    errors: Vec<String>,
+}
+
+impl Importer for Org {
+    fn name(&self) -> &'static str {
+        "org"
+    }
+
+    fn read_tasks(&self, reader: &mut dyn BufRead) -> Result<Vec<Task>, ImportError> {
+        let mut tasks = Vec::new();
+        let mut errors = Vec::new();
+        let mut finish = |entry: Entry| match entry.into_task() {
+            Ok(task) => tasks.push(task),
+            Err(reason) => errors.push(reason),
+        };
+        let mut parent_tags: Vec<(usize, Vec<String>)> = Vec::new();
+        let mut entry = None;
+        for (idx, line) in reader.lines().enumerate() {
+            let line = line.map_err(ImportError::Io)?;
+            if let Some(heading) = Heading::parse(&line) {
+                if let Some(entry) = entry.take() {
+                    finish(entry);
+                }
+                parent_tags.retain(|(level, _)| *level < heading.level);
+                let category = heading
+                    .tags
+                    .first()
+                    .or_else(|| parent_tags.iter().rev().find_map(|(_, tags)| tags.first()))
+                    .cloned();
+                entry = heading.status.map(|status| Entry::new(idx + 1, status, heading.title, category));
+                parent_tags.push((heading.level, heading.tags));
+            } else if let Some(entry) = &mut entry {
+                entry.read_line(line.trim());
+            }
+        }
+        if let Some(entry) = entry {
+            finish(entry);
+        }
+
+        if errors.is_empty() {
+            Ok(tasks)
+        } else {
+            Err(ImportError::InvalidLines(errors))
+        }
+    }
+}
+
+impl Exporter for Org {
+    fn name(&self) -> &'static str {
+        "org"
+    }
+
+    fn write_tasks(&self, tasks: &[Task], writer: &mut dyn Write) -> Result<(), ExportError> {
+        for task in tasks {
+            let keyword = match task.status {
+                Status::On => "DONE",
+                Status::Off => "TODO",
+            };
+            let tag = task.category.replace(|char: char| !(char.is_alphanumeric() || "_@#%".contains(char)), "_");
+            writeln!(writer, "* {keyword} {} :{tag}:", task.name)?;
+            writeln!(writer, "  DEADLINE: <{}>", task.date.format("%Y-%m-%d %a %H:%M"))?;
+
+            let properties = task
+                .owner
+                .iter()
+                .map(|owner| format!(":OWNER: {owner}"))
+                .chain(task.assignee.iter().map(|assignee| format!(":ASSIGNEE: {assignee}")))
+                .chain(task.external_refs.iter().map(|external_ref| {
+                    format!(":EXTERNAL_REF: {} {} {}", external_ref.source, external_ref.id, external_ref.url)
+                }))
+                .collect::<Vec<_>>();
+            if !properties.is_empty() {
+                writeln!(writer, "  :PROPERTIES:")?;
+                for property in properties {
+                    writeln!(writer, "  {property}")?;
+                }
+                writeln!(writer, "  :END:")?;
+            }
+
+            for line in task.description.lines() {
+                writeln!(writer, "  {line}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Heading {
+    /// Parse heading line: `** TODO Title :tag1:tag2:`. Returns `None` if the line is not a heading.
+    fn parse(line: &str) -> Option<Heading> {
+        let title = line.trim_start_matches('*');
+        let level = line.len() - title.len();
+        let title = title.strip_prefix(' ').filter(|_| level > 0)?.trim();
+
+        let (keyword, rest) = title.split_once(' ').unwrap_or((title, ""));
+        let (status, title) = if TODO_KEYWORDS.contains(&keyword) {
+            (Some(Status::Off), rest.trim())
+        } else if DONE_KEYWORDS.contains(&keyword) {
+            (Some(Status::On), rest.trim())
+        } else {
+            (None, title)
+        };
+        let title = title
+            .strip_prefix("[#")
+            .and_then(|title| title.split_once(']'))
+            .map_or(title, |(_, title)| title.trim());
+
+        let (title, tags) = match title.rsplit_once(' ') {
+            Some((title, tags)) if tags.len() > 1 && tags.starts_with(':') && tags.ends_with(':') => (
+                title.trim(),
+                tags.split(':').filter(|tag| !tag.is_empty()).map(ToString::to_string).collect(),
+            ),
+            _ => (title, Vec::new()),
+        };
+
+        Some(Heading {
+            level,
+            status,
+            title: title.to_string(),
+            tags,
+        })
+    }
+}
+
+impl Entry {
+    fn new(line: usize, status: Status, name: String, category: Option<String>) -> Entry {
+        Entry {
+            line,
+            status,
+            name,
+            category,
+            deadline: None,
+            scheduled: None,
+            properties: Vec::new(),
+            description: Vec::new(),
+            in_drawer: false,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Read trimmed line of the entry section: planning, property drawer or description.
+    fn read_line(&mut self, line: &str) {
+        if self.in_drawer {
+            if line.eq_ignore_ascii_case(":END:") {
+                self.in_drawer = false;
+            } else if let Some((key, value)) = line.strip_prefix(':').and_then(|line| line.split_once(':')) {
+                self.properties.push((key.to_uppercase(), value.trim().to_string()));
+            }
+        } else if line.eq_ignore_ascii_case(":PROPERTIES:") {
+            self.in_drawer = true;
+        } else if ["DEADLINE:", "SCHEDULED:", "CLOSED:"].iter().any(|keyword| line.starts_with(keyword)) {
+            self.deadline = self.deadline.or(self.planning(line, "DEADLINE:"));
+            self.scheduled = self.scheduled.or(self.planning(line, "SCHEDULED:"));
+        } else if !line.is_empty() || !self.description.is_empty() {
+            self.description.push(line.to_string());
+        }
+    }
+
+    /// Read timestamp of the planning `keyword` from the planning `line`.
+    fn planning(&mut self, line: &str, keyword: &str) -> Option<DateTime<Utc>> {
+        let (_, timestamp) = line.split_once(keyword)?;
+        let timestamp = timestamp.trim_start().strip_prefix(['<', '['])?;
+        let timestamp = &timestamp[..timestamp.find(['>', ']'])?];
+        let date_time = parse_timestamp(timestamp);
+        if date_time.is_none() {
+            self.errors.push(format!("Invalid timestamp '{timestamp}'"));
+        }
+
+        date_time
+    }
+
+    fn into_task(self) -> Result<Task, LineError> {
+        let error = |reason: String| LineError { line: self.line, reason };
+        if let Some(reason) = self.errors.first() {
+            return Err(error(reason.clone()));
+        }
+        let date = self
+            .deadline
+            .or(self.scheduled)
+            .ok_or_else(|| error("Heading has no DEADLINE or SCHEDULED timestamp".to_string()))?;
+        let category = self
+            .category
+            .clone()
+            .ok_or_else(|| error("Heading has no tag, that is used as the task category".to_string()))?;
+        if self.name.is_empty() {
+            return Err(error("Heading has no title".to_string()));
+        }
+
+        let property = |key: &str| {
+            self.properties.iter().find(|(property, _)| property == key).map(|(_, value)| value.clone())
+        };
+        let external_refs = self
+            .properties
+            .iter()
+            .filter(|(property, _)| property == "EXTERNAL_REF")
+            .filter_map(|(_, value)| {
+                let mut parts = value.splitn(3, ' ');
+                Some(ExternalRef {
+                    source: parts.next()?.to_string(),
+                    id: parts.next()?.to_string(),
+                    url: parts.next().unwrap_or_default().to_string(),
+                })
+            })
+            .collect();
+        let description = self.description.join("\n").trim().to_string();
+
+        Ok(Task {
+            description: if description.is_empty() { self.name.clone() } else { description },
+            name: self.name.clone(),
+            date,
+            category,
+            status: self.status,
+            owner: property("OWNER"),
+            assignee: property("ASSIGNEE"),
+            external_refs,
+        })
+    }
+}
+
+/// Parse org timestamp without brackets: `2024-10-10 Thu 20:10`. Date without time is at midnight.
+/// Time ranges and repeaters are ignored.
+fn parse_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    let mut parts = timestamp.split_whitespace();
+    let date = NaiveDate::parse_from_str(parts.next()?, "%Y-%m-%d").ok()?;
+    let time = match parts.find(|part| part.contains(':')) {
+        Some(time) => NaiveTime::parse_from_str(time.split('-').next()?, "%H:%M").ok()?,
+        None => NaiveTime::MIN,
+    };
+
+    Some(date.and_time(time).and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_org() {
+        let input = "\
+#+TITLE: Tasks
+* Work :work:
+** TODO [#A] Fix parser :bug:
+   SCHEDULED: <2024-10-09 Wed> DEADLINE: <2024-10-10 Thu 20:10 +1w>
+   :PROPERTIES:
+   :OWNER: alice
+   :END:
+   Parser fails on empty input
+** DONE Release
+   CLOSED: [2024-10-01 Tue 10:00] SCHEDULED: <2024-10-01 Tue>
+** Notes
+   Not a task
+* TODO Untagged
+  DEADLINE: <2024-10-10 Thu>
+* TODO Undated :home:
+";
+
+        let result = Org.read_tasks(&mut input.as_bytes());
+
+        assert!(matches!(result, Err(ImportError::InvalidLines(errors)) if errors.iter().map(|err| err.line).eq([13, 15])));
+
+        let input = input.lines().take(12).collect::<Vec<_>>().join("\n");
+        let tasks = Org.read_tasks(&mut input.as_bytes()).unwrap();
+
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].name, "Fix parser");
+        assert_eq!(tasks[0].category, "bug");
+        assert_eq!(tasks[0].date.to_string(), "2024-10-10 20:10:00 UTC");
+        assert_eq!(tasks[0].owner.as_deref(), Some("alice"));
+        assert_eq!(tasks[0].description, "Parser fails on empty input");
+        assert_eq!(tasks[1].status, Status::On);
+        assert_eq!(tasks[1].category, "work");
+        assert_eq!(tasks[1].date.to_string(), "2024-10-01 00:00:00 UTC");
+        assert_eq!(tasks[1].description, "Release");
+    }
+
+    #[test]
+    fn org_round_trip() {
+        let task = Task {
+            name: "Fix parser".to_string(),
+            description: "Parser fails\non empty input".to_string(),
+            date: parse_timestamp("2024-10-10 20:10").unwrap(),
+            category: "work".to_string(),
+            status: Status::Off,
+            owner: Some("alice".to_string()),
+            assignee: None,
+            external_refs: vec![ExternalRef {
+                source: "github".to_string(),
+                id: "owner/repo#1".to_string(),
+                url: "https://github.com/owner/repo/issues/1".to_string(),
+            }],
+        };
+
+        let mut output = Vec::new();
+        Org.write_tasks(std::slice::from_ref(&task), &mut output).unwrap();
+
+        assert!(String::from_utf8_lossy(&output).starts_with("* TODO Fix parser :work:\n  DEADLINE: <2024-10-10 Thu 20:10>\n"));
+        assert_eq!(Org.read_tasks(&mut output.as_slice()).unwrap(), [task]);
+    }
+}
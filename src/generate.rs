@@ -0,0 +1,133 @@
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use chrono::{DateTime, DurationRound, TimeDelta, Utc};
+use clap::Args;
+use crate::format::exporter_names;
+use crate::task::{Status, Task};
+
+const VERBS: [&str; 16] = [
+    "Write", "Review", "Fix", "Plan", "Call", "Buy", "Update", "Prepare",
+    "Clean", "Book", "Send", "Check", "Finish", "Read", "Schedule", "Organize",
+];
+
+const OBJECTS: [&str; 16] = [
+    "report", "invoice", "presentation", "groceries", "tests", "budget", "meeting notes", "release",
+    "dentist appointment", "flight tickets", "documentation", "backups", "garage", "newsletter", "proposal", "taxes",
+];
+
+/// Categories with their weights. Few categories hold most of the tasks, as in the real lists.
+const CATEGORIES: [(&str, u64); 6] = [("work", 40), ("home", 25), ("shopping", 15), ("health", 10), ("finance", 6), ("travel", 4)];
+
+const USERS: [&str; 4] = ["alice", "bob", "carol", "dave"];
+
+/// Generate random tasks for benchmarks and demos. Tasks with the same `seed` are the same, so big lists of
+/// the performance bug reports can be reproduced.
+#[derive(Debug, Clone, Args, PartialEq)]
+pub struct Generate {
+    #[arg(long, default_value_t = 1000, help = "Number of generated tasks")]
+    pub count: usize,
+    #[arg(long, help = "Seed of the random generator. Random seed if not specified")]
+    pub seed: Option<u64>,
+    #[arg(long, value_name = "FILE", help = "Write tasks to the file instead of the storage")]
+    pub output: Option<PathBuf>,
+    #[arg(long, value_parser = exporter_names(), default_value = "jsonl", requires = "output", help = "Format of the written file")]
+    pub format: String,
+}
+
+impl Generate {
+    /// Returns the seed of the random generator.
+    pub fn seed(&self) -> u64 {
+        self.seed.unwrap_or_else(|| {
+            SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_nanos() as u64).unwrap_or_default()
+        })
+    }
+
+    /// Generate `count` tasks with distinct names, that are dated around `now`.
+    ///
+    /// Dates are spread over the past year and the next three months, the most of them are close to `now`.
+    /// Past tasks are mostly completed, and the future ones are mostly not.
+    pub fn tasks(&self, seed: u64, now: DateTime<Utc>) -> Vec<Task> {
+        let mut rng = Rng(seed);
+        let now = now.duration_trunc(TimeDelta::minutes(1)).unwrap_or(now);
+        let total_weight = CATEGORIES.iter().map(|(_, weight)| weight).sum();
+
+        (0..self.count)
+            .map(|number| {
+                let verb = VERBS[rng.below(VERBS.len() as u64) as usize];
+                let object = OBJECTS[rng.below(OBJECTS.len() as u64) as usize];
+                let category = weighted_category(rng.below(total_weight));
+                // Minimum of two uniform offsets concentrates dates around the current moment.
+                let days = rng.below(365).min(rng.below(365)) as i64;
+                let offset = if rng.below(4) == 0 { (days / 4).min(90) } else { -days };
+                let date = now + TimeDelta::days(offset) + TimeDelta::minutes(rng.below(24 * 4) as i64 * 15);
+                let completed = if date < now { rng.below(10) < 8 } else { rng.below(10) < 1 };
+                let owner = USERS[rng.below(USERS.len() as u64) as usize];
+
+                Task {
+                    name: format!("{verb} {object} #{}", number + 1),
+                    description: format!("{verb} the {object} for {category}"),
+                    date,
+                    category: category.to_string(),
+                    status: if completed { Status::On } else { Status::Off },
+                    owner: Some(owner.to_string()),
+                    assignee: (rng.below(3) == 0).then(|| USERS[rng.below(USERS.len() as u64) as usize].to_string()),
+                    external_refs: Vec::new(),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Returns the category, that the `weight` in `0..total_weight` falls into.
+fn weighted_category(mut weight: u64) -> &'static str {
+    for (category, category_weight) in CATEGORIES {
+        if weight < category_weight {
+            return category;
+        }
+        weight -= category_weight;
+    }
+
+    CATEGORIES[0].0
+}
+
+/// Small `splitmix64` random generator. It is not suitable for cryptography, but it is fast, and its sequence
+/// depends only on the seed.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns random number in `0..bound`.
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use super::*;
+
+    #[test]
+    fn generate_tasks() {
+        let generate = Generate { count: 500, seed: Some(7), output: None, format: "jsonl".to_string() };
+        let now = Utc::now();
+
+        let tasks = generate.tasks(7, now);
+        assert_eq!(tasks.len(), 500);
+        assert_eq!(tasks, generate.tasks(7, now));
+        assert_ne!(tasks, generate.tasks(8, now));
+        assert_eq!(tasks.iter().map(|task| &task.name).collect::<HashSet<_>>().len(), 500);
+        assert!(tasks.iter().all(|task| task.date > now - TimeDelta::days(366) && task.date < now + TimeDelta::days(92)));
+        assert!(tasks.iter().any(|task| task.status == Status::Off) && tasks.iter().any(|task| task.status == Status::On));
+        let work = tasks.iter().filter(|task| task.category == "work").count();
+        let travel = tasks.iter().filter(|task| task.category == "travel").count();
+        assert!(work > travel);
+    }
+}
@@ -0,0 +1,200 @@
+use chrono::{DateTime, Utc};
+use clap::Args;
+use serde::Deserialize;
+use thiserror::Error;
+use crate::task::{ExternalRef, Status, Task};
+
+const PAGE_SIZE: usize = 100;
+
+/// Import open issues of GitHub repository as tasks.
+///
+/// Issue title becomes the task name, milestone due date - the task date. Issues without due date are dated by creation.
+/// Task category is the first label of the issue, that is not used as a filter, or the repository name.
+/// Issues, that were imported before, update their tasks.
+#[derive(Debug, Clone, Args, PartialEq)]
+pub struct GithubImport {
+    #[arg(long, value_name = "OWNER/NAME", value_parser = parse_repo, help = "Repository to import issues from")]
+    pub repo: String,
+    #[arg(long, value_name = "LABEL", help = "Import only issues with the label. May be repeated")]
+    pub label: Vec<String>,
+    #[arg(long, env = "GITHUB_TOKEN", hide_env_values = true, help = "GitHub access token, required for private repositories")]
+    pub token: Option<String>,
+    #[arg(long, value_name = "URL", default_value = "https://api.github.com", help = "GitHub API URL")]
+    pub api_url: String,
+}
+
+/// Issue as it is returned by GitHub REST API.
+#[derive(Debug, Deserialize)]
+pub struct Issue {
+    pub number: u64,
+    pub title: String,
+    pub body: Option<String>,
+    pub html_url: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub labels: Vec<Label>,
+    pub milestone: Option<Milestone>,
+    pub assignee: Option<User>,
+    pub user: Option<User>,
+    pub pull_request: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Label {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Milestone {
+    pub due_on: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct User {
+    pub login: String,
+}
+
+impl GithubImport {
+    /// Fetch open issues of the repository and map them to tasks. Pull requests are skipped.
+    pub fn fetch(&self) -> Result<Vec<Task>, GithubError> {
+        let agent = ureq::Agent::new();
+        let mut tasks = Vec::new();
+        for page in 1.. {
+            let mut request = agent
+                .get(&format!("{}/repos/{}/issues", self.api_url.trim_end_matches('/'), self.repo))
+                .set("Accept", "application/vnd.github+json")
+                .set("User-Agent", "todo-list")
+                .query("state", "open")
+                .query("per_page", &PAGE_SIZE.to_string())
+                .query("page", &page.to_string());
+            if !self.label.is_empty() {
+                request = request.query("labels", &self.label.join(","));
+            }
+            if let Some(token) = &self.token {
+                request = request.set("Authorization", &format!("Bearer {token}"));
+            }
+
+            let issues: Vec<Issue> = request
+                .call()
+                .map_err(|err| GithubError::Request(Box::new(err)))?
+                .into_json()
+                .map_err(GithubError::Response)?;
+            let is_last = issues.len() < PAGE_SIZE;
+            tasks.extend(issues.into_iter().filter(|issue| issue.pull_request.is_none()).map(|issue| self.to_task(issue)));
+            if is_last {
+                break;
+            }
+        }
+
+        Ok(tasks)
+    }
+
+    /// Map `issue` to the task.
+    pub fn to_task(&self, issue: Issue) -> Task {
+        let repo_name = self.repo.rsplit('/').next().unwrap_or(&self.repo);
+        let category = issue
+            .labels
+            .iter()
+            .map(|label| &label.name)
+            .find(|label| !self.label.contains(label))
+            .map_or(repo_name, |label| label.as_str())
+            .to_string();
+        let description = issue
+            .body
+            .as_deref()
+            .and_then(|body| body.lines().map(str::trim).find(|line| !line.is_empty()))
+            .unwrap_or(&issue.html_url)
+            .to_string();
+
+        Task {
+            name: issue.title,
+            description,
+            date: issue.milestone.and_then(|milestone| milestone.due_on).unwrap_or(issue.created_at),
+            category,
+            status: Status::Off,
+            owner: issue.user.map(|user| user.login),
+            assignee: issue.assignee.map(|user| user.login),
+            external_refs: vec![ExternalRef {
+                source: "github".to_string(),
+                id: format!("{}#{}", self.repo, issue.number),
+                url: issue.html_url,
+            }],
+        }
+    }
+}
+
+/// Parse repository in format `OWNER/NAME`.
+fn parse_repo(repo: &str) -> Result<String, String> {
+    match repo.split_once('/') {
+        Some((owner, name)) if !owner.is_empty() && !name.is_empty() && !name.contains('/') => Ok(repo.to_string()),
+        _ => Err("Repository must be in format 'OWNER/NAME'".to_string()),
+    }
+}
+
+/// Represents possible errors of GitHub import.
+#[derive(Error, Debug)]
+pub enum GithubError {
+    #[error("Request to GitHub failed. \nReason: {0}")]
+    Request(Box<ureq::Error>),
+    #[error("Failed to read GitHub response. \nReason: {0}")]
+    Response(std::io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issue_to_task() {
+        let import = GithubImport {
+            repo: "owner/repo".to_string(),
+            label: vec!["todo".to_string()],
+            token: None,
+            api_url: "https://api.github.com".to_string(),
+        };
+        let issue = serde_json::from_str::<Issue>(r#"{
+            "number": 1,
+            "title": "Fix parser",
+            "body": "\n  Parser fails on empty input\nMore details",
+            "html_url": "https://github.com/owner/repo/issues/1",
+            "created_at": "2024-12-01T10:00:00Z",
+            "labels": [{ "name": "todo" }, { "name": "bug" }],
+            "milestone": { "due_on": "2024-12-12T20:20:00Z" },
+            "assignee": { "login": "bob" },
+            "user": { "login": "alice" }
+        }"#).unwrap();
+
+        let task = import.to_task(issue);
+
+        assert_eq!(task.name, "Fix parser");
+        assert_eq!(task.description, "Parser fails on empty input");
+        assert_eq!(task.date.to_rfc3339(), "2024-12-12T20:20:00+00:00");
+        assert_eq!(task.category, "bug");
+        assert_eq!(task.owner.as_deref(), Some("alice"));
+        assert_eq!(task.assignee.as_deref(), Some("bob"));
+        assert_eq!(task.external_refs, [ExternalRef {
+            source: "github".to_string(),
+            id: "owner/repo#1".to_string(),
+            url: "https://github.com/owner/repo/issues/1".to_string(),
+        }]);
+
+        let issue = serde_json::from_str::<Issue>(r#"{
+            "number": 2,
+            "title": "Release",
+            "body": null,
+            "html_url": "https://github.com/owner/repo/issues/2",
+            "created_at": "2024-12-01T10:00:00Z",
+            "labels": [{ "name": "todo" }],
+            "milestone": null,
+            "assignee": null,
+            "user": null
+        }"#).unwrap();
+
+        let task = import.to_task(issue);
+
+        assert_eq!(task.description, "https://github.com/owner/repo/issues/2");
+        assert_eq!(task.date.to_rfc3339(), "2024-12-01T10:00:00+00:00");
+        assert_eq!(task.category, "repo");
+        assert!(parse_repo("owner").is_err());
+    }
+}
@@ -0,0 +1,112 @@
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::query::reflect::{FieldsIterator, ReflectError, Reflectable, ToValue, Value};
+use crate::storage::{Storage, StorageError};
+
+/// Name of the storage tree, where history of task changes is kept.
+pub const HISTORY_TREE: &str = "history";
+
+/// Represents entry of the task changes history.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HistoryEntry {
+    pub time: DateTime<Utc>,
+    pub action: Action,
+    pub task: String,
+}
+
+/// Possible actions on the task.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum Action {
+    Add,
+    Done,
+    Update,
+    Delete,
+    Archive,
+    Purge,
+}
+
+impl HistoryEntry {
+    /// Record entry about `action` on the task with name `task` to the `history`.
+    pub fn record(history: &Storage<HistoryEntry>, action: Action, task: &str) -> Result<(), StorageError> {
+        let entry = HistoryEntry {
+            time: Utc::now(),
+            action,
+            task: task.to_string(),
+        };
+        history.insert(history.generate_id()?.to_be_bytes(), &entry)?;
+
+        Ok(())
+    }
+}
+
+/// Reflectable implementation to be able to use history entries in select queries.
+impl Reflectable for HistoryEntry {
+    fn get_field(&self, field: &str) -> Result<Value<'_>, ReflectError> {
+        let value = match field {
+            "time" => self.time.to_value(),
+            "action" => Value::String(self.action.to_string().into()),
+            "task" => self.task.to_value(),
+            field => return Err(ReflectError::NoField(field.to_string())),
+        };
+
+        Ok(value)
+    }
+
+    fn fields(&self) -> FieldsIterator<'_> {
+        Box::new([
+            ("time".into(), self.time.to_value()),
+            ("action".into(), Value::String(self.action.to_string().into())),
+            ("task".into(), self.task.to_value()),
+        ].into_iter())
+    }
+
+    fn field_names() -> Cow<'static, [Cow<'static, str>]> {
+        (&[Cow::Borrowed("time"), Cow::Borrowed("action"), Cow::Borrowed("task")]).into()
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            Action::Add => "add",
+            Action::Done => "done",
+            Action::Update => "update",
+            Action::Delete => "delete",
+            Action::Archive => "archive",
+            Action::Purge => "purge",
+        };
+
+        Display::fmt(value, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use tempfile::tempdir;
+    use crate::query::Query;
+    use super::*;
+
+    #[test]
+    fn select_history() {
+        let tempdir = tempdir().unwrap();
+        let history = Storage::<HistoryEntry>::open(&tempdir).unwrap();
+
+        HistoryEntry::record(&history, Action::Add, "first").unwrap();
+        HistoryEntry::record(&history, Action::Add, "second").unwrap();
+        HistoryEntry::record(&history, Action::Delete, "first").unwrap();
+        HistoryEntry::record(&history, Action::Done, "second").unwrap();
+
+        let result_set = history
+            .select(Query::from_str("SELECT task, action WHERE action = 'delete' OR task = 'second'").unwrap())
+            .unwrap();
+
+        assert!(result_set.rows().eq([
+            [Value::String("second".into()), Value::String("add".into())],
+            [Value::String("first".into()), Value::String("delete".into())],
+            [Value::String("second".into()), Value::String("done".into())],
+        ]));
+    }
+}
@@ -0,0 +1,40 @@
+use std::fmt::{Display, Formatter};
+use clap::Subcommand;
+use thiserror::Error;
+use crate::format::FormatError;
+use crate::github::{GithubError, GithubImport};
+
+/// Possible sources of imported tasks.
+///
+/// * `ImportSource::Github` - open issues of GitHub repository;
+#[derive(Debug, Subcommand, PartialEq)]
+pub enum ImportSource {
+    #[command(about = "Import open issues of GitHub repository", long_about = None)]
+    Github(GithubImport),
+}
+
+/// Error of the specific line of imported data.
+#[derive(Debug)]
+pub struct LineError {
+    pub line: usize,
+    pub reason: String,
+}
+
+/// Represents possible errors of tasks import.
+#[derive(Error, Debug)]
+pub enum ImportError {
+    #[error("Failed to read input. \nReason: {0}")]
+    Io(std::io::Error),
+    #[error("Input contains invalid tasks. Nothing was imported. \n{}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n"))]
+    InvalidLines(Vec<LineError>),
+    #[error(transparent)]
+    Github(#[from] GithubError),
+    #[error(transparent)]
+    Format(#[from] FormatError),
+}
+
+impl Display for LineError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.reason)
+    }
+}
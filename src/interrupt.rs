@@ -0,0 +1,117 @@
+use std::sync::Mutex;
+use thiserror::Error;
+use crate::query::evaluator::cancel::CancellationToken;
+use crate::storage::Storage;
+use crate::task::Task;
+
+/// Exit code of the process, that is interrupted by Ctrl-C.
+pub const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// State of the Ctrl-C handler.
+struct State {
+    /// Number of the running critical sections.
+    critical_sections: usize,
+    /// Ctrl-C is received during the critical section.
+    interrupted: bool,
+    /// Token of the running query, that is cancelled by Ctrl-C instead of the exit.
+    query: Option<CancellationToken>,
+}
+
+static STATE: Mutex<State> = Mutex::new(State { critical_sections: 0, interrupted: false, query: None });
+
+/// Storage, that is flushed before the process exits on Ctrl-C. It is held only until the [`Installed`] guard is
+/// dropped, so the storage is closed and flushed, when the command returns.
+static STORAGE: Mutex<Option<Storage<Task>>> = Mutex::new(None);
+
+/// Install the Ctrl-C handler, that flushes the `storage` and exits the process.
+///
+/// Ctrl-C received during a [`CriticalSection`] is deferred until the section ends, so the multistep changes, e.g.
+/// rename of the task, import or migration, are applied completely, and the process exits right after.
+///
+/// Storage is released, when the returned guard is dropped.
+pub fn install(storage: Option<&Storage<Task>>) -> Result<Installed, InterruptError> {
+    *STORAGE.lock().unwrap_or_else(|err| err.into_inner()) = storage.cloned();
+    let handler = ctrlc::set_handler(|| {
+        let mut state = STATE.lock().unwrap_or_else(|err| err.into_inner());
+        if state.critical_sections == 0 {
+            match state.query.as_ref().filter(|token| !token.is_cancelled()) {
+                Some(token) => {
+                    eprintln!("Interrupted. Cancelling current query...");
+                    return token.cancel();
+                }
+                None => exit(),
+            }
+        }
+        eprintln!("Interrupted. Finishing current changes...");
+        state.interrupted = true;
+    });
+
+    match handler {
+        // Handler is already installed, e.g. by the previous run in the same process.
+        Ok(()) | Err(ctrlc::Error::MultipleHandlers) => Ok(Installed(())),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Flush the storage and exit the process with [`INTERRUPTED_EXIT_CODE`].
+fn exit() -> ! {
+    if let Some(storage) = &*STORAGE.lock().unwrap_or_else(|err| err.into_inner()) {
+        if let Err(err) = storage.flush() {
+            eprintln!("{err}");
+        }
+    }
+    std::process::exit(INTERRUPTED_EXIT_CODE)
+}
+
+/// Guard of the installed Ctrl-C handler, that releases the storage, when dropped.
+pub struct Installed(());
+
+impl Drop for Installed {
+    fn drop(&mut self) {
+        STORAGE.lock().unwrap_or_else(|err| err.into_inner()).take();
+    }
+}
+
+/// Guard of the changes, that must not be interrupted in the middle. Ctrl-C received while any section is running
+/// exits the process, when the last section is dropped.
+pub struct CriticalSection(());
+
+/// Enter the critical section, that lasts until the returned guard is dropped.
+pub fn critical_section() -> CriticalSection {
+    STATE.lock().unwrap_or_else(|err| err.into_inner()).critical_sections += 1;
+    CriticalSection(())
+}
+
+impl Drop for CriticalSection {
+    fn drop(&mut self) {
+        let mut state = STATE.lock().unwrap_or_else(|err| err.into_inner());
+        state.critical_sections -= 1;
+        if state.critical_sections == 0 && state.interrupted {
+            exit();
+        }
+    }
+}
+
+/// Guard of the query, that is cancelled by Ctrl-C, instead of the exit of the process. Next Ctrl-C exits the
+/// process as usual.
+pub struct CancelOnInterrupt(Option<CancellationToken>);
+
+/// Cancel the `token` on Ctrl-C, until the returned guard is dropped, e.g. while the REPL runs the command.
+pub fn cancel_on_interrupt(token: &CancellationToken) -> CancelOnInterrupt {
+    let mut state = STATE.lock().unwrap_or_else(|err| err.into_inner());
+    CancelOnInterrupt(state.query.replace(token.clone()))
+}
+
+impl Drop for CancelOnInterrupt {
+    fn drop(&mut self) {
+        STATE.lock().unwrap_or_else(|err| err.into_inner()).query = self.0.take();
+    }
+}
+
+/// Represents possible errors of the Ctrl-C handler installation.
+#[derive(Error, Debug)]
+pub enum InterruptError {
+    #[error("Failed to install Ctrl-C handler. \nReason: {0}")]
+    Handler(#[from] ctrlc::Error),
+}
+
@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::history::{Action, HistoryEntry, HISTORY_TREE};
+use crate::key::{KeyError, TaskKeys};
+use crate::storage::{Storage, StorageError};
+use crate::task::Task;
+
+/// Name of the storage tree, where journal of undoable batch changes is kept.
+pub const JOURNAL_TREE: &str = "journal";
+
+/// Represents entry of the journal: batch change of the tasks along with the tasks before the change.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JournalEntry {
+    pub time: DateTime<Utc>,
+    pub description: String,
+    pub prev_tasks: Vec<Task>,
+}
+
+impl JournalEntry {
+    /// Record entry about the change with `description` of the `prev_tasks` to the `journal`.
+    pub fn record(journal: &Storage<JournalEntry>, description: String, prev_tasks: Vec<Task>) -> Result<(), StorageError> {
+        let entry = JournalEntry {
+            time: Utc::now(),
+            description,
+            prev_tasks,
+        };
+        journal.insert(journal.generate_id()?.to_be_bytes(), &entry)?;
+
+        Ok(())
+    }
+
+    /// Undo the latest change of the `journal`: restore tasks of the `storage` as they were before the change.
+    /// Returns the undone entry or `None` if there is nothing to undo.
+    ///
+    /// Tasks are restored atomically, overwriting later changes of the same tasks.
+    pub fn undo(storage: &Storage<Task>, journal: &Storage<JournalEntry>) -> Result<Option<JournalEntry>, KeyError> {
+        let Some(entry) = journal.pop_last()? else {
+            return Ok(None);
+        };
+        let mut keys = TaskKeys::load(storage)?;
+        let items = entry
+            .prev_tasks
+            .iter()
+            .map(|task| Ok((keys.get_or_generate(storage, &task.name)?, task)))
+            .collect::<Result<Vec<_>, KeyError>>()?;
+        storage.insert_batch(items)?;
+        let history = storage.open_tree(HISTORY_TREE)?;
+        for task in &entry.prev_tasks {
+            HistoryEntry::record(&history, Action::Update, &task.name)?;
+        }
+
+        Ok(Some(entry))
+    }
+}
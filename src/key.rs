@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use chrono::Utc;
+use clap::ValueEnum;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use sled::IVec;
+use thiserror::Error;
+use crate::retention::ARCHIVE_TREE;
+use crate::storage::{self, Storage, StorageBatch, StorageError};
+use crate::task::Task;
+
+/// Name of the storage tree, where the key strategy of the storage is kept.
+pub const KEY_STRATEGY_TREE: &str = "key_strategy";
+
+const STRATEGY_KEY: &str = "strategy";
+
+/// Scheme of the keys, that tasks are stored by.
+///
+/// * `KeyStrategy::Name` - task name. Keys are readable, but renaming the task changes its key;
+/// * `KeyStrategy::Ulid` - ULID, that is unique and sorted by creation time;
+/// * `KeyStrategy::Uuid` - random UUID, that is unique across storages, e.g. when they are synchronized;
+/// * `KeyStrategy::Int` - incrementing integer;
+///
+/// Tasks are addressed by name with any strategy. Storage without the strategy uses name keys.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ValueEnum, PartialEq)]
+pub enum KeyStrategy {
+    #[default]
+    Name,
+    Ulid,
+    Uuid,
+    Int,
+}
+
+impl KeyStrategy {
+    /// Returns the key strategy of the `storage`.
+    pub fn load(storage: &Storage<Task>) -> Result<KeyStrategy, StorageError> {
+        Ok(storage.open_tree::<KeyStrategy>(KEY_STRATEGY_TREE)?.get(STRATEGY_KEY)?.unwrap_or_default())
+    }
+
+    /// Check, that the `storage` uses the `configured` strategy. Empty storage takes the configured strategy.
+    pub fn configure(storage: &Storage<Task>, configured: KeyStrategy) -> Result<(), KeyError> {
+        let strategies = storage.open_tree::<KeyStrategy>(KEY_STRATEGY_TREE)?;
+        match strategies.get(STRATEGY_KEY)? {
+            None if storage.iter().next().is_none() => {
+                strategies.insert(STRATEGY_KEY, &configured)?;
+                Ok(())
+            }
+            stored => match stored.unwrap_or_default() {
+                stored if stored == configured => Ok(()),
+                stored => Err(KeyError::Mismatch { stored, configured }),
+            },
+        }
+    }
+
+    /// Generate the key of the task with `name`.
+    fn generate(self, storage: &Storage<Task>, name: &str) -> Result<Vec<u8>, KeyError> {
+        let key = match self {
+            KeyStrategy::Name => name.as_bytes().to_vec(),
+            KeyStrategy::Ulid => {
+                let mut ulid = [0u8; 16];
+                ulid[..6].copy_from_slice(&Utc::now().timestamp_millis().to_be_bytes()[2..]);
+                SystemRandom::new().fill(&mut ulid[6..]).map_err(|_| KeyError::Generation)?;
+                crockford_base32(u128::from_be_bytes(ulid)).into_bytes()
+            }
+            KeyStrategy::Uuid => {
+                let mut uuid = [0u8; 16];
+                SystemRandom::new().fill(&mut uuid).map_err(|_| KeyError::Generation)?;
+                // Version 4, variant 1.
+                uuid[6] = (uuid[6] & 0x0f) | 0x40;
+                uuid[8] = (uuid[8] & 0x3f) | 0x80;
+                let hex = uuid.iter().map(|byte| format!("{byte:02x}")).collect::<String>();
+                format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..]).into_bytes()
+            }
+            KeyStrategy::Int => storage.generate_id()?.to_be_bytes().to_vec(),
+        };
+
+        Ok(key)
+    }
+}
+
+/// Encode 128-bit `value` as 26 characters of Crockford's base32.
+fn crockford_base32(value: u128) -> String {
+    const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+    (0..26)
+        .rev()
+        .map(|idx| ALPHABET[((value >> (idx * 5)) & 0x1f) as usize] as char)
+        .collect()
+}
+
+impl Display for KeyStrategy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let value = self.to_possible_value().expect("strategies are not skipped");
+
+        Display::fmt(value.get_name(), f)
+    }
+}
+
+/// Keys of the tasks of the storage by the task names.
+pub struct TaskKeys {
+    strategy: KeyStrategy,
+    keys: HashMap<String, IVec>,
+}
+
+impl TaskKeys {
+    /// Load keys of the tasks of the `storage`. Name keys are not loaded, as they are the names themselves.
+    pub fn load(storage: &Storage<Task>) -> Result<TaskKeys, KeyError> {
+        let strategy = KeyStrategy::load(storage)?;
+        let keys = match strategy {
+            KeyStrategy::Name => HashMap::new(),
+            _ => storage
+                .iter()
+                .map(|entry| entry.map(|(key, task)| (task.name, key)))
+                .collect::<Result<_, _>>()?,
+        };
+
+        Ok(TaskKeys { strategy, keys })
+    }
+
+    /// Returns the key strategy.
+    pub fn strategy(&self) -> KeyStrategy {
+        self.strategy
+    }
+
+    /// Returns the key of the task with `name`, or `None` if there is no such task.
+    ///
+    /// Name key is returned, even if there is no such task.
+    pub fn get(&self, name: &str) -> Option<Vec<u8>> {
+        match self.strategy {
+            KeyStrategy::Name => Some(name.as_bytes().to_vec()),
+            _ => self.keys.get(name).map(|key| key.to_vec()),
+        }
+    }
+
+    /// Returns the key of the task with `name`, generating a new key if there is no such task.
+    pub fn get_or_generate(&mut self, storage: &Storage<Task>, name: &str) -> Result<Vec<u8>, KeyError> {
+        if let Some(key) = self.get(name) {
+            return Ok(key);
+        }
+        let key = self.strategy.generate(storage, name)?;
+        self.keys.insert(name.to_string(), key.as_slice().into());
+
+        Ok(key)
+    }
+
+    /// Returns the key of the task `name` after it is renamed to `new_name`.
+    ///
+    /// Name key changes with the name, other keys are kept.
+    pub fn renamed(&mut self, storage: &Storage<Task>, name: &str, new_name: &str) -> Result<Vec<u8>, KeyError> {
+        match self.strategy {
+            KeyStrategy::Name => Ok(new_name.as_bytes().to_vec()),
+            _ => {
+                let key = self.get_or_generate(storage, name)?;
+                self.keys.remove(name);
+                self.keys.insert(new_name.to_string(), key.as_slice().into());
+                Ok(key)
+            }
+        }
+    }
+}
+
+/// Re-key tasks and archived tasks of the `storage` with the `strategy`. Returns the number of re-keyed tasks.
+///
+/// Tasks, archived tasks and the strategy of the storage are re-keyed in a single transaction.
+pub fn rekey(storage: &Storage<Task>, strategy: KeyStrategy) -> Result<usize, KeyError> {
+    let archive = storage.open_tree(ARCHIVE_TREE)?;
+    let tasks = storage.iter().collect::<Result<Vec<_>, _>>()?;
+    let archived = archive.iter().collect::<Result<Vec<_>, _>>()?;
+    storage::apply_batches(&[
+        rekey_batch(storage, &tasks, storage, strategy)?,
+        rekey_batch(&archive, &archived, storage, strategy)?,
+        storage.open_tree::<KeyStrategy>(KEY_STRATEGY_TREE)?.batch([(STRATEGY_KEY, &strategy)], [])?,
+    ])?;
+
+    Ok(tasks.len() + archived.len())
+}
+
+/// Returns the batch, that re-keys `entries` of the `tree` of the `storage` with the `strategy`.
+fn rekey_batch(tree: &Storage<Task>, entries: &[(IVec, Task)], storage: &Storage<Task>, strategy: KeyStrategy) -> Result<StorageBatch, KeyError> {
+    let keys = entries
+        .iter()
+        .map(|(_, task)| strategy.generate(storage, &task.name))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(tree.batch(
+        keys.iter().zip(entries).map(|(key, (_, task))| (key.as_slice(), task)),
+        entries.iter().map(|(key, _)| &key[..]),
+    )?)
+}
+
+/// Represents possible errors of the task keys.
+#[derive(Error, Debug)]
+pub enum KeyError {
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    #[error("Failed to generate random key")]
+    Generation,
+    #[error("Storage keys tasks by '{stored}', but '{configured}' keys are configured. Run 'todo-list rekey {configured}' to re-key the storage")]
+    Mismatch {
+        stored: KeyStrategy,
+        configured: KeyStrategy,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+    use tempfile::tempdir;
+    use crate::store::TaskStore;
+    use crate::task::Status;
+    use super::*;
+
+    fn task(name: &str) -> Task {
+        Task {
+            name: name.to_string(),
+            description: "description".to_string(),
+            date: NaiveDateTime::parse_from_str("2024-12-12 20:20", "%Y-%m-%d %H:%M").unwrap().and_utc(),
+            category: "category".to_string(),
+            status: Status::Off,
+            owner: None,
+            assignee: None,
+            external_refs: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn generate_keys() {
+        let tempdir = tempdir().unwrap();
+        let storage = Storage::<Task>::open(&tempdir).unwrap();
+
+        let ulid = String::from_utf8(KeyStrategy::Ulid.generate(&storage, "task").unwrap()).unwrap();
+        let uuid = String::from_utf8(KeyStrategy::Uuid.generate(&storage, "task").unwrap()).unwrap();
+
+        assert_eq!(ulid.len(), 26);
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(&uuid[14..15], "4");
+        assert_eq!(KeyStrategy::Name.generate(&storage, "task").unwrap(), b"task");
+    }
+
+    #[test]
+    fn store_and_rekey() {
+        let tempdir = tempdir().unwrap();
+        let storage = Storage::<Task>::open(&tempdir).unwrap();
+        KeyStrategy::configure(&storage, KeyStrategy::Uuid).unwrap();
+        let store: &dyn TaskStore = &storage;
+
+        store.insert_batch(&[task("first"), task("second")]).unwrap();
+        let key = TaskKeys::load(&storage).unwrap().get("first").unwrap();
+        store.update("first", &task("renamed")).unwrap();
+
+        assert_eq!(store.get("renamed").unwrap(), Some(task("renamed")));
+        assert_eq!(store.get("first").unwrap(), None);
+        assert_eq!(TaskKeys::load(&storage).unwrap().get("renamed"), Some(key));
+        assert!(matches!(
+            KeyStrategy::configure(&storage, KeyStrategy::Name),
+            Err(KeyError::Mismatch { stored: KeyStrategy::Uuid, configured: KeyStrategy::Name })
+        ));
+
+        assert_eq!(rekey(&storage, KeyStrategy::Name).unwrap(), 2);
+
+        assert_eq!(Storage::get(&storage, "second").unwrap(), Some(task("second")));
+        assert_eq!(store.delete("renamed").unwrap(), Some(task("renamed")));
+        assert_eq!(store.tasks().unwrap(), [task("second")]);
+        assert!(KeyStrategy::configure(&storage, KeyStrategy::Name).is_ok());
+    }
+}
@@ -0,0 +1,70 @@
+pub mod task;
+pub mod query;
+pub mod storage;
+pub mod error;
+#[cfg(feature = "cli")]
+pub mod cli;
+#[cfg(feature = "cli")]
+pub mod command;
+#[cfg(feature = "cli")]
+pub mod retention;
+#[cfg(feature = "cli")]
+pub mod history;
+#[cfg(feature = "cli")]
+pub mod import;
+#[cfg(feature = "cli")]
+pub mod format;
+#[cfg(feature = "cli")]
+pub mod notify;
+#[cfg(feature = "cli")]
+pub mod calendar;
+#[cfg(feature = "cli")]
+pub mod server;
+#[cfg(feature = "cli")]
+pub mod audit;
+#[cfg(feature = "cli")]
+pub mod store;
+#[cfg(feature = "cli")]
+pub mod remote;
+#[cfg(feature = "cli")]
+pub mod migration;
+#[cfg(feature = "cli")]
+pub mod view;
+#[cfg(feature = "cli")]
+pub mod share;
+#[cfg(feature = "cli")]
+pub mod github;
+#[cfg(feature = "cli")]
+pub mod pick;
+#[cfg(feature = "cli")]
+pub mod sort;
+#[cfg(feature = "cli")]
+pub mod category;
+#[cfg(feature = "cli")]
+pub mod journal;
+#[cfg(feature = "cli")]
+pub mod dedupe;
+#[cfg(feature = "cli")]
+pub mod validator;
+#[cfg(feature = "cli")]
+pub mod key;
+#[cfg(feature = "cli")]
+pub mod diff;
+#[cfg(feature = "cli")]
+pub mod report;
+#[cfg(feature = "cli")]
+pub mod interrupt;
+#[cfg(feature = "cli")]
+pub mod status;
+#[cfg(feature = "cli")]
+pub mod pager;
+#[cfg(feature = "cli")]
+pub mod generate;
+
+pub use error::{Error, Result};
+
+/// Dependencies of the code generated by `todo-list-macros`. Not part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use chrono;
+}
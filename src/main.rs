@@ -1,12 +1,6 @@
 use clap::Parser;
-use crate::cli::Cli;
-use crate::command::CommandError;
-
-mod task;
-mod cli;
-mod query;
-mod storage;
-mod command;
+use todo_list::cli::Cli;
+use todo_list::command::CommandError;
 
 fn main() -> Result<(), CommandError> {
     Cli::parse().run()
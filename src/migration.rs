@@ -0,0 +1,192 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::retention::ARCHIVE_TREE;
+use crate::storage::{Storage, StorageError};
+use crate::task::{ExternalRef, Status, Task};
+
+/// Name of the storage tree, where the schema version of the storage is kept.
+pub const SCHEMA_TREE: &str = "schema";
+/// Current schema version of the storage.
+///
+/// * `0` - initial schema;
+/// * `1` - `owner` and `assignee` fields of the task;
+/// * `2` - `externals` field of the task;
+/// * `3` - `externals` links are replaced by structured `external_refs`;
+pub const SCHEMA_VERSION: u32 = 3;
+
+const VERSION_KEY: &str = "version";
+
+/// Task as it was stored in schema version `0`.
+#[derive(Serialize, Deserialize)]
+struct TaskV0 {
+    name: String,
+    description: String,
+    date: DateTime<Utc>,
+    category: String,
+    status: Status,
+}
+
+/// Task as it was stored in schema version `1`.
+#[derive(Serialize, Deserialize)]
+struct TaskV1 {
+    name: String,
+    description: String,
+    date: DateTime<Utc>,
+    category: String,
+    status: Status,
+    owner: Option<String>,
+    assignee: Option<String>,
+}
+
+/// Task as it was stored in schema version `2`.
+#[derive(Serialize, Deserialize)]
+struct TaskV2 {
+    name: String,
+    description: String,
+    date: DateTime<Utc>,
+    category: String,
+    status: Status,
+    owner: Option<String>,
+    assignee: Option<String>,
+    externals: Vec<String>,
+}
+
+impl Task {
+    fn from_v0(task: TaskV0) -> Task {
+        Task::from_v1(TaskV1 {
+            name: task.name,
+            description: task.description,
+            date: task.date,
+            category: task.category,
+            status: task.status,
+            owner: None,
+            assignee: None,
+        })
+    }
+
+    fn from_v1(task: TaskV1) -> Task {
+        Task::from_v2(TaskV2 {
+            name: task.name,
+            description: task.description,
+            date: task.date,
+            category: task.category,
+            status: task.status,
+            owner: task.owner,
+            assignee: task.assignee,
+            externals: Vec::new(),
+        })
+    }
+
+    /// Links to GitHub issues become `github` references, other links - `url` references.
+    fn from_v2(task: TaskV2) -> Task {
+        Task {
+            name: task.name,
+            description: task.description,
+            date: task.date,
+            category: task.category,
+            status: task.status,
+            owner: task.owner,
+            assignee: task.assignee,
+            external_refs: task.externals.into_iter().map(external_ref_from_url).collect(),
+        }
+    }
+}
+
+fn external_ref_from_url(url: String) -> ExternalRef {
+    let github_issue = url
+        .strip_prefix("https://github.com/")
+        .and_then(|path| path.split_once("/issues/"))
+        .filter(|(repo, number)| repo.split('/').count() == 2 && number.parse::<u64>().is_ok())
+        .map(|(repo, number)| format!("{repo}#{number}"));
+
+    match github_issue {
+        Some(id) => ExternalRef {
+            source: "github".to_string(),
+            id,
+            url,
+        },
+        None => ExternalRef {
+            source: "url".to_string(),
+            id: url.clone(),
+            url,
+        },
+    }
+}
+
+/// Returns the schema version of the `storage`.
+///
+/// Storage without schema version is considered to be of version `0`.
+pub fn schema_version(storage: &Storage<Task>) -> Result<u32, StorageError> {
+    Ok(storage.open_tree::<u32>(SCHEMA_TREE)?.get(VERSION_KEY)?.unwrap_or(0))
+}
+
+/// Migrate tasks and archived tasks in `storage` to the current schema version.
+pub fn migrate(storage: &Storage<Task>) -> Result<(), StorageError> {
+    let version = schema_version(storage)?;
+    if version >= SCHEMA_VERSION {
+        return Ok(());
+    }
+
+    let archive = storage.open_tree::<Task>(ARCHIVE_TREE)?;
+    match version {
+        0 => {
+            storage.migrate(Task::from_v0)?;
+            archive.migrate(Task::from_v0)?;
+        }
+        1 => {
+            storage.migrate(Task::from_v1)?;
+            archive.migrate(Task::from_v1)?;
+        }
+        _ => {
+            storage.migrate(Task::from_v2)?;
+            archive.migrate(Task::from_v2)?;
+        }
+    }
+    storage.open_tree::<u32>(SCHEMA_TREE)?.insert(VERSION_KEY, &SCHEMA_VERSION)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+    use tempfile::tempdir;
+    use super::*;
+
+    #[test]
+    fn migrate_v0() {
+        let tempdir = tempdir().unwrap();
+        let old_task = || TaskV0 {
+            name: "task".to_string(),
+            description: "description".to_string(),
+            date: NaiveDateTime::parse_from_str("2024-12-12 20:20", "%Y-%m-%d %H:%M").unwrap().and_utc(),
+            category: "work".to_string(),
+            status: Status::Off,
+        };
+        {
+            let storage = Storage::<TaskV0>::open(&tempdir).unwrap();
+            storage.insert("task", &old_task()).unwrap();
+            storage.open_tree::<TaskV0>(ARCHIVE_TREE).unwrap().insert("task", &old_task()).unwrap();
+        }
+
+        let storage = Storage::<Task>::open(&tempdir).unwrap();
+        assert!(storage.get("task").is_err());
+
+        migrate(&storage).unwrap();
+        migrate(&storage).unwrap();
+
+        assert_eq!(storage.get("task").unwrap(), Some(Task::from_v0(old_task())));
+        assert_eq!(storage.open_tree::<Task>(ARCHIVE_TREE).unwrap().get("task").unwrap(), Some(Task::from_v0(old_task())));
+        assert_eq!(storage.open_tree::<u32>(SCHEMA_TREE).unwrap().get(VERSION_KEY).unwrap(), Some(SCHEMA_VERSION));
+    }
+
+    #[test]
+    fn external_refs_from_links() {
+        assert_eq!(external_ref_from_url("https://github.com/owner/repo/issues/12".to_string()), ExternalRef {
+            source: "github".to_string(),
+            id: "owner/repo#12".to_string(),
+            url: "https://github.com/owner/repo/issues/12".to_string(),
+        });
+        assert_eq!(external_ref_from_url("https://example.com/issues/12".to_string()).source, "url");
+    }
+}
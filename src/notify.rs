@@ -0,0 +1,141 @@
+use std::fmt::{Display, Formatter};
+use chrono::{DateTime, TimeDelta, Utc};
+use clap::{Args, ValueEnum};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use thiserror::Error;
+use crate::category::CategoryFilter;
+use crate::task::{Status, Task};
+
+/// Notification channels, that accept messages through incoming webhooks.
+///
+/// * `Channel::Slack` - Slack incoming webhook;
+/// * `Channel::Matrix` - Matrix generic webhook of the hookshot bridge;
+#[derive(Debug, Clone, Copy, ValueEnum, Serialize, Deserialize, PartialEq)]
+pub enum Channel {
+    Slack,
+    Matrix,
+}
+
+/// Settings of the due tasks notification.
+#[derive(Debug, Clone, Args, PartialEq)]
+pub struct Notify {
+    #[arg(long, value_enum, help = "Channel to post notification to")]
+    pub channel: Channel,
+    #[arg(long, env = "TODO_LIST_WEBHOOK", required_unless_present = "dry_run", help = "Webhook URL of the channel. Not required for a dry run")]
+    pub webhook: Option<String>,
+    #[arg(long, value_name = "DAYS", default_value_t = 1, help = "Notify about tasks due within DAYS")]
+    pub within: i64,
+    #[arg(long, help = "Print notification instead of posting it")]
+    pub dry_run: bool,
+    #[command(flatten)]
+    pub filter: CategoryFilter,
+}
+
+/// Digest of pending tasks, that are overdue or due soon.
+#[derive(Debug, PartialEq)]
+pub struct Digest {
+    pub overdue: Vec<Task>,
+    pub due_soon: Vec<Task>,
+}
+
+impl Digest {
+    /// Collect pending `tasks`, that are overdue or due within `within` at the moment `now`.
+    pub fn collect(tasks: impl IntoIterator<Item = Task>, now: DateTime<Utc>, within: TimeDelta) -> Digest {
+        let mut overdue = Vec::new();
+        let mut due_soon = Vec::new();
+        for task in tasks {
+            if task.status == Status::On {
+                continue;
+            }
+            if task.date < now {
+                overdue.push(task);
+            } else if task.date < now + within {
+                due_soon.push(task);
+            }
+        }
+
+        Digest { overdue, due_soon }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.overdue.is_empty() && self.due_soon.is_empty()
+    }
+}
+
+impl Channel {
+    /// Returns the webhook request body with `message`.
+    pub fn payload(self, message: &str) -> serde_json::Value {
+        match self {
+            Channel::Slack => json!({ "text": message }),
+            Channel::Matrix => json!({ "text": message, "username": "todo-list" }),
+        }
+    }
+
+    /// Post `digest` to the channel `webhook`.
+    pub fn post(self, webhook: &str, digest: &Digest) -> Result<(), NotifyError> {
+        self.post_message(webhook, &digest.to_string())
+    }
+
+    /// Post `message` to the channel `webhook`.
+    pub fn post_message(self, webhook: &str, message: &str) -> Result<(), NotifyError> {
+        ureq::post(webhook)
+            .set("Content-Type", "application/json")
+            .send_string(&self.payload(message).to_string())
+            .map_err(|err| NotifyError::Webhook(Box::new(err)))?;
+
+        Ok(())
+    }
+}
+
+impl Display for Digest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut sections = Vec::new();
+        for (title, tasks) in [("Overdue", &self.overdue), ("Due soon", &self.due_soon)] {
+            if tasks.is_empty() {
+                continue;
+            }
+            let lines = tasks
+                .iter()
+                .map(|task| format!("• {} ({}) due {}", task.name, task.category, task.date.format("%Y-%m-%d %H:%M")))
+                .collect::<Vec<_>>()
+                .join("\n");
+            sections.push(format!("{title} ({}):\n{lines}", tasks.len()));
+        }
+
+        write!(f, "{}", sections.join("\n\n"))
+    }
+}
+
+/// Represents possible errors of posting notification.
+#[derive(Error, Debug)]
+pub enum NotifyError {
+    #[error("Failed to post notification to webhook. \nReason: {0}")]
+    Webhook(Box<ureq::Error>),
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::task::tests::{date, task};
+    use super::*;
+
+    #[test]
+    fn collect_digest() {
+        let now = date("2024-12-12 20:20");
+        let tasks = [
+            task("overdue", "2024-12-10 20:20", Status::Off),
+            task("completed", "2024-12-10 20:20", Status::On),
+            task("tomorrow", "2024-12-13 10:00", Status::Off),
+            task("later", "2024-12-20 10:00", Status::Off),
+        ];
+
+        let digest = Digest::collect(tasks, now, TimeDelta::days(1));
+
+        assert_eq!(digest.overdue, [task("overdue", "2024-12-10 20:20", Status::Off)]);
+        assert_eq!(digest.due_soon, [task("tomorrow", "2024-12-13 10:00", Status::Off)]);
+        assert_eq!(
+            Channel::Slack.payload(&digest.to_string()),
+            json!({ "text": "Overdue (1):\n• overdue (category) due 2024-12-10 20:20\n\nDue soon (1):\n• tomorrow (category) due 2024-12-13 10:00" })
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use std::io::{stdin, stdout, ErrorKind, IsTerminal, Write};
+use std::process::{Command, Stdio};
+use crate::query::evaluator::context::ExecutionContext;
+use crate::query::evaluator::result_set::{rows_footer, TableLayout};
+use crate::query::reflect::Value;
+use crate::query::ResultSet;
+
+/// Returns rows per page of the printed query results of the current [`ExecutionContext`], if paging is enabled.
+pub fn page_size() -> Option<usize> {
+    ExecutionContext::with(|context| context.page_size).filter(|rows| *rows > 0)
+}
+
+/// Print `result_set` as table.
+///
+/// If paging is enabled and the output is a terminal, the table is piped through `$PAGER`, if it is set, otherwise
+/// it is printed by pages, and the next page is printed, when the user presses Enter, until `q` is entered.
+pub fn print(result_set: &ResultSet) -> std::io::Result<()> {
+    let rows = match page_size() {
+        Some(rows) if stdout().is_terminal() => rows,
+        _ => {
+            println!("{result_set}");
+            return Ok(());
+        }
+    };
+    if let Some(pager) = std::env::var("PAGER").ok().and_then(|pager| shlex::split(&pager)).filter(|pager| !pager.is_empty()) {
+        return page_through(&pager, result_set);
+    }
+
+    // Totals are of the whole result set, so they are printed after the last page.
+    let context = ExecutionContext::current();
+    let page_context = ExecutionContext { table_layout: TableLayout { totals: false, ..context.table_layout.clone() }, ..context.clone() };
+    let total = result_set.row_count();
+    for (index, page) in result_set.pages(rows).enumerate() {
+        println!("{}", page.to_table(&page_context));
+        let shown = (index * rows + page.row_count()).min(total);
+        if shown == total {
+            break;
+        }
+        eprint!("-- {shown} of {total} rows. Press Enter for more, 'q' to stop --");
+        let mut answer = String::new();
+        if stdin().read_line(&mut answer)? == 0 || answer.trim().eq_ignore_ascii_case("q") {
+            return Ok(());
+        }
+    }
+    if context.table_layout.totals {
+        let mut totals = ResultSet::with_columns(result_set.columns());
+        totals.add_row(result_set.columns().map(|column| (column, Value::String(result_set.column_total(column).into()))));
+        println!("{}\n{}", totals.to_table(&page_context), rows_footer(total));
+    }
+
+    Ok(())
+}
+
+/// Pipe `result_set` through the `pager` command, e.g. `less -S`.
+fn page_through(pager: &[String], result_set: &ResultSet) -> std::io::Result<()> {
+    let mut child = Command::new(&pager[0]).args(&pager[1..]).stdin(Stdio::piped()).spawn()?;
+    if let Some(mut input) = child.stdin.take() {
+        // Pager, that is quit before the end of the table, closes its input.
+        match writeln!(input, "{result_set}") {
+            Err(err) if err.kind() != ErrorKind::BrokenPipe => return Err(err),
+            _ => {}
+        }
+    }
+    child.wait()?;
+
+    Ok(())
+}
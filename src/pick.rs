@@ -0,0 +1,106 @@
+use std::io::stdin;
+use std::str::FromStr;
+use clap::{Args, ValueEnum};
+use crate::query::ast::{ParseError, Predicate};
+use crate::query::Query;
+use crate::task::Task;
+
+/// Print tasks as `id\tname\tdate` lines for fuzzy finders, such as fzf or skim,
+/// or apply action to the lines, that were picked from them.
+///
+/// ```console
+/// $ todo-list pick --query "status = 'off'" | fzf --multi | todo-list pick --apply done
+/// ```
+#[derive(Debug, Clone, Args, PartialEq)]
+pub struct Pick {
+    #[arg(long, value_name = "PREDICATE", value_parser = parse_predicate, conflicts_with = "apply", help = "List only tasks, that satisfy predicate")]
+    pub query: Option<Predicate>,
+    #[arg(long, value_enum, help = "Apply action to the picked lines, that are read from arguments or stdin")]
+    pub apply: Option<PickAction>,
+    #[arg(requires = "apply", value_name = "LINE", help = "Picked lines")]
+    pub lines: Vec<String>,
+}
+
+/// Possible actions on the picked tasks.
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq)]
+pub enum PickAction {
+    Done,
+    Delete,
+    Show,
+}
+
+impl Pick {
+    /// Read picked lines from stdin, if the action is applied and the lines are not passed as arguments.
+    pub fn read_stdin(&mut self) -> std::io::Result<()> {
+        if self.apply.is_some() && self.lines.is_empty() {
+            self.lines = stdin().lines().collect::<Result<_, _>>()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the line of the task. Tabs and line breaks of the task name are replaced by spaces.
+pub fn line(task: &Task) -> String {
+    format!(
+        "{}\t{}\t{}",
+        id(&task.name),
+        task.name.replace(['\t', '\n', '\r'], " "),
+        task.date.format("%Y-%m-%d %H:%M"),
+    )
+}
+
+/// Returns the task, which is picked by the `line`. The task is identified by the first column of the line.
+pub fn find<'a>(tasks: &'a [Task], line: &str) -> Option<&'a Task> {
+    let picked_id = line.split('\t').next()?.trim();
+
+    tasks.iter().find(|task| id(&task.name) == picked_id)
+}
+
+/// Returns stable short id of the task with `name`: 32-bit FNV-1a hash of the name.
+fn id(name: &str) -> String {
+    let hash = name
+        .bytes()
+        .fold(0x811c9dc5u32, |hash, byte| (hash ^ byte as u32).wrapping_mul(0x01000193));
+
+    format!("{hash:08x}")
+}
+
+/// Parse predicate of the `WHERE` clause.
+fn parse_predicate(predicate: &str) -> Result<Predicate, ParseError> {
+    let query = Query::from_str(&format!("SELECT * WHERE {predicate}"))?;
+
+    Ok(query.predicate.expect("query with WHERE clause has predicate"))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+    use crate::task::Status;
+    use super::*;
+
+    #[test]
+    fn pick_lines() {
+        let task = |name: &str| Task {
+            name: name.to_string(),
+            description: "description".to_string(),
+            date: NaiveDateTime::parse_from_str("2024-12-12 20:20", "%Y-%m-%d %H:%M").unwrap().and_utc(),
+            category: "category".to_string(),
+            status: Status::Off,
+            owner: None,
+            assignee: None,
+            external_refs: Vec::new(),
+        };
+        let tasks = [task("first"), task("second\ttask")];
+
+        let lines = tasks.iter().map(line).collect::<Vec<_>>();
+
+        assert_eq!(lines[0], format!("{}\tfirst\t2024-12-12 20:20", id("first")));
+        assert_eq!(lines[1], format!("{}\tsecond task\t2024-12-12 20:20", id("second\ttask")));
+        assert_eq!(find(&tasks, &lines[1]).unwrap().name, "second\ttask");
+        assert_eq!(find(&tasks, &format!("{}\n", id("first"))).unwrap().name, "first");
+        assert!(find(&tasks, "00000000\tother").is_none());
+        assert!(parse_predicate("status = 'off'").is_ok());
+        assert!(parse_predicate("status =").is_err());
+    }
+}
@@ -1,4 +1,8 @@
 use std::fmt::{Display, Formatter};
+use chrono::{DateTime, NaiveTime, TimeDelta, Utc};
+use crate::query::ast::Query;
+use crate::query::evaluator::value::conversion::format_duration;
+use crate::query::evaluator::value::Value;
 
 pub use crate::query::evaluator::value::Number;
 
@@ -7,19 +11,65 @@ pub use crate::query::evaluator::value::Number;
 pub enum Expression{
     Identifier(Identifier),
     Literal(Literal),
-    Operation(Box<Operation>)
+    Operation(Box<Operation>),
+    FunctionCall(FunctionCall),
+    RelativeDate(RelativeDate),
+    In(Box<InSubquery>),
+    List(Vec<Expression>)
 }
 
 /// Name of the identifier that can be read from the type that implement [`Reflectable`].
+///
+/// Identifier may be a dotted path, e.g. `metadata.owner`, to read the field of the nested [`Reflectable`] value.
 #[derive(Clone,Debug, PartialEq)]
 pub struct Identifier(pub String);
 
+/// Call of the scalar function with `arguments`.
+#[derive(Clone,Debug, PartialEq)]
+pub struct FunctionCall{
+    pub name: Identifier,
+    pub arguments: Vec<Expression>
+}
+
+/// Test, that the value of the `expression` is one of the values of the `subquery`.
+#[derive(Clone,Debug, PartialEq)]
+pub struct InSubquery{
+    pub expression: Expression,
+    pub subquery: Subquery
+}
+
+/// Subquery, that selects single column, or values to test the expression against.
+///
+/// * `Subquery::Query` - query over the same items as the outer query;
+/// * `Subquery::Materialized` - values of the query, that is evaluated once before the items are scanned;
+/// * `Subquery::Values` - expression, that is evaluated with the item, e.g. list literal `('a', 'b')`.
+///   Value, that is not a list, is tested as a list of one value;
+#[derive(Clone,Debug, PartialEq)]
+pub enum Subquery{
+    Query(Query),
+    Materialized(Vec<Value<'static>>),
+    Values(Expression)
+}
+
+/// Relative date keywords, that are expanded at query time.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RelativeDate{
+    Yesterday,
+    Today,
+    Tomorrow,
+    LastWeek,
+    ThisWeek,
+    NextWeek
+}
+
 /// Possible literals.
 #[derive(Clone,Debug, PartialEq)]
 pub enum Literal{
     Number(Number),
     String(String),
     Bool(bool),
+    DateTime(DateTime<Utc>),
+    Duration(TimeDelta),
     Null
 }
 
@@ -37,9 +87,10 @@ pub struct UnaryOperation{
 }
 
 /// Possible unary operators.
-#[derive(Clone,Debug, PartialEq)]
+#[derive(Copy, Clone,Debug, PartialEq)]
 pub enum UnaryOp{
-    Not
+    Not,
+    Neg
 }
 
 /// Binary operation that can be evaluated to [`Value`].
@@ -58,11 +109,218 @@ pub enum BinaryOp{
     Gte,
     Lte,
     Eq,
+    NullSafeEq,
     Like,
+    StartsWith,
+    EndsWith,
+    Add,
+    Sub,
     And,
     Or
 }
 
+impl BinaryOp{
+    /// All binary operators.
+    pub const ALL: [BinaryOp; 13] = [
+        BinaryOp::Or,
+        BinaryOp::And,
+        BinaryOp::Gt,
+        BinaryOp::Lt,
+        BinaryOp::Gte,
+        BinaryOp::Lte,
+        BinaryOp::Eq,
+        BinaryOp::NullSafeEq,
+        BinaryOp::Like,
+        BinaryOp::StartsWith,
+        BinaryOp::EndsWith,
+        BinaryOp::Add,
+        BinaryOp::Sub,
+    ];
+
+    /// Returns precedence of the operator. Operators with lower precedence bind tighter.
+    pub fn precedence(&self) -> u8{
+        match self {
+            BinaryOp::Add | BinaryOp::Sub => 1,
+            BinaryOp::Gt | BinaryOp::Lt | BinaryOp::Gte | BinaryOp::Lte | BinaryOp::Eq | BinaryOp::NullSafeEq
+            | BinaryOp::Like | BinaryOp::StartsWith | BinaryOp::EndsWith => 2,
+            BinaryOp::And => 4,
+            BinaryOp::Or => 5,
+        }
+    }
+}
+
+impl UnaryOp{
+    /// All unary operators.
+    pub const ALL: [UnaryOp; 2] = [UnaryOp::Not, UnaryOp::Neg];
+
+    /// Returns precedence of the operator. Operators with lower precedence bind tighter.
+    pub fn precedence(&self) -> u8{
+        match self {
+            UnaryOp::Neg => 0,
+            UnaryOp::Not => 3,
+        }
+    }
+}
+
+impl RelativeDate{
+    /// All relative date keywords.
+    pub const ALL: [RelativeDate; 6] = [
+        RelativeDate::Yesterday,
+        RelativeDate::Today,
+        RelativeDate::Tomorrow,
+        RelativeDate::LastWeek,
+        RelativeDate::ThisWeek,
+        RelativeDate::NextWeek,
+    ];
+}
+
+impl Expression{
+    /// Precedence of the `IN` operator, that is the same as precedence of the comparisons.
+    pub const IN_PRECEDENCE: u8 = 2;
+
+    /// Returns precedence of the expression. Operands, that are not operations, bind tightest.
+    pub fn precedence(&self) -> u8{
+        match self {
+            Expression::Operation(operation) => match &**operation {
+                Operation::Unary(operation) => operation.op.precedence(),
+                Operation::Binary(operation) => operation.op.precedence(),
+            },
+            Expression::In(_) => Expression::IN_PRECEDENCE,
+            _ => 0,
+        }
+    }
+
+    /// Write the expression, enclosing it in parentheses if it binds looser than `max_precedence`.
+    fn fmt_operand(&self, f: &mut Formatter<'_>, max_precedence: u8) -> std::fmt::Result {
+        if self.precedence() > max_precedence {
+            write!(f, "({self})")
+        } else {
+            Display::fmt(self, f)
+        }
+    }
+}
+
+/// Canonical query text of the expression, that is parsed back to the same expression.
+///
+/// Parentheses are written only where the operator precedence requires them.
+impl Display for Expression{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expression::Identifier(identifier) => Display::fmt(identifier, f),
+            Expression::Literal(literal) => Display::fmt(literal, f),
+            Expression::Operation(operation) => Display::fmt(operation, f),
+            Expression::FunctionCall(function_call) => Display::fmt(function_call, f),
+            Expression::RelativeDate(relative_date) => Display::fmt(relative_date, f),
+            Expression::In(in_subquery) => Display::fmt(in_subquery, f),
+            // List of one value is written in brackets, as `(a)` is just parenthesized `a`.
+            Expression::List(items) => {
+                let items = items.iter().map(ToString::to_string).collect::<Vec<_>>();
+                if items.len() > 1 {
+                    write!(f, "({})", items.join(", "))
+                } else {
+                    write!(f, "[{}]", items.join(", "))
+                }
+            }
+        }
+    }
+}
+
+/// Materialized subquery is written as the list of its values.
+impl Display for InSubquery{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.expression.fmt_operand(f, Expression::IN_PRECEDENCE - 1)?;
+        match &self.subquery {
+            Subquery::Query(query) => write!(f, " IN ({query})"),
+            Subquery::Materialized(values) => {
+                let values = values.iter().map(|value| Expression::from(value).to_string()).collect::<Vec<_>>();
+                write!(f, " IN ({})", values.join(", "))
+            }
+            Subquery::Values(expression) => {
+                f.write_str(" IN ")?;
+                expression.fmt_operand(f, 0)
+            }
+        }
+    }
+}
+
+impl Display for Operation{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operation::Unary(UnaryOperation{ op: UnaryOp::Not, expression }) => {
+                f.write_str("NOT ")?;
+                expression.fmt_operand(f, UnaryOp::Not.precedence())
+            }
+            Operation::Unary(UnaryOperation{ op: UnaryOp::Neg, expression }) => {
+                // `-5` is a negative literal and `--` starts a comment, so negation of these is separated by space.
+                let is_separated = matches!(expression, Expression::Literal(Literal::Number(_)))
+                    || expression.to_string().starts_with('-');
+                f.write_str(if is_separated { "- " } else { "-" })?;
+                expression.fmt_operand(f, UnaryOp::Neg.precedence())
+            }
+            // Operators are left-associative, so chain of the operators is grouped from the left, as `a - b - c` is
+            // `(a - b) - c`, and right operand with the same precedence is enclosed in parentheses.
+            Operation::Binary(BinaryOperation{ left_expression, op, right_expression }) => {
+                left_expression.fmt_operand(f, op.precedence())?;
+                write!(f, " {op} ")?;
+                right_expression.fmt_operand(f, op.precedence() - 1)
+            }
+        }
+    }
+}
+
+impl Display for FunctionCall{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}(", self.name)?;
+        for (idx, argument) in self.arguments.iter().enumerate() {
+            if idx > 0 {
+                f.write_str(", ")?;
+            }
+            Display::fmt(argument, f)?;
+        }
+        f.write_str(")")
+    }
+}
+
+impl Display for Identifier{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl Display for Literal{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Literal::Number(Number::Int(int)) => Display::fmt(int, f),
+            // Debug representation always has fractional part or exponent, so the number is parsed back as float.
+            Literal::Number(Number::Float(float)) => write!(f, "{float:?}"),
+            Literal::Number(Number::Decimal(decimal)) => write!(f, "DECIMAL '{decimal}'"),
+            Literal::String(string) => write_string(string, f),
+            Literal::Bool(bool) => Display::fmt(bool, f),
+            Literal::DateTime(date_time) if date_time.time() == NaiveTime::MIN => write!(f, "DATE '{}'", date_time.format("%Y-%m-%d")),
+            Literal::DateTime(date_time) => write!(f, "DATETIME '{}'", date_time.format("%Y-%m-%d %H:%M")),
+            Literal::Duration(duration) => write!(f, "INTERVAL '{}'", format_duration(*duration)),
+            Literal::Null => f.write_str("NULL"),
+        }
+    }
+}
+
+/// Write string literal. Escape sequences are kept in the string as they were written, so the string is enclosed in
+/// the single quotes, unless it contains unescaped single quote.
+fn write_string(string: &str, f: &mut Formatter<'_>) -> std::fmt::Result {
+    let mut is_escaped = false;
+    let has_single_quote = string.chars().any(|char| {
+        let is_quote = char == '\'' && !is_escaped;
+        is_escaped = char == '\\' && !is_escaped;
+        is_quote
+    });
+
+    if has_single_quote {
+        write!(f, "\"{string}\"")
+    } else {
+        write!(f, "'{string}'")
+    }
+}
+
 impl Display for BinaryOp{
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let value = match self {
@@ -71,11 +329,41 @@ impl Display for BinaryOp{
             BinaryOp::Gte => ">=",
             BinaryOp::Lte => "<=",
             BinaryOp::Eq => "=",
+            BinaryOp::NullSafeEq => "<=>",
             BinaryOp::Like => "LIKE",
+            BinaryOp::StartsWith => "STARTSWITH",
+            BinaryOp::EndsWith => "ENDSWITH",
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
             BinaryOp::And => "AND",
             BinaryOp::Or => "OR"
         };
 
         Display::fmt(value, f)
     }
-}
\ No newline at end of file
+}
+
+impl Display for UnaryOp{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            UnaryOp::Not => "NOT",
+            UnaryOp::Neg => "-"
+        };
+
+        Display::fmt(value, f)
+    }
+}
+impl Display for RelativeDate{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let value = match self {
+            RelativeDate::Yesterday => "YESTERDAY",
+            RelativeDate::Today => "TODAY",
+            RelativeDate::Tomorrow => "TOMORROW",
+            RelativeDate::LastWeek => "LAST WEEK",
+            RelativeDate::ThisWeek => "THIS WEEK",
+            RelativeDate::NextWeek => "NEXT WEEK"
+        };
+
+        Display::fmt(value, f)
+    }
+}
@@ -1,20 +1,42 @@
+use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 use nom::combinator::all_consuming;
-use nom::error::convert_error;
+use nom::error::ErrorKind;
+use nom_language::error::{VerboseError, VerboseErrorKind};
 use nom::Finish;
 use nom::Parser;
 use thiserror::Error;
-use crate::query::ast::expression::{Expression, Identifier};
-use crate::query::ast::parser::query;
+use crate::query::ast::expression::{BinaryOp, BinaryOperation, Expression, Identifier, Literal, Operation};
+use crate::query::ast::parser::{identifier, insert, query};
 
 mod parser;
 pub mod expression;
 
 /// Represents a query, that will filter items by predicate and then project them to [`ResultSet`].
+///
+/// Items are taken from the list `from`, or from the current list if it is omitted. The list is resolved by the
+/// caller, that passes its items to [`Query::execute`].
 #[derive(Clone, Debug, PartialEq)]
 pub struct Query {
     pub fields_projection: FieldsProjection,
-    pub predicate: Option<Predicate>
+    pub from: Option<Identifier>,
+    pub predicate: Option<Predicate>,
+    /// Expressions, which values group the items, that are aggregated to a single row, see [`Query::is_aggregate`].
+    pub group_by: Vec<Expression>,
+    /// Collation of the string comparisons in the query; subqueries use the collation of the outer query,
+    /// unless it is omitted.
+    pub collation: Option<Collation>
+}
+
+/// Rule of the string comparison.
+///
+///  * `Collation::Binary` - strings are compared by their characters;
+///  * `Collation::NoCase` - strings are compared ignoring case and accents, so `'Résumé' = 'resume'`;
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Collation{
+    #[default]
+    Binary,
+    NoCase
 }
 
 /// Fields that will be projected to [`ResultSet`].
@@ -25,11 +47,21 @@ pub struct FieldsProjection(pub Vec<Field>);
 /// One of the possible field projection type.
 ///
 ///  * `Field::Asterisk` - all fields of projectable types will be included in [`ResultSet`];
-///  * `Field::Name` - specified field will be included in [`ResultSet`];
+///  * `Field::Expression` - expression evaluated for every item will be included in [`ResultSet`] as column `name`;
 #[derive(Clone, Debug, PartialEq)]
 pub enum Field{
     Asterisk,
-    Name(Identifier)
+    Expression{
+        expression: Expression,
+        name: String
+    }
+}
+
+/// Represents an insert statement, that adds item for every row of `values` of the `columns`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Insert {
+    pub columns: Vec<Identifier>,
+    pub rows: Vec<Vec<Expression>>
 }
 
 /// Predicate that will filter values.
@@ -38,6 +70,143 @@ pub struct Predicate{
     pub expr: Expression
 }
 
+impl Query{
+    /// Narrow the query by `filter`, that selected items must satisfy in addition to the query predicate.
+    pub fn and_where(&mut self, filter: Predicate){
+        self.predicate = Some(match self.predicate.take() {
+            Some(predicate) => predicate.and(filter),
+            None => filter,
+        });
+    }
+}
+
+impl Predicate{
+    /// Create predicate, that tests that `field` is equal to `value`.
+    pub fn eq(field: &str, value: Literal) -> Predicate{
+        Predicate{
+            expr: Expression::Operation(Box::new(Operation::Binary(BinaryOperation{
+                left_expression: Expression::Identifier(Identifier(field.to_string())),
+                op: BinaryOp::Eq,
+                right_expression: Expression::Literal(value),
+            })))
+        }
+    }
+
+    /// Create predicate, that tests that `field` is equal to any of the `values`.
+    ///
+    /// If `values` is empty, `None` will be returned.
+    pub fn any_of(field: &str, values: impl IntoIterator<Item = Literal>) -> Option<Predicate>{
+        values
+            .into_iter()
+            .map(|value| Predicate::eq(field, value))
+            .reduce(Predicate::or)
+    }
+
+    /// Combine this predicate with `other` by logical "or".
+    pub fn or(self, other: Predicate) -> Predicate{
+        Predicate{
+            expr: Expression::Operation(Box::new(Operation::Binary(BinaryOperation{
+                left_expression: self.expr,
+                op: BinaryOp::Or,
+                right_expression: other.expr,
+            })))
+        }
+    }
+
+    /// Combine this predicate with `other` by logical "and".
+    pub fn and(self, other: Predicate) -> Predicate{
+        Predicate{
+            expr: Expression::Operation(Box::new(Operation::Binary(BinaryOperation{
+                left_expression: self.expr,
+                op: BinaryOp::And,
+                right_expression: other.expr,
+            })))
+        }
+    }
+}
+
+/// Canonical query text, that is parsed back to the same query.
+impl Display for Query{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SELECT {}", self.fields_projection)?;
+        if let Some(from) = &self.from {
+            write!(f, " FROM {from}")?;
+        }
+        if let Some(predicate) = &self.predicate {
+            write!(f, " WHERE {predicate}")?;
+        }
+        if !self.group_by.is_empty() {
+            let group_by = self.group_by.iter().map(ToString::to_string).collect::<Vec<_>>();
+            write!(f, " GROUP BY {}", group_by.join(", "))?;
+        }
+        if let Some(collation) = &self.collation {
+            write!(f, " COLLATE {collation}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Collation{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Collation::Binary => write!(f, "BINARY"),
+            Collation::NoCase => write!(f, "NOCASE"),
+        }
+    }
+}
+
+/// Canonical statement text, that is parsed back to the same statement.
+impl Display for Insert{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let join = |items: Vec<String>| items.join(", ");
+        let columns = join(self.columns.iter().map(ToString::to_string).collect());
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| format!("({})", join(row.iter().map(ToString::to_string).collect())))
+            .collect();
+
+        write!(f, "INSERT ({columns}) VALUES {}", join(rows))
+    }
+}
+
+impl Display for FieldsProjection{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (idx, field) in self.0.iter().enumerate() {
+            if idx > 0 {
+                f.write_str(", ")?;
+            }
+            Display::fmt(field, f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Name of the expression field is written as alias, if it differs from the expression text and is a valid identifier.
+impl Display for Field{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Field::Asterisk => f.write_str("*"),
+            Field::Expression { expression, name } => {
+                let expression = expression.to_string();
+                let is_alias = *name != expression && all_consuming(identifier).parse(name.as_str()).is_ok();
+                if is_alias {
+                    write!(f, "{expression} AS {name}")
+                } else {
+                    f.write_str(&expression)
+                }
+            }
+        }
+    }
+}
+
+impl Display for Predicate{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.expr, f)
+    }
+}
 
 impl FromStr for Query{
     type Err = ParseError;
@@ -46,12 +215,166 @@ impl FromStr for Query{
         all_consuming(query)
             .parse(s)
             .finish()
-            .map_err(|x| ParseError(convert_error(s, x)))
+            .map_err(|err| ParseError::new(s, err))
             .map(|(_, x)| x)
     }
 }
 
-/// Represents possible errors of query parsing.
-#[derive(Error, Debug)]
-#[error("Query parsing failed. Error: {0}")]
-pub struct ParseError(String);
\ No newline at end of file
+impl FromStr for Insert{
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        all_consuming(insert)
+            .parse(s)
+            .finish()
+            .map_err(|err| ParseError::new(s, err))
+            .map(|(_, x)| x)
+    }
+}
+
+/// Represents query parsing error, that points to the position in the query, where parsing failed.
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("Query parsing failed at line {line}, column {column}: {message}\n{snippet}")]
+pub struct ParseError{
+    /// Byte offset of the error in the query.
+    pub offset: usize,
+    /// Line of the error, starting from 1.
+    pub line: usize,
+    /// Column of the error in characters, starting from 1.
+    pub column: usize,
+    /// Token, that failed to parse. Empty at the end of the query.
+    pub token: String,
+    /// Description of the error.
+    pub message: String,
+    /// Line of the query with the caret under the token.
+    pub snippet: String,
+}
+
+impl ParseError{
+    /// Build error from the parser error of the `query`.
+    ///
+    /// Among the errors reported by the nested parsers, the one that made the most progress is used.
+    /// Of the errors at the same position, the context, that describes expected input, is preferred.
+    fn new(query: &str, err: VerboseError<&str>) -> ParseError{
+        let (rest, kind) = err
+            .errors
+            .into_iter()
+            .min_by_key(|(rest, kind)| (rest.len(), !matches!(kind, VerboseErrorKind::Context(_))))
+            .unwrap_or((query, VerboseErrorKind::Nom(ErrorKind::Fail)));
+        let offset = query.len() - rest.len();
+        let token = Self::token(rest);
+
+        let line_start = query[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+        let line_end = query[offset..].find('\n').map_or(query.len(), |idx| offset + idx);
+        let line = query[..offset].matches('\n').count() + 1;
+        let column = query[line_start..offset].chars().count() + 1;
+
+        let message = match (kind, token.as_str()) {
+            (VerboseErrorKind::Char(char), "") => format!("expected '{char}', found end of query"),
+            (VerboseErrorKind::Char(char), token) => format!("expected '{char}', found '{token}'"),
+            (VerboseErrorKind::Context(context), _) => format!("expected {context}"),
+            (VerboseErrorKind::Nom(_), "") => "unexpected end of query".to_string(),
+            (VerboseErrorKind::Nom(_), token) => format!("unexpected '{token}'"),
+        };
+        let snippet = format!(
+            "{}\n{}{}",
+            &query[line_start..line_end],
+            " ".repeat(column - 1),
+            "^".repeat(token.chars().count().max(1)),
+        );
+
+        ParseError{ offset, line, column, token, message, snippet }
+    }
+
+    /// Returns the word or the single symbol at the beginning of `input`.
+    fn token(input: &str) -> String{
+        let word_len = input
+            .find(|char: char| !(char.is_alphanumeric() || char == '_'))
+            .unwrap_or(input.len());
+        match input.chars().next() {
+            Some(char) if word_len == 0 && !char.is_whitespace() => char.to_string(),
+            _ => input[..word_len].to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::*;
+
+    #[test]
+    fn display_round_trip(){
+        let queries = [
+            "SELECT *",
+            "SELECT name, LOWER(category) AS category, date + INTERVAL '1 day 2 hours' WHERE NOT (status = 'on' OR done) AND date < TOMORROW",
+            "SELECT (a OR b) AND c, a - (b - c), a - b - c, -(a + 1), - 5, - -5, (NOT a) = b, a = (b = c), a = b = c, a <=> NULL <= b",
+            "SELECT name, status FROM archive WHERE date < TODAY",
+            "SELECT name WHERE name LIKE 'a' COLLATE NOCASE",
+            "SELECT category, COUNT(), MAX(date) AS latest WHERE status = 'on' GROUP BY category, YEAR(date) COLLATE NOCASE",
+            "SELECT name WHERE category IN (SELECT category WHERE status = 'off') AND c = (a IN (SELECT b)) AND a IN (SELECT b) = c",
+            "SELECT ('a', 'b'), [1], [], ((1, 2), [a + 1]) WHERE name IN ('a', 'b') AND status IN [NULL] AND a IN b",
+            "SELECT \"it's\", 'say \\'hi\\'', 1.0, 1e300, NULL, true, DATE '2024-12-12', DATETIME '2024-12-12 20:20'",
+        ];
+
+        for text in queries {
+            let query = Query::from_str(text).unwrap();
+
+            assert_eq!(query.to_string(), text);
+            assert_eq!(Query::from_str(&query.to_string()).unwrap(), query);
+        }
+
+        let query = Query::from_str("select  lower( name ) , ((a)) where /* comment */ a>1").unwrap();
+
+        assert_eq!(query.to_string(), "SELECT lower(name), a WHERE a > 1");
+    }
+
+    #[test]
+    fn left_associative_operators(){
+        let expression = |text: &str| Query::from_str(&format!("SELECT * WHERE {text}")).unwrap().predicate.unwrap().expr;
+        let binary = |left: Expression, op: BinaryOp, right: &str| Expression::Operation(Box::new(Operation::Binary(BinaryOperation {
+            left_expression: left,
+            op,
+            right_expression: Expression::Identifier(Identifier(right.to_string())),
+        })));
+        let identifier = |name: &str| Expression::Identifier(Identifier(name.to_string()));
+
+        for op in [BinaryOp::And, BinaryOp::Or, BinaryOp::Eq, BinaryOp::Sub] {
+            assert_eq!(
+                expression(&format!("a {op} b {op} c")),
+                binary(binary(identifier("a"), op, "b"), op, "c")
+            );
+        }
+        assert_eq!(expression("a OR b AND NOT c = d + e"), expression("a OR (b AND (NOT (c = (d + e))))"));
+    }
+
+    #[test]
+    fn insert_round_trip(){
+        let text = "INSERT (name, date, status) VALUES ('first', DATE '2024-12-12', 'off'), ('second', TODAY + INTERVAL '1 day', 'on')";
+        let insert = Insert::from_str(text).unwrap();
+
+        assert_eq!(insert.columns.len(), 3);
+        assert_eq!(insert.rows.len(), 2);
+        assert_eq!(insert.to_string(), text);
+
+        let err = Insert::from_str("insert (name, date) values ('first')").unwrap_err();
+
+        assert_eq!(err.message, "expected as many values as columns");
+    }
+
+    #[test]
+    fn parse_error_position(){
+        let err = Query::from_str("SELECT name\nWHERE name = 'a' ) OR done").unwrap_err();
+
+        assert_eq!(err.offset, 29);
+        assert_eq!((err.line, err.column), (2, 18));
+        assert_eq!(err.token, ")");
+        assert_eq!(err.message, "unexpected ')'");
+        assert_eq!(err.snippet, "WHERE name = 'a' ) OR done\n                 ^");
+
+        let err = Query::from_str("SELECT LOWER(name").unwrap_err();
+
+        assert_eq!(err.column, 18);
+        assert_eq!(err.message, "expected ')', found end of query");
+        assert_eq!(err.snippet, "SELECT LOWER(name\n                 ^");
+    }
+}
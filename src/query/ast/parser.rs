@@ -1,53 +1,126 @@
 use super::expression::{
-    BinaryOp, BinaryOperation, Expression, Identifier, Literal, Number, Operation, UnaryOp,
-    UnaryOperation,
+    BinaryOp, BinaryOperation, Expression, FunctionCall, Identifier, InSubquery, Literal, Number, Operation,
+    RelativeDate, Subquery, UnaryOp, UnaryOperation,
 };
-use super::{Field, FieldsProjection, Predicate, Query};
+use super::{Collation, Field, FieldsProjection, Insert, Predicate, Query};
 use nom::branch::alt;
-use nom::bytes::complete::{escaped, tag, tag_no_case};
-use nom::character::complete::{alpha1, alphanumeric1, char, i64, multispace0, none_of, one_of};
-use nom::combinator::{cut, map, not, opt, recognize, value};
-use nom::error::{ParseError, VerboseError};
-use nom::multi::{many0_count, separated_list1};
+use nom::bytes::complete::{escaped, tag, tag_no_case, take_until, take_while};
+use nom::character::complete::{anychar, char, i64, multispace0, multispace1, none_of, not_line_ending, one_of, satisfy};
+use nom::combinator::{consumed, cut, map, map_res, not, opt, peek, recognize, value, verify};
+use nom::error::{context, ParseError};
+use nom_language::error::VerboseError;
+use nom::multi::{fold_many0, many0, many0_count, separated_list0, separated_list1};
+use std::iter::once;
 use nom::number::complete::double;
-use nom::sequence::{delimited, preceded, separated_pair, terminated};
+use nom::sequence::{delimited, preceded, terminated};
 use nom::{IResult, Parser};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Utc};
+use crate::query::evaluator::value::conversion::parse_duration;
+use unicode_ident::{is_xid_continue, is_xid_start};
 
 type ParseResult<'a, O> = IResult<&'a str, O, VerboseError<&'a str>>;
 
-/// Skips surrounding whitespace
+/// Skips surrounding whitespace and comments
 pub fn ws<'a, O, E: ParseError<&'a str>>(
     wrapped: impl Parser<&'a str, Output = O, Error = E>,
 ) -> impl Parser<&'a str, Output = O, Error = E> {
-    delimited(multispace0, wrapped, multispace0)
+    delimited(space0, wrapped, space0)
 }
 
-pub fn literal(input: &str) -> ParseResult<Literal> {
+/// Skips whitespace and comments
+pub fn space0<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, &'a str, E> {
+    recognize(many0_count(alt((multispace1, comment)))).parse(input)
+}
+
+/// Parse comment: `-- line comment` or `/* block comment */`
+pub fn comment<'a, E: ParseError<&'a str>>(input: &'a str) -> IResult<&'a str, &'a str, E> {
+    alt((
+        recognize((tag("--"), not_line_ending)),
+        recognize((tag("/*"), take_until("*/"), tag("*/"))),
+    ))
+    .parse(input)
+}
+
+/// Returns text without comments. String literals are kept as is, even if they contain comment markers
+fn strip_comments(text: &str) -> String {
+    let stripped: ParseResult<'_, String> = fold_many0(
+        alt((
+            map(recognize(string), Some),
+            map(comment, |_| None),
+            map(recognize(anychar), Some),
+        )),
+        String::new,
+        |mut text, fragment| {
+            text.extend(fragment);
+            text
+        },
+    )
+    .parse(text);
+
+    stripped.map_or_else(|_| text.to_string(), |(_, stripped)| stripped)
+}
+
+pub fn literal(input: &str) -> ParseResult<'_, Literal> {
     alt((
         map(null, |_| Literal::Null),
         map(number, Literal::Number),
         map(boolean, Literal::Bool),
         map(string, Literal::String),
+        map(date_time, Literal::DateTime),
+        map(interval, Literal::Duration),
     ))
     .parse(input)
 }
 
-pub fn null(input: &str) -> ParseResult<()> {
+/// Parse typed date literals: `DATE '%Y-%m-%d'` and `DATETIME '%Y-%m-%d %H:%M'`
+pub fn date_time(input: &str) -> ParseResult<'_, DateTime<Utc>> {
+    alt((
+        preceded(
+            (tag_no_case("DATETIME"), multispace0, peek(one_of("'\""))),
+            cut(map_res(string, |date_time| {
+                NaiveDateTime::parse_from_str(&date_time, "%Y-%m-%d %H:%M").map(|date_time| date_time.and_utc())
+            })),
+        ),
+        preceded(
+            (tag_no_case("DATE"), multispace0, peek(one_of("'\""))),
+            cut(map_res(string, |date| {
+                NaiveDate::parse_from_str(&date, "%Y-%m-%d").map(|date| date.and_time(NaiveTime::MIN).and_utc())
+            })),
+        ),
+    ))
+    .parse(input)
+}
+
+/// Parse interval literals: `INTERVAL '3 days'`, `INTERVAL '1 day 2 hours'`
+pub fn interval(input: &str) -> ParseResult<'_, TimeDelta> {
+    preceded(
+        (tag_no_case("INTERVAL"), multispace0, peek(one_of("'\""))),
+        cut(map_res(string, |interval| parse_duration(&interval))),
+    )
+    .parse(input)
+}
+
+pub fn null(input: &str) -> ParseResult<'_, ()> {
     value((), tag_no_case("null")).parse(input)
 }
-pub fn number(input: &str) -> ParseResult<Number> {
+/// Parse numbers: integers, floats and typed exact decimals `DECIMAL '12.30'`
+pub fn number(input: &str) -> ParseResult<'_, Number> {
     alt((
+        preceded(
+            (tag_no_case("DECIMAL"), multispace0, peek(one_of("'\""))),
+            cut(map_res(string, |decimal| decimal.parse().map(Number::Decimal))),
+        ),
         map(terminated(i64, not(one_of(".eE"))), Number::Int),
         map(double, Number::Float),
     ))
     .parse(input)
 }
 
-pub fn boolean(input: &str) -> ParseResult<bool> {
+pub fn boolean(input: &str) -> ParseResult<'_, bool> {
     alt((value(false, tag("false")), value(true, tag("true")))).parse(input)
 }
 
-pub fn string(input: &str) -> ParseResult<String> {
+pub fn string(input: &str) -> ParseResult<'_, String> {
     alt((
         delimited(char('\''), escaped_single_quote_string, cut(char('\''))),
         delimited(char('"'), escaped_double_quote_string, cut(char('"'))),
@@ -56,7 +129,7 @@ pub fn string(input: &str) -> ParseResult<String> {
 }
 
 /// Parse double-quoted string, escaping control characters
-pub fn escaped_double_quote_string(input: &str) -> ParseResult<String> {
+pub fn escaped_double_quote_string(input: &str) -> ParseResult<'_, String> {
     map(
         map(
             opt(escaped(none_of(r#"\""#), '\\', one_of(r#""\/bfnrt"#))),
@@ -67,7 +140,7 @@ pub fn escaped_double_quote_string(input: &str) -> ParseResult<String> {
     .parse(input)
 }
 /// Parse single-quoted string, escaping control characters
-pub fn escaped_single_quote_string(input: &str) -> ParseResult<String> {
+pub fn escaped_single_quote_string(input: &str) -> ParseResult<'_, String> {
     map(
         map(
             opt(escaped(none_of(r#"\'"#), '\\', one_of(r#"'\/bfnrt"#))),
@@ -78,100 +151,197 @@ pub fn escaped_single_quote_string(input: &str) -> ParseResult<String> {
         .parse(input)
 }
 
-pub fn identifier(input: &str) -> ParseResult<Identifier> {
+/// Parse identifier: dot-separated segments, that start with `_` or `XID_Start` character and continue with
+/// `XID_Continue` characters, so non-English names, e.g. `категория` or `件名`, are identifiers
+pub fn identifier(input: &str) -> ParseResult<'_, Identifier> {
+    let segment = || recognize(preceded(
+        satisfy(|char| char == '_' || is_xid_start(char)),
+        take_while(is_xid_continue),
+    ));
+
     map(
-        recognize(preceded(
-            alt((alpha1, tag("_"))),
-            many0_count(alt((alphanumeric1, tag("_")))),
-        )),
+        recognize(separated_list1(char('.'), segment())),
         |identifier: &str| Identifier(identifier.to_string()),
     )
     .parse(input)
 }
 
-/// Parse operators with precedence 4
-pub fn expression(input: &str) -> ParseResult<Expression> {
-    alt((
-        map(
-            separated_pair(expression1, ws(tag_no_case("OR")), expression),
-            |(left, right)| {
-                Expression::Operation(Box::new(Operation::Binary(BinaryOperation {
-                    left_expression: left,
-                    op: BinaryOp::Or,
-                    right_expression: right,
-                })))
-            },
-        ),
-        ws(expression1),
-    ))
-    .parse(input)
+/// Parse expression with operators of any precedence
+pub fn expression(input: &str) -> ParseResult<'_, Expression> {
+    ws(operation(BinaryOp::Or.precedence())).parse(input)
+}
+
+/// Operator, that follows the left operand.
+#[derive(Clone, Copy)]
+enum Infix {
+    Binary(BinaryOp),
+    In,
+}
+
+impl Infix {
+    fn precedence(self) -> u8 {
+        match self {
+            Infix::Binary(op) => op.precedence(),
+            Infix::In => Expression::IN_PRECEDENCE,
+        }
+    }
+}
+
+/// Parse expression, which operators have precedence at most `max_precedence`, by precedence climbing.
+///
+/// Operand of the prefix operator is parsed with the precedence of the operator, and right operand of the infix
+/// operator with precedence one less, so every binary operator is left-associative: `a AND b AND c` is
+/// `(a AND b) AND c`. Operator, which right operand can't be parsed, is left for the caller.
+fn operation(max_precedence: u8) -> impl Fn(&str) -> ParseResult<'_, Expression> {
+    move |input| {
+        let not_precedence = UnaryOp::Not.precedence();
+        let (mut input, mut left) = if max_precedence >= not_precedence {
+            alt((
+                map(preceded(ws(keyword("NOT")), operation(not_precedence)), |expression| {
+                    Expression::Operation(Box::new(Operation::Unary(UnaryOperation { op: UnaryOp::Not, expression })))
+                }),
+                primary,
+            ))
+            .parse(input)?
+        } else {
+            primary(input)?
+        };
+
+        loop {
+            let Ok((rest, infix)) = ws(infix_operator).parse(input) else {
+                return Ok((input, left));
+            };
+            if infix.precedence() > max_precedence {
+                return Ok((input, left));
+            }
+            left = match infix {
+                Infix::In => {
+                    let (rest, subquery) = cut(alt((subquery, map(ws(primary), Subquery::Values)))).parse(rest)?;
+                    input = rest;
+                    Expression::In(Box::new(InSubquery { expression: left, subquery }))
+                }
+                Infix::Binary(op) => {
+                    let Ok((rest, right)) = ws(operation(op.precedence() - 1)).parse(rest) else {
+                        return Ok((input, left));
+                    };
+                    input = rest;
+                    Expression::Operation(Box::new(Operation::Binary(BinaryOperation {
+                        left_expression: left,
+                        op,
+                        right_expression: right,
+                    })))
+                }
+            };
+        }
+    }
 }
 
-/// Parse operators with precedence 3
-pub fn expression1(input: &str) -> ParseResult<Expression> {
+/// Parse binary operator or `IN`
+fn infix_operator(input: &str) -> ParseResult<'_, Infix> {
     alt((
-        map(
-            separated_pair(expression2, ws(tag_no_case("AND")), expression1),
-            |(left, right)| {
-                Expression::Operation(Box::new(Operation::Binary(BinaryOperation {
-                    left_expression: left,
-                    op: BinaryOp::And,
-                    right_expression: right,
-                })))
-            },
-        ),
-        ws(expression2),
+        value(Infix::Binary(BinaryOp::Or), keyword("OR")),
+        value(Infix::Binary(BinaryOp::And), keyword("AND")),
+        value(Infix::In, keyword("IN")),
+        map(relation_operator, Infix::Binary),
+        map(additive_operator, Infix::Binary),
     ))
     .parse(input)
 }
 
-/// Parse operators with precedence 2
-pub fn expression2(input: &str) -> ParseResult<Expression> {
+/// Parse expressions in parentheses, list literals, literals, function calls, identifiers and negations
+pub fn primary(input: &str) -> ParseResult<'_, Expression> {
     alt((
-        map(preceded(ws(tag_no_case("NOT")), expression2), |expr| {
+        parenthesized,
+        map(
+            preceded(char('['), cut(terminated(separated_list0(char(','), expression), (space0, char(']'))))),
+            Expression::List,
+        ),
+        map(literal, Expression::Literal),
+        map(relative_date, Expression::RelativeDate),
+        map(function_call, Expression::FunctionCall),
+        map(identifier, Expression::Identifier),
+        map(preceded(terminated(char('-'), multispace0), primary), |expr| {
             Expression::Operation(Box::new(Operation::Unary(UnaryOperation {
-                op: UnaryOp::Not,
+                op: UnaryOp::Neg,
                 expression: expr,
             })))
         }),
-        ws(expression3),
     ))
     .parse(input)
 }
 
-/// Parse operators with precedence 1
-pub fn expression3(input: &str) -> ParseResult<Expression> {
+/// Parse expression in parentheses, or list literal of comma-separated expressions in parentheses, e.g. `('a', 'b')`.
+/// List of one expression is written in brackets: `['a']`
+fn parenthesized(input: &str) -> ParseResult<'_, Expression> {
+    map(
+        delimited(tag("("), (expression, many0(preceded(char(','), expression))), cut(tag(")"))),
+        |(first, rest)| match rest.is_empty() {
+            true => first,
+            false => Expression::List(once(first).chain(rest).collect()),
+        },
+    )
+    .parse(input)
+}
+
+/// Parse subquery in parentheses: `(SELECT <field> [WHERE <predicate>])`
+pub fn subquery(input: &str) -> ParseResult<'_, Subquery> {
+    map(delimited(ws(char('(')), query, ws(char(')'))), Subquery::Query).parse(input)
+}
+
+/// Parse relative date keywords: `YESTERDAY`, `TODAY`, `TOMORROW`, `LAST WEEK`, `THIS WEEK`, `NEXT WEEK`
+pub fn relative_date(input: &str) -> ParseResult<'_, RelativeDate> {
+    let week = |prefix| (tag_no_case(prefix), multispace1, keyword("WEEK"));
+
     alt((
-        map(
-            (expression4, ws(relation_operator), expression3),
-            |(left, op, right)| {
-                Expression::Operation(Box::new(Operation::Binary(BinaryOperation {
-                    left_expression: left,
-                    op,
-                    right_expression: right,
-                })))
-            },
-        ),
-        ws(expression4),
+        value(RelativeDate::Yesterday, keyword("YESTERDAY")),
+        value(RelativeDate::Today, keyword("TODAY")),
+        value(RelativeDate::Tomorrow, keyword("TOMORROW")),
+        value(RelativeDate::LastWeek, week("LAST")),
+        value(RelativeDate::ThisWeek, week("THIS")),
+        value(RelativeDate::NextWeek, week("NEXT")),
     ))
     .parse(input)
 }
 
-/// Parse expressions in parentheses, literals and identifiers
-pub fn expression4(input: &str) -> ParseResult<Expression> {
+/// Parse case-insensitive keyword, that is not a prefix of an identifier
+pub fn keyword<'a>(keyword: &'static str) -> impl Parser<&'a str, Output = &'a str, Error = VerboseError<&'a str>> {
+    terminated(tag_no_case(keyword), not(satisfy(is_xid_continue)))
+}
+
+/// Parse function call with comma-separated arguments. `*` is no arguments, e.g. `COUNT(*)`
+pub fn function_call(input: &str) -> ParseResult<'_, FunctionCall> {
+    map(
+        (
+            identifier,
+            preceded(
+                ws(char('(')),
+                cut(terminated(
+                    alt((value(Vec::new(), ws(char('*'))), separated_list0(char(','), expression))),
+                    char(')'),
+                )),
+            ),
+        ),
+        |(name, arguments)| FunctionCall { name, arguments },
+    )
+    .parse(input)
+}
+
+pub fn additive_operator(input: &str) -> ParseResult<'_, BinaryOp> {
     alt((
-        delimited(tag("("), ws(expression), cut(tag(")"))),
-        map(literal, Expression::Literal),
-        map(identifier, Expression::Identifier),
+        value(BinaryOp::Add, char('+')),
+        value(BinaryOp::Sub, char('-')),
     ))
     .parse(input)
 }
 
-pub fn relation_operator(input: &str) -> ParseResult<BinaryOp> {
+pub fn relation_operator(input: &str) -> ParseResult<'_, BinaryOp> {
     alt((
         value(BinaryOp::Like, tag("LIKE")),
+        value(BinaryOp::StartsWith, tag("STARTSWITH")),
+        value(BinaryOp::EndsWith, tag("ENDSWITH")),
         value(BinaryOp::Gte, tag(">=")),
         value(BinaryOp::Gt, tag(">")),
+        value(BinaryOp::NullSafeEq, tag("<=>")),
         value(BinaryOp::Lte, tag("<=")),
         value(BinaryOp::Lt, tag("<")),
         value(BinaryOp::Eq, tag("=")),
@@ -180,33 +350,88 @@ pub fn relation_operator(input: &str) -> ParseResult<BinaryOp> {
 }
 
 /// Parse predicate
-pub fn predicate(input: &str) -> ParseResult<Predicate> {
+pub fn predicate(input: &str) -> ParseResult<'_, Predicate> {
     map(expression, |expr| Predicate { expr }).parse(input)
 }
 /// Parse query
-pub fn query(input: &str) -> ParseResult<Query> {
+pub fn query(input: &str) -> ParseResult<'_, Query> {
     map(
         ws((
             preceded(ws(tag_no_case("SELECT")), fields_projection),
+            opt(preceded(ws(keyword("FROM")), cut(identifier))),
             opt(preceded(ws(tag_no_case("WHERE")), predicate)),
+            opt(preceded(
+                ws((keyword("GROUP"), ws(keyword("BY")))),
+                cut(separated_list1(ws(char(',')), expression)),
+            )),
+            opt(preceded(ws(keyword("COLLATE")), cut(ws(collation)))),
         )),
-        |(fields_projection, predicate)| Query {
+        |(fields_projection, from, predicate, group_by, collation)| Query {
             fields_projection,
+            from,
             predicate,
+            group_by: group_by.unwrap_or_default(),
+            collation,
         },
     )
     .parse(input)
 }
 
+/// Parse collation name: `BINARY` or `NOCASE`
+pub fn collation(input: &str) -> ParseResult<'_, Collation> {
+    context(
+        "collation BINARY or NOCASE",
+        alt((
+            value(Collation::Binary, keyword("BINARY")),
+            value(Collation::NoCase, keyword("NOCASE")),
+        )),
+    )
+    .parse(input)
+}
+
+/// Parse insert statement: `INSERT (<column>, ...) VALUES (<expression>, ...), ...`
+pub fn insert(input: &str) -> ParseResult<'_, Insert> {
+    context(
+        "as many values as columns",
+        verify(
+            map(
+                ws((
+                    preceded(ws(keyword("INSERT")), parenthesized_list(identifier)),
+                    preceded(ws(keyword("VALUES")), separated_list1(ws(char(',')), parenthesized_list(expression))),
+                )),
+                |(columns, rows)| Insert { columns, rows },
+            ),
+            |insert: &Insert| insert.rows.iter().all(|row| row.len() == insert.columns.len()),
+        ),
+    )
+    .parse(input)
+}
+
+/// Parse comma-separated list of items in parentheses
+fn parenthesized_list<'a, O>(
+    item: impl Parser<&'a str, Output = O, Error = VerboseError<&'a str>>,
+) -> impl Parser<&'a str, Output = Vec<O>, Error = VerboseError<&'a str>> {
+    delimited(ws(char('(')), separated_list1(ws(char(',')), item), ws(char(')')))
+}
+
 /// Parse fields projection
-pub fn fields_projection(input: &str) -> ParseResult<FieldsProjection> {
+pub fn fields_projection(input: &str) -> ParseResult<'_, FieldsProjection> {
     map(separated_list1(ws(char(',')), field), FieldsProjection).parse(input)
 }
 
-pub fn field(input: &str) -> ParseResult<Field> {
+/// Parse field: `*` or expression with optional alias `AS <identifier>`.
+///
+/// If alias is not specified, the expression text without comments is used as a column name.
+pub fn field(input: &str) -> ParseResult<'_, Field> {
     alt((
-        map(identifier, Field::Name),
         value(Field::Asterisk, char('*')),
+        map(
+            (consumed(expression), opt(preceded(ws(keyword("AS")), identifier))),
+            |((text, expression), alias)| Field::Expression {
+                name: alias.map_or_else(|| strip_comments(text).trim().to_string(), |alias| alias.0),
+                expression,
+            },
+        ),
     ))
     .parse(input)
 }
@@ -228,7 +453,7 @@ mod tests {
 
         let invalid = number(input);
 
-        assert!(matches!(invalid, Err(_)));
+        assert!(invalid.is_err());
     }
 
     #[test]
@@ -243,7 +468,7 @@ mod tests {
 
         let invalid = string(input);
 
-        assert!(matches!(invalid, Err(_)));
+        assert!(invalid.is_err());
     }
 
     #[test]
@@ -258,7 +483,7 @@ mod tests {
 
         let invalid = string(input);
 
-        assert!(matches!(invalid, Err(_)));
+        assert!(invalid.is_err());
     }
 
     #[test]
@@ -276,6 +501,79 @@ mod tests {
         assert!(matches!(invalid, Ok(("ing", str)) if str == "str"));
     }
 
+    #[test]
+    fn parse_date_literal() {
+        let valid = literal("DATE '2024-12-12'");
+
+        assert!(matches!(valid, Ok(("", Literal::DateTime(date))) if date.to_string() == "2024-12-12 00:00:00 UTC"));
+
+        let valid = literal("datetime \"2024-12-12 20:20\"");
+
+        assert!(matches!(valid, Ok(("", Literal::DateTime(date))) if date.to_string() == "2024-12-12 20:20:00 UTC"));
+
+        let invalid = literal("DATE '2024-12-12 20:20'");
+
+        assert!(matches!(invalid, Err(nom::Err::Failure(_))));
+
+        let function = expression("DATE(date) = DATE '2024-12-12'").unwrap().1;
+
+        assert!(matches!(function, Expression::Operation(operation) if matches!(&*operation, Operation::Binary(BinaryOperation{
+            left_expression: Expression::FunctionCall(_),
+            right_expression: Expression::Literal(Literal::DateTime(_)),
+            ..
+        }))));
+    }
+
+    #[test]
+    fn parse_interval_arithmetic() {
+        let input = "date < NOW() + INTERVAL '7 days'";
+
+        let received = expression(input).unwrap().1;
+
+        let expect = Expression::Operation(Box::new(Operation::Binary(BinaryOperation{
+            op: BinaryOp::Lt,
+            left_expression: Expression::Identifier(Identifier("date".to_string())),
+            right_expression: Expression::Operation(Box::new(Operation::Binary(BinaryOperation{
+                op: BinaryOp::Add,
+                left_expression: Expression::FunctionCall(FunctionCall{
+                    name: Identifier("NOW".to_string()),
+                    arguments: vec![]
+                }),
+                right_expression: Expression::Literal(Literal::Duration(TimeDelta::days(7)))
+            })))
+        })));
+
+        assert_eq!(received, expect);
+
+        let input = "1 - 2 - -3";
+
+        let received = expression(input).unwrap().1;
+
+        let binary = |op, left_expression, right_expression| Expression::Operation(Box::new(Operation::Binary(BinaryOperation{
+            op,
+            left_expression,
+            right_expression
+        })));
+        let number = |number| Expression::Literal(Literal::Number(Number::Int(number)));
+        let expect = binary(BinaryOp::Sub, binary(BinaryOp::Sub, number(1), number(2)), number(-3));
+
+        assert_eq!(received, expect);
+
+        assert!(matches!(literal("INTERVAL '3 fortnights'"), Err(nom::Err::Failure(_))));
+        assert!(matches!(literal("DECIMAL '12.30'"), Ok(("", Literal::Number(Number::Decimal(decimal)))) if decimal.to_string() == "12.30"));
+        assert!(matches!(literal("DECIMAL '12,30'"), Err(nom::Err::Failure(_))));
+    }
+
+    #[test]
+    fn parse_relative_date() {
+        assert!(matches!(expression("date <= tomorrow"), Ok(("", Expression::Operation(operation))) if matches!(&*operation, Operation::Binary(BinaryOperation{
+            right_expression: Expression::RelativeDate(RelativeDate::Tomorrow),
+            ..
+        }))));
+        assert!(matches!(expression("THIS  WEEK"), Ok(("", Expression::RelativeDate(RelativeDate::ThisWeek)))));
+        assert!(matches!(expression("today_tasks"), Ok(("", Expression::Identifier(_)))));
+    }
+
     #[test]
     fn parse_fields() {
         let input = "field1, field2, field3";
@@ -291,6 +589,34 @@ mod tests {
         assert!(matches!(invalid, Ok((",", FieldsProjection(_)))));
     }
 
+    #[test]
+    fn parse_expression_fields() {
+        let (_, FieldsProjection(fields)) = fields_projection("name, date > NOW() AS overdue, LOWER(category)").unwrap();
+
+        let names = fields.iter().map(|field| match field {
+            Field::Expression { name, .. } => name.as_str(),
+            Field::Asterisk => "*",
+        });
+
+        assert!(names.eq(["name", "overdue", "LOWER(category)"]));
+        assert!(matches!(&fields[1], Field::Expression { expression: Expression::Operation(_), .. }));
+    }
+
+    #[test]
+    fn parse_comments() {
+        let input = "SELECT name, -- task name
+            date /* due date */
+            WHERE /* multi-line
+            comment */ status = 'on' -- pending tasks only";
+
+        let (rest, received) = query(input).unwrap();
+
+        assert_eq!(rest, "");
+        assert_eq!(received, query("SELECT name, date WHERE status = 'on'").unwrap().1);
+        assert_eq!(query("SELECT name WHERE name = '--not a comment'").unwrap().0, "");
+        assert!(matches!(query("SELECT name /* unterminated"), Ok((rest, _)) if rest == "/* unterminated"));
+    }
+
     #[test]
     fn parse_identifier() {
         let input = "_identifier_123";
@@ -299,11 +625,111 @@ mod tests {
 
         assert!(matches!(valid, Ok(("", Identifier(_)))));
 
+        let input = "task.metadata.owner";
+
+        assert_eq!(identifier(input), Ok(("", Identifier("task.metadata.owner".to_string()))));
+        assert!(matches!(identifier("task."), Ok((".", _))));
+
         let input = r#"123_identifier"#;
 
         let invalid = identifier(input);
 
-        assert!(matches!(invalid, Err(_)));
+        assert!(invalid.is_err());
+        assert_eq!(identifier("категория.имя_2"), Ok(("", Identifier("категория.имя_2".to_string()))));
+        assert_eq!(identifier("件名 = 1"), Ok((" = 1", Identifier("件名".to_string()))));
+        assert!(identifier("·name").is_err());
+    }
+
+    #[test]
+    fn parse_starts_with() {
+        let input = "name STARTSWITH 'Hello'";
+
+        let received = expression(input).unwrap().1;
+
+        let expect = Expression::Operation(Box::new(Operation::Binary(BinaryOperation{
+            op: BinaryOp::StartsWith,
+            left_expression: Expression::Identifier(Identifier("name".to_string())),
+            right_expression: Expression::Literal(Literal::String("Hello".to_string()))
+        })));
+
+        assert_eq!(received, expect)
+    }
+
+    #[test]
+    fn parse_negation() {
+        let input = "-(number) > - 5";
+
+        let received = expression(input).unwrap().1;
+
+        let neg = |expression| Expression::Operation(Box::new(Operation::Unary(UnaryOperation{
+            op: UnaryOp::Neg,
+            expression
+        })));
+        let expect = Expression::Operation(Box::new(Operation::Binary(BinaryOperation{
+            op: BinaryOp::Gt,
+            left_expression: neg(Expression::Identifier(Identifier("number".to_string()))),
+            right_expression: neg(Expression::Literal(Literal::Number(Number::Int(5))))
+        })));
+
+        assert_eq!(received, expect);
+
+        let input = "number > -5";
+
+        let received = expression(input).unwrap().1;
+
+        assert!(matches!(received, Expression::Operation(operation) if matches!(&*operation, Operation::Binary(BinaryOperation{
+            right_expression: Expression::Literal(Literal::Number(Number::Int(-5))),
+            ..
+        }))));
+    }
+
+    #[test]
+    fn parse_function_call() {
+        let input = "SUBSTR(LOWER(name), 1, 3) = 'abc'";
+
+        let received = expression(input).unwrap().1;
+
+        let expect = Expression::Operation(Box::new(Operation::Binary(BinaryOperation{
+            op: BinaryOp::Eq,
+            left_expression: Expression::FunctionCall(FunctionCall{
+                name: Identifier("SUBSTR".to_string()),
+                arguments: vec![
+                    Expression::FunctionCall(FunctionCall{
+                        name: Identifier("LOWER".to_string()),
+                        arguments: vec![Expression::Identifier(Identifier("name".to_string()))]
+                    }),
+                    Expression::Literal(Literal::Number(Number::Int(1))),
+                    Expression::Literal(Literal::Number(Number::Int(3))),
+                ]
+            }),
+            right_expression: Expression::Literal(Literal::String("abc".to_string()))
+        })));
+
+        assert_eq!(received, expect);
+
+        let input = "NOW()";
+
+        let received = expression(input).unwrap().1;
+
+        assert!(matches!(received, Expression::FunctionCall(FunctionCall{ arguments, .. }) if arguments.is_empty()));
+    }
+
+    #[test]
+    fn parse_list() {
+        let string = |string: &str| Expression::Literal(Literal::String(string.to_string()));
+        let number = |number: i64| Expression::Literal(Literal::Number(Number::Int(number)));
+
+        assert_eq!(expression("('a', 'b' , 'c')").unwrap().1, Expression::List(vec![string("a"), string("b"), string("c")]));
+        assert_eq!(expression("[1, 2, 3]").unwrap().1, Expression::List(vec![number(1), number(2), number(3)]));
+        assert_eq!(expression("[ 1 ]").unwrap().1, Expression::List(vec![number(1)]));
+        assert_eq!(expression("[ ]").unwrap().1, Expression::List(vec![]));
+        assert_eq!(expression("(1)").unwrap().1, number(1));
+        assert!(expression("[1, 2").is_err());
+
+        let Expression::In(in_subquery) = expression("name IN ('a', 'b')").unwrap().1 else {
+            panic!("expected IN");
+        };
+        assert_eq!(in_subquery.subquery, Subquery::Values(Expression::List(vec![string("a"), string("b")])));
     }
 
     #[test]
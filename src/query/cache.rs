@@ -0,0 +1,127 @@
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+use crate::query::ast::ParseError;
+use crate::query::evaluator::context::ExecutionContext;
+use crate::query::Query;
+
+/// Number of the queries, that are kept parsed by [`Query::parse_cached`] by default.
+pub const DEFAULT_CAPACITY: usize = 64;
+
+/// Least recently used cache of the parsed queries, keyed by the query text. Hits and evictions take `O(log n)`.
+///
+/// Queries, that fail to parse, are not cached.
+#[derive(Debug)]
+pub struct QueryCache {
+    capacity: usize,
+    /// Tick of the latest use, that increases with every use.
+    tick: u64,
+    /// Cached queries with the ticks of their latest use.
+    entries: HashMap<String, (u64, Query)>,
+    /// Query texts by the ticks of their latest use, from the least to the most recently used one.
+    recency: BTreeMap<u64, String>,
+}
+
+impl QueryCache {
+    /// Create empty cache, that keeps at most `capacity` queries.
+    pub fn new(capacity: usize) -> QueryCache {
+        QueryCache { capacity, tick: 0, entries: HashMap::new(), recency: BTreeMap::new() }
+    }
+
+    /// Returns the query parsed from `text`, parsing it only if it is not cached.
+    ///
+    /// When the cache is full, the least recently used query is evicted.
+    pub fn parse(&mut self, text: &str) -> Result<Query, ParseError> {
+        self.tick += 1;
+        if let Some((tick, query)) = self.entries.get_mut(text) {
+            let key = self.recency.remove(tick).expect("cached query has a tick");
+            self.recency.insert(self.tick, key);
+            *tick = self.tick;
+            return Ok(query.clone());
+        }
+
+        let query = Query::from_str(text)?;
+        if self.capacity > 0 {
+            if self.entries.len() == self.capacity {
+                if let Some((_, key)) = self.recency.pop_first() {
+                    self.entries.remove(&key);
+                }
+            }
+            self.entries.insert(text.to_string(), (self.tick, query.clone()));
+            self.recency.insert(self.tick, text.to_string());
+        }
+
+        Ok(query)
+    }
+
+    /// Returns the number of the cached queries.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true`, if no query is cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Default for QueryCache {
+    fn default() -> QueryCache {
+        QueryCache::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl Query {
+    /// Parse query from `text` through the [`QueryCache`] of the current [`ExecutionContext`].
+    ///
+    /// Used where the same query is parsed over and over, e.g. in the repl.
+    pub fn parse_cached(text: &str) -> Result<Query, ParseError> {
+        let cache = ExecutionContext::with(|context| context.query_cache.clone());
+        let mut cache = cache.lock().unwrap_or_else(|err| err.into_inner());
+        cache.parse(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use super::*;
+
+    #[test]
+    fn lru_eviction() {
+        let mut cache = QueryCache::new(2);
+        let keys = |cache: &QueryCache| cache.recency.values().cloned().collect::<Vec<_>>();
+
+        let first = cache.parse("SELECT a").unwrap();
+        assert_eq!(first, Query::from_str("SELECT a").unwrap());
+        cache.parse("SELECT b").unwrap();
+        assert_eq!(cache.parse("SELECT a").unwrap(), first);
+        cache.parse("SELECT c").unwrap();
+        assert_eq!(cache.len(), 2);
+        assert_eq!(keys(&cache), ["SELECT a", "SELECT c"]);
+
+        assert!(cache.parse("SELECT").is_err());
+        assert_eq!(cache.parse("SELECT a").unwrap(), first);
+        assert_eq!(keys(&cache), ["SELECT c", "SELECT a"]);
+        assert_eq!(cache.entries.len(), cache.recency.len());
+
+        let mut disabled = QueryCache::new(0);
+        assert_eq!(disabled.parse("SELECT a").unwrap(), first);
+        assert!(disabled.is_empty());
+    }
+
+    #[test]
+    fn cache_of_context() {
+        let context = ExecutionContext { query_cache: Arc::new(Mutex::new(QueryCache::new(1))), ..ExecutionContext::default() };
+        let len = |context: &ExecutionContext| context.query_cache.lock().unwrap().len();
+
+        context.clone().scope(|| Query::parse_cached("SELECT a")).unwrap();
+        assert_eq!(len(&context), 1);
+
+        let other = ExecutionContext::default();
+        other.scope(|| Query::parse_cached("SELECT a")).unwrap();
+        assert_eq!(len(&other), 1);
+        context.scope(|| Query::parse_cached("SELECT b")).unwrap();
+        assert_eq!(len(&context), 1);
+        assert_eq!(len(&other), 1);
+    }
+}
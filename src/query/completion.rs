@@ -0,0 +1,153 @@
+use std::ops::Range;
+use std::str::FromStr;
+use unicode_ident::is_xid_continue;
+use crate::query::ast::expression::{BinaryOp, RelativeDate};
+use crate::query::evaluator::function::Function;
+use crate::query::reflect::Reflectable;
+use crate::query::Query;
+
+/// Identifier, that stands for the rest of the query, when the candidates are probed.
+const PLACEHOLDER: &str = "__placeholder";
+
+/// Kind of the suggested text.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SuggestionKind {
+    Field,
+    Function,
+    Keyword,
+    Operator,
+}
+
+/// Text, that may be inserted at the cursor.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Suggestion {
+    pub text: String,
+    pub kind: SuggestionKind,
+    /// Range of the input, that is replaced by the text: the word before the cursor, that is partially typed.
+    pub replace: Range<usize>,
+}
+
+impl Suggestion {
+    /// Returns the `input` with the suggestion applied.
+    pub fn apply(&self, input: &str) -> String {
+        let mut output = input.to_string();
+        output.replace_range(self.replace.clone(), &self.text);
+        output
+    }
+}
+
+/// Suggest keywords, fields of `T`, functions and operators, that may be typed at the `cursor` of the query `input`.
+///
+/// Candidates are the ones, that start with the word before the cursor. Every candidate is probed by the parser:
+/// it is suggested, if the query before the word followed by the candidate, is a valid beginning of the query.
+pub fn complete<T: Reflectable>(input: &str, cursor: usize) -> Vec<Suggestion> {
+    let Some(typed) = input.get(..cursor) else {
+        return Vec::new();
+    };
+    // Word is either identifier or keyword, or operator.
+    let word_start = match typed.trim_end_matches(|char: char| is_xid_continue(char) || char == '.').len() {
+        cursor_start if cursor_start == cursor => typed.trim_end_matches(['<', '>', '=']).len(),
+        word_start => word_start,
+    };
+    let (before, word) = typed.split_at(word_start);
+    let Some(closing) = closing_brackets(before) else {
+        return Vec::new();
+    };
+    let is_valid = |probe: &str| Query::from_str(&format!("{before}{probe}{closing}")).is_ok();
+
+    let fields = T::field_names();
+    let fields = fields.iter().map(|field| (field.to_string(), SuggestionKind::Field, field.to_string()));
+    let functions = Function::all()
+        .into_iter()
+        .map(|function| (format!("{}(", function.name), SuggestionKind::Function, format!("{}()", function.name)));
+    let keywords = [
+        ("SELECT", "SELECT *".to_string()),
+        ("FROM", format!("FROM {PLACEHOLDER}")),
+        ("WHERE", format!("WHERE {PLACEHOLDER}")),
+        ("AS", format!("AS {PLACEHOLDER}")),
+        ("NOT", format!("NOT {PLACEHOLDER}")),
+        ("COLLATE", "COLLATE NOCASE".to_string()),
+        ("NOCASE", "NOCASE".to_string()),
+        ("BINARY", "BINARY".to_string()),
+        ("IN", format!("IN ({PLACEHOLDER})")),
+        ("NULL", "NULL".to_string()),
+        ("TRUE", "true".to_string()),
+        ("FALSE", "false".to_string()),
+        ("DECIMAL", "DECIMAL '1.0'".to_string()),
+        ("DATE", "DATE '2024-12-12'".to_string()),
+        ("DATETIME", "DATETIME '2024-12-12 20:20'".to_string()),
+        ("INTERVAL", "INTERVAL '1 day'".to_string()),
+    ]
+    .into_iter()
+    .map(|(keyword, probe)| (keyword.to_string(), SuggestionKind::Keyword, probe))
+    .chain(RelativeDate::ALL.iter().map(|date| (date.to_string(), SuggestionKind::Keyword, date.to_string())));
+    let operators = BinaryOp::ALL
+        .iter()
+        .map(|op| (op.to_string(), SuggestionKind::Operator, format!("{op} {PLACEHOLDER}")));
+
+    let mut suggestions: Vec<Suggestion> = Vec::new();
+    for (text, kind, probe) in fields.chain(functions).chain(keywords).chain(operators) {
+        let is_typed = text.get(..word.len()).is_some_and(|prefix| prefix.eq_ignore_ascii_case(word));
+        if is_typed && !suggestions.iter().any(|suggestion| suggestion.text == text) && is_valid(&probe) {
+            suggestions.push(Suggestion { text, kind, replace: word_start..cursor });
+        }
+    }
+
+    suggestions
+}
+
+/// Returns the brackets, that close the brackets opened in the `text`, or `None` if the text ends inside a string.
+fn closing_brackets(text: &str) -> Option<String> {
+    let mut opened = Vec::new();
+    let mut quote = None;
+    let mut is_escaped = false;
+    for char in text.chars() {
+        match (quote, char) {
+            (Some(_), '\\') if !is_escaped => {
+                is_escaped = true;
+                continue;
+            }
+            (Some(open), char) if char == open && !is_escaped => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(char),
+            (None, '(') => opened.push(')'),
+            (None, '[') => opened.push(']'),
+            (None, ')' | ']') => {
+                opened.pop();
+            }
+            (None, _) => {}
+        }
+        is_escaped = false;
+    }
+
+    quote.is_none().then(|| opened.into_iter().rev().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::query::reflect::tests::TestReflect;
+    use super::*;
+
+    fn texts(input: &str) -> Vec<String> {
+        complete::<TestReflect>(input, input.len()).into_iter().map(|suggestion| suggestion.text).collect()
+    }
+
+    #[test]
+    fn complete_query() {
+        assert_eq!(texts("SEL"), ["SELECT"]);
+        assert_eq!(texts("SELECT str"), ["string"]);
+        assert!(texts("SELECT string ").contains(&"WHERE".to_string()));
+        assert!(!texts("SELECT string ").contains(&"string".to_string()));
+        assert_eq!(texts("SELECT * WHERE up"), ["UPPER("]);
+        assert_eq!(texts("SELECT * WHERE upper(num"), ["number"]);
+        assert!(texts("SELECT * WHERE number ").contains(&"AND".to_string()));
+        assert!(texts("SELECT * WHERE number ").contains(&">=".to_string()));
+        assert_eq!(texts("SELECT * WHERE number <"), ["<", "<=", "<=>"]);
+        assert_eq!(texts("SELECT * WHERE date > tod"), ["TODAY"]);
+        assert!(texts("SELECT * WHERE string = 'str").is_empty());
+
+        let suggestion = &complete::<TestReflect>("SELECT num, string", 10)[0];
+        assert_eq!(suggestion.kind, SuggestionKind::Field);
+        assert_eq!(suggestion.apply("SELECT num, string"), "SELECT number, string");
+    }
+}
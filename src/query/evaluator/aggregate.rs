@@ -0,0 +1,449 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use chrono::{DateTime, TimeDelta, Utc};
+use crate::query::ast::expression::{
+    BinaryOperation, Expression, FunctionCall, Identifier, InSubquery, Operation, Subquery, UnaryOperation,
+};
+use crate::query::ast::{Collation, Field, FieldsProjection, Query};
+use crate::query::evaluator::reflect::{FieldsIterator, ReflectError, Reflectable, Type};
+use crate::query::evaluator::result_set::ResultSet;
+use crate::query::evaluator::cancel::check_cancelled;
+use crate::query::evaluator::limits::check_deadline;
+use crate::query::evaluator::trace::item_error;
+use crate::query::evaluator::value::{Number, Value};
+use crate::query::EvaluationError;
+
+/// Aggregate function, that is evaluated over the items of the group rather than a single item.
+///
+/// Aggregates skip `NULL` values. Over no values `COUNT` is `0`, the other aggregates are `NULL`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Aggregate {
+    Count,
+    Sum,
+    Min,
+    Max,
+    Avg,
+}
+
+impl Aggregate {
+    /// All aggregate functions.
+    pub const ALL: [Aggregate; 5] = [Aggregate::Count, Aggregate::Sum, Aggregate::Min, Aggregate::Max, Aggregate::Avg];
+
+    /// Returns aggregate function with specified `name`. Names are case-insensitive.
+    pub fn lookup(name: &str) -> Option<Aggregate> {
+        Aggregate::ALL.into_iter().find(|aggregate| aggregate.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Aggregate::Count => "COUNT",
+            Aggregate::Sum => "SUM",
+            Aggregate::Min => "MIN",
+            Aggregate::Max => "MAX",
+            Aggregate::Avg => "AVG",
+        }
+    }
+
+    /// Allowed number of arguments. `COUNT()` and `COUNT(*)` count the items.
+    pub fn arity(self) -> RangeInclusive<usize> {
+        match self {
+            Aggregate::Count => 0..=1,
+            _ => 1..=1,
+        }
+    }
+
+    /// Names of the parameters, as they are shown in the query language reference.
+    pub fn parameters(self) -> &'static str {
+        match self {
+            Aggregate::Count => "*|value",
+            _ => "value",
+        }
+    }
+
+    /// Short description, that is shown in the query language reference.
+    pub fn description(self) -> &'static str {
+        match self {
+            Aggregate::Count => "Returns number of the items, or of the values, that are not NULL",
+            Aggregate::Sum => "Returns sum of the values",
+            Aggregate::Min => "Returns the least value",
+            Aggregate::Max => "Returns the greatest value",
+            Aggregate::Avg => "Returns average of the numbers or durations",
+        }
+    }
+
+    /// Evaluate the aggregate function over the `values`, e.g. of the column of the [`ResultSet`].
+    pub fn apply<'a>(self, values: impl IntoIterator<Item = Value<'a>>) -> Result<Value<'static>, EvaluationError> {
+        let mut accumulator = Accumulator::new(self);
+        for value in values {
+            accumulator.update(value)?;
+        }
+
+        accumulator.finish()
+    }
+}
+
+/// Running value of the aggregate function, that is updated by the values one by one, so the values of the group
+/// are not kept.
+enum Accumulator {
+    Count(i64),
+    Sum(Value<'static>),
+    Min(Value<'static>),
+    Max(Value<'static>),
+    Avg { sum: Value<'static>, count: i64 },
+}
+
+impl Accumulator {
+    fn new(aggregate: Aggregate) -> Accumulator {
+        match aggregate {
+            Aggregate::Count => Accumulator::Count(0),
+            Aggregate::Sum => Accumulator::Sum(Value::Null),
+            Aggregate::Min => Accumulator::Min(Value::Null),
+            Aggregate::Max => Accumulator::Max(Value::Null),
+            Aggregate::Avg => Accumulator::Avg { sum: Value::Null, count: 0 },
+        }
+    }
+
+    /// Add the `value` of the item. `NULL` values are skipped.
+    fn update(&mut self, value: Value<'_>) -> Result<(), EvaluationError> {
+        if value == Value::Null {
+            return Ok(());
+        }
+        let add = |sum: &Value<'static>, value: Value<'_>| match sum {
+            Value::Null => Ok(value.into_static()),
+            sum => Value::add(sum, &value),
+        };
+        match self {
+            Accumulator::Count(count) => *count += 1,
+            Accumulator::Sum(sum) => *sum = add(sum, value)?,
+            Accumulator::Avg { sum, count } => {
+                *sum = add(sum, value)?;
+                *count += 1;
+            }
+            Accumulator::Min(min) => {
+                if *min == Value::Null || Value::lt(&value, min)? == Value::Bool(true) {
+                    *min = value.into_static();
+                }
+            }
+            Accumulator::Max(max) => {
+                if *max == Value::Null || Value::gt(&value, max)? == Value::Bool(true) {
+                    *max = value.into_static();
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Value<'static>, EvaluationError> {
+        Ok(match self {
+            Accumulator::Count(count) => Value::Number(count.into()),
+            Accumulator::Sum(value) | Accumulator::Min(value) | Accumulator::Max(value) => value,
+            Accumulator::Avg { sum: Value::Null, .. } => Value::Null,
+            Accumulator::Avg { sum: Value::Duration(sum), count } => {
+                // Average is divided in nanoseconds, that never overflow 128 bits, and it is not greater than the sum.
+                let nanos = i128::from(sum.num_seconds()) * 1_000_000_000 + i128::from(sum.subsec_nanos());
+                let average = nanos / i128::from(count);
+                Value::Duration(TimeDelta::seconds((average / 1_000_000_000) as i64) + TimeDelta::nanoseconds((average % 1_000_000_000) as i64))
+            }
+            Accumulator::Avg { sum, count } => Value::Number(Number::Float(sum.cast_to_number()?.as_f64() / count as f64)),
+        })
+    }
+}
+
+/// Aggregate function call of the projected expression.
+struct AggregateCall {
+    aggregate: Aggregate,
+    /// Name of the column, which expression calls the aggregate.
+    column: String,
+    argument: Option<Expression>,
+}
+
+impl Query {
+    /// Tests that the query aggregates the items: it has `GROUP BY` or selects aggregate functions.
+    pub fn is_aggregate(&self) -> bool {
+        !self.group_by.is_empty() || self.fields_projection.0.iter().any(|field| match field {
+            Field::Asterisk => false,
+            Field::Expression { expression, .. } => expression.has_aggregate(),
+        })
+    }
+}
+
+impl Expression {
+    /// Tests that the expression calls an aggregate function. Aggregates of the subqueries are not taken into account.
+    pub fn has_aggregate(&self) -> bool {
+        match self {
+            Expression::FunctionCall(call) => {
+                Aggregate::lookup(&call.name.0).is_some() || call.arguments.iter().any(Expression::has_aggregate)
+            }
+            Expression::Operation(operation) => match &**operation {
+                Operation::Unary(operation) => operation.expression.has_aggregate(),
+                Operation::Binary(operation) => {
+                    operation.left_expression.has_aggregate() || operation.right_expression.has_aggregate()
+                }
+            },
+            Expression::In(in_subquery) => {
+                in_subquery.expression.has_aggregate()
+                    || matches!(&in_subquery.subquery, Subquery::Values(values) if values.has_aggregate())
+            }
+            Expression::List(items) => items.iter().any(Expression::has_aggregate),
+            _ => false,
+        }
+    }
+
+    /// Returns the expression of the `column`, which aggregate calls are moved to `calls` and replaced by the
+    /// identifiers `#<index>` of their values.
+    fn extract_aggregates(self, column: &str, calls: &mut Vec<AggregateCall>) -> Expression {
+        match self {
+            Expression::FunctionCall(call) => match Aggregate::lookup(&call.name.0) {
+                Some(aggregate) => {
+                    calls.push(AggregateCall { aggregate, column: column.to_string(), argument: call.arguments.into_iter().next() });
+                    Expression::Identifier(Identifier(format!("#{}", calls.len() - 1)))
+                }
+                None => Expression::FunctionCall(FunctionCall {
+                    name: call.name,
+                    arguments: call.arguments.into_iter().map(|argument| argument.extract_aggregates(column, calls)).collect(),
+                }),
+            },
+            Expression::Operation(operation) => Expression::Operation(Box::new(match *operation {
+                Operation::Unary(UnaryOperation { expression, op }) => {
+                    Operation::Unary(UnaryOperation { expression: expression.extract_aggregates(column, calls), op })
+                }
+                Operation::Binary(BinaryOperation { left_expression, op, right_expression }) => Operation::Binary(BinaryOperation {
+                    left_expression: left_expression.extract_aggregates(column, calls),
+                    op,
+                    right_expression: right_expression.extract_aggregates(column, calls),
+                }),
+            })),
+            Expression::In(in_subquery) => {
+                let InSubquery { expression, subquery } = *in_subquery;
+                let subquery = match subquery {
+                    Subquery::Values(values) => Subquery::Values(values.extract_aggregates(column, calls)),
+                    subquery => subquery,
+                };
+                Expression::In(Box::new(InSubquery { expression: expression.extract_aggregates(column, calls), subquery }))
+            }
+            Expression::List(items) => Expression::List(items.into_iter().map(|item| item.extract_aggregates(column, calls)).collect()),
+            expression => expression,
+        }
+    }
+}
+
+/// Value of the `GROUP BY` expression, by which the group is found. Keys of the equal values are equal: numbers are
+/// compared by value regardless of their type, e.g. `1` and `1.0` are one group, and strings are folded by the
+/// collation.
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum GroupKey {
+    Null,
+    Bool(bool),
+    /// Integral number.
+    Int(i64),
+    /// Bits of the fractional number in `f64`, in which the numbers of different types are compared.
+    Float(u64),
+    String(String),
+    DateTime(DateTime<Utc>),
+    Duration(TimeDelta),
+    List(Vec<GroupKey>),
+}
+
+impl GroupKey {
+    fn new(value: &Value, collation: Collation) -> GroupKey {
+        match value {
+            Value::Null => GroupKey::Null,
+            Value::Bool(bool) => GroupKey::Bool(*bool),
+            Value::Number(number) => GroupKey::number(*number),
+            Value::String(string) => GroupKey::String(collation.fold(string).into_owned()),
+            Value::DateTime(date_time) => GroupKey::DateTime(*date_time),
+            Value::Duration(duration) => GroupKey::Duration(*duration),
+            Value::List(values) => GroupKey::List(values.iter().map(|value| GroupKey::new(value, collation)).collect()),
+        }
+    }
+
+    fn number(number: Number) -> GroupKey {
+        let integral = match number {
+            Number::Int(int) => Some(int),
+            Number::Decimal(decimal) => (decimal.mantissa() % 10i64.pow(decimal.scale()) == 0).then(|| decimal.as_i64()),
+            Number::Float(float) => (float.fract() == 0. && (i64::MIN as f64..i64::MAX as f64).contains(&float)).then_some(float as i64),
+        };
+        match integral {
+            Some(int) => GroupKey::Int(int),
+            None => GroupKey::Float(number.as_f64().to_bits()),
+        }
+    }
+}
+
+/// Items, that have the same values of the `GROUP BY` expressions.
+struct Group<'a, T> {
+    /// The first item of the group, that the fields outside of the aggregates are read from.
+    item: Option<&'a T>,
+    accumulators: Vec<Accumulator>,
+}
+
+/// Group with the values of its aggregates, that are read as the fields `#<index>`.
+struct Aggregated<'a, T> {
+    item: Option<&'a T>,
+    values: Vec<Value<'static>>,
+}
+
+impl<T: Reflectable> Reflectable for Aggregated<'_, T> {
+    fn get_field(&self, field: &str) -> Result<Value<'_>, ReflectError> {
+        if let Some(value) = field.strip_prefix('#').and_then(|index| index.parse::<usize>().ok()).and_then(|index| self.values.get(index)) {
+            return Ok(value.clone());
+        }
+        match self.item {
+            Some(item) => item.get_field(field),
+            None => Ok(Value::Null),
+        }
+    }
+
+    fn fields(&self) -> FieldsIterator<'_> {
+        match self.item {
+            Some(item) => item.fields(),
+            None => Box::new(std::iter::empty()),
+        }
+    }
+
+    fn field_names() -> Cow<'static, [Cow<'static, str>]> {
+        T::field_names()
+    }
+
+    fn field_types() -> Cow<'static, [(Cow<'static, str>, Type)]> {
+        T::field_types()
+    }
+
+    fn get_nested(&self, field: &str) -> Result<&dyn Reflectable, ReflectError> {
+        match self.item {
+            Some(item) => item.get_nested(field),
+            None => Err(ReflectError::NoField(field.to_string())),
+        }
+    }
+}
+
+impl FieldsProjection {
+    /// Projects groups of the `items`, that have the same values of the `group_by` expressions, to the rows of the
+    /// [`ResultSet`].
+    ///
+    /// Groups are kept in a hash map by their key and only the running values of their aggregates are kept, so the
+    /// memory depends on the number of groups rather than the items. Without `group_by` all items are a single group,
+    /// even if there are no items. Fields outside of the aggregates are read from the first item of the group, that
+    /// is the same for any item, as [`Query::validate`] allows only the `GROUP BY` fields outside of the aggregates.
+    /// Rows are in the order of the first items of the groups.
+    pub fn aggregate<'a, T: Reflectable + 'a>(
+        &self,
+        group_by: &[Expression],
+        items: impl IntoIterator<Item = &'a T>,
+    ) -> Result<ResultSet, EvaluationError> {
+        let mut calls = Vec::new();
+        let fields = self
+            .0
+            .iter()
+            .filter_map(|field| match field {
+                Field::Asterisk => None,
+                Field::Expression { expression, name } => Some((name, expression.clone().extract_aggregates(name, &mut calls))),
+            })
+            .collect::<Vec<_>>();
+        let new_group = |item| Group { item, accumulators: calls.iter().map(|call| Accumulator::new(call.aggregate)).collect() };
+
+        let collation = Collation::current();
+        let mut keys = HashMap::new();
+        let mut groups = Vec::new();
+        if group_by.is_empty() {
+            groups.push(new_group(None));
+        }
+        for item in items {
+            check_deadline()?;
+            check_cancelled()?;
+            let index = if group_by.is_empty() {
+                0
+            } else {
+                let key = group_by
+                    .iter()
+                    .map(|expression| {
+                        let value = expression.eval(item).map_err(|err| item_error(err, "the GROUP BY expression".to_string(), expression, item))?;
+                        Ok(GroupKey::new(&value, collation))
+                    })
+                    .collect::<Result<Vec<_>, EvaluationError>>()?;
+                *keys.entry(key).or_insert_with(|| {
+                    groups.push(new_group(Some(item)));
+                    groups.len() - 1
+                })
+            };
+            let group = &mut groups[index];
+            group.item.get_or_insert(item);
+            for (call, accumulator) in calls.iter().zip(&mut group.accumulators) {
+                let value = match &call.argument {
+                    Some(argument) => argument
+                        .eval(item)
+                        .map_err(|err| item_error(err, format!("column '{}'", call.column), argument, item))?,
+                    None => Value::Bool(true),
+                };
+                accumulator.update(value)?;
+            }
+        }
+
+        let mut result_set = ResultSet::with_columns(self.columns::<T>());
+        for group in groups {
+            let values = group.accumulators.into_iter().map(Accumulator::finish).collect::<Result<_, _>>()?;
+            let aggregated = Aggregated { item: group.item, values };
+            let row = fields
+                .iter()
+                .map(|(name, expression)| Ok((name.as_str(), expression.eval(&aggregated)?.into_static())))
+                .collect::<Result<Vec<_>, EvaluationError>>()?;
+            result_set.add_row(row);
+        }
+
+        Ok(result_set)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use crate::query::reflect::tests::TestReflect;
+    use crate::query::evaluator::value::decimal::Decimal;
+    use super::*;
+
+    #[test]
+    fn aggregate_groups() {
+        let item = |string: &str, number| TestReflect { string: string.to_string(), number, ..Default::default() };
+        let items = [item("a", 1), item("b", 2), item("A", 3), item("b", 6)];
+        let select = |query: &str| {
+            let result_set = Query::from_str(query).unwrap().execute(&items).unwrap();
+            result_set.rows().map(|row| row.iter().map(ToString::to_string).collect::<Vec<_>>().join(" ")).collect::<Vec<_>>()
+        };
+
+        assert_eq!(select("SELECT COUNT(*), SUM(number), MIN(string), MAX(number), AVG(number)"), ["4 12 A 6 3"]);
+        assert_eq!(select("SELECT string, COUNT() AS count, SUM(number) + 1 GROUP BY string"), ["a 1 2", "b 2 9", "A 1 4"]);
+        assert_eq!(select("SELECT string, COUNT() GROUP BY string COLLATE NOCASE"), ["a 2", "b 2"]);
+        assert_eq!(select("SELECT COUNT(*), SUM(number) WHERE number > 10"), ["0 NULL"]);
+        assert_eq!(select("SELECT string WHERE number > 10 GROUP BY string"), Vec::<String>::new());
+        assert_eq!(select("SELECT string WHERE number IN (SELECT MAX(number))"), ["b"]);
+    }
+
+    #[test]
+    fn group_keys() {
+        let date_time = |date_time: &str| DateTime::parse_from_rfc3339(date_time).unwrap().to_utc();
+        let items = [
+            TestReflect { date_time: date_time("2024-01-01T10:00:00Z"), ..Default::default() },
+            TestReflect { date_time: date_time("2024-01-01T10:00:30Z"), ..Default::default() },
+        ];
+        let result_set = Query::from_str("SELECT COUNT() AS count GROUP BY date_time").unwrap().execute(&items).unwrap();
+        assert_eq!(result_set.get_column("count").collect::<Vec<_>>(), [&Value::Number(1.into()), &Value::Number(1.into())]);
+
+        let key = |value: Value| GroupKey::new(&value, Collation::Binary);
+        let string = |string: &'static str| Value::String(string.into());
+        assert_ne!(key(Value::List(vec![string("a, b")])), key(Value::List(vec![string("a"), string("b")])));
+        assert_eq!(key(Value::Number(1.into())), key(Value::Number(Decimal::new(10, 1).unwrap().into())));
+        assert_eq!(key(Value::Number(1.into())), key(Value::Number((1.).into())));
+        assert_eq!(key(Value::Number((0.5).into())), key(Value::Number(Decimal::new(50, 2).unwrap().into())));
+        assert_ne!(key(Value::Number(1.into())), key(string("1")));
+        assert_eq!(GroupKey::new(&string("Ä"), Collation::NoCase), GroupKey::new(&string("a"), Collation::NoCase));
+    }
+
+    #[test]
+    fn average_duration() {
+        let average = Accumulator::Avg { sum: Value::Duration(TimeDelta::hours(3)), count: i64::from(i32::MAX) + 1 };
+
+        assert_eq!(average.finish().unwrap(), Value::Duration(TimeDelta::nanoseconds(5029)));
+    }
+}
@@ -0,0 +1,74 @@
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use crate::query::EvaluationError;
+
+/// Token, that cancels the queries, which are executed within its [`scope`](CancellationToken::scope), e.g. when
+/// the user presses Ctrl-C. Clones of the token share the cancellation, so it is cancelled from another thread.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+thread_local! {
+    /// Tokens of the scopes, that are running on this thread.
+    static CURRENT: RefCell<Vec<CancellationToken>> = const { RefCell::new(Vec::new()) };
+}
+
+impl CancellationToken {
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Cancel the queries of the token. Queries fail with [`EvaluationError::Cancelled`] before the next item.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Runs `f`, which queries, e.g. of [`Storage::select`](crate::storage::Storage::select), are cancelled by the
+    /// token. Queries of the nested scopes are cancelled by the tokens of the outer scopes too.
+    pub fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        struct Pop;
+        impl Drop for Pop {
+            fn drop(&mut self) {
+                CURRENT.with_borrow_mut(Vec::pop);
+            }
+        }
+
+        CURRENT.with_borrow_mut(|tokens| tokens.push(self.clone()));
+        let _pop = Pop;
+        f()
+    }
+}
+
+/// Check, that the query, that is executed on this thread, is not cancelled. Called for every evaluated item.
+pub(crate) fn check_cancelled() -> Result<(), EvaluationError> {
+    if CURRENT.with_borrow(|tokens| tokens.iter().any(CancellationToken::is_cancelled)) {
+        Err(EvaluationError::Cancelled)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use crate::query::Query;
+    use crate::query::reflect::tests::TestReflect;
+    use super::*;
+
+    #[test]
+    fn cancel_query() {
+        let items = [TestReflect::default()];
+        let query = Query::from_str("SELECT string WHERE number IN (SELECT number)").unwrap();
+        let token = CancellationToken::new();
+
+        assert!(query.execute_cancellable(&items, &token).is_ok());
+        token.cancel();
+        assert!(matches!(query.execute_cancellable(&items, &token), Err(EvaluationError::Cancelled)));
+        assert!(CancellationToken::new().scope(|| token.scope(|| query.execute(&items))).is_err());
+        assert!(query.execute(&items).is_ok());
+    }
+}
@@ -0,0 +1,102 @@
+use std::borrow::Cow;
+use std::cell::Cell;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+use crate::query::ast::Collation;
+use crate::query::evaluator::value::Value;
+
+thread_local! {
+    /// Collation of the query, that is executed on this thread.
+    static CURRENT: Cell<Collation> = const { Cell::new(Collation::Binary) };
+}
+
+impl Collation {
+    /// Returns the collation of the query, that is executed on this thread.
+    pub fn current() -> Collation {
+        CURRENT.get()
+    }
+
+    /// Runs `f` with this collation applied to the string comparisons, restoring the previous one afterwards.
+    pub fn scope<R>(self, f: impl FnOnce() -> R) -> R {
+        struct Restore(Collation);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CURRENT.set(self.0);
+            }
+        }
+
+        let _restore = Restore(CURRENT.replace(self));
+        f()
+    }
+
+    /// Returns the `string` in the form, in which it is compared.
+    ///
+    /// `NOCASE` decomposes characters, drops the accents and converts the rest to lowercase.
+    pub fn fold<'a>(&self, string: &'a str) -> Cow<'a, str> {
+        match self {
+            Collation::Binary => Cow::Borrowed(string),
+            // Folded strings, e.g. the patterns folded by the optimizer, are not copied again.
+            Collation::NoCase if string.is_ascii() && !string.bytes().any(|byte| byte.is_ascii_uppercase()) => Cow::Borrowed(string),
+            Collation::NoCase if string.is_ascii() => Cow::Owned(string.to_ascii_lowercase()),
+            Collation::NoCase => Cow::Owned(string.nfd().filter(|char| !is_combining_mark(*char)).flat_map(char::to_lowercase).collect()),
+        }
+    }
+}
+
+impl<'v> Value<'v> {
+    /// Returns the value, which strings, including the ones in lists, are folded by the `collation`.
+    pub fn collate(&self, collation: Collation) -> Cow<'_, Value<'v>> {
+        match (self, collation) {
+            (_, Collation::Binary) => Cow::Borrowed(self),
+            (Value::String(string), collation) => match collation.fold(string) {
+                Cow::Borrowed(_) => Cow::Borrowed(self),
+                Cow::Owned(folded) => Cow::Owned(Value::String(folded.into())),
+            },
+            (Value::List(values), collation) => {
+                Cow::Owned(Value::List(values.iter().map(|value| value.collate(collation).into_owned()).collect()))
+            }
+            _ => Cow::Borrowed(self),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use crate::query::Query;
+    use crate::query::reflect::tests::TestReflect;
+    use super::*;
+
+    #[test]
+    fn nocase_collation() {
+        assert_eq!(Collation::NoCase.fold("Résumé CAFÉ Straße"), "resume cafe straße");
+        assert_eq!(Collation::Binary.fold("Résumé"), "Résumé");
+        assert_eq!(Collation::current(), Collation::Binary);
+
+        let equal = Collation::NoCase.scope(|| {
+            assert_eq!(Collation::current(), Collation::NoCase);
+            Value::eq(&Value::String("Résumé".into()), &Value::String("RESUME".into())).unwrap()
+        });
+        assert_eq!(equal, Value::Bool(true));
+        assert_eq!(Collation::current(), Collation::Binary);
+    }
+
+    #[test]
+    fn query_collation() {
+        let items = [
+            TestReflect { string: "Élan".to_string(), number: 1, ..Default::default() },
+            TestReflect { string: "elan".to_string(), number: 2, ..Default::default() },
+            TestReflect { string: "Other".to_string(), number: 3, ..Default::default() },
+        ];
+        let select = |query: &str| {
+            let result_set = Query::from_str(query).unwrap().execute(&items).unwrap();
+            result_set.get_column("number").map(ToString::to_string).collect::<Vec<_>>()
+        };
+
+        assert_eq!(select("SELECT number WHERE string = 'elan'"), ["2"]);
+        assert_eq!(select("SELECT number WHERE string = 'ELAN' COLLATE NOCASE"), ["1", "2"]);
+        assert_eq!(select("SELECT number WHERE string LIKE 'LA' OR string < 'f' COLLATE NOCASE"), ["1", "2"]);
+        assert_eq!(select("SELECT number WHERE 'OTHER' IN (SELECT string WHERE number > 2) COLLATE NOCASE"), ["1", "2", "3"]);
+        assert_eq!(select("SELECT number WHERE [string] = ['ÉLAN'] COLLATE BINARY"), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,175 @@
+use std::any::TypeId;
+use std::borrow::Cow;
+use std::str::FromStr;
+use std::sync::Arc;
+use thiserror::Error;
+use crate::query::ast::expression::Expression;
+use crate::query::ast::{Field, ParseError, Query};
+use crate::query::evaluator::reflect::{ReflectError, Reflectable, Value};
+use crate::query::evaluator::validate::ValidationError;
+use crate::query::evaluator::context::ExecutionContext;
+
+/// Field, that is computed from the other fields of the item by the expression, e.g. `overdue = date < NOW()`.
+///
+/// Computed fields are not stored: they are evaluated, when they are read.
+#[derive(Debug)]
+pub struct ComputedField {
+    pub name: String,
+    pub expression: Expression,
+}
+
+#[derive(Error, Debug)]
+pub enum ComputedFieldError {
+    #[error("Computed field must be defined as '<name>=<expression>', but it is '{0}'")]
+    Definition(String),
+    #[error("Field '{0}' already exists")]
+    Exists(String),
+    #[error("Expression of the computed field '{name}' is invalid. {source}")]
+    Parse { name: String, source: ParseError },
+    #[error("Expression of the computed field '{name}' is invalid. {source}")]
+    Invalid { name: String, source: ValidationError },
+}
+
+impl ExecutionContext {
+    /// Register the field `name` of the type `T`, that is computed by the `expression` over the other fields,
+    /// including the computed ones, that were registered in the context before it.
+    ///
+    /// Computed field is read as the other fields within the [`scope`](ExecutionContext::scope) of the context, if
+    /// the type supports computed fields, see [`get_computed`].
+    ///
+    /// ```
+    /// use todo_list::query::evaluator::context::ExecutionContext;
+    /// use todo_list::task::Task;
+    ///
+    /// let mut context = ExecutionContext::default();
+    /// context.register_field::<Task>("age_days", "-DAYS_UNTIL(date)").unwrap();
+    /// ```
+    pub fn register_field<T: Reflectable + 'static>(&mut self, name: &str, expression: &str) -> Result<(), ComputedFieldError> {
+        let field = self.scope(|| ComputedField::parse::<T>(name, expression))?;
+        self.computed_fields.push((TypeId::of::<T>(), Arc::new(field)));
+
+        Ok(())
+    }
+
+    /// Register the computed field of the type `T` by its `definition` `<name>=<expression>`, e.g.
+    /// `overdue=date < NOW()`.
+    pub fn register_definition<T: Reflectable + 'static>(&mut self, definition: &str) -> Result<(), ComputedFieldError> {
+        match definition.split_once('=') {
+            Some((name, expression)) if !name.trim().is_empty() => self.register_field::<T>(name.trim(), expression),
+            _ => Err(ComputedFieldError::Definition(definition.to_string())),
+        }
+    }
+}
+
+impl ComputedField {
+    /// Parse the field `name` of the type `T`, that is computed by the `expression`, and validate it against the
+    /// fields of `T`.
+    fn parse<T: Reflectable + 'static>(name: &str, expression: &str) -> Result<ComputedField, ComputedFieldError> {
+        if T::field_names().iter().any(|field| field == name) {
+            return Err(ComputedFieldError::Exists(name.to_string()));
+        }
+        let parse_error = |source| ComputedFieldError::Parse { name: name.to_string(), source };
+        let query = Query::from_str(&format!("SELECT {expression}")).map_err(parse_error)?;
+        query.validate::<T>().map_err(|source| ComputedFieldError::Invalid { name: name.to_string(), source })?;
+        match <[Field; 1]>::try_from(query.fields_projection.0) {
+            Ok([Field::Expression { expression, .. }]) if query.predicate.is_none() && query.from.is_none() => {
+                Ok(ComputedField { name: name.to_string(), expression })
+            }
+            _ => Err(ComputedFieldError::Definition(format!("{name}={expression}"))),
+        }
+    }
+}
+
+/// Returns computed fields of the type `T`, that are registered in the current [`ExecutionContext`].
+pub fn computed_fields<T: 'static>() -> Vec<Arc<ComputedField>> {
+    ExecutionContext::with(|context| {
+        context.computed_fields.iter().filter(|(type_id, _)| *type_id == TypeId::of::<T>()).map(|(_, field)| field.clone()).collect()
+    })
+}
+
+/// Returns `names` of the stored fields of the type `T` followed by the names of its computed fields.
+pub fn with_computed_names<T: 'static>(names: &'static [Cow<'static, str>]) -> Cow<'static, [Cow<'static, str>]> {
+    let computed = computed_fields::<T>();
+    if computed.is_empty() {
+        return Cow::Borrowed(names);
+    }
+
+    names.iter().cloned().chain(computed.into_iter().map(|field| Cow::Owned(field.name.clone()))).collect()
+}
+
+/// Returns the value of the computed `field` of the `item`.
+///
+/// If the type has no computed field with the name, or it cannot be evaluated, an error will be returned.
+pub fn get_computed<T: Reflectable + 'static>(item: &T, field: &str) -> Result<Value<'static>, ReflectError> {
+    let Some(computed) = computed_fields::<T>().into_iter().find(|computed| computed.name == field) else {
+        return Err(ReflectError::NoField(field.to_string()));
+    };
+
+    computed
+        .expression
+        .eval(item)
+        .map(Value::into_static)
+        .map_err(|err| ReflectError::Computed { field: field.to_string(), reason: err.to_string() })
+}
+
+/// Returns names and values of the computed fields of the `item`. Fields, that cannot be evaluated, are skipped.
+pub fn computed_values<'a, T: Reflectable + 'static>(item: &'a T) -> impl Iterator<Item = (Cow<'static, str>, Value<'a>)> + 'a {
+    computed_fields::<T>().into_iter().filter_map(|computed| {
+        let value: Value<'a> = computed.expression.eval(item).ok()?.into_static();
+        Some((Cow::Owned(computed.name.clone()), value))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::reflect::FieldsIterator;
+
+    struct Item {
+        number: i64,
+    }
+
+    impl Reflectable for Item {
+        fn get_field(&self, field: &str) -> Result<Value<'_>, ReflectError> {
+            match field {
+                "number" => Ok(Value::Number(self.number.into())),
+                field => get_computed(self, field),
+            }
+        }
+
+        fn fields(&self) -> FieldsIterator<'_> {
+            Box::new(std::iter::once(("number".into(), Value::Number(self.number.into()))).chain(computed_values(self)))
+        }
+
+        fn field_names() -> Cow<'static, [Cow<'static, str>]> {
+            with_computed_names::<Item>(&[Cow::Borrowed("number")])
+        }
+    }
+
+    #[test]
+    fn computed_field() {
+        let mut context = ExecutionContext::default();
+        context.register_field::<Item>("double", "number + number").unwrap();
+        context.register_definition::<Item>("large = double > 200").unwrap();
+        let item = Item { number: 125 };
+
+        context.scope(|| {
+            assert_eq!(item.get_field("double").unwrap(), Value::Number(250.into()));
+            assert_eq!(item.get_field("large").unwrap(), Value::Bool(true));
+            assert!(matches!(item.get_field("unknown"), Err(ReflectError::NoField(_))));
+            assert!(item.fields().map(|(name, _)| name).eq(["number", "double", "large"]));
+            assert!(Item::field_names().iter().eq(["number", "double", "large"]));
+        });
+        assert!(matches!(item.get_field("double"), Err(ReflectError::NoField(_))));
+        assert!(Item::field_names().iter().eq(["number"]));
+
+        let result_set = Query::from_str("SELECT double WHERE large").unwrap().execute_in([&item], &context).unwrap();
+        assert_eq!(result_set.rows().collect::<Vec<_>>(), [[Value::Number(250.into())]]);
+
+        assert!(matches!(context.register_field::<Item>("number", "1"), Err(ComputedFieldError::Exists(_))));
+        assert!(matches!(context.register_field::<Item>("double", "1"), Err(ComputedFieldError::Exists(_))));
+        assert!(matches!(context.register_field::<Item>("itself", "itself + 1"), Err(ComputedFieldError::Invalid { .. })));
+        assert!(matches!(context.register_field::<Item>("broken", "1 +"), Err(ComputedFieldError::Parse { .. })));
+        assert!(matches!(context.register_definition::<Item>("no definition"), Err(ComputedFieldError::Definition(_))));
+    }
+}
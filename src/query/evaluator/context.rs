@@ -0,0 +1,105 @@
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex};
+use chrono_tz::Tz;
+use crate::query::cache::QueryCache;
+use crate::query::evaluator::computed::ComputedField;
+use crate::query::evaluator::function::Function;
+use crate::query::evaluator::limits::Limits;
+#[cfg(feature = "table")]
+use crate::query::evaluator::result_set::TableLayout;
+
+/// Settings of the query execution: time zone of the user, date time formats, registered functions and computed
+/// fields, limits, tracing, cache of the parsed queries, and the layout and paging of the printed result sets.
+///
+/// Settings apply to the queries, that are executed within the [`scope`](ExecutionContext::scope) of the context,
+/// e.g. by [`Query::execute_in`](crate::query::Query::execute_in). Queries outside of any scope use the default one.
+#[derive(Clone, Default)]
+pub struct ExecutionContext {
+    /// Time zone of the user, see [`timezone`](crate::query::evaluator::timezone::timezone).
+    pub timezone: Tz,
+    /// Tolerance of the comparison with floats. Numbers, that differ by no more than it, are equal, so `0.1 + 0.2 = 0.3`
+    /// holds with `1e-9`. Zero makes the comparison exact.
+    pub float_epsilon: f64,
+    /// Limits of the queries. Not limited by default.
+    pub limits: Limits,
+    /// Trace the expression, that fails for an item, see [`is_tracing`](crate::query::evaluator::trace::is_tracing).
+    pub trace: bool,
+    /// Cache of [`Query::parse_cached`](crate::query::Query::parse_cached). Clones of the context share it.
+    pub query_cache: Arc<Mutex<QueryCache>>,
+    /// Layout of the result sets, that are printed by [`Display`](std::fmt::Display).
+    #[cfg(feature = "table")]
+    pub table_layout: TableLayout,
+    /// Rows per page of the printed query results. `None` disables paging.
+    #[cfg(feature = "table")]
+    pub page_size: Option<usize>,
+    /// Date time formats, that are tried before the built-in ones, the latest registered first.
+    pub(crate) datetime_formats: Arc<[String]>,
+    /// Functions, that are looked up before the built-in ones.
+    pub(crate) functions: Vec<Function>,
+    /// Computed fields of the types in the order of their registration.
+    pub(crate) computed_fields: Vec<(TypeId, Arc<ComputedField>)>,
+}
+
+thread_local! {
+    /// Contexts of the scopes, that are running on this thread.
+    static CURRENT: RefCell<Vec<ExecutionContext>> = const { RefCell::new(Vec::new()) };
+    /// Context of the queries, that are executed outside of any scope.
+    static DEFAULT: ExecutionContext = ExecutionContext::default();
+}
+
+impl ExecutionContext {
+    /// Returns the context of the innermost scope, that is running on this thread, or the default one.
+    pub fn current() -> ExecutionContext {
+        ExecutionContext::with(ExecutionContext::clone)
+    }
+
+    /// Calls `f` with the current context. `f` must not enter a scope.
+    pub(crate) fn with<R>(f: impl FnOnce(&ExecutionContext) -> R) -> R {
+        CURRENT.with_borrow(|contexts| match contexts.last() {
+            Some(context) => f(context),
+            None => DEFAULT.with(f),
+        })
+    }
+
+    /// Runs `f` with this context applied to the executed queries and the printed result sets, restoring the
+    /// previous one afterwards.
+    pub fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        struct Pop;
+        impl Drop for Pop {
+            fn drop(&mut self) {
+                CURRENT.with_borrow_mut(Vec::pop);
+            }
+        }
+
+        CURRENT.with_borrow_mut(|contexts| contexts.push(self.clone()));
+        let _pop = Pop;
+        f()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use crate::query::evaluator::validate::Constant;
+    use crate::query::Query;
+    use crate::query::reflect::Value;
+    use super::*;
+
+    #[test]
+    fn nested_scopes() {
+        let query = Query::from_str("SELECT 0.1 + 0.2 = 0.3 AS equal").unwrap();
+        let equal = |context: &ExecutionContext| {
+            let result_set = query.execute_in([&Constant], context).unwrap();
+            let equal = result_set.get_column("equal").next().cloned().unwrap();
+            equal
+        };
+        let tolerant = ExecutionContext { float_epsilon: 1e-9, ..ExecutionContext::default() };
+
+        assert_eq!(equal(&tolerant), Value::Bool(true));
+        assert_eq!(equal(&ExecutionContext::default()), Value::Bool(false));
+        assert_eq!(tolerant.scope(|| ExecutionContext::default().scope(ExecutionContext::current).float_epsilon), 0.);
+        assert_eq!(tolerant.scope(ExecutionContext::current).float_epsilon, 1e-9);
+        assert_eq!(ExecutionContext::current().float_epsilon, 0.);
+    }
+}
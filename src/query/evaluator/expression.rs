@@ -1,22 +1,45 @@
 use crate::query::evaluator::reflect::{Reflectable};
 use crate::query::evaluator::value::Value;
-use crate::query::ast::expression::{BinaryOp, BinaryOperation, Expression, Identifier, Literal, Operation, UnaryOp, UnaryOperation};
+use crate::query::evaluator::function::{Function, FunctionError};
+use crate::query::ast::expression::{BinaryOp, BinaryOperation, Expression, FunctionCall, Identifier, Literal, Operation, RelativeDate, UnaryOp, UnaryOperation};
+use chrono::{DateTime, TimeDelta, Utc};
 use crate::query::EvaluationError;
+use crate::query::evaluator::timezone::{timezone, to_local};
 
 impl Expression{
     /// Evaluate this expression with a given `context`.
-    pub fn eval<C: Reflectable + ?Sized>(&self, context: &C) -> Result<Value, EvaluationError>{
+    pub fn eval<'a, C: Reflectable + ?Sized>(&'a self, context: &'a C) -> Result<Value<'a>, EvaluationError>{
         match self {
             Expression::Identifier(identifier) => identifier.read(context),
             Expression::Literal(literal) => Ok(literal.value()),
-            Expression::Operation(operation) => operation.apply(context)
+            Expression::Operation(operation) => operation.apply(context),
+            Expression::FunctionCall(function_call) => function_call.call(context),
+            Expression::RelativeDate(relative_date) => Ok(relative_date.value_at(Utc::now())),
+            Expression::In(in_subquery) => in_subquery.apply(context),
+            Expression::List(items) => Ok(Value::List(
+                items.iter().map(|item| item.eval(context)).collect::<Result<_, _>>()?
+            )),
         }
     }
 }
 
+impl FunctionCall{
+    /// Evaluate arguments with a given `context` and call the function.
+    pub fn call<C: Reflectable + ?Sized>(&self, context: &C) -> Result<Value<'static>, EvaluationError>{
+        let function = Function::lookup(&self.name.0)
+            .ok_or_else(|| FunctionError::Unknown(self.name.0.to_string()))?;
+        let arguments = self.arguments
+            .iter()
+            .map(|argument| argument.eval(context))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        function.apply(&arguments)
+    }
+}
+
 impl Operation{
     /// Apply this operation with a given `context`.
-    pub fn apply<C: Reflectable + ?Sized>(&self, context: &C) -> Result<Value, EvaluationError>{
+    pub fn apply<C: Reflectable + ?Sized>(&self, context: &C) -> Result<Value<'static>, EvaluationError>{
         match self {
             Operation::Unary(binary_operator) => binary_operator.apply(context),
             Operation::Binary(unary_operator) => unary_operator.apply(context)
@@ -26,7 +49,7 @@ impl Operation{
 
 impl BinaryOperation{
     /// Apply this binary operation with a given `context`.
-    pub fn apply<C: Reflectable + ?Sized>(&self, context: &C) -> Result<Value, EvaluationError>{
+    pub fn apply<C: Reflectable + ?Sized>(&self, context: &C) -> Result<Value<'static>, EvaluationError>{
         let left = self.left_expression.eval(context)?;
         let right = self.right_expression.eval(context)?;
 
@@ -36,7 +59,12 @@ impl BinaryOperation{
             BinaryOp::Gte => Value::gte(&left, &right),
             BinaryOp::Lte => Value::lte(&left, &right),
             BinaryOp::Eq => Value::eq(&left, &right),
+            BinaryOp::NullSafeEq => Value::null_safe_eq(&left, &right),
             BinaryOp::Like => Value::like(&left, &right),
+            BinaryOp::StartsWith => Value::starts_with(&left, &right),
+            BinaryOp::EndsWith => Value::ends_with(&left, &right),
+            BinaryOp::Add => Value::add(&left, &right),
+            BinaryOp::Sub => Value::sub(&left, &right),
             BinaryOp::And => Value::and(&left, &right),
             BinaryOp::Or => Value::or(&left, &right),
         }
@@ -45,29 +73,59 @@ impl BinaryOperation{
 
 impl UnaryOperation{
     /// Apply this unary operation with a given `context`.
-    pub fn apply<C: Reflectable + ?Sized>(&self, context: &C) -> Result<Value, EvaluationError>{
+    pub fn apply<C: Reflectable + ?Sized>(&self, context: &C) -> Result<Value<'static>, EvaluationError>{
         let value = self.expression.eval(context)?;
 
         match self.op {
-            UnaryOp::Not => Value::not(&value)
+            UnaryOp::Not => Value::not(&value),
+            UnaryOp::Neg => Value::neg(&value)
         }
     }
 }
 
 impl Identifier{
     /// Read the value of identifier for a given `context`.
-    pub fn read<C: Reflectable + ?Sized>(&self, context: &C) -> Result<Value, EvaluationError>{
-        Ok(context.get_field(&self.0)?)
+    ///
+    /// Dotted path is traversed through the nested [`Reflectable`] values, the last segment is read as field.
+    pub fn read<'a, C: Reflectable + ?Sized>(&self, context: &'a C) -> Result<Value<'a>, EvaluationError>{
+        let Some((first, mut path)) = self.0.split_once('.') else {
+            return Ok(context.get_field(&self.0)?);
+        };
+        let mut context = context.get_nested(first)?;
+        while let Some((segment, rest)) = path.split_once('.') {
+            context = context.get_nested(segment)?;
+            path = rest;
+        }
+
+        Ok(context.get_field(path)?)
     }
 }
 
 impl Literal{
     /// Convert literal to the value.
-    pub fn value(&self) -> Value{
+    pub fn value(&self) -> Value<'_>{
         Value::from(self)
     }
 }
 
+impl RelativeDate{
+    /// Expand relative date at the moment `now`.
+    ///
+    /// Days are expanded to the date-only value `%Y-%m-%d`, weeks to the ISO week `%G-W%V` of the user's
+    /// [`timezone`], so comparison with date time covers the whole period.
+    pub fn value_at(&self, now: DateTime<Utc>) -> Value<'_>{
+        let (days, format) = match self {
+            RelativeDate::Yesterday => (-1, "%Y-%m-%d"),
+            RelativeDate::Today => (0, "%Y-%m-%d"),
+            RelativeDate::Tomorrow => (1, "%Y-%m-%d"),
+            RelativeDate::LastWeek => (-7, "%G-W%V"),
+            RelativeDate::ThisWeek => (0, "%G-W%V"),
+            RelativeDate::NextWeek => (7, "%G-W%V"),
+        };
+
+        Value::String((to_local(timezone(), now) + TimeDelta::days(days)).format(format).to_string().into())
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -90,6 +148,38 @@ mod tests {
         assert!(matches!(value, Ok(Value::Number(Number::Int(125)))));
     }
 
+    #[test]
+    fn nested_identifier() {
+        struct Nested(TestReflect);
+        impl Reflectable for Nested {
+            fn get_field(&self, field: &str) -> Result<Value<'_>, ReflectError> {
+                Err(ReflectError::NoField(field.to_string()))
+            }
+            fn fields(&self) -> crate::query::reflect::FieldsIterator<'_> {
+                Box::new(std::iter::empty())
+            }
+            fn field_names() -> std::borrow::Cow<'static, [std::borrow::Cow<'static, str>]> {
+                (&[]).into()
+            }
+            fn get_nested(&self, field: &str) -> Result<&dyn Reflectable, ReflectError> {
+                match field {
+                    "inner" => Ok(&self.0),
+                    field => Err(ReflectError::NoField(field.to_string())),
+                }
+            }
+        }
+        let test_context = Nested(TestReflect::default());
+
+        let value = Identifier("inner.number".to_string()).read(&test_context);
+        assert!(matches!(value, Ok(Value::Number(Number::Int(125)))));
+
+        let no_field = Identifier("inner.number.value".to_string()).read(&test_context);
+        assert!(matches!(no_field, Err(EvaluationError::Reflect(ReflectError::NoField(field))) if field == "number"));
+
+        let no_field = Identifier("number".to_string()).read(&test_context);
+        assert!(matches!(no_field, Err(EvaluationError::Reflect(ReflectError::NoField(_)))));
+    }
+
     #[test]
     fn invalid_identifier() {
         let test_context = TestReflect::default();
@@ -125,6 +215,58 @@ mod tests {
         assert!(matches!(value, Err(EvaluationError::Conversion(ConversionError::NotAllowed { .. }))));
     }
 
+    #[test]
+    fn negation() {
+        let test_reflect = TestReflect::default();
+
+        let exp = UnaryOperation{
+            expression: Expression::Identifier(Identifier("number".to_string())),
+            op: UnaryOp::Neg
+        };
+
+        let value = exp.apply(&test_reflect);
+
+        assert!(matches!(value, Ok(Value::Number(Number::Int(-125)))));
+    }
+
+    #[test]
+    fn function_call() {
+        let test_reflect = TestReflect::default();
+
+        let exp = FunctionCall{
+            name: Identifier("upper".to_string()),
+            arguments: vec![Expression::Identifier(Identifier("string".to_string()))]
+        };
+
+        assert!(matches!(exp.call(&test_reflect), Ok(Value::String(str)) if &*str == "DEFAULT STRING"));
+
+        let exp = FunctionCall{
+            name: Identifier("no_function".to_string()),
+            arguments: vec![]
+        };
+
+        assert!(matches!(exp.call(&test_reflect), Err(EvaluationError::Function(FunctionError::Unknown(_)))));
+    }
+
+    #[test]
+    fn relative_date() {
+        let now = chrono::NaiveDateTime::parse_from_str("2024-12-31 20:20", "%Y-%m-%d %H:%M").unwrap().and_utc();
+
+        assert_eq!(RelativeDate::Today.value_at(now), Value::String("2024-12-31".into()));
+        assert_eq!(RelativeDate::Tomorrow.value_at(now), Value::String("2025-01-01".into()));
+        assert_eq!(RelativeDate::Yesterday.value_at(now), Value::String("2024-12-30".into()));
+        assert_eq!(RelativeDate::ThisWeek.value_at(now), Value::String("2025-W01".into()));
+        assert_eq!(RelativeDate::LastWeek.value_at(now), Value::String("2024-W52".into()));
+
+        let exp = BinaryOperation{
+            left_expression: Expression::Literal(Literal::DateTime(now)),
+            op: BinaryOp::Lt,
+            right_expression: Expression::RelativeDate(RelativeDate::Tomorrow),
+        };
+
+        assert!(matches!(exp.apply(&EmptyContext), Ok(Value::Bool(true))));
+    }
+
     #[test]
     fn valid_binary_operation() {
         let test_reflect = TestReflect::default();
@@ -0,0 +1,459 @@
+use std::ops::RangeInclusive;
+use chrono::{Datelike, NaiveTime, TimeDelta, Timelike, Utc};
+use thiserror::Error;
+use crate::query::evaluator::value::{Number, Value};
+use crate::query::EvaluationError;
+use crate::query::evaluator::timezone::{from_local, timezone, to_local};
+use crate::query::evaluator::context::ExecutionContext;
+
+/// Scalar function, that can be called from the query expression.
+#[derive(Clone)]
+pub struct Function {
+    /// Name of the function. Function names are case-insensitive.
+    pub name: &'static str,
+    /// Allowed number of arguments.
+    pub arity: RangeInclusive<usize>,
+    /// Names of the parameters, as they are shown in the query language reference.
+    pub parameters: &'static str,
+    /// Short description, that is shown in the query language reference.
+    pub description: &'static str,
+    /// Implementation of the function. Called with the number of arguments within `arity`.
+    pub call: &'static FunctionCall,
+}
+
+/// Implementation of the scalar function.
+pub type FunctionCall = dyn Fn(&[Value]) -> Result<Value<'static>, EvaluationError> + Send + Sync;
+
+/// Built-in scalar functions.
+pub static BUILTIN_FUNCTIONS: &[Function] = &[
+    Function { name: "LOWER", arity: 1..=1, parameters: "string", description: "Converts string to lower case", call: &lower },
+    Function { name: "UPPER", arity: 1..=1, parameters: "string", description: "Converts string to upper case", call: &upper },
+    Function { name: "TRIM", arity: 1..=1, parameters: "string", description: "Removes leading and trailing whitespace", call: &trim },
+    Function { name: "LENGTH", arity: 1..=1, parameters: "value", description: "Returns number of characters of the string, or number of list items", call: &length },
+    Function { name: "CONTAINS", arity: 2..=2, parameters: "list, value", description: "Tests that the list contains the value", call: &contains },
+    Function { name: "SUBSTR", arity: 2..=3, parameters: "string, start[, length]", description: "Returns substring starting at 1-based position", call: &substr },
+    Function { name: "NOW", arity: 0..=0, parameters: "", description: "Returns current date time", call: &now },
+    Function { name: "DATE", arity: 1..=1, parameters: "date", description: "Truncates date time to the start of the day", call: &date },
+    Function { name: "DATE_TRUNC", arity: 2..=2, parameters: "unit, date", description: "Truncates date time to the start of the 'minute', 'hour', 'day', 'week', 'month' or 'year'", call: &date_trunc },
+    Function { name: "SAME_DAY", arity: 2..=2, parameters: "date, date", description: "Tests that the date times are on the same calendar day", call: &same_day },
+    Function { name: "YEAR", arity: 1..=1, parameters: "date", description: "Returns year of the date", call: &year },
+    Function { name: "MONTH", arity: 1..=1, parameters: "date", description: "Returns month of the date", call: &month },
+    Function { name: "DAY", arity: 1..=1, parameters: "date", description: "Returns day of month of the date", call: &day },
+    Function { name: "ADD_DAYS", arity: 2..=2, parameters: "date, days", description: "Adds possibly fractional or negative number of days", call: &add_days },
+    Function { name: "DAYS_UNTIL", arity: 1..=1, parameters: "date", description: "Returns number of calendar days from today until the date", call: &days_until },
+    Function { name: "OVERDUE", arity: 1..=1, parameters: "date", description: "Tests that the date has already passed", call: &overdue },
+    Function { name: "COALESCE", arity: 1..=usize::MAX, parameters: "value, ...", description: "Returns the first argument, that is not NULL", call: &coalesce },
+    Function { name: "IFNULL", arity: 2..=2, parameters: "value, default", description: "Returns default if value is NULL", call: &coalesce },
+];
+
+impl Function {
+    /// Returns function with specified `name`, that is registered in the current [`ExecutionContext`], or built-in.
+    pub fn lookup(name: &str) -> Option<Function> {
+        ExecutionContext::with(|context| {
+            context.functions.iter().chain(BUILTIN_FUNCTIONS).find(|function| function.name.eq_ignore_ascii_case(name)).cloned()
+        })
+    }
+
+    /// Returns functions registered in the current [`ExecutionContext`] followed by the built-in ones, that are not
+    /// overridden by them.
+    pub fn all() -> Vec<Function> {
+        ExecutionContext::with(|context| {
+            let builtin = BUILTIN_FUNCTIONS.iter().filter(|function| {
+                !context.functions.iter().any(|registered| registered.name.eq_ignore_ascii_case(function.name))
+            });
+
+            context.functions.iter().chain(builtin).cloned().collect()
+        })
+    }
+
+    /// Call the function with `arguments`.
+    ///
+    /// If the number of arguments is not within function arity, an error will be returned.
+    pub fn apply(&self, arguments: &[Value]) -> Result<Value<'static>, EvaluationError> {
+        if !self.arity.contains(&arguments.len()) {
+            return Err(FunctionError::Arity {
+                name: self.name,
+                min: *self.arity.start(),
+                max: *self.arity.end(),
+                received: arguments.len(),
+            }.into());
+        }
+
+        (self.call)(arguments)
+    }
+}
+
+impl ExecutionContext {
+    /// Register the `function`, that becomes callable from the queries of the context. Function replaces the
+    /// registered or built-in function with the same name.
+    pub fn register_function(&mut self, function: Function) {
+        self.functions.retain(|registered| !registered.name.eq_ignore_ascii_case(function.name));
+        self.functions.push(function);
+    }
+
+    /// Register function `name`, that accepts any number of arguments and is implemented by `call`.
+    ///
+    /// Implementation lives until the process exits.
+    ///
+    /// ```
+    /// use todo_list::query::evaluator::context::ExecutionContext;
+    /// use todo_list::query::evaluator::function::Function;
+    /// use todo_list::query::reflect::Value;
+    ///
+    /// let mut context = ExecutionContext::default();
+    /// context.register_fn("DOUBLE", |arguments| Ok(Value::Number((arguments[0].cast_to_number()?.as_i64() * 2).into())));
+    ///
+    /// let double = context.scope(|| Function::lookup("double")).unwrap();
+    /// assert_eq!(double.apply(&[Value::Number(21.into())]).unwrap(), Value::Number(42.into()));
+    /// ```
+    pub fn register_fn(
+        &mut self,
+        name: &'static str,
+        call: impl Fn(&[Value]) -> Result<Value<'static>, EvaluationError> + Send + Sync + 'static,
+    ) {
+        self.register_function(Function {
+            name,
+            arity: 0..=usize::MAX,
+            parameters: "...",
+            description: "User-defined function",
+            call: Box::leak(Box::new(call)),
+        });
+    }
+}
+
+/// Returns `NULL` if any of the `arguments` is `NULL`, otherwise applies `function`.
+fn null_propagating(
+    arguments: &[Value],
+    function: impl FnOnce(&[Value]) -> Result<Value<'static>, EvaluationError>,
+) -> Result<Value<'static>, EvaluationError> {
+    if arguments.contains(&Value::Null) {
+        Ok(Value::Null)
+    } else {
+        function(arguments)
+    }
+}
+
+fn lower(arguments: &[Value]) -> Result<Value<'static>, EvaluationError> {
+    null_propagating(arguments, |arguments| {
+        Ok(Value::String(arguments[0].cast_to_string()?.to_lowercase().into()))
+    })
+}
+
+fn upper(arguments: &[Value]) -> Result<Value<'static>, EvaluationError> {
+    null_propagating(arguments, |arguments| {
+        Ok(Value::String(arguments[0].cast_to_string()?.to_uppercase().into()))
+    })
+}
+
+fn trim(arguments: &[Value]) -> Result<Value<'static>, EvaluationError> {
+    null_propagating(arguments, |arguments| {
+        Ok(Value::String(arguments[0].cast_to_string()?.trim().to_string().into()))
+    })
+}
+
+fn length(arguments: &[Value]) -> Result<Value<'static>, EvaluationError> {
+    null_propagating(arguments, |arguments| {
+        let length = match &arguments[0] {
+            Value::List(items) => items.len(),
+            value => value.cast_to_string()?.chars().count(),
+        };
+
+        Ok(Value::Number(Number::Int(length as i64)))
+    })
+}
+
+/// Tests that the list contains the value. Value, that is not a list, is a list of one item.
+///
+/// Unlike other functions, `NULL` value is not propagated, but is searched in the list.
+fn contains(arguments: &[Value]) -> Result<Value<'static>, EvaluationError> {
+    match &arguments[0] {
+        Value::Null => Ok(Value::Null),
+        list => Ok(Value::Bool(list.contains(&arguments[1])?)),
+    }
+}
+
+/// Returns substring starting at 1-based position `start` with at most `length` characters.
+fn substr(arguments: &[Value]) -> Result<Value<'static>, EvaluationError> {
+    null_propagating(arguments, |arguments| {
+        let string = arguments[0].cast_to_string()?;
+        let start = arguments[1].cast_to_number()?.as_i64().max(1) as usize - 1;
+        let length = match arguments.get(2) {
+            Some(length) => length.cast_to_number()?.as_i64().max(0) as usize,
+            None => usize::MAX,
+        };
+
+        Ok(Value::String(string.chars().skip(start).take(length).collect::<String>().into()))
+    })
+}
+
+fn now(_: &[Value]) -> Result<Value<'static>, EvaluationError> {
+    Ok(Value::DateTime(Utc::now()))
+}
+
+/// Truncates date time to the start of the day in the user's time zone.
+fn date(arguments: &[Value]) -> Result<Value<'static>, EvaluationError> {
+    null_propagating(arguments, |arguments| {
+        let tz = timezone();
+        let date = to_local(tz, arguments[0].cast_to_datetime()?).date();
+
+        Ok(Value::DateTime(from_local(tz, date.and_time(NaiveTime::MIN))))
+    })
+}
+
+/// Truncates date time to the start of the `unit` in the user's time zone. Weeks start on Monday.
+fn date_trunc(arguments: &[Value]) -> Result<Value<'static>, EvaluationError> {
+    null_propagating(arguments, |arguments| {
+        let tz = timezone();
+        let local = to_local(tz, arguments[1].cast_to_datetime()?);
+        let date = local.date();
+        let unit = arguments[0].cast_to_string()?;
+        let truncated = match unit.to_lowercase().as_str() {
+            "minute" => date.and_hms_opt(local.hour(), local.minute(), 0),
+            "hour" => date.and_hms_opt(local.hour(), 0, 0),
+            "day" => Some(date.and_time(NaiveTime::MIN)),
+            "week" => Some((date - TimeDelta::days(date.weekday().num_days_from_monday().into())).and_time(NaiveTime::MIN)),
+            "month" => date.with_day(1).map(|date| date.and_time(NaiveTime::MIN)),
+            "year" => date.with_ordinal(1).map(|date| date.and_time(NaiveTime::MIN)),
+            _ => {
+                return Err(FunctionError::Failed {
+                    name: "DATE_TRUNC",
+                    reason: format!("Unit '{unit}' is none of 'minute', 'hour', 'day', 'week', 'month' and 'year'"),
+                }.into())
+            }
+        };
+
+        Ok(truncated.map_or(Value::Null, |truncated| Value::DateTime(from_local(tz, truncated))))
+    })
+}
+
+/// Tests that the date times are on the same day of the user's time zone.
+fn same_day(arguments: &[Value]) -> Result<Value<'static>, EvaluationError> {
+    null_propagating(arguments, |arguments| {
+        let tz = timezone();
+        let day = |value: &Value| Ok::<_, EvaluationError>(to_local(tz, value.cast_to_datetime()?).date());
+
+        Ok(Value::Bool(day(&arguments[0])? == day(&arguments[1])?))
+    })
+}
+
+fn year(arguments: &[Value]) -> Result<Value<'static>, EvaluationError> {
+    null_propagating(arguments, |arguments| {
+        Ok(Value::Number(Number::Int(to_local(timezone(), arguments[0].cast_to_datetime()?).year() as i64)))
+    })
+}
+
+fn month(arguments: &[Value]) -> Result<Value<'static>, EvaluationError> {
+    null_propagating(arguments, |arguments| {
+        Ok(Value::Number(Number::Int(to_local(timezone(), arguments[0].cast_to_datetime()?).month() as i64)))
+    })
+}
+
+fn day(arguments: &[Value]) -> Result<Value<'static>, EvaluationError> {
+    null_propagating(arguments, |arguments| {
+        Ok(Value::Number(Number::Int(to_local(timezone(), arguments[0].cast_to_datetime()?).day() as i64)))
+    })
+}
+
+/// Adds a number of days to the date time. Number of days may be fractional or negative.
+fn add_days(arguments: &[Value]) -> Result<Value<'static>, EvaluationError> {
+    null_propagating(arguments, |arguments| {
+        let date_time = arguments[0].cast_to_datetime()?;
+        let days = arguments[1].cast_to_number()?.as_f64();
+
+        TimeDelta::try_milliseconds((days * 86_400_000.0) as i64)
+            .and_then(|delta| date_time.checked_add_signed(delta))
+            .map(Value::DateTime)
+            .ok_or_else(|| FunctionError::Failed {
+                name: "ADD_DAYS",
+                reason: "Date is out-of-range".to_string(),
+            }.into())
+    })
+}
+
+/// Returns the number of calendar days of the user's time zone from today until the date. Negative for past dates.
+fn days_until(arguments: &[Value]) -> Result<Value<'static>, EvaluationError> {
+    null_propagating(arguments, |arguments| {
+        let tz = timezone();
+        let date = to_local(tz, arguments[0].cast_to_datetime()?).date();
+        let days = date.signed_duration_since(to_local(tz, Utc::now()).date()).num_days();
+
+        Ok(Value::Number(Number::Int(days)))
+    })
+}
+
+/// Tests that the date has already passed.
+fn overdue(arguments: &[Value]) -> Result<Value<'static>, EvaluationError> {
+    null_propagating(arguments, |arguments| {
+        Ok(Value::Bool(arguments[0].cast_to_datetime()? < Utc::now()))
+    })
+}
+
+/// Returns the first argument, that is not `NULL`, or `NULL` if all of them are `NULL`.
+fn coalesce(arguments: &[Value]) -> Result<Value<'static>, EvaluationError> {
+    Ok(arguments
+        .iter()
+        .find(|argument| **argument != Value::Null)
+        .map(Value::to_static)
+        .unwrap_or(Value::Null))
+}
+
+/// Represents possible errors of function call.
+#[derive(Error, Debug)]
+pub enum FunctionError {
+    #[error("Function '{0}' not exists")]
+    Unknown(String),
+    #[error("Function '{name}' expects {} arguments, but {received} were given", expected_arity(*.min, *.max))]
+    Arity {
+        name: &'static str,
+        min: usize,
+        max: usize,
+        received: usize,
+    },
+    #[error("Failed to call function '{name}'. \nReason: {reason}")]
+    Failed {
+        name: &'static str,
+        reason: String,
+    },
+}
+
+pub(crate) fn expected_arity(min: usize, max: usize) -> String {
+    match (min, max) {
+        (min, max) if min == max => min.to_string(),
+        (min, usize::MAX) => format!("at least {min}"),
+        (min, max) => format!("from {min} to {max}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use crate::query::evaluator::validate::Constant;
+    use crate::query::Query;
+    use super::*;
+
+    fn call(name: &str, arguments: &[Value]) -> Result<Value<'static>, EvaluationError> {
+        Function::lookup(name).unwrap().apply(arguments)
+    }
+
+    fn string(value: &str) -> Value<'static> {
+        Value::String(value.to_string().into())
+    }
+
+    #[test]
+    fn lookup_case_insensitive() {
+        assert!(Function::lookup("lower").is_some());
+        assert!(Function::lookup("Lower").is_some());
+        assert!(Function::lookup("no_function").is_none());
+    }
+
+    #[test]
+    fn string_functions() {
+        assert!(matches!(call("LOWER", &[string("HeLLo")]), Ok(Value::String(str)) if &*str == "hello"));
+        assert!(matches!(call("UPPER", &[string("HeLLo")]), Ok(Value::String(str)) if &*str == "HELLO"));
+        assert!(matches!(call("TRIM", &[string("  Hello ")]), Ok(Value::String(str)) if &*str == "Hello"));
+        assert!(matches!(call("LENGTH", &[string("Привет")]), Ok(Value::Number(Number::Int(6)))));
+        assert!(matches!(call("LENGTH", &[Value::Null]), Ok(Value::Null)));
+        assert!(matches!(call("LENGTH", &[Value::List(vec![string("a"), Value::Null])]), Ok(Value::Number(Number::Int(2)))));
+    }
+
+    #[test]
+    fn substr() {
+        assert!(matches!(call("SUBSTR", &[string("Hello World"), Value::Number(7.into())]), Ok(Value::String(str)) if &*str == "World"));
+        assert!(matches!(call("SUBSTR", &[string("Hello World"), Value::Number(1.into()), Value::Number(5.into())]), Ok(Value::String(str)) if &*str == "Hello"));
+        assert!(matches!(call("SUBSTR", &[string("Hello"), Value::Number(10.into())]), Ok(Value::String(str)) if str.is_empty()));
+    }
+
+    #[test]
+    fn date_functions() {
+        let date_time = || string("2024-12-12 20:20");
+
+        assert!(matches!(call("YEAR", &[date_time()]), Ok(Value::Number(Number::Int(2024)))));
+        assert!(matches!(call("MONTH", &[date_time()]), Ok(Value::Number(Number::Int(12)))));
+        assert!(matches!(call("DAY", &[date_time()]), Ok(Value::Number(Number::Int(12)))));
+        assert_eq!(call("DATE", &[date_time()]).unwrap().to_string(), "2024-12-12 00:00");
+        assert_eq!(call("DATE_TRUNC", &[string("hour"), date_time()]).unwrap().to_string(), "2024-12-12 20:00");
+        assert_eq!(call("DATE_TRUNC", &[string("Week"), date_time()]).unwrap().to_string(), "2024-12-09 00:00");
+        assert_eq!(call("DATE_TRUNC", &[string("year"), date_time()]).unwrap().to_string(), "2024-01-01 00:00");
+        assert!(matches!(call("DATE_TRUNC", &[string("fortnight"), date_time()]), Err(EvaluationError::Function(FunctionError::Failed { .. }))));
+        assert!(matches!(call("SAME_DAY", &[date_time(), string("2024-12-12 01:00")]), Ok(Value::Bool(true))));
+        assert!(matches!(call("SAME_DAY", &[date_time(), string("2024-12-13 01:00")]), Ok(Value::Bool(false))));
+        assert_eq!(call("ADD_DAYS", &[date_time(), Value::Number(20.into())]).unwrap().to_string(), "2025-01-01 20:20");
+        assert_eq!(call("ADD_DAYS", &[date_time(), Value::Number((-0.5).into())]).unwrap().to_string(), "2024-12-12 08:20");
+        assert!(matches!(call("ADD_DAYS", &[date_time(), Value::Number(1e300.into())]), Err(EvaluationError::Function(FunctionError::Failed { .. }))));
+        assert!(matches!(call("NOW", &[]), Ok(Value::DateTime(_))));
+    }
+
+    #[test]
+    fn due_functions() {
+        let in_three_days = Value::DateTime(Utc::now() + TimeDelta::days(3));
+        let hour_ago = Value::DateTime(Utc::now() - TimeDelta::hours(1));
+        let week_ago = Value::DateTime(Utc::now() - TimeDelta::weeks(1));
+
+        assert!(matches!(call("DAYS_UNTIL", std::slice::from_ref(&in_three_days)), Ok(Value::Number(Number::Int(3)))));
+        assert!(matches!(call("DAYS_UNTIL", &[week_ago]), Ok(Value::Number(Number::Int(-7)))));
+        assert!(matches!(call("OVERDUE", &[in_three_days]), Ok(Value::Bool(false))));
+        assert!(matches!(call("OVERDUE", &[hour_ago]), Ok(Value::Bool(true))));
+        assert!(matches!(call("OVERDUE", &[Value::Null]), Ok(Value::Null)));
+    }
+
+    #[test]
+    fn contains() {
+        let list = Value::List(vec![string("work"), Value::Number(1.into()), Value::Null]);
+
+        assert!(matches!(call("CONTAINS", &[list.clone(), string("work")]), Ok(Value::Bool(true))));
+        assert!(matches!(call("CONTAINS", &[list.clone(), string("1")]), Ok(Value::Bool(true))));
+        assert!(matches!(call("CONTAINS", &[list.clone(), Value::Null]), Ok(Value::Bool(true))));
+        assert!(matches!(call("CONTAINS", &[list, string("home")]), Err(EvaluationError::Conversion(_))));
+        assert!(matches!(call("CONTAINS", &[Value::List(vec![string("work")]), string("home")]), Ok(Value::Bool(false))));
+        assert!(matches!(call("CONTAINS", &[string("work"), string("work")]), Ok(Value::Bool(true))));
+        assert!(matches!(call("CONTAINS", &[Value::Null, string("work")]), Ok(Value::Null)));
+    }
+
+    #[test]
+    fn coalesce() {
+        assert!(matches!(call("COALESCE", &[Value::Null, string("default"), string("other")]), Ok(Value::String(str)) if &*str == "default"));
+        assert!(matches!(call("COALESCE", &[Value::Null, Value::Null]), Ok(Value::Null)));
+        assert!(matches!(call("IFNULL", &[Value::Number(1.into()), Value::Number(2.into())]), Ok(Value::Number(Number::Int(1)))));
+        assert!(matches!(call("IFNULL", &[Value::Null]), Err(EvaluationError::Function(FunctionError::Arity { .. }))));
+        assert_eq!(call("COALESCE", &[]).unwrap_err().to_string(), "Function 'COALESCE' expects at least 1 arguments, but 0 were given");
+    }
+
+    #[test]
+    fn wrong_arity() {
+        assert!(matches!(call("LOWER", &[]), Err(EvaluationError::Function(FunctionError::Arity { received: 0, .. }))));
+        assert!(matches!(call("SUBSTR", &[string("Hello")]), Err(EvaluationError::Function(FunctionError::Arity { .. }))));
+    }
+
+    #[test]
+    fn registered_function() {
+        let query = |text: &str| {
+            let query = Query::from_str(&format!("SELECT {text} AS value")).unwrap();
+            let result_set = query.execute([&Constant])?;
+            let value = result_set.get_column("value").next().cloned().unwrap();
+            Ok::<_, EvaluationError>(value)
+        };
+        assert!(query("test_repeat('ab', 2)").is_err());
+
+        let mut context = ExecutionContext::default();
+        context.register_fn("TEST_REPEAT", |arguments| Ok(string(&"?".repeat(arguments.len()))));
+        assert_eq!(context.scope(|| query("test_repeat('ab', 2)")).unwrap(), string("??"));
+
+        let separator = String::from("-");
+        context.register_function(Function {
+            name: "test_repeat",
+            arity: 2..=2,
+            parameters: "string, count",
+            description: "Repeats the string",
+            call: Box::leak(Box::new(move |arguments: &[Value]| {
+                let count = arguments[1].cast_to_number()?.as_i64() as usize;
+                Ok(string(&vec![arguments[0].cast_to_string()?.to_string(); count].join(&separator)))
+            })),
+        });
+        context.scope(|| {
+            assert_eq!(query("TEST_REPEAT('ab', 2)").unwrap(), string("ab-ab"));
+            assert_eq!(Function::all().iter().filter(|function| function.name.eq_ignore_ascii_case("test_repeat")).count(), 1);
+            assert!(matches!(query("test_repeat('ab')"), Err(EvaluationError::Validation(_))));
+        });
+        assert!(query("test_repeat('ab', 2)").is_err());
+    }
+}
@@ -0,0 +1,58 @@
+use crate::query::ast::Insert;
+use crate::query::evaluator::reflect::FromFields;
+use crate::query::evaluator::validate::Constant;
+use crate::query::EvaluationError;
+use std::collections::HashMap;
+
+impl Insert {
+    /// Evaluate rows of the statement to the inserted items.
+    ///
+    /// Values are evaluated without item context, so they may be literals, relative dates, function calls and
+    /// operations on them, but not identifiers.
+    pub fn values<T: FromFields>(&self) -> Result<Vec<T>, EvaluationError> {
+        self.rows
+            .iter()
+            .map(|row| {
+                let fields = self
+                    .columns
+                    .iter()
+                    .zip(row)
+                    .map(|(column, expression)| Ok((column.0.clone(), expression.eval(&Constant)?)))
+                    .collect::<Result<HashMap<_, _>, EvaluationError>>()?;
+
+                Ok(T::from_fields(&fields)?)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use crate::query::reflect::ReflectError;
+    use crate::task::{Status, Task};
+    use super::*;
+
+    #[test]
+    fn insert_tasks() {
+        let insert = Insert::from_str(r"
+            INSERT (name, description, date, category, status, assignee)
+            VALUES ('first', 'description', '2024-12-12 20:20', 'work', 'off', 'bob'),
+                   (UPPER('second'), 'description', DATE '2024-12-12' + INTERVAL '1 day', 'home', 'on', NULL)"
+        ).unwrap();
+
+        let tasks = insert.values::<Task>().unwrap();
+
+        assert_eq!(tasks.len(), 2);
+        assert_eq!((tasks[0].name.as_str(), tasks[0].status, tasks[0].assignee.as_deref()), ("first", Status::Off, Some("bob")));
+        assert_eq!(tasks[1].name, "SECOND");
+        assert_eq!(tasks[1].date.to_string(), "2024-12-13 00:00:00 UTC");
+        assert_eq!(tasks[1].assignee, None);
+
+        let missing = Insert::from_str("INSERT (name) VALUES ('first')").unwrap().values::<Task>();
+        assert!(matches!(missing, Err(EvaluationError::Reflect(ReflectError::Required(field))) if field == "description"));
+
+        let identifier = Insert::from_str("INSERT (name) VALUES (name)").unwrap().values::<Task>();
+        assert!(matches!(identifier, Err(EvaluationError::Reflect(ReflectError::NoField(_)))));
+    }
+}
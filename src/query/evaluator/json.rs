@@ -0,0 +1,95 @@
+use std::borrow::Cow;
+use serde_json::Value as Json;
+use crate::query::evaluator::reflect::{FieldsIterator, ReflectError, Reflectable};
+use crate::query::evaluator::value::{Number, Value};
+
+/// JSON value, that is not an object, has no fields.
+static NULL: Json = Json::Null;
+
+/// Reflectable implementation to be able to query arbitrary JSON documents, e.g. imported data.
+///
+/// Object members are dynamic fields: numbers, strings, booleans and arrays are reflected as values, nested
+/// objects - as nested values, that are read by dotted paths, e.g. `metadata.owner`. Members, that the object
+/// does not have, are `NULL`, as well as the fields of values, that are not objects.
+impl Reflectable for Json {
+    fn get_field(&self, field: &str) -> Result<Value<'_>, ReflectError> {
+        match self.get(field) {
+            Some(Json::Object(_)) => Err(ReflectError::UnsupportedType { field: field.to_string().into(), r#type: "object".into() }),
+            Some(value) => Ok(to_value(value).expect("value is not an object")),
+            None => Ok(Value::Null),
+        }
+    }
+
+    /// Members, which are objects, are skipped.
+    fn fields(&self) -> FieldsIterator<'_> {
+        match self {
+            Json::Object(members) => Box::new(
+                members.iter().filter_map(|(name, value)| Some((Cow::Owned(name.clone()), to_value(value)?))),
+            ),
+            _ => Box::new(std::iter::empty()),
+        }
+    }
+
+    fn field_names() -> Cow<'static, [Cow<'static, str>]> {
+        Cow::Borrowed(&[])
+    }
+
+    fn has_dynamic_fields() -> bool {
+        true
+    }
+
+    fn get_nested(&self, field: &str) -> Result<&dyn Reflectable, ReflectError> {
+        Ok(self.get(field).unwrap_or(&NULL))
+    }
+}
+
+/// Returns the value of JSON, that is not an object. Items of arrays, which are objects, are `NULL`.
+fn to_value(json: &Json) -> Option<Value<'_>> {
+    let value = match json {
+        Json::Null => Value::Null,
+        Json::Bool(bool) => Value::Bool(*bool),
+        Json::Number(number) => match number.as_i64() {
+            Some(int) => Value::Number(Number::Int(int)),
+            None => Value::Number(Number::Float(number.as_f64().unwrap_or(f64::NAN))),
+        },
+        Json::String(string) => Value::String(string.as_str().into()),
+        Json::Array(items) => Value::List(items.iter().map(|item| to_value(item).unwrap_or(Value::Null)).collect()),
+        Json::Object(_) => return None,
+    };
+
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use serde_json::json;
+    use crate::query::Query;
+    use super::*;
+
+    #[test]
+    fn query_json() {
+        let documents = [
+            json!({"title": "Pay rent", "amount": 1200, "tags": ["home"], "meta": {"source": "bank"}}),
+            json!({"title": "Buy milk", "amount": 2.5, "meta": {"source": "shop"}}),
+            json!({"title": "Call mom"}),
+        ];
+
+        assert_eq!(documents[0].get_field("amount").unwrap(), Value::Number(Number::Int(1200)));
+        assert_eq!(documents[1].get_field("tags").unwrap(), Value::Null);
+        assert!(matches!(documents[0].get_field("meta"), Err(ReflectError::UnsupportedType { .. })));
+        assert!(documents[0].fields().map(|(name, _)| name).eq(["amount", "tags", "title"]));
+
+        let result_set = Query::from_str("SELECT title, meta.source WHERE meta.source = 'bank' OR 'home' IN tags OR amount = NULL")
+            .unwrap()
+            .execute(&documents)
+            .unwrap();
+        assert_eq!(
+            result_set.rows().collect::<Vec<_>>(),
+            [
+                [Value::String("Pay rent".into()), Value::String("bank".into())],
+                [Value::String("Call mom".into()), Value::Null],
+            ]
+        );
+    }
+}
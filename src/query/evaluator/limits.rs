@@ -0,0 +1,129 @@
+use std::cell::Cell;
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+use crate::query::EvaluationError;
+use crate::query::evaluator::context::ExecutionContext;
+
+/// Guards of the query execution, that stop the runaway queries. Limits, that are `None`, are not enforced.
+///
+/// Limits are applied to every query, including the subqueries, by [`Query::execute`](crate::query::Query::execute).
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Limits {
+    /// Maximal number of the items, that a query scans.
+    pub max_scanned_rows: Option<usize>,
+    /// Maximal number of the rows of the query result.
+    pub max_result_rows: Option<usize>,
+    /// Maximal time of the query execution, including its subqueries.
+    pub max_duration: Option<Duration>,
+}
+
+/// Limit, that was exceeded by the query.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Limit {
+    ScannedRows(usize),
+    ResultRows(usize),
+    Duration(Duration),
+}
+
+thread_local! {
+    /// Limits of the outermost query, that is executed on this thread.
+    static CURRENT: Cell<Option<Scope>> = const { Cell::new(None) };
+}
+
+/// Limits of the executed query along with the moment, when it exceeds its time limit.
+#[derive(Copy, Clone)]
+struct Scope {
+    limits: Limits,
+    deadline: Option<Instant>,
+}
+
+/// Returns limits of the queries of the current [`ExecutionContext`].
+pub fn limits() -> Limits {
+    ExecutionContext::with(|context| context.limits)
+}
+
+impl Limits {
+    /// Runs the query `f` over `scanned` items within the limits.
+    ///
+    /// Subqueries are run within the limits of the outermost query, so they don't extend its time limit.
+    pub(crate) fn scope<R>(self, scanned: usize, f: impl FnOnce() -> Result<R, EvaluationError>) -> Result<R, EvaluationError> {
+        struct Restore(Option<Scope>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CURRENT.set(self.0);
+            }
+        }
+
+        let scope = CURRENT.get().unwrap_or_else(|| Scope {
+            limits: self,
+            deadline: self.max_duration.and_then(|max| Instant::now().checked_add(max)),
+        });
+        if let Some(max) = scope.limits.max_scanned_rows.filter(|max| scanned > *max) {
+            return Err(EvaluationError::LimitExceeded(Limit::ScannedRows(max)));
+        }
+        let _restore = Restore(CURRENT.replace(Some(scope)));
+        f()
+    }
+}
+
+/// Check, that the query, that is executed on this thread, has time left. Called for every evaluated item.
+pub(crate) fn check_deadline() -> Result<(), EvaluationError> {
+    match CURRENT.get() {
+        Some(Scope { limits, deadline: Some(deadline) }) if Instant::now() >= deadline => {
+            Err(EvaluationError::LimitExceeded(Limit::Duration(limits.max_duration.unwrap_or_default())))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Check, that the result of `rows` rows of the query, that is executed on this thread, is within the limit.
+pub(crate) fn check_result(rows: usize) -> Result<(), EvaluationError> {
+    let max_result_rows = CURRENT.get().and_then(|scope| scope.limits.max_result_rows);
+    match max_result_rows.filter(|max| rows > *max) {
+        Some(max) => Err(EvaluationError::LimitExceeded(Limit::ResultRows(max))),
+        None => Ok(()),
+    }
+}
+
+impl Display for Limit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Limit::ScannedRows(max) => write!(f, "{max} scanned rows"),
+            Limit::ResultRows(max) => write!(f, "{max} result rows"),
+            Limit::Duration(max) => write!(f, "{} ms of execution", max.as_millis()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use crate::query::Query;
+    use crate::query::reflect::tests::TestReflect;
+    use super::*;
+
+    #[test]
+    fn query_limits() {
+        let items = [TestReflect::default(), TestReflect::default(), TestReflect::default()];
+        let query = Query::from_str("SELECT string").unwrap();
+        let execute = |limits: Limits| limits.scope(items.len(), || query.execute(&items));
+
+        assert!(execute(Limits { max_scanned_rows: Some(3), max_result_rows: Some(3), ..Limits::default() }).is_ok());
+        assert!(matches!(
+            execute(Limits { max_scanned_rows: Some(2), ..Limits::default() }),
+            Err(EvaluationError::LimitExceeded(Limit::ScannedRows(2)))
+        ));
+        assert!(matches!(
+            execute(Limits { max_result_rows: Some(1), ..Limits::default() }),
+            Err(EvaluationError::LimitExceeded(Limit::ResultRows(1)))
+        ));
+        assert!(matches!(
+            execute(Limits { max_duration: Some(Duration::ZERO), ..Limits::default() }),
+            Err(EvaluationError::LimitExceeded(Limit::Duration(_)))
+        ));
+        assert_eq!(
+            EvaluationError::LimitExceeded(Limit::ResultRows(2)).to_string(),
+            "Query exceeded the limit of 2 result rows"
+        );
+    }
+}
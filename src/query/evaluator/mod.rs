@@ -1,5 +1,22 @@
 pub mod value;
 pub mod reflect;
 pub mod expression;
+pub mod function;
 pub mod query;
-pub mod result_set;
\ No newline at end of file
+pub mod result_set;
+pub mod validate;
+pub mod insert;
+pub mod subquery;
+pub mod collation;
+pub mod timezone;
+pub mod optimize;
+pub mod plan;
+pub mod computed;
+pub mod trace;
+pub mod aggregate;
+pub mod limits;
+pub mod cancel;
+pub mod context;
+#[cfg(feature = "json")]
+pub mod json;
+
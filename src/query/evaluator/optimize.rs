@@ -0,0 +1,227 @@
+use crate::query::ast::expression::{
+    BinaryOp, BinaryOperation, Expression, InSubquery, Literal, Operation, Subquery, UnaryOp, UnaryOperation,
+};
+use crate::query::ast::{Collation, Field, FieldsProjection, Predicate, Query};
+use crate::query::evaluator::validate::Constant;
+
+impl Query {
+    /// Returns the query, which projected expressions and predicate are simplified by [`Expression::optimize`].
+    pub fn optimize(&self) -> Query {
+        let fields = self
+            .fields_projection
+            .0
+            .iter()
+            .map(|field| match field {
+                Field::Asterisk => Field::Asterisk,
+                Field::Expression { expression, name } => Field::Expression {
+                    expression: expression.clone().optimize(),
+                    name: name.clone(),
+                },
+            })
+            .collect();
+
+        Query {
+            fields_projection: FieldsProjection(fields),
+            from: self.from.clone(),
+            predicate: self.predicate.as_ref().map(Predicate::optimize),
+            group_by: self.group_by.iter().map(|expression| expression.clone().optimize()).collect(),
+            collation: self.collation,
+        }
+    }
+}
+
+impl Predicate {
+    /// Returns the predicate, which expression is simplified by [`Expression::optimize`].
+    pub fn optimize(&self) -> Predicate {
+        Predicate { expr: self.expr.clone().optimize() }
+    }
+}
+
+impl Expression {
+    /// Returns the expression, that evaluates to the same value for every item, but does less work.
+    ///
+    ///  * operations and `IN` over constants are evaluated once, unless they fail, so the error is still reported
+    ///    when the items are evaluated. Function calls are kept, as `NOW()` and alike change over time;
+    ///  * `a AND false` is `false`, `a OR true` is `true`, `a AND true` and `a OR false` are `a`,
+    ///    when `a` is a boolean expression, so non-boolean operands still fail to evaluate;
+    ///  * `NOT (a AND b)` is `NOT a OR NOT b`, `NOT (a OR b)` is `NOT a AND NOT b`,
+    ///    `NOT NOT a` is `a`, when `a` and `b` are boolean expressions;
+    ///  * string patterns of `LIKE`, `STARTSWITH` and `ENDSWITH` are folded by the current collation once, rather
+    ///    than for every item.
+    pub fn optimize(self) -> Expression {
+        match self {
+            Expression::Operation(operation) => match *operation {
+                Operation::Unary(UnaryOperation { expression, op }) => Expression::unary(op, expression.optimize()),
+                Operation::Binary(BinaryOperation { left_expression, op, right_expression }) => {
+                    Expression::binary(left_expression.optimize(), op, right_expression.optimize())
+                }
+            },
+            Expression::FunctionCall(mut call) => {
+                call.arguments = call.arguments.into_iter().map(Expression::optimize).collect();
+                Expression::FunctionCall(call)
+            }
+            Expression::In(in_subquery) => {
+                let InSubquery { expression, subquery } = *in_subquery;
+                let subquery = match subquery {
+                    Subquery::Values(values) => Subquery::Values(values.optimize()),
+                    subquery => subquery,
+                };
+                let is_constant = expression.is_constant() && match &subquery {
+                    Subquery::Query(_) => false,
+                    Subquery::Materialized(_) => true,
+                    Subquery::Values(values) => values.is_constant(),
+                };
+                let expression = Expression::In(Box::new(InSubquery { expression: expression.optimize(), subquery }));
+
+                if is_constant { expression.fold() } else { expression }
+            }
+            Expression::List(items) => Expression::List(items.into_iter().map(Expression::optimize).collect()),
+            expression => expression,
+        }
+    }
+
+    /// Returns simplified unary operation over the optimized `expression`.
+    fn unary(op: UnaryOp, expression: Expression) -> Expression {
+        if op == UnaryOp::Not {
+            if let Expression::Operation(operation) = &expression {
+                match &**operation {
+                    Operation::Binary(BinaryOperation { left_expression, op: op @ (BinaryOp::And | BinaryOp::Or), right_expression })
+                        if left_expression.is_boolean() && right_expression.is_boolean() =>
+                    {
+                        let dual = if *op == BinaryOp::And { BinaryOp::Or } else { BinaryOp::And };
+                        let left = Expression::unary(UnaryOp::Not, left_expression.clone());
+                        let right = Expression::unary(UnaryOp::Not, right_expression.clone());
+                        return Expression::binary(left, dual, right);
+                    }
+                    Operation::Unary(UnaryOperation { expression, op: UnaryOp::Not }) if expression.is_boolean() => {
+                        return expression.clone();
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let is_constant = expression.is_constant();
+        let expression = Expression::Operation(Box::new(Operation::Unary(UnaryOperation { expression, op })));
+        if is_constant { expression.fold() } else { expression }
+    }
+
+    /// Returns simplified binary operation over the optimized `left` and `right` expressions.
+    fn binary(left: Expression, op: BinaryOp, right: Expression) -> Expression {
+        let bool = |expression: &Expression| match expression {
+            Expression::Literal(Literal::Bool(bool)) => Some(*bool),
+            _ => None,
+        };
+        match (op, bool(&left), bool(&right)) {
+            (BinaryOp::And, Some(false), _) | (BinaryOp::Or, Some(true), _) if right.is_boolean() => return left,
+            (BinaryOp::And, _, Some(false)) | (BinaryOp::Or, _, Some(true)) if left.is_boolean() => return right,
+            (BinaryOp::And, Some(true), _) | (BinaryOp::Or, Some(false), _) if right.is_boolean() => return right,
+            (BinaryOp::And, _, Some(true)) | (BinaryOp::Or, _, Some(false)) if left.is_boolean() => return left,
+            _ => {}
+        }
+
+        let right = match (op, right) {
+            (BinaryOp::Like | BinaryOp::StartsWith | BinaryOp::EndsWith, Expression::Literal(Literal::String(pattern))) => {
+                Expression::Literal(Literal::String(Collation::current().fold(&pattern).into_owned()))
+            }
+            (_, right) => right,
+        };
+
+        let is_constant = left.is_constant() && right.is_constant();
+        let expression = Expression::Operation(Box::new(Operation::Binary(BinaryOperation {
+            left_expression: left,
+            op,
+            right_expression: right,
+        })));
+        if is_constant { expression.fold() } else { expression }
+    }
+
+    /// Returns the expression replaced by its value, or the expression itself, if it fails to evaluate.
+    fn fold(self) -> Expression {
+        match self.eval(&Constant) {
+            Ok(value) => Expression::from(&value),
+            Err(_) => self,
+        }
+    }
+
+    /// Tests, that the expression is a literal or a list of constants.
+    fn is_constant(&self) -> bool {
+        match self {
+            Expression::Literal(_) => true,
+            Expression::List(items) => items.iter().all(Expression::is_constant),
+            _ => false,
+        }
+    }
+
+    /// Tests, that the expression always evaluates to a boolean.
+    fn is_boolean(&self) -> bool {
+        match self {
+            Expression::Literal(Literal::Bool(_)) | Expression::In(_) => true,
+            Expression::Operation(operation) => match &**operation {
+                Operation::Unary(operation) => operation.op == UnaryOp::Not,
+                Operation::Binary(operation) => matches!(
+                    operation.op,
+                    BinaryOp::Gt
+                        | BinaryOp::Lt
+                        | BinaryOp::Gte
+                        | BinaryOp::Lte
+                        | BinaryOp::Eq
+                        | BinaryOp::NullSafeEq
+                        | BinaryOp::Like
+                        | BinaryOp::StartsWith
+                        | BinaryOp::EndsWith
+                        | BinaryOp::And
+                        | BinaryOp::Or
+                ),
+            },
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use crate::query::ast::Collation;
+    use crate::query::Query;
+    use crate::query::reflect::tests::TestReflect;
+
+    #[test]
+    fn constant_folding() {
+        let optimize = |predicate: &str| {
+            let query = Query::from_str(&format!("SELECT * WHERE {predicate}")).unwrap();
+            query.optimize().predicate.unwrap().to_string()
+        };
+
+        assert_eq!(optimize("number > 1 + 1"), "number > 2");
+        assert_eq!(optimize("NOT false AND date < DATE '2024-12-12' + INTERVAL '1 day'"), "date < DATE '2024-12-13'");
+        assert_eq!(optimize("a > 1 AND (false OR b = 'x')"), "a > 1 AND b = 'x'");
+        assert_eq!(optimize("a = 1 AND 1 = 2 OR b = 1"), "b = 1");
+        assert_eq!(optimize("a AND 1 = 2 OR b = 1"), "a AND false OR b = 1");
+        assert_eq!(optimize("a = 1 OR NOT (1 IN (1, 2))"), "a = 1");
+        assert_eq!(optimize("a = 1 OR true"), "true");
+        assert_eq!(optimize("a OR true"), "a OR true");
+        assert_eq!(optimize("a AND true"), "a AND true");
+        assert_eq!(optimize("NOT (a = 1 AND NOT b = 2)"), "NOT a = 1 OR b = 2");
+        assert_eq!(optimize("NOT (a = 1 OR b LIKE 'x')"), "NOT a = 1 AND NOT b LIKE 'x'");
+        assert_eq!(optimize("NOT (a OR b)"), "NOT (a OR b)");
+        assert_eq!(optimize("NOT (a + 1 OR b = 1)"), "NOT (a + 1 OR b = 1)");
+        assert_eq!(optimize("UPPER('a') = a AND 9223372036854775807 + 1 > a"), "UPPER('a') = a AND 9223372036854775807 + 1 > a");
+        assert_eq!(optimize("['a', 'b' + 1] = a"), "('a', 'b' + 1) = a");
+        assert_eq!(optimize("a LIKE 'Ab'"), "a LIKE 'Ab'");
+        assert_eq!(Collation::NoCase.scope(|| optimize("a LIKE 'Ab' AND b STARTSWITH 'Cé'")), "a LIKE 'ab' AND b STARTSWITH 'ce'");
+    }
+
+    #[test]
+    fn non_boolean_operands_fail() {
+        let items = [TestReflect::default()];
+        let execute = |predicate: &str| Query::from_str(&format!("SELECT * WHERE {predicate}")).unwrap().execute(&items);
+
+        assert!(execute("string AND false").is_err());
+        assert!(execute("false AND string").is_err());
+        assert!(execute("true AND string").is_err());
+        assert!(execute("date_time OR true").is_err());
+        assert!(execute("NOT (string OR number)").is_err());
+        assert!(execute("number = 1 OR true").is_ok());
+    }
+}
@@ -0,0 +1,235 @@
+use std::ops::Bound;
+use crate::query::ast::expression::{BinaryOp, BinaryOperation, Expression, Operation, Subquery};
+use crate::query::ast::{Collation, Field, Query};
+use crate::query::evaluator::value::Value;
+use crate::query::EvaluationError;
+
+/// Strategy of fetching the items, that may satisfy the query predicate.
+///
+/// Plan only narrows the candidates: the query is still executed over them, so the fetched items may not satisfy it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Plan {
+    /// Every item is a candidate.
+    Scan,
+    /// Items, which `field` is equal to one of the `values`.
+    Lookup { field: String, values: Vec<Value<'static>> },
+    /// Items, which `field` is within the bounds.
+    Range { field: String, lower: Bound<Value<'static>>, upper: Bound<Value<'static>> },
+}
+
+impl Query {
+    /// Returns the plan of fetching the candidates of the query through the `indexed` fields.
+    ///
+    /// Equality and `IN` over constants are planned as lookups, comparisons with constants as ranges, `AND` takes
+    /// the narrower plan of its operands and `OR` unites lookups of the same field. Queries with subqueries and
+    /// case-insensitive queries are always scanned, as subqueries select from all items and index keys are
+    /// case-sensitive.
+    pub fn plan(&self, indexed: &[&str]) -> Plan {
+        let has_subquery = self.fields_projection.0.iter().any(|field| match field {
+            Field::Asterisk => false,
+            Field::Expression { expression, .. } => expression.has_subquery(),
+        }) || self.predicate.as_ref().is_some_and(|predicate| predicate.expr.has_subquery());
+        if has_subquery || self.collation.unwrap_or_else(Collation::current) == Collation::NoCase {
+            return Plan::Scan;
+        }
+
+        match &self.predicate {
+            Some(predicate) => predicate.optimize().expr.plan(indexed),
+            None => Plan::Scan,
+        }
+    }
+}
+
+impl Expression {
+    fn plan(&self, indexed: &[&str]) -> Plan {
+        match self {
+            Expression::Operation(operation) => match &**operation {
+                Operation::Binary(BinaryOperation { left_expression, op: BinaryOp::And, right_expression }) => {
+                    Plan::intersect(left_expression.plan(indexed), right_expression.plan(indexed))
+                }
+                Operation::Binary(BinaryOperation { left_expression, op: BinaryOp::Or, right_expression }) => {
+                    Plan::unite(left_expression.plan(indexed), right_expression.plan(indexed))
+                }
+                Operation::Binary(operation) => operation.plan(indexed).unwrap_or(Plan::Scan),
+                Operation::Unary(_) => Plan::Scan,
+            },
+            Expression::In(in_subquery) => {
+                let Some(field) = in_subquery.expression.indexed_field(indexed) else {
+                    return Plan::Scan;
+                };
+                let values = match &in_subquery.subquery {
+                    Subquery::Materialized(values) => values.clone(),
+                    Subquery::Values(Expression::List(items)) => {
+                        match items.iter().map(Expression::constant).collect::<Option<Vec<_>>>() {
+                            Some(values) => values,
+                            None => return Plan::Scan,
+                        }
+                    }
+                    Subquery::Values(expression) => match expression.constant() {
+                        Some(Value::List(values)) => values,
+                        Some(value) => vec![value],
+                        None => return Plan::Scan,
+                    },
+                    Subquery::Query(_) => return Plan::Scan,
+                };
+
+                Plan::Lookup { field, values: values.into_iter().filter(|value| *value != Value::Null).collect() }
+            }
+            _ => Plan::Scan,
+        }
+    }
+
+    /// Returns the name of the field, if the expression is an identifier of one of the `indexed` fields.
+    fn indexed_field(&self, indexed: &[&str]) -> Option<String> {
+        match self {
+            Expression::Identifier(identifier) if indexed.contains(&identifier.0.as_str()) => Some(identifier.0.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of the literal, that is not `NULL`.
+    fn constant(&self) -> Option<Value<'static>> {
+        match self {
+            Expression::Literal(literal) => Some(literal.value().into_static()).filter(|value| *value != Value::Null),
+            _ => None,
+        }
+    }
+
+    /// Tests, that the expression has a subquery, that is not materialized.
+    fn has_subquery(&self) -> bool {
+        match self {
+            Expression::Operation(operation) => match &**operation {
+                Operation::Unary(operation) => operation.expression.has_subquery(),
+                Operation::Binary(operation) => {
+                    operation.left_expression.has_subquery() || operation.right_expression.has_subquery()
+                }
+            },
+            Expression::FunctionCall(call) => call.arguments.iter().any(Expression::has_subquery),
+            Expression::In(in_subquery) => {
+                in_subquery.expression.has_subquery() || match &in_subquery.subquery {
+                    Subquery::Query(_) => true,
+                    Subquery::Materialized(_) => false,
+                    Subquery::Values(expression) => expression.has_subquery(),
+                }
+            }
+            Expression::List(items) => items.iter().any(Expression::has_subquery),
+            _ => false,
+        }
+    }
+}
+
+impl BinaryOperation {
+    /// Returns the plan of the comparison of an indexed field with a constant.
+    fn plan(&self, indexed: &[&str]) -> Option<Plan> {
+        let (field, op, value) = match (self.left_expression.indexed_field(indexed), self.right_expression.indexed_field(indexed)) {
+            (Some(field), _) => (field, self.op, self.right_expression.constant()?),
+            (_, Some(field)) => {
+                let op = match self.op {
+                    BinaryOp::Gt => BinaryOp::Lt,
+                    BinaryOp::Lt => BinaryOp::Gt,
+                    BinaryOp::Gte => BinaryOp::Lte,
+                    BinaryOp::Lte => BinaryOp::Gte,
+                    op => op,
+                };
+                (field, op, self.left_expression.constant()?)
+            }
+            _ => return None,
+        };
+
+        let (lower, upper) = match op {
+            BinaryOp::Eq | BinaryOp::NullSafeEq => return Some(Plan::Lookup { field, values: vec![value] }),
+            BinaryOp::Gt => (Bound::Excluded(value), Bound::Unbounded),
+            BinaryOp::Gte => (Bound::Included(value), Bound::Unbounded),
+            BinaryOp::Lt => (Bound::Unbounded, Bound::Excluded(value)),
+            BinaryOp::Lte => (Bound::Unbounded, Bound::Included(value)),
+            _ => return None,
+        };
+
+        Some(Plan::Range { field, lower, upper })
+    }
+}
+
+impl Plan {
+    /// Returns the plan of the items, that are candidates of both plans.
+    fn intersect(left: Plan, right: Plan) -> Plan {
+        match (left, right) {
+            (Plan::Scan, plan) | (plan, Plan::Scan) => plan,
+            (Plan::Lookup { field, values }, Plan::Lookup { values: other, .. }) if values.len() <= other.len() => {
+                Plan::Lookup { field, values }
+            }
+            (Plan::Lookup { .. }, lookup @ Plan::Lookup { .. }) => lookup,
+            (lookup @ Plan::Lookup { .. }, Plan::Range { .. }) | (Plan::Range { .. }, lookup @ Plan::Lookup { .. }) => lookup,
+            (Plan::Range { field, lower, upper }, Plan::Range { field: other, lower: other_lower, upper: other_upper }) => {
+                if field == other {
+                    Plan::Range {
+                        field,
+                        lower: tighter(lower, other_lower, Value::gt),
+                        upper: tighter(upper, other_upper, Value::lt),
+                    }
+                } else {
+                    Plan::Range { field, lower, upper }
+                }
+            }
+        }
+    }
+
+    /// Returns the plan of the items, that are candidates of any of the plans.
+    fn unite(left: Plan, right: Plan) -> Plan {
+        match (left, right) {
+            (Plan::Lookup { field, mut values }, Plan::Lookup { field: other, values: other_values }) if field == other => {
+                values.extend(other_values);
+                Plan::Lookup { field, values }
+            }
+            _ => Plan::Scan,
+        }
+    }
+}
+
+/// Returns the bound, that is tighter by `is_tighter`. Bounds, which values are not comparable, are kept as is.
+fn tighter(
+    bound: Bound<Value<'static>>,
+    other: Bound<Value<'static>>,
+    is_tighter: fn(&Value, &Value) -> Result<Value<'static>, EvaluationError>,
+) -> Bound<Value<'static>> {
+    let value = |bound: &Bound<Value<'static>>| match bound {
+        Bound::Included(value) | Bound::Excluded(value) => Some(value.clone()),
+        Bound::Unbounded => None,
+    };
+    match (value(&bound), value(&other)) {
+        (None, _) => other,
+        (_, None) => bound,
+        (Some(value), Some(other_value)) => match is_tighter(&other_value, &value) {
+            Ok(Value::Bool(true)) => other,
+            Ok(_) if value == other_value && matches!(other, Bound::Excluded(_)) => other,
+            _ => bound,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use super::*;
+
+    #[test]
+    fn plan_predicates() {
+        let plan = |query: &str| Query::from_str(query).unwrap().plan(&["name", "date"]);
+        let string = |string: &str| Value::String(string.to_string().into());
+        let lookup = |values: &[&str]| Plan::Lookup { field: "name".to_string(), values: values.iter().map(|value| string(value)).collect() };
+
+        assert_eq!(plan("SELECT *"), Plan::Scan);
+        assert_eq!(plan("SELECT * WHERE name = 'a'"), lookup(&["a"]));
+        assert_eq!(plan("SELECT * WHERE 'a' = name AND status = 'on'"), lookup(&["a"]));
+        assert_eq!(plan("SELECT * WHERE name IN ('a', 'b') OR name = 'c'"), lookup(&["a", "b", "c"]));
+        assert_eq!(plan("SELECT * WHERE name IN ['a'] AND date > DATE '2024-01-01'"), lookup(&["a"]));
+        assert_eq!(plan("SELECT * WHERE name = 'a' OR status = 'on'"), Plan::Scan);
+        assert_eq!(plan("SELECT * WHERE name = 'a' COLLATE NOCASE"), Plan::Scan);
+        assert_eq!(plan("SELECT * WHERE name = 'a' AND name IN (SELECT name)"), Plan::Scan);
+        assert_eq!(plan("SELECT * WHERE name = LOWER('A')"), Plan::Scan);
+        assert_eq!(plan("SELECT * WHERE name > 'a' AND 'c' >= name AND name > 'b'"), Plan::Range {
+            field: "name".to_string(),
+            lower: Bound::Excluded(string("b")),
+            upper: Bound::Included(string("c")),
+        });
+    }
+}
@@ -1,6 +1,10 @@
-use crate::query::ast::{Field, FieldsProjection, Predicate, Query};
+use crate::query::ast::{Collation, Field, FieldsProjection, Predicate, Query};
 use crate::query::evaluator::reflect::Reflectable;
 use crate::query::evaluator::result_set::ResultSet;
+use crate::query::evaluator::cancel::{check_cancelled, CancellationToken};
+use crate::query::evaluator::context::ExecutionContext;
+use crate::query::evaluator::limits::{check_deadline, check_result, limits};
+use crate::query::evaluator::trace::item_error;
 use crate::query::EvaluationError;
 use std::borrow::Cow;
 use std::collections::{HashMap};
@@ -8,22 +12,59 @@ use std::collections::{HashMap};
 impl Query {
     /// Execute [`Query`] on given `items`.
     ///
-    /// Method will filter items by predicate and then project them to [`ResultSet`]
+    /// Method will validate query against the schema of `T`, evaluate subqueries and constants once, filter items by predicate
+    /// and then project them, or their groups, if the query is aggregate, to [`ResultSet`]. Strings are compared by the
+    /// query collation, if it is set. Query fails, if it exceeds the [`Limits`](crate::query::evaluator::limits::Limits).
+    ///
+    /// Query is executed with the [`ExecutionContext`] of the scope, that is running on this thread.
     pub fn execute<'a, T: Reflectable + 'a>(
         &self,
         items: impl IntoIterator<Item = &'a T>,
     ) -> Result<ResultSet, EvaluationError> {
-        if let Some(predicate) = &self.predicate {
-            self.fields_projection.project(predicate.filter(items)?)
-        } else {
-            self.fields_projection.project(items)
-        }
+        self.validate::<T>()?;
+        let items = items.into_iter().collect::<Vec<_>>();
+        let collation = self.collation.unwrap_or_else(Collation::current);
+        limits().scope(items.len(), || collation.scope(|| {
+            let query = self.materialize(&items)?.optimize();
+            let items = match &query.predicate {
+                Some(predicate) => predicate.filter(items)?,
+                None => items,
+            };
+            let result_set = if query.is_aggregate() {
+                query.fields_projection.aggregate(&query.group_by, items)?
+            } else {
+                query.fields_projection.project(items)?
+            };
+            check_result(result_set.rows().count())?;
+
+            Ok(result_set)
+        }))
+    }
+
+    /// Execute [`Query`] on given `items`, like [`Query::execute`], but fail with [`EvaluationError::Cancelled`], as
+    /// soon as the `token` is cancelled.
+    pub fn execute_cancellable<'a, T: Reflectable + 'a>(
+        &self,
+        items: impl IntoIterator<Item = &'a T>,
+        token: &CancellationToken,
+    ) -> Result<ResultSet, EvaluationError> {
+        token.scope(|| self.execute(items))
+    }
+
+    /// Execute [`Query`] on given `items`, like [`Query::execute`], with the time zone, date time formats, registered
+    /// functions and computed fields, float tolerance and limits of the `context`.
+    pub fn execute_in<'a, T: Reflectable + 'a>(
+        &self,
+        items: impl IntoIterator<Item = &'a T>,
+        context: &ExecutionContext,
+    ) -> Result<ResultSet, EvaluationError> {
+        context.scope(|| self.execute(items))
     }
 }
 
 impl FieldsProjection {
     /// Return an iterator over column names, that need to be projected in [`ResultSet`].
-    pub fn columns<'a, T: Reflectable + 'a>(&self) -> impl Iterator<Item = Cow<str>> {
+    pub fn columns<'a, T: Reflectable + 'a>(&self) -> impl Iterator<Item = Cow<'_, str>> {
         let fields_names = T::field_names();
         let mut columns = self
             .0
@@ -39,9 +80,9 @@ impl FieldsProjection {
                                 }
                             }
                         }
-                        Field::Name(field) => {
-                            if !columns.contains_key(&Cow::from(&field.0)) {
-                                columns.insert((&field.0).into(), columns.len());
+                        Field::Expression { name, .. } => {
+                            if !columns.contains_key(&Cow::from(name)) {
+                                columns.insert(name.into(), columns.len());
                             }
                         }
                     }
@@ -63,14 +104,21 @@ impl FieldsProjection {
         items.into_iter().try_fold(
             ResultSet::with_columns(self.columns::<T>()),
             |mut result_set, item| {
+                check_deadline()?;
+                check_cancelled()?;
                 let mut values = Vec::new();
                 for field in &self.0 {
                     match field {
                         Field::Asterisk => {
-                            values.extend(item.fields().map(|(name, value)| (name, value)))
+                            for (name, value) in item.fields() {
+                                values.push((name, value));
+                            }
                         }
-                        Field::Name(name) => {
-                            values.push(((&name.0).into(), item.get_field(&name.0)?))
+                        Field::Expression { expression, name } => {
+                            let value = expression
+                                .eval(item)
+                                .map_err(|err| item_error(err, format!("column '{name}'"), expression, item))?;
+                            values.push((name.into(), value))
                         }
                     }
                 }
@@ -89,20 +137,45 @@ impl Predicate {
         Ok(self.expr.eval(value)?.cast_to_bool()?)
     }
 
-    /// Filter given values by predicate.
-    pub fn filter<'a, T: Reflectable + ?Sized>(
+    /// Filter given values by predicate. Subqueries and constant expressions are evaluated over the values once.
+    pub fn filter<'a, T: Reflectable>(
         &self,
         items: impl IntoIterator<Item = &'a T>,
     ) -> Result<Vec<&'a T>, EvaluationError> {
+        Ok(self.partition(items)?.0)
+    }
+
+    /// Test every item by predicate in one pass. Returns whether the item at every index matches the predicate.
+    pub fn matches_indices<'a, T: Reflectable + 'a>(
+        &self,
+        items: impl IntoIterator<Item = &'a T>,
+    ) -> Result<Vec<bool>, EvaluationError> {
+        let items = items.into_iter().collect::<Vec<_>>();
+        let predicate = self.materialize(&items)?.optimize();
         items
             .into_iter()
-            .filter_map(|value| match self.test(value) {
-                Ok(true) => Some(Ok(value)),
-                Ok(false) => None,
-                Err(err) => Some(Err(err)),
+            .map(|value| {
+                check_deadline()?;
+                check_cancelled()?;
+                predicate
+                    .test(value)
+                    .map_err(|err| item_error(err, "the WHERE predicate".to_string(), &predicate.expr, value))
             })
             .collect()
     }
+
+    /// Split items to the ones, that match the predicate, and the ones, that don't, in one pass, e.g. to act on the
+    /// matches and report the rest.
+    pub fn partition<'a, T: Reflectable>(
+        &self,
+        items: impl IntoIterator<Item = &'a T>,
+    ) -> Result<(Vec<&'a T>, Vec<&'a T>), EvaluationError> {
+        let items = items.into_iter().collect::<Vec<_>>();
+        let matches = self.matches_indices(items.iter().copied())?;
+        let (matched, rest): (Vec<_>, Vec<_>) = items.into_iter().zip(matches).partition(|(_, matches)| *matches);
+
+        Ok((matched.into_iter().map(|(item, _)| item).collect(), rest.into_iter().map(|(item, _)| item).collect()))
+    }
 }
 
 #[cfg(test)]
@@ -112,7 +185,7 @@ pub mod tests {
     use chrono::{NaiveDateTime};
     use std::str::FromStr;
     use crate::query::evaluator::value::conversion::ConversionError;
-    use crate::query::reflect::{ReflectError, Value};
+    use crate::query::reflect::Value;
 
     #[test]
     fn predicate_filter() {
@@ -129,6 +202,19 @@ pub mod tests {
 
     }
 
+    #[test]
+    fn predicate_partition() {
+        let predicate = Query::from_str("SELECT * WHERE number > 10").unwrap().predicate.unwrap();
+        let test_dataset = test_dataset();
+
+        let matches = predicate.matches_indices(&test_dataset).unwrap();
+        assert_eq!(matches, test_dataset.iter().map(|test| test.number > 10).collect::<Vec<_>>());
+        let (matched, rest) = predicate.partition(&test_dataset).unwrap();
+        assert_eq!(matched.len(), matches.iter().filter(|matches| **matches).count());
+        assert_eq!(matched.len() + rest.len(), test_dataset.len());
+        assert!(rest.iter().all(|test| test.number <= 10));
+    }
+
     #[test]
     fn field_projection_asterisk() {
         let query = Query::from_str(r"SELECT *").unwrap();
@@ -162,6 +248,18 @@ pub mod tests {
         assert!(matches!(result, Ok(vec) if vec.columns().eq(["date_time","string", "number"])))
     }
 
+    #[test]
+    fn field_projection_expressions() {
+        let query = Query::from_str(r"SELECT number, -number AS negated, number > 10").unwrap();
+        let projection = query.fields_projection;
+        let test_dataset = test_dataset();
+
+        let result = projection.project(&test_dataset[..1]).unwrap();
+
+        assert!(result.columns().eq(["number", "negated", "number > 10"]));
+        assert!(result.rows().eq([[Value::Number(1.into()), Value::Number((-1).into()), Value::Bool(false)]]));
+    }
+
     #[test]
     fn query() {
         let query = Query::from_str(r"
@@ -192,7 +290,7 @@ pub mod tests {
 
         let result = query.execute(&test_dataset);
 
-        assert!(matches!(result, Err(EvaluationError::Reflect(ReflectError::NoField(_)))));
+        assert!(matches!(result, Err(EvaluationError::Validation(_))));
     }
 
     #[test]
@@ -205,7 +303,11 @@ pub mod tests {
 
         let result = query.execute(&test_dataset);
 
-        assert!(matches!(result, Err(EvaluationError::Conversion(ConversionError::Failed { .. }))));
+        assert!(matches!(
+            result,
+            Err(EvaluationError::Item { ref item, ref location, ref source, .. })
+                if item == "string = Hello" && location == "the WHERE predicate" && matches!(**source, EvaluationError::Conversion(ConversionError::Failed { .. }))
+        ));
     }
 
     pub fn test_dataset() -> Vec<TestReflect> {
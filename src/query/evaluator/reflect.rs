@@ -1,28 +1,370 @@
-use super::value::conversion::Type;
+use super::value::conversion::ConversionError;
+pub use super::value::conversion::Type;
+use super::value::Number;
+use super::value::decimal::ParseDecimalError;
+pub use super::value::decimal::Decimal;
+use chrono::{DateTime, Utc};
 use std::borrow::Cow;
+use std::collections::HashMap;
 use thiserror::Error;
 
-pub use super::value::Value;
+pub use super::value::{Text, Value};
 
 /// Iterator over [`Reflectable`] type fields.
-pub type FieldsIterator = Box<dyn Iterator<Item = (Cow<'static, str>, Value)>>;
+pub type FieldsIterator<'a> = Box<dyn Iterator<Item = (Cow<'static, str>, Value<'a>)> + 'a>;
 
 /// Trait for runtime reflection and observation of struct fields.
+///
+/// Field values are converted with [`ToValue`]. Enum fields with [`Display`](std::fmt::Display) and
+/// [`FromStr`](std::str::FromStr) implementations can be reflected as strings with [`impl_value_via_string`],
+/// newtype wrappers - as their inner type with [`impl_value_via_newtype`].
 pub trait Reflectable {
     /// Returns value of `field`.
     ///
     /// If field is not exists or cannot be converted to [`Value`] type, an error will be returned.
-    fn get_field(&self, field: &str) -> Result<Value, ReflectError>;
+    fn get_field(&self, field: &str) -> Result<Value<'_>, ReflectError>;
     /// Returns field names along with their values.
     ///
     /// If field cannot be converted to [`Value`] type, it will be skipped.
-    fn fields(&self) -> FieldsIterator;
+    fn fields(&self) -> FieldsIterator<'_>;
     /// Returns field names.
     fn field_names() -> Cow<'static, [Cow<'static, str>]>
     where
         Self: Sized;
+    /// Returns field names along with the types of their values, that are not `NULL`.
+    ///
+    /// By default types of the fields are unknown, so the list is empty.
+    fn field_types() -> Cow<'static, [(Cow<'static, str>, Type)]>
+    where
+        Self: Sized,
+    {
+        Cow::Borrowed(&[])
+    }
+    /// Tests that the type has dynamic fields besides [`field_names`](Reflectable::field_names), such as
+    /// user-defined custom fields. Queries over such types are not validated for unknown fields.
+    ///
+    /// By default type has only the fields of `field_names`.
+    fn has_dynamic_fields() -> bool
+    where
+        Self: Sized,
+    {
+        false
+    }
+    /// Returns nested reflectable value of `field`, that is read by dotted path identifiers, e.g. `metadata.owner`.
+    ///
+    /// By default type has no nested values.
+    fn get_nested(&self, field: &str) -> Result<&dyn Reflectable, ReflectError> {
+        Err(ReflectError::NoField(field.to_string()))
+    }
+}
+
+/// Optional nested value. Fields of `None` are `NULL`, as well as the fields of its nested values.
+impl<T: Reflectable> Reflectable for Option<T> {
+    fn get_field(&self, field: &str) -> Result<Value<'_>, ReflectError> {
+        match self {
+            Some(value) => value.get_field(field),
+            None if T::field_names().iter().any(|name| name == field) => Ok(Value::Null),
+            None => Err(ReflectError::NoField(field.to_string())),
+        }
+    }
+
+    fn fields(&self) -> FieldsIterator<'_> {
+        match self {
+            Some(value) => value.fields(),
+            None => Box::new(T::field_names().into_owned().into_iter().map(|name| (name, Value::Null))),
+        }
+    }
+
+    fn field_names() -> Cow<'static, [Cow<'static, str>]> {
+        T::field_names()
+    }
+
+    fn field_types() -> Cow<'static, [(Cow<'static, str>, Type)]> {
+        T::field_types()
+    }
+
+    fn has_dynamic_fields() -> bool {
+        T::has_dynamic_fields()
+    }
+
+    fn get_nested(&self, field: &str) -> Result<&dyn Reflectable, ReflectError> {
+        match self {
+            Some(value) => value.get_nested(field),
+            None => Ok(&Absent),
+        }
+    }
+}
+
+/// Dynamic fields, e.g. user-defined custom fields, that are queried without the schema. Fields, that are not in
+/// the map, are `NULL`, as they may be set in the other items.
+impl Reflectable for HashMap<String, Value<'static>> {
+    fn get_field(&self, field: &str) -> Result<Value<'_>, ReflectError> {
+        Ok(self.get(field).cloned().unwrap_or(Value::Null))
+    }
+
+    /// Fields are ordered by name, so the columns of the result set are stable.
+    fn fields(&self) -> FieldsIterator<'_> {
+        let mut fields = self.iter().collect::<Vec<_>>();
+        fields.sort_unstable_by_key(|(name, _)| *name);
+        Box::new(fields.into_iter().map(|(name, value)| (Cow::Owned(name.clone()), value.clone())))
+    }
+
+    fn field_names() -> Cow<'static, [Cow<'static, str>]> {
+        Cow::Borrowed(&[])
+    }
+
+    fn has_dynamic_fields() -> bool {
+        true
+    }
 }
 
+/// Nested value of `None`, which fields are all `NULL`.
+struct Absent;
+
+impl Reflectable for Absent {
+    fn get_field(&self, _field: &str) -> Result<Value<'_>, ReflectError> {
+        Ok(Value::Null)
+    }
+
+    fn fields(&self) -> FieldsIterator<'_> {
+        Box::new(std::iter::empty())
+    }
+
+    fn field_names() -> Cow<'static, [Cow<'static, str>]> {
+        Cow::Borrowed(&[])
+    }
+
+    fn get_nested(&self, _field: &str) -> Result<&dyn Reflectable, ReflectError> {
+        Ok(&Absent)
+    }
+}
+
+/// Construction of the type from the field values, e.g. by `INSERT` statement.
+pub trait FromFields: Sized {
+    /// Build value from `fields`. Fields, that are not specified, take their default values.
+    ///
+    /// If the field is not exists, is required but not specified, or cannot be converted from [`Value`], an error
+    /// will be returned.
+    fn from_fields(fields: &HashMap<String, Value>) -> Result<Self, ReflectError>;
+}
+
+/// Modification of the reflected fields, e.g. by `UPDATE` statement and bulk edits.
+pub trait ReflectableMut: Reflectable {
+    /// Set `field` to `value`, that is converted to the field type.
+    ///
+    /// If the field is not exists, is read-only, is required but `value` is `NULL`, or `value` cannot be converted to
+    /// the field type, an error will be returned and the field is left unchanged.
+    fn set_field(&mut self, field: &str, value: Value) -> Result<(), ReflectError>;
+}
+
+/// Conversion of the field type to [`Value`].
+pub trait ToValue {
+    /// Type of the values, that are not `NULL`.
+    const TYPE: Type;
+
+    /// Returns field value as [`Value`].
+    fn to_value(&self) -> Value<'_>;
+}
+
+/// Conversion of [`Value`] back to the field type.
+pub trait FromValue: Sized {
+    /// Try to convert `value` to the field type.
+    ///
+    /// `value` is cast to the required type, if conversion fails or is not possible, an error will be returned.
+    fn from_value(value: &Value) -> Result<Self, ConversionError>;
+}
+
+impl ToValue for String {
+    const TYPE: Type = Type::String;
+
+    fn to_value(&self) -> Value<'_> {
+        Value::String(self.as_str().into())
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: &Value) -> Result<Self, ConversionError> {
+        Ok(value.cast_to_string()?.into_owned())
+    }
+}
+
+impl ToValue for i64 {
+    const TYPE: Type = Type::Number;
+
+    fn to_value(&self) -> Value<'_> {
+        Value::Number(Number::Int(*self))
+    }
+}
+
+impl FromValue for i64 {
+    fn from_value(value: &Value) -> Result<Self, ConversionError> {
+        Ok(value.cast_to_number()?.as_i64())
+    }
+}
+
+impl ToValue for f64 {
+    const TYPE: Type = Type::Number;
+
+    fn to_value(&self) -> Value<'_> {
+        Value::Number(Number::Float(*self))
+    }
+}
+
+impl FromValue for f64 {
+    fn from_value(value: &Value) -> Result<Self, ConversionError> {
+        Ok(value.cast_to_number()?.as_f64())
+    }
+}
+
+impl ToValue for Decimal {
+    const TYPE: Type = Type::Number;
+
+    fn to_value(&self) -> Value<'_> {
+        Value::Number(Number::Decimal(*self))
+    }
+}
+
+/// Strings are parsed as decimal, floats are converted through their shortest representation, e.g. `0.1` is `0.1`.
+impl FromValue for Decimal {
+    fn from_value(value: &Value) -> Result<Self, ConversionError> {
+        let parse = |string: &str| string.parse().map_err(|err: ParseDecimalError| ConversionError::Failed {
+            value: value.to_static(),
+            dest_type: Type::Number,
+            reason: err.to_string(),
+        });
+        if let Value::String(string) = value {
+            return parse(string);
+        }
+        match value.cast_to_number()? {
+            Number::Int(int) => Ok(int.into()),
+            Number::Decimal(decimal) => Ok(decimal),
+            Number::Float(float) => parse(&float.to_string()),
+        }
+    }
+}
+
+impl ToValue for bool {
+    const TYPE: Type = Type::Bool;
+
+    fn to_value(&self) -> Value<'_> {
+        Value::Bool(*self)
+    }
+}
+
+impl FromValue for bool {
+    fn from_value(value: &Value) -> Result<Self, ConversionError> {
+        value.cast_to_bool()
+    }
+}
+
+impl ToValue for DateTime<Utc> {
+    const TYPE: Type = Type::DateTime;
+
+    fn to_value(&self) -> Value<'_> {
+        Value::DateTime(*self)
+    }
+}
+
+impl FromValue for DateTime<Utc> {
+    fn from_value(value: &Value) -> Result<Self, ConversionError> {
+        value.cast_to_datetime()
+    }
+}
+
+/// `None` is represented as [`Value::Null`].
+impl<T: ToValue> ToValue for Option<T> {
+    const TYPE: Type = T::TYPE;
+
+    fn to_value(&self) -> Value<'_> {
+        self.as_ref().map_or(Value::Null, ToValue::to_value)
+    }
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(value: &Value) -> Result<Self, ConversionError> {
+        match value {
+            Value::Null => Ok(None),
+            value => T::from_value(value).map(Some),
+        }
+    }
+}
+
+/// Vectors are represented as [`Value::List`] of their items.
+impl<T: ToValue> ToValue for Vec<T> {
+    const TYPE: Type = Type::List;
+
+    fn to_value(&self) -> Value<'_> {
+        Value::List(self.iter().map(ToValue::to_value).collect())
+    }
+}
+
+/// `NULL` is an empty vector, value, that is not a list, is a vector of one item.
+impl<T: FromValue> FromValue for Vec<T> {
+    fn from_value(value: &Value) -> Result<Self, ConversionError> {
+        match value {
+            Value::Null => Ok(Vec::new()),
+            Value::List(items) => items.iter().map(T::from_value).collect(),
+            value => Ok(vec![T::from_value(value)?]),
+        }
+    }
+}
+
+/// Implements [`ToValue`] and [`FromValue`] for types with [`Display`](std::fmt::Display) and
+/// [`FromStr`](std::str::FromStr) implementations, such as enums. Values are represented as [`Value::String`].
+#[macro_export]
+macro_rules! impl_value_via_string {
+    ($type:ty) => {
+        impl $crate::query::reflect::ToValue for $type {
+            const TYPE: $crate::query::evaluator::value::conversion::Type =
+                $crate::query::evaluator::value::conversion::Type::String;
+
+            fn to_value(&self) -> $crate::query::reflect::Value<'_> {
+                $crate::query::reflect::Value::String(self.to_string().into())
+            }
+        }
+
+        impl $crate::query::reflect::FromValue for $type {
+            fn from_value(
+                value: &$crate::query::reflect::Value,
+            ) -> Result<Self, $crate::query::evaluator::value::conversion::ConversionError> {
+                value.cast_to_string()?.parse::<$type>().map_err(|err| {
+                    $crate::query::evaluator::value::conversion::ConversionError::Failed {
+                        value: value.to_static(),
+                        dest_type: $crate::query::evaluator::value::conversion::Type::String,
+                        reason: err.to_string(),
+                    }
+                })
+            }
+        }
+    };
+}
+
+/// Implements [`ToValue`] and [`FromValue`] for newtype wrappers, representing them as their inner type.
+#[macro_export]
+macro_rules! impl_value_via_newtype {
+    ($type:ident($inner:ty)) => {
+        impl $crate::query::reflect::ToValue for $type {
+            const TYPE: $crate::query::evaluator::value::conversion::Type =
+                <$inner as $crate::query::reflect::ToValue>::TYPE;
+
+            fn to_value(&self) -> $crate::query::reflect::Value<'_> {
+                <$inner as $crate::query::reflect::ToValue>::to_value(&self.0)
+            }
+        }
+
+        impl $crate::query::reflect::FromValue for $type {
+            fn from_value(
+                value: &$crate::query::reflect::Value,
+            ) -> Result<Self, $crate::query::evaluator::value::conversion::ConversionError> {
+                <$inner as $crate::query::reflect::FromValue>::from_value(value).map($type)
+            }
+        }
+    };
+}
+
+pub use impl_value_via_newtype;
+pub use impl_value_via_string;
+
 /// Represents possible errors of type reflection.
 #[derive(Error, Debug)]
 pub enum ReflectError {
@@ -33,6 +375,17 @@ pub enum ReflectError {
     },
     #[error("Field not exists")]
     NoField(String),
+    #[error("Field '{0}' is required")]
+    Required(String),
+    #[error("Field '{0}' is read-only")]
+    ReadOnly(String),
+    #[error("Field '{field}' cannot be computed. {reason}")]
+    Computed { field: String, reason: String },
+    #[error("Field '{field}' has invalid value. {source}")]
+    InvalidValue {
+        field: String,
+        source: ConversionError,
+    },
 }
 
 #[cfg(test)]
@@ -53,7 +406,7 @@ pub mod tests {
         let test_reflect = TestReflect::default();
         let field_value = test_reflect.get_field("string");
 
-        assert!(matches!(field_value, Ok(Value::String(str)) if str == "Default string"));
+        assert!(matches!(field_value, Ok(Value::String(str)) if &*str == "Default string"));
     }
 
     #[test]
@@ -62,14 +415,80 @@ pub mod tests {
         let fields = test_reflect.fields();
 
         assert!(fields.eq([
-            ("string".into(), Value::Number(125.into())),
-            ("number".into(), Value::String("Default string".to_string())),
+            ("string".into(), Value::String("Default string".into())),
+            ("number".into(), Value::Number(125.into())),
             ("date_time".into(), Value::DateTime(NaiveDateTime::parse_from_str("2020-12-12 20:20", "%Y-%m-%d %H:%M")
                     .unwrap()
                     .and_utc()))
         ]));
     }
 
+    #[test]
+    fn optional_nested() {
+        let some = Some(TestReflect::default());
+        let none: Option<TestReflect> = None;
+
+        assert_eq!(some.get_field("number").unwrap(), Value::Number(125.into()));
+        assert_eq!(none.get_field("number").unwrap(), Value::Null);
+        assert!(matches!(none.get_field("unknown"), Err(ReflectError::NoField(_))));
+        assert_eq!(none.fields().count(), 3);
+        assert_eq!(none.get_nested("inner").unwrap().get_field("any").unwrap(), Value::Null);
+    }
+
+    #[test]
+    fn dynamic_fields() {
+        let fields = HashMap::from([
+            ("size".to_string(), Value::Number(3.into())),
+            ("estimate".to_string(), Value::String("2h".into())),
+        ]);
+
+        assert_eq!(fields.get_field("size").unwrap(), Value::Number(3.into()));
+        assert_eq!(fields.get_field("unknown").unwrap(), Value::Null);
+        assert!(fields.fields().map(|(name, _)| name).eq(["estimate", "size"]));
+        assert!(HashMap::<String, Value<'static>>::has_dynamic_fields());
+    }
+
+    #[test]
+    fn list_round_trip() {
+        let tags = vec!["urgent".to_string(), "home".to_string()];
+
+        assert_eq!(tags.to_value(), Value::List(vec![Value::String("urgent".into()), Value::String("home".into())]));
+        assert_eq!(Vec::<String>::from_value(&tags.to_value()).unwrap(), tags);
+        assert_eq!(Vec::<String>::from_value(&Value::String("urgent".into())).unwrap(), ["urgent"]);
+        assert_eq!(Vec::<i64>::from_value(&Value::Null).unwrap(), Vec::<i64>::new());
+    }
+
+    #[test]
+    fn newtype_round_trip() {
+        #[derive(Debug, PartialEq)]
+        struct Priority(i64);
+        impl_value_via_newtype!(Priority(i64));
+
+        let value = Priority(3).to_value();
+
+        assert_eq!(value, Value::Number(3.into()));
+        assert_eq!(Priority::from_value(&value).unwrap(), Priority(3));
+        assert!(Priority::from_value(&Value::Null).is_err());
+    }
+
+    #[test]
+    fn option_round_trip() {
+        assert_eq!(Some("owner".to_string()).to_value(), Value::String("owner".into()));
+        assert_eq!(None::<String>.to_value(), Value::Null);
+        assert_eq!(Option::<i64>::from_value(&Value::Null).unwrap(), None);
+        assert_eq!(Option::<i64>::from_value(&Value::Number(3.into())).unwrap(), Some(3));
+    }
+
+    #[test]
+    fn decimal_round_trip() {
+        let decimal = "12.30".parse::<Decimal>().unwrap();
+
+        assert_eq!(decimal.to_value().to_string(), "12.30");
+        assert_eq!(Decimal::from_value(&Value::String("12.30".into())).unwrap().to_string(), "12.30");
+        assert_eq!(Decimal::from_value(&Value::Number(0.1.into())).unwrap().to_string(), "0.1");
+        assert!(Decimal::from_value(&Value::String("twelve".into())).is_err());
+    }
+
     #[test]
     fn fields_name() {
         let fields = TestReflect::field_names();
@@ -84,11 +503,11 @@ pub mod tests {
     pub struct EmptyContext;
 
     impl Reflectable for EmptyContext {
-        fn get_field(&self, field: &str) -> Result<Value, ReflectError> {
+        fn get_field(&self, field: &str) -> Result<Value<'_>, ReflectError> {
             Err(ReflectError::NoField(field.to_string()))
         }
 
-        fn fields(&self) -> FieldsIterator {
+        fn fields(&self) -> FieldsIterator<'_> {
             Box::new(empty())
         }
 
@@ -96,29 +515,29 @@ pub mod tests {
             (&[]).into()
         }
     }
-    #[derive(Deserialize, Serialize, PartialEq, Debug)]
+    #[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
     pub struct TestReflect {
         pub string: String,
         pub number: i64,
         pub date_time: DateTime<Utc>,
     }
     impl Reflectable for TestReflect {
-        fn get_field(&self, field: &str) -> Result<Value, ReflectError> {
+        fn get_field(&self, field: &str) -> Result<Value<'_>, ReflectError> {
             let value = match field {
-                "string" => Value::String(self.string.to_string()),
+                "string" => Value::String(self.string.as_str().into()),
                 "number" => Value::Number(self.number.into()),
                 "date_time" => Value::DateTime(self.date_time),
                 field => return Err(ReflectError::NoField(field.to_string())),
             };
 
-            return Ok(value);
+            Ok(value)
         }
 
-        fn fields(&self) -> FieldsIterator {
+        fn fields(&self) -> FieldsIterator<'_> {
             Box::new(
                 [
-                    ("string".into(), Value::Number(self.number.into())),
-                    ("number".into(), Value::String(self.string.to_string())),
+                    ("string".into(), Value::String(self.string.as_str().into())),
+                    ("number".into(), Value::Number(self.number.into())),
                     ("date_time".into(), Value::DateTime(self.date_time)),
                 ]
                 .into_iter(),
@@ -1,11 +1,32 @@
 use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::collections::HashMap;
+#[cfg(feature = "json")]
+use std::io::Write;
+#[cfg(feature = "table")]
 use std::fmt::{Display, Formatter};
+#[cfg(feature = "table")]
 use std::iter::once;
 use std::ops::Deref;
+use std::sync::Arc;
+use fxhash::FxHashSet;
+#[cfg(feature = "table")]
 use tabled::builder::Builder;
-use tabled::settings::Style;
-use crate::query::evaluator::value::Value;
+#[cfg(feature = "table")]
+use crate::query::evaluator::context::ExecutionContext;
+#[cfg(feature = "table")]
+use tabled::settings::object::Columns;
+#[cfg(feature = "table")]
+use tabled::settings::peaker::PriorityMax;
+#[cfg(feature = "table")]
+use tabled::settings::{Style, Width};
+use crate::query::evaluator::aggregate::Aggregate;
+use crate::query::evaluator::value::{Text, Value};
+use crate::query::EvaluationError;
+#[cfg(feature = "json")]
+use crate::query::evaluator::value::Number;
+#[cfg(feature = "json")]
+use crate::query::evaluator::timezone::timezone;
 
 /// A table of data representing a [`Query`] result set.
 ///
@@ -22,18 +43,23 @@ use crate::query::evaluator::value::Value;
 /// result_set.add_row([("third", Value::Null), ("first", Value::Number(1.into())), ("second", Value::Bool(true))]);
 /// result_set.add_row([("first", Value::Number(1.into())), ("second", Value::Bool(true)), ("third", Value::Null)]);
 ///
+/// # #[cfg(feature = "table")]
 /// println!("{}", result_set);
 /// ```
+///
+/// String values are interned, so repeated values, e.g. categories and statuses, share one allocation across rows.
 pub struct ResultSet{
     columns: HashMap<String, usize>,
-    rows: Vec<Vec<Value>>
+    rows: Vec<Vec<Value<'static>>>,
+    strings: FxHashSet<Arc<str>>
 }
 impl ResultSet{
     /// Create new empty [`ResultSet`].
     pub fn new() -> ResultSet{
         ResultSet{
             columns: HashMap::new(),
-            rows: Vec::new()
+            rows: Vec::new(),
+            strings: FxHashSet::default()
         }
     }
     /// Create [`ResultSet`] with predefined `columns`.
@@ -68,10 +94,11 @@ impl ResultSet{
     /// Add new row with `values` to [`ResultSet`] .
     ///
     /// New columns will be added if required
-    pub fn add_row<'a, T: Into<Cow<'a, str>>>(&mut self, values: impl IntoIterator<Item = (T, Value)>){
+    pub fn add_row<'a, T: Into<Cow<'a, str>>>(&mut self, values: impl IntoIterator<Item = (T, Value<'a>)>){
         let mut row = vec![Value::Null; self.columns.len()];
 
         for (column_name, value) in values{
+            let value = self.intern(value);
             let column_name = column_name.into();
             if let Some(&id) = self.columns.get(&*column_name){
                 row[id] = value;
@@ -84,16 +111,46 @@ impl ResultSet{
         self.rows.push(row);
     }
 
+    /// Returns the `value`, which strings, including the ones in lists, e.g. tags, are replaced by the equal strings
+    /// of the previous rows.
+    ///
+    /// Borrowed strings are copied only once, when they first appear.
+    fn intern(&mut self, value: Value) -> Value<'static> {
+        let string = match value {
+            Value::String(string) => string,
+            Value::List(values) => return Value::List(values.into_iter().map(|value| self.intern(value)).collect()),
+            value => return value.into_static(),
+        };
+        match self.strings.get(&*string) {
+            Some(interned) => Value::String(Text::Shared(interned.clone())),
+            None => {
+                let string = string.into_shared();
+                self.strings.insert(string.clone());
+                Value::String(Text::Shared(string))
+            }
+        }
+    }
+
     /// Add multiple `rows` to [`ResultSet`] .
     ///
     /// New columns will be added if required
-    pub fn add_rows<'a, R: IntoIterator<Item = (T, Value)>, T: Into<Cow<'a, str>>>(&mut self, rows: impl IntoIterator<Item = R>){
+    pub fn add_rows<'a, R: IntoIterator<Item = (T, Value<'a>)>, T: Into<Cow<'a, str>>>(&mut self, rows: impl IntoIterator<Item = R>){
         for row in rows{
             self.add_row(row);
         }
 
     }
 
+    /// Append rows of the `other` [`ResultSet`], e.g. of the same query over another list.
+    ///
+    /// Columns are united: columns of `other`, that are new, are added and filled with `Value::Null` on existing rows,
+    /// as well as the columns, that `other` doesn't have, on its rows.
+    pub fn merge(&mut self, other: ResultSet){
+        let columns = other.columns().map(str::to_string).collect::<Vec<_>>();
+        self.add_columns(&columns);
+        self.add_rows(other.rows.into_iter().map(|row| columns.iter().map(String::as_str).zip(row)));
+    }
+
     /// Returns the iterator over the column names.
     ///
     /// The columns will be returned in the order in which they were added.
@@ -108,7 +165,7 @@ impl ResultSet{
     ///
     /// The rows will be returned in the order in which they were added.
     /// The values in the row are ordered according to the order of the columns in the current [`ResultSet`]
-    pub fn rows(&self) -> impl Iterator<Item=&[Value]>{
+    pub fn rows(&self) -> impl Iterator<Item=&[Value<'static>]>{
         self.rows
             .iter()
             .map(|x| x.deref())
@@ -117,19 +174,58 @@ impl ResultSet{
     /// Returns the iterator over references to the all [`Value`] of column with name `column_name`.
     ///
     /// If there is no such column in [`ResultSet`], an empty iterator will be returned.
-    pub fn get_column(&self, column_name: &str) -> impl Iterator<Item=&Value>{
+    pub fn get_column(&self, column_name: &str) -> impl Iterator<Item=&Value<'static>>{
         let idx = self.columns.get(column_name).copied();
 
         self.rows
             .iter()
-            .map(move |x| idx.and_then(|idx| x.get(idx)))
-            .flatten()
+            .filter_map(move |x| idx.and_then(|idx| x.get(idx)))
+
+    }
+    /// Returns the reference to the [`Value`] of the column `column_name` in the row at index `row`.
+    ///
+    /// If there is no such row or column in [`ResultSet`], `None` will be returned.
+    pub fn get_value(&self, row: usize, column_name: &str) -> Option<&Value<'static>>{
+        let idx = *self.columns.get(column_name)?;
+
+        self.rows.get(row)?.get(idx)
+    }
+
+    /// Replace the [`Value`] of the column `column_name` in the row at index `row` with `value`. Returns the replaced
+    /// value.
+    ///
+    /// If there is no such row or column in [`ResultSet`], it is not changed and `None` will be returned.
+    pub fn set_value(&mut self, row: usize, column_name: &str, value: Value) -> Option<Value<'static>>{
+        let idx = *self.columns.get(column_name)?;
+        self.rows.get(row)?;
+        let value = self.intern(value);
+
+        Some(std::mem::replace(&mut self.rows[row][idx], value))
+    }
 
+    /// Remove the row at index `row`, shifting the next rows. Returns the values of the removed row.
+    ///
+    /// If there is no row in [`ResultSet`] at the specified index, `None` will be returned.
+    pub fn remove_row(&mut self, row: usize) -> Option<Vec<Value<'static>>>{
+        (row < self.rows.len()).then(|| self.rows.remove(row))
     }
+
+    /// Returns the iterator over pages of the [`ResultSet`], that have the same columns and `rows` rows, except the
+    /// last one.
+    ///
+    /// [`ResultSet`] without rows has no pages.
+    pub fn pages(&self, rows: usize) -> impl Iterator<Item=ResultSet> + '_{
+        self.rows.chunks(rows.max(1)).map(|rows| ResultSet{
+            columns: self.columns.clone(),
+            rows: rows.to_vec(),
+            strings: FxHashSet::default()
+        })
+    }
+
     /// Returns the iterator over references to the [`Value`] in to the row at index `idx`.
     ///
     /// If there is no row in [`ResultSet`] at the specified index, an empty iterator will be returned.
-    pub fn get_row(&self, idx: usize) -> impl Iterator<Item=&Value>{
+    pub fn get_row(&self, idx: usize) -> impl Iterator<Item=&Value<'static>>{
         self.rows
             .get(idx)
             .into_iter()
@@ -138,23 +234,248 @@ impl ResultSet{
 
 }
 
-impl Display for ResultSet{
+impl ResultSet{
+    /// Returns the number of the rows.
+    pub fn row_count(&self) -> usize{
+        self.rows.len()
+    }
 
-    /// Print [`ResultSet`] in the table format.
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    /// Returns the number of the values of the column `column_name`, that are not `NULL`.
+    ///
+    /// If there is no such column in [`ResultSet`], `0` will be returned.
+    pub fn count_non_null(&self, column_name: &str) -> usize{
+        self.get_column(column_name).filter(|value| **value != Value::Null).count()
+    }
+
+    /// Returns sum of the numbers or durations of the column `column_name`.
+    pub fn sum(&self, column_name: &str) -> Result<Value<'static>, EvaluationError>{
+        self.aggregate(column_name, Aggregate::Sum)
+    }
+
+    /// Returns the least value of the column `column_name`.
+    pub fn min(&self, column_name: &str) -> Result<Value<'static>, EvaluationError>{
+        self.aggregate(column_name, Aggregate::Min)
+    }
+
+    /// Returns the greatest value of the column `column_name`.
+    pub fn max(&self, column_name: &str) -> Result<Value<'static>, EvaluationError>{
+        self.aggregate(column_name, Aggregate::Max)
+    }
+
+    /// Evaluate the `aggregate` function over the values of the column `column_name`, as it is evaluated by queries.
+    ///
+    /// `NULL` values are skipped. If there is no such column in [`ResultSet`], it is aggregated as a column without
+    /// values, e.g. its sum is `NULL`.
+    pub fn aggregate(&self, column_name: &str, aggregate: Aggregate) -> Result<Value<'static>, EvaluationError>{
+        aggregate.apply(self.get_column(column_name).cloned())
+    }
+}
+
+/// Returns the row count, that follows the totals of the table, e.g. `(2 rows)`.
+#[cfg(feature = "table")]
+pub(crate) fn rows_footer(rows: usize) -> String {
+    format!("({rows} {})", if rows == 1 { "row" } else { "rows" })
+}
+
+/// Direction of the rows order by the column.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum SortOrder {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl ResultSet{
+    /// Sort rows by the column `column_name` in the `order`.
+    ///
+    /// `NULL` values go last in both directions. If there is no such column in [`ResultSet`], rows keep their order.
+    pub fn sort_by_column(&mut self, column_name: &str, order: SortOrder){
+        self.sort_by_columns([(column_name, order)])
+    }
+
+    /// Sort rows by the first of the `keys`, ties are broken by the next ones. Sort is stable, so the rows, that are
+    /// equal by all keys, keep their order.
+    ///
+    /// `NULL` values go last in both directions. Columns, that [`ResultSet`] doesn't have, are skipped.
+    pub fn sort_by_columns<'a>(&mut self, keys: impl IntoIterator<Item = (&'a str, SortOrder)>){
+        let keys = keys
+            .into_iter()
+            .filter_map(|(column_name, order)| Some((*self.columns.get(column_name)?, order)))
+            .collect::<Vec<_>>();
+        let compare = |left: &Value, right: &Value, order: SortOrder| match (left, right) {
+            (Value::Null, Value::Null) => Ordering::Equal,
+            (Value::Null, _) => Ordering::Greater,
+            (_, Value::Null) => Ordering::Less,
+            (left, right) if order == SortOrder::Descending => right.partial_cmp(left).unwrap_or(Ordering::Equal),
+            (left, right) => left.partial_cmp(right).unwrap_or(Ordering::Equal),
+        };
+
+        self.rows.sort_by(|left, right| {
+            keys.iter()
+                .map(|&(idx, order)| compare(&left[idx], &right[idx], order))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+    }
+}
+
+impl Default for ResultSet{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Widths of the columns of the printed [`ResultSet`]. Values, that are wider than their column, are truncated with
+/// ellipsis or wrapped. Columns are not limited by default.
+#[cfg(feature = "table")]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TableLayout {
+    /// Maximal width of every column in characters.
+    pub max_column_width: Option<usize>,
+    /// Maximal widths of the columns by their names, that override `max_column_width`.
+    pub column_widths: Vec<(String, usize)>,
+    /// Maximal width of the whole table, e.g. of the terminal. The widest columns are shrunk first.
+    pub max_width: Option<usize>,
+    /// Wrap the values, that are wider than their column, by words instead of the truncation.
+    pub wrap: bool,
+    /// Show the footer with the totals of the columns and the row count, see [`ResultSet::column_total`].
+    pub totals: bool,
+}
+
+#[cfg(feature = "table")]
+impl ResultSet{
+    /// Render [`ResultSet`] in the table format with the column widths of the layout of the `context`. Values are
+    /// displayed in its time zone.
+    pub fn to_table(&self, context: &ExecutionContext) -> String {
+        context.scope(|| self.render_table(&context.table_layout))
+    }
+
+    fn render_table(&self, layout: &TableLayout) -> String {
         let mut table = Builder::new();
-        let mut columns = self.columns.iter().collect::<Vec<_>>();
-        columns.sort_by_key(|&(_,idx)| idx);
-        for (column,_) in columns{
+        for column in self.columns(){
             table.push_column(once(column));
         }
         for row in &self.rows{
             table.push_record(row);
         }
+        if layout.totals {
+            table.push_record(self.columns().map(|column| self.column_total(column)));
+        }
 
         let mut table = table.build();
+        table.with(Style::modern_rounded());
+        for (idx, column) in self.columns().enumerate() {
+            let width = layout
+                .column_widths
+                .iter()
+                .find(|(name, _)| name == column)
+                .map(|(_, width)| *width)
+                .or(layout.max_column_width);
+            match width {
+                Some(width) if layout.wrap => table.modify(Columns::single(idx), Width::wrap(width).keep_words(true)),
+                Some(width) => table.modify(Columns::single(idx), Width::truncate(width).suffix("…")),
+                None => &mut table,
+            };
+        }
+        match layout.max_width {
+            Some(width) if layout.wrap => table.with(Width::wrap(width).keep_words(true).priority(PriorityMax)),
+            Some(width) => table.with(Width::truncate(width).suffix("…").priority(PriorityMax)),
+            None => &mut table,
+        };
+
+        if layout.totals {
+            format!("{table}\n{}", rows_footer(self.row_count()))
+        } else {
+            table.to_string()
+        }
+    }
+
+    /// Returns the total of the column `column_name`, that is shown in the footer: sum of the numbers or durations,
+    /// the earliest and the latest of the date times, or the number of the other values, that are not `NULL`.
+    pub fn column_total(&self, column_name: &str) -> String {
+        let values = self.get_column(column_name).filter(|value| **value != Value::Null).collect::<Vec<_>>();
+        let total = match values.first() {
+            Some(Value::Number(_) | Value::Duration(_)) => self.sum(column_name).ok().map(|sum| format!("SUM {sum}")),
+            Some(Value::DateTime(_)) => match (self.min(column_name), self.max(column_name)) {
+                (Ok(min), Ok(max)) => Some(format!("{min} - {max}")),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        total.unwrap_or_else(|| format!("COUNT {}", values.len()))
+    }
+}
+
+#[cfg(feature = "table")]
+impl Display for ResultSet{
+
+    /// Print [`ResultSet`] in the table format with the layout of the current [`ExecutionContext`].
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_table(&ExecutionContext::current()))
+    }
+}
+
+impl ResultSet{
+    /// Render the rows as Markdown pipe table, e.g. to be pasted into GitHub issues.
+    ///
+    /// Pipes of the values are escaped, line breaks are replaced by `<br>`.
+    pub fn to_markdown(&self) -> String {
+        let line = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+        let escape = |cell: &str| cell.replace('|', "\\|").replace("\r\n", "<br>").replace('\n', "<br>");
+        let mut markdown = line(self.columns().map(escape).collect());
+        markdown.push_str(&line(self.columns().map(|_| "---".to_string()).collect()));
+        for row in &self.rows {
+            markdown.push_str(&line(row.iter().map(|value| escape(&value.to_string())).collect()));
+        }
+
+        markdown
+    }
+}
+
+#[cfg(feature = "json")]
+impl ResultSet{
+    /// Returns the rows as JSON array of objects, that are keyed by the column names.
+    pub fn to_json(&self) -> serde_json::Value {
+        self.rows().map(|row| serde_json::Value::Object(self.json_row(row).collect())).collect()
+    }
+
+    /// Write the rows as JSON array of objects, which members follow the order of the columns, to the `writer`.
+    pub fn write_json(&self, writer: impl Write) -> serde_json::Result<()> {
+        struct Rows<'a>(&'a ResultSet);
+        impl serde::Serialize for Rows<'_> {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_seq(self.0.rows().map(|row| Row(self.0, row)))
+            }
+        }
+        struct Row<'a>(&'a ResultSet, &'a [Value<'static>]);
+        impl serde::Serialize for Row<'_> {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_map(self.0.json_row(self.1))
+            }
+        }
+
+        serde_json::to_writer(writer, &Rows(self))
+    }
 
-        Display::fmt(table.with(Style::modern_rounded()), f)
+    fn json_row<'a>(&'a self, row: &'a [Value<'static>]) -> impl Iterator<Item = (String, serde_json::Value)> + 'a {
+        self.columns().zip(row).map(|(column, value)| (column.to_string(), to_json(value)))
+    }
+}
+
+/// Returns JSON of the `value`. Date times are in RFC 3339 in the user's timezone, durations are displayed as in
+/// queries.
+#[cfg(feature = "json")]
+fn to_json(value: &Value) -> serde_json::Value {
+    match value {
+        Value::Null => serde_json::Value::Null,
+        Value::Bool(bool) => (*bool).into(),
+        Value::Number(Number::Int(int)) => (*int).into(),
+        Value::Number(number) => number.as_f64().into(),
+        Value::String(string) => (**string).into(),
+        Value::DateTime(date_time) => date_time.with_timezone(&timezone()).to_rfc3339().into(),
+        Value::Duration(_) => value.to_string().into(),
+        Value::List(values) => values.iter().map(to_json).collect(),
     }
 }
 
@@ -178,6 +499,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "table")]
     fn print_table() {
         let result_set = test_result_set();
         println!("{}", result_set);
@@ -195,6 +517,169 @@ mod tests {
         ].join("\n"));
     }
 
+    #[test]
+    #[cfg(feature = "table")]
+    fn table_layout() {
+        let mut result_set = ResultSet::new();
+        result_set.add_row([("name", Value::String("Long task name".into())), ("done", Value::Bool(true))]);
+        let layout = TableLayout { column_widths: vec![("name".to_string(), 6)], ..TableLayout::default() };
+        let table = |table_layout| result_set.to_table(&ExecutionContext { table_layout, ..ExecutionContext::default() });
+
+        assert!(table(layout.clone()).contains("│ Long … │ true │"));
+        assert!(table(TableLayout { wrap: true, ..layout }).contains("│ Long   │ true │\n│ task   │      │"));
+        let table = table(TableLayout { max_width: Some(16), ..TableLayout::default() });
+        assert!(table.lines().all(|line| line.chars().count() <= 16));
+    }
+
+    #[test]
+    #[cfg(feature = "table")]
+    fn totals() {
+        let mut result_set = test_result_set();
+        result_set.add_row([("first", Value::Number(2.into())), ("third", Value::Duration(chrono::TimeDelta::hours(1)))]);
+        let context = ExecutionContext { table_layout: TableLayout { totals: true, ..TableLayout::default() }, ..ExecutionContext::default() };
+        let table = result_set.to_table(&context);
+
+        assert_eq!(result_set.column_total("first"), "SUM 5");
+        assert_eq!(result_set.column_total("second"), "COUNT 3");
+        assert!(result_set.column_total("third").starts_with("SUM "));
+        assert!(table.contains("│ SUM 5 │ COUNT 3 │"));
+        assert!(table.ends_with("\n(4 rows)"));
+    }
+
+    #[test]
+    fn intern_strings() {
+        let mut result_set = ResultSet::new();
+        result_set.add_rows([
+            [("category", Value::String("work".into()))],
+            [("category", Value::String("work".into()))],
+            [("category", Value::String("home".into()))],
+        ]);
+        result_set.add_row([("tags", Value::List(vec![Value::String("work".into())]))]);
+
+        let strings = result_set
+            .get_column("category")
+            .take(3)
+            .map(|value| match value {
+                Value::String(Text::Shared(string)) => string.clone(),
+                value => panic!("unexpected value {value}"),
+            })
+            .collect::<Vec<_>>();
+        assert!(Arc::ptr_eq(&strings[0], &strings[1]));
+        assert!(!Arc::ptr_eq(&strings[0], &strings[2]));
+        assert!(matches!(
+            result_set.get_column("tags").last(),
+            Some(Value::List(tags)) if matches!(&tags[0], Value::String(Text::Shared(tag)) if Arc::ptr_eq(tag, &strings[0]))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn write_json() {
+        let mut result_set = test_result_set();
+        result_set.add_row([("second", Value::List(vec![Value::String("tag".into())])), ("first", Value::Number(0.5.into()))]);
+        let mut json = Vec::new();
+        result_set.write_json(&mut json).unwrap();
+
+        assert_eq!(
+            String::from_utf8(json).unwrap(),
+            r#"[{"first":1,"second":true,"third":null},{"first":1,"second":true,"third":null},{"first":1,"second":true,"third":null},{"first":0.5,"second":["tag"],"third":null}]"#
+        );
+        assert_eq!(result_set.to_json()[3], serde_json::json!({"first": 0.5, "second": ["tag"], "third": null}));
+    }
+
+    #[test]
+    fn edit_values() {
+        let mut result_set = test_result_set();
+
+        assert_eq!(result_set.get_value(0, "first"), Some(&Value::Number(1.into())));
+        assert_eq!(result_set.get_value(3, "first"), None);
+        assert_eq!(result_set.get_value(0, "fourth"), None);
+        assert_eq!(result_set.set_value(1, "third", Value::String("set".into())), Some(Value::Null));
+        assert_eq!(result_set.get_value(1, "third"), Some(&Value::String("set".into())));
+        assert_eq!(result_set.set_value(1, "fourth", Value::Null), None);
+        assert_eq!(result_set.remove_row(0), Some(vec![Value::Number(1.into()), Value::Bool(true), Value::Null]));
+        assert_eq!(result_set.get_value(0, "third"), Some(&Value::String("set".into())));
+        assert_eq!(result_set.remove_row(2), None);
+        assert_eq!(result_set.rows().count(), 2);
+    }
+
+    #[test]
+    fn summary() {
+        let mut result_set = ResultSet::new();
+        result_set.add_rows([
+            [("number", Value::Number(2.into())), ("name", Value::String("b".into()))],
+            [("number", Value::Null), ("name", Value::String("a".into()))],
+            [("number", Value::Number(3.into())), ("name", Value::Null)],
+        ]);
+
+        assert_eq!(result_set.row_count(), 3);
+        assert_eq!(result_set.count_non_null("number"), 2);
+        assert_eq!(result_set.sum("number").unwrap(), Value::Number(5.into()));
+        assert_eq!(result_set.min("name").unwrap(), Value::String("a".into()));
+        assert_eq!(result_set.max("number").unwrap(), Value::Number(3.into()));
+        assert_eq!(result_set.sum("unknown").unwrap(), Value::Null);
+        assert!(result_set.sum("name").is_err());
+    }
+
+    #[test]
+    fn merge() {
+        let mut result_set = test_result_set();
+        let mut other = ResultSet::with_columns(["fourth", "first"]);
+        other.add_row([("first", Value::Number(2.into())), ("fourth", Value::Bool(false))]);
+        result_set.merge(other);
+
+        assert!(result_set.columns().eq(["first", "second", "third", "fourth"]));
+        assert!(result_set.get_column("first").eq(&[1, 1, 1, 2].map(|number| Value::Number(number.into()))));
+        assert!(result_set.get_column("fourth").eq(&[Value::Null, Value::Null, Value::Null, Value::Bool(false)]));
+        assert!(result_set.get_row(3).eq(&[Value::Number(2.into()), Value::Null, Value::Null, Value::Bool(false)]));
+    }
+
+    #[test]
+    fn sort_by_columns() {
+        let mut result_set = ResultSet::new();
+        result_set.add_rows([
+            [("name", Value::String("b".into())), ("number", Value::Number(1.into()))],
+            [("name", Value::Null), ("number", Value::Number(2.into()))],
+            [("name", Value::String("a".into())), ("number", Value::Number(1.into()))],
+            [("name", Value::String("c".into())), ("number", Value::Number(2.into()))],
+        ]);
+        let names = |result_set: &ResultSet| result_set.get_column("name").map(Value::to_string).collect::<Vec<_>>();
+
+        result_set.sort_by_column("name", SortOrder::Descending);
+        assert_eq!(names(&result_set), ["c", "b", "a", "NULL"]);
+        result_set.sort_by_columns([("number", SortOrder::Descending), ("name", SortOrder::Ascending)]);
+        assert_eq!(names(&result_set), ["c", "NULL", "a", "b"]);
+        result_set.sort_by_column("unknown", SortOrder::Ascending);
+        assert_eq!(names(&result_set), ["c", "NULL", "a", "b"]);
+    }
+
+    #[test]
+    fn pages() {
+        let pages = test_result_set().pages(2).collect::<Vec<_>>();
+
+        assert_eq!(pages.len(), 2);
+        assert!(pages[0].columns().eq(["first", "second", "third"]));
+        assert_eq!(pages[0].rows().count(), 2);
+        assert_eq!(pages[1].rows().count(), 1);
+        assert_eq!(ResultSet::new().pages(2).count(), 0);
+    }
+
+    #[test]
+    fn print_markdown() {
+        let mut result_set = test_result_set();
+        result_set.add_row([("first", Value::String("a | b\nc".into()))]);
+
+        assert_eq!(result_set.to_markdown(), [
+            "| first | second | third |",
+            "| --- | --- | --- |",
+            "| 1 | true | NULL |",
+            "| 1 | true | NULL |",
+            "| 1 | true | NULL |",
+            "| a \\| b<br>c | NULL | NULL |",
+            "",
+        ].join("\n"));
+    }
+
     pub fn test_result_set() -> ResultSet{
         let mut result_set = ResultSet::with_columns(["first", "second", "third"]);
         result_set.add_rows([
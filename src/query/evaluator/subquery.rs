@@ -0,0 +1,160 @@
+use crate::query::ast::expression::{Expression, FunctionCall, InSubquery, Operation, Subquery};
+use crate::query::ast::{Field, FieldsProjection, Predicate, Query};
+use crate::query::evaluator::reflect::Reflectable;
+use crate::query::evaluator::value::Value;
+use crate::query::EvaluationError;
+
+impl InSubquery {
+    /// Test, that the value of the expression with a given `context` is equal to one of the subquery values.
+    ///
+    /// Subquery must be materialized, as it is evaluated over all items rather than the `context`.
+    pub fn apply<C: Reflectable + ?Sized>(&self, context: &C) -> Result<Value<'static>, EvaluationError> {
+        let value = self.expression.eval(context)?;
+        let is_contained = match &self.subquery {
+            Subquery::Query(_) => return Err(EvaluationError::NotMaterialized),
+            Subquery::Materialized(values) => Value::any_eq(values, &value)?,
+            Subquery::Values(expression) => expression.eval(context)?.contains(&value)?,
+        };
+
+        Ok(Value::Bool(is_contained))
+    }
+}
+
+impl Query {
+    /// Returns the query, which subqueries are evaluated over `items` and replaced by their values.
+    pub fn materialize<T: Reflectable>(&self, items: &[&T]) -> Result<Query, EvaluationError> {
+        let fields = self
+            .fields_projection
+            .0
+            .iter()
+            .map(|field| match field {
+                Field::Asterisk => Ok(Field::Asterisk),
+                Field::Expression { expression, name } => Ok(Field::Expression {
+                    expression: expression.materialize(items)?,
+                    name: name.clone(),
+                }),
+            })
+            .collect::<Result<Vec<_>, EvaluationError>>()?;
+
+        Ok(Query {
+            fields_projection: FieldsProjection(fields),
+            from: self.from.clone(),
+            predicate: self.predicate.as_ref().map(|predicate| predicate.materialize(items)).transpose()?,
+            group_by: self.group_by.iter().map(|expression| expression.materialize(items)).collect::<Result<_, _>>()?,
+            collation: self.collation,
+        })
+    }
+}
+
+impl Predicate {
+    /// Returns the predicate, which subqueries are evaluated over `items` and replaced by their values.
+    pub fn materialize<T: Reflectable>(&self, items: &[&T]) -> Result<Predicate, EvaluationError> {
+        Ok(Predicate { expr: self.expr.materialize(items)? })
+    }
+}
+
+impl Expression {
+    /// Returns the expression, which subqueries are evaluated over `items` and replaced by their values.
+    ///
+    /// Subquery must select exactly one column.
+    pub fn materialize<T: Reflectable>(&self, items: &[&T]) -> Result<Expression, EvaluationError> {
+        let expression = match self {
+            Expression::Operation(operation) => Expression::Operation(Box::new(match &**operation {
+                Operation::Unary(operation) => {
+                    let mut operation = operation.clone();
+                    operation.expression = operation.expression.materialize(items)?;
+                    Operation::Unary(operation)
+                }
+                Operation::Binary(operation) => {
+                    let mut operation = operation.clone();
+                    operation.left_expression = operation.left_expression.materialize(items)?;
+                    operation.right_expression = operation.right_expression.materialize(items)?;
+                    Operation::Binary(operation)
+                }
+            })),
+            Expression::FunctionCall(call) => Expression::FunctionCall(FunctionCall {
+                name: call.name.clone(),
+                arguments: call.arguments.iter().map(|argument| argument.materialize(items)).collect::<Result<_, _>>()?,
+            }),
+            Expression::In(in_subquery) => {
+                let subquery = match &in_subquery.subquery {
+                    Subquery::Query(query) => {
+                        let result_set = query.execute(items.iter().copied())?;
+                        let columns = result_set.columns().count();
+                        if columns != 1 {
+                            return Err(EvaluationError::SubqueryColumns(columns));
+                        }
+                        Subquery::Materialized(result_set.rows().map(|row| row[0].clone()).collect())
+                    }
+                    Subquery::Values(expression) => Subquery::Values(expression.materialize(items)?),
+                    materialized => materialized.clone(),
+                };
+                Expression::In(Box::new(InSubquery { expression: in_subquery.expression.materialize(items)?, subquery }))
+            }
+            Expression::List(list) => {
+                Expression::List(list.iter().map(|item| item.materialize(items)).collect::<Result<_, _>>()?)
+            }
+            expression => expression.clone(),
+        };
+
+        Ok(expression)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use crate::query::reflect::tests::TestReflect;
+    use super::*;
+
+    #[test]
+    fn in_subquery() {
+        let items = [
+            TestReflect { string: "first".to_string(), number: 1, ..Default::default() },
+            TestReflect { string: "second".to_string(), number: 2, ..Default::default() },
+            TestReflect { string: "third".to_string(), number: 3, ..Default::default() },
+        ];
+        let query = Query::from_str("SELECT string WHERE number IN (SELECT number + 1 WHERE number < 3)").unwrap();
+
+        let result_set = query.execute(&items).unwrap();
+
+        assert_eq!(result_set.get_column("string").cloned().collect::<Vec<_>>(), [
+            Value::String("second".into()),
+            Value::String("third".into()),
+        ]);
+        assert_eq!(
+            query.materialize(&items.iter().collect::<Vec<_>>()).unwrap().to_string(),
+            "SELECT string WHERE number IN (2, 3)"
+        );
+
+        let query = Query::from_str("SELECT string WHERE number IN (SELECT *)").unwrap();
+        assert!(matches!(query.execute(&items), Err(EvaluationError::Validation(_))));
+
+        let predicate = Query::from_str("SELECT * WHERE number IN (SELECT number)").unwrap().predicate.unwrap();
+        assert!(matches!(predicate.test(&items[0]), Err(EvaluationError::NotMaterialized)));
+    }
+
+    #[test]
+    fn in_list() {
+        let items = [
+            TestReflect { string: "first".to_string(), number: 1, ..Default::default() },
+            TestReflect { string: "second".to_string(), number: 2, ..Default::default() },
+            TestReflect { string: "third".to_string(), number: 3, ..Default::default() },
+        ];
+        let select = |query: &str| {
+            let result_set = Query::from_str(query).unwrap().execute(&items).unwrap();
+            result_set.get_column("string").map(ToString::to_string).collect::<Vec<_>>()
+        };
+
+        assert_eq!(select("SELECT string WHERE string IN ('first', 'third')"), ["first", "third"]);
+        assert_eq!(select("SELECT string WHERE number IN [number - 1, 2]"), ["second"]);
+        assert_eq!(select("SELECT string WHERE number IN (1)"), ["first"]);
+        assert_eq!(select("SELECT string WHERE number IN []"), Vec::<String>::new());
+
+        let result_set = Query::from_str("SELECT [number, string] AS list").unwrap().execute(&items[..1]).unwrap();
+        assert_eq!(result_set.get_column("list").cloned().collect::<Vec<_>>(), [
+            Value::List(vec![Value::Number(1.into()), Value::String("first".into())]),
+        ]);
+        assert_eq!(result_set.get_column("list").next().unwrap().to_string(), "[1, first]");
+    }
+}
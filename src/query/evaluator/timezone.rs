@@ -0,0 +1,46 @@
+use chrono::{DateTime, NaiveDateTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+use crate::query::evaluator::context::ExecutionContext;
+
+/// Returns the time zone of the user of the current [`ExecutionContext`]. Defaults to UTC.
+///
+/// Date times are stored in UTC and converted from and to this time zone, when they are written without the offset:
+/// cast from strings, displayed, and compared with days and weeks, such as `TODAY`. Typed literals, e.g.
+/// `DATE '2024-12-12'`, are always UTC, so the parsed query does not depend on the time zone.
+pub fn timezone() -> Tz {
+    ExecutionContext::with(|context| context.timezone)
+}
+
+/// Returns the moment of the `local` date time in the time zone `tz`.
+///
+/// Ambiguous local time, which repeats when the clock is turned back, is the earliest of the moments. Local time,
+/// that is skipped when the clock is turned forward, is shifted back by the skipped period.
+pub fn from_local(tz: Tz, local: NaiveDateTime) -> DateTime<Utc> {
+    match tz.from_local_datetime(&local).earliest() {
+        Some(date_time) => date_time.to_utc(),
+        None => (local - tz.offset_from_utc_datetime(&local).fix()).and_utc(),
+    }
+}
+
+/// Returns the date time on the clock of the time zone `tz` at the moment `date_time`.
+pub fn to_local(tz: Tz, date_time: DateTime<Utc>) -> NaiveDateTime {
+    date_time.with_timezone(&tz).naive_local()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_time() {
+        let date_time = |date_time: &str| NaiveDateTime::parse_from_str(date_time, "%Y-%m-%d %H:%M").unwrap();
+        let berlin = Tz::Europe__Berlin;
+
+        assert_eq!(from_local(berlin, date_time("2024-12-12 20:20")), date_time("2024-12-12 19:20").and_utc());
+        assert_eq!(to_local(berlin, date_time("2024-07-12 19:20").and_utc()), date_time("2024-07-12 21:20"));
+        assert_eq!(from_local(Tz::UTC, date_time("2024-12-12 20:20")), date_time("2024-12-12 20:20").and_utc());
+        // Clock is turned back from 03:00 to 02:00, and forward from 02:00 to 03:00.
+        assert_eq!(from_local(berlin, date_time("2024-10-27 02:30")), date_time("2024-10-27 00:30").and_utc());
+        assert_eq!(from_local(berlin, date_time("2024-03-31 02:30")), date_time("2024-03-31 00:30").and_utc());
+    }
+}
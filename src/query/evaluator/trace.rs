@@ -0,0 +1,126 @@
+use std::fmt::{Display, Formatter};
+use crate::query::ast::expression::{Expression, Operation, Subquery};
+use crate::query::evaluator::reflect::{Reflectable, Value};
+use crate::query::EvaluationError;
+use crate::query::evaluator::context::ExecutionContext;
+
+/// Tests that the tracing of the failed evaluations is enabled in the current [`ExecutionContext`]. Disabled by
+/// default.
+///
+/// When tracing is enabled, the error of the query, that fails for an item, [`EvaluationError::Item`], includes the
+/// values of every node of the failed expression.
+pub fn is_tracing() -> bool {
+    ExecutionContext::with(|context| context.trace)
+}
+
+/// Values of the nodes of the expression tree, that was evaluated for an item.
+///
+/// Nodes are listed from the root, every node is followed by its operands.
+#[derive(Debug)]
+pub struct Trace(pub Vec<TraceStep>);
+
+/// Value of the expression node.
+#[derive(Debug)]
+pub struct TraceStep {
+    /// Depth of the node, the root is `0`.
+    pub depth: usize,
+    pub expression: String,
+    /// Value of the node, or the error, that it fails with.
+    pub result: Result<Value<'static>, String>,
+}
+
+impl Expression {
+    /// Evaluate every node of the expression for the `context`, so the node, that fails, is seen with its operands.
+    ///
+    /// Nodes are evaluated independently, so the expression is evaluated several times. It is meant for the items,
+    /// that failed.
+    pub fn trace<C: Reflectable + ?Sized>(&self, context: &C) -> Trace {
+        let mut steps = Vec::new();
+        self.trace_node(context, 0, &mut steps);
+        Trace(steps)
+    }
+
+    fn trace_node<C: Reflectable + ?Sized>(&self, context: &C, depth: usize, steps: &mut Vec<TraceStep>) {
+        let result = self.eval(context).map(Value::into_static).map_err(|err| err.to_string());
+        steps.push(TraceStep { depth, expression: self.to_string(), result });
+
+        let operands: Vec<&Expression> = match self {
+            Expression::Operation(operation) => match &**operation {
+                Operation::Unary(operation) => vec![&operation.expression],
+                Operation::Binary(operation) => vec![&operation.left_expression, &operation.right_expression],
+            },
+            Expression::FunctionCall(call) => call.arguments.iter().collect(),
+            Expression::In(in_subquery) => match &in_subquery.subquery {
+                Subquery::Values(values) => vec![&in_subquery.expression, values],
+                _ => vec![&in_subquery.expression],
+            },
+            Expression::List(items) => items.iter().collect(),
+            _ => Vec::new(),
+        };
+        for operand in operands {
+            operand.trace_node(context, depth + 1, steps);
+        }
+    }
+}
+
+impl Display for Trace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lines = self.0.iter().map(|step| {
+            let indent = "  ".repeat(step.depth + 1);
+            match &step.result {
+                Ok(value) => format!("{indent}{} = {value}", step.expression),
+                Err(err) => format!("{indent}{} failed: {}", step.expression, err.replace('\n', " ")),
+            }
+        });
+
+        f.write_str(&lines.collect::<Vec<_>>().join("\n"))
+    }
+}
+
+/// Returns the `err` of the `expression`, that is evaluated in the `location` of the query, e.g. `column 'name'`,
+/// for the item `context`, along with the item and the trace of the evaluation, if tracing is enabled.
+pub(crate) fn item_error<C: Reflectable + ?Sized>(
+    err: EvaluationError,
+    location: String,
+    expression: &Expression,
+    context: &C,
+) -> EvaluationError {
+    // The item is identified by its first field, that is the name of the task.
+    let item = match context.fields().next() {
+        Some((field, value)) => format!("{field} = {value}"),
+        None => "without fields".to_string(),
+    };
+    let trace = is_tracing().then(|| expression.trace(context));
+
+    EvaluationError::Item { item, location, trace, source: Box::new(err) }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use crate::query::Query;
+    use crate::query::reflect::tests::TestReflect;
+    use super::*;
+
+    #[test]
+    fn trace_failed_item() {
+        let query = Query::from_str("SELECT * WHERE number > 1 AND date_time > string").unwrap();
+        let trace = query.predicate.unwrap().expr.trace(&TestReflect::default());
+
+        assert_eq!(trace.0.len(), 7);
+        assert!(trace.0[0].result.is_err());
+        assert_eq!(trace.0[1].result, Ok(Value::Bool(true)));
+        assert_eq!(trace.0[2].depth, 2);
+        assert!(trace.to_string().contains("\n      string = Default string"));
+    }
+
+    #[test]
+    fn trace_in_context() {
+        let query = Query::from_str("SELECT * WHERE date_time > string").unwrap();
+        let items = [TestReflect::default()];
+        let context = ExecutionContext { trace: true, ..ExecutionContext::default() };
+
+        assert!(matches!(query.execute_in(&items, &context), Err(EvaluationError::Item { trace: Some(_), .. })));
+        assert!(matches!(query.execute(&items), Err(EvaluationError::Item { trace: None, .. })));
+    }
+}
@@ -0,0 +1,453 @@
+use crate::query::ast::expression::{BinaryOp, BinaryOperation, Expression, FunctionCall, Operation, Subquery};
+use crate::query::ast::{Field, Predicate, Query};
+use crate::query::evaluator::aggregate::Aggregate;
+use crate::query::evaluator::function::{expected_arity, Function};
+use crate::query::evaluator::reflect::{FieldsIterator, ReflectError, Reflectable, Type, Value};
+use chrono::{DateTime, TimeDelta};
+use std::collections::HashMap;
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+use thiserror::Error;
+
+impl Query {
+    /// Validate the query against the schema of `T`, before it is executed.
+    ///
+    /// All problems of the query are reported at once, see [`Predicate::validate`].
+    pub fn validate<T: Reflectable>(&self) -> Result<(), ValidationError> {
+        let mut validator = Validator::new::<T>();
+        validator.check_projection(self);
+        if let Some(predicate) = &self.predicate {
+            validator.check(&predicate.expr);
+        }
+        for expression in &self.group_by {
+            validator.check(expression);
+        }
+
+        validator.finish()
+    }
+}
+
+impl Predicate {
+    /// Validate the predicate against the schema of `T`, before it is tested.
+    ///
+    /// Validation reports unknown fields and functions, wrong number of function arguments and operations on
+    /// constant operands, that fail regardless of the tested value, e.g. `'text' > 10`. Dotted identifiers
+    /// are not validated, because nested values have no schema.
+    pub fn validate<T: Reflectable>(&self) -> Result<(), ValidationError> {
+        let mut validator = Validator::new::<T>();
+        validator.check(&self.expr);
+
+        validator.finish()
+    }
+}
+
+/// Problems of the query, that were found by validation.
+#[derive(Error, Debug)]
+pub struct ValidationError(pub Vec<Problem>);
+
+/// Problem of the query, that would fail its execution.
+#[derive(Debug, PartialEq)]
+pub enum Problem {
+    NoField { name: String, suggestion: Option<String> },
+    NoFunction { name: String, suggestion: Option<String> },
+    Arity { name: &'static str, min: usize, max: usize, received: usize },
+    Failed { expression: String, reason: String },
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Query is invalid:")?;
+        for problem in &self.0 {
+            write!(f, "\n  {problem}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Problem::NoField { name, suggestion } => {
+                write!(f, "no field '{name}'")?;
+                write_suggestion(suggestion, f)
+            }
+            Problem::NoFunction { name, suggestion } => {
+                write!(f, "no function '{name}'")?;
+                write_suggestion(suggestion, f)
+            }
+            Problem::Arity { name, min, max, received } => write!(
+                f,
+                "function '{name}' expects {} arguments, but {received} were given",
+                expected_arity(*min, *max)
+            ),
+            Problem::Failed { expression, reason } => write!(f, "'{expression}' cannot be evaluated: {reason}"),
+        }
+    }
+}
+
+fn write_suggestion(suggestion: &Option<String>, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match suggestion {
+        Some(suggestion) => write!(f, ", did you mean '{suggestion}'?"),
+        None => Ok(()),
+    }
+}
+
+/// Collects problems of the expressions.
+struct Validator {
+    fields: Cow<'static, [Cow<'static, str>]>,
+    /// Types of the fields, that are known.
+    types: Cow<'static, [(Cow<'static, str>, Type)]>,
+    /// Fields, that are not in `fields`, may exist, so they are not reported.
+    dynamic: bool,
+    /// Aggregate functions may be called by the checked expression, as it is projected.
+    aggregates: bool,
+    problems: Vec<Problem>,
+}
+
+impl Validator {
+    fn new<T: Reflectable>() -> Self {
+        Validator {
+            fields: T::field_names(),
+            types: T::field_types(),
+            dynamic: T::has_dynamic_fields(),
+            aggregates: false,
+            problems: Vec::new(),
+        }
+    }
+
+    /// Check the `expression`. Returns `true` if the expression is constant and can be evaluated.
+    fn check(&mut self, expression: &Expression) -> bool {
+        let is_constant = match expression {
+            Expression::Identifier(identifier) => {
+                let name = &identifier.0;
+                // Types without fields, such as the result sets, are not validated.
+                if !name.contains('.') && !self.dynamic && !self.fields.is_empty() && !self.fields.iter().any(|field| field == name) {
+                    let suggestion = suggest(name, self.fields.iter().map(|field| &**field));
+                    self.problems.push(Problem::NoField { name: name.clone(), suggestion });
+                }
+                false
+            }
+            Expression::Literal(_) | Expression::RelativeDate(_) => true,
+            Expression::Operation(operation) => match &**operation {
+                Operation::Unary(operation) => self.check(&operation.expression),
+                Operation::Binary(operation) => {
+                    let left = self.check(&operation.left_expression);
+                    let right = self.check(&operation.right_expression);
+                    self.check_comparison(expression, operation, left, right);
+                    left && right
+                }
+            },
+            Expression::FunctionCall(call) => match Aggregate::lookup(&call.name.0) {
+                Some(aggregate) => self.check_aggregate(expression, call, aggregate),
+                None => self.check_call(call),
+            },
+            Expression::In(in_subquery) => {
+                self.check(&in_subquery.expression);
+                match &in_subquery.subquery {
+                    Subquery::Query(query) => self.check_subquery(query),
+                    Subquery::Values(expression) => {
+                        self.check(expression);
+                    }
+                    Subquery::Materialized(_) => {}
+                }
+                false
+            }
+            // Every item is checked, so problems of all items are reported.
+            Expression::List(items) => {
+                let mut is_constant = true;
+                for item in items {
+                    is_constant &= self.check(item);
+                }
+                is_constant
+            }
+        };
+
+        // Only the outermost failing constant is reported, as it includes the failing operands.
+        if is_constant {
+            if let Err(err) = expression.eval(&Constant) {
+                self.problems.push(Problem::Failed { expression: expression.to_string(), reason: err.to_string() });
+                return false;
+            }
+        }
+
+        is_constant
+    }
+
+    fn check_call(&mut self, call: &FunctionCall) -> bool {
+        // All arguments are checked, so the problems of the later ones are reported too.
+        let arguments = call.arguments.iter().map(|argument| self.check(argument)).collect::<Vec<_>>();
+        let Some(function) = Function::lookup(&call.name.0) else {
+            let suggestion = suggest(&call.name.0, Function::all().into_iter().map(|function| function.name));
+            self.problems.push(Problem::NoFunction { name: call.name.0.clone(), suggestion });
+            return false;
+        };
+        if !function.arity.contains(&call.arguments.len()) {
+            self.problems.push(Problem::Arity {
+                name: function.name,
+                min: *function.arity.start(),
+                max: *function.arity.end(),
+                received: call.arguments.len(),
+            });
+            return false;
+        }
+
+        arguments.into_iter().all(|is_constant| is_constant)
+    }
+
+    /// Check the aggregate function call, that is allowed only in the projected expressions outside of the other
+    /// aggregates. Aggregate is never constant.
+    fn check_aggregate(&mut self, expression: &Expression, call: &FunctionCall, aggregate: Aggregate) -> bool {
+        if !self.aggregates {
+            self.problems.push(Problem::Failed {
+                expression: expression.to_string(),
+                reason: "aggregate functions are allowed only in the selected fields outside of the other aggregates".to_string(),
+            });
+        }
+        let aggregates = std::mem::replace(&mut self.aggregates, false);
+        for argument in &call.arguments {
+            self.check(argument);
+        }
+        self.aggregates = aggregates;
+        if !aggregate.arity().contains(&call.arguments.len()) {
+            self.problems.push(Problem::Arity {
+                name: aggregate.name(),
+                min: *aggregate.arity().start(),
+                max: *aggregate.arity().end(),
+                received: call.arguments.len(),
+            });
+        }
+
+        false
+    }
+
+    /// Check the projected expressions of the `query`, that may call aggregate functions. Fields of the aggregate
+    /// query cannot be selected by `*`, as they are not aggregated. Returns the number of the projected columns.
+    fn check_projection(&mut self, query: &Query) -> usize {
+        let is_aggregate = query.is_aggregate();
+        let mut columns = 0;
+        for field in &query.fields_projection.0 {
+            match field {
+                Field::Asterisk if is_aggregate => {
+                    self.problems.push(Problem::Failed {
+                        expression: "*".to_string(),
+                        reason: "fields of the query with aggregates or GROUP BY cannot be selected by *".to_string(),
+                    });
+                    columns += 1;
+                }
+                Field::Asterisk => columns += self.fields.len().max(1),
+                Field::Expression { expression, .. } => {
+                    let aggregates = std::mem::replace(&mut self.aggregates, true);
+                    self.check(expression);
+                    self.aggregates = aggregates;
+                    if is_aggregate {
+                        self.check_grouped(expression, &query.group_by);
+                    }
+                    columns += 1;
+                }
+            }
+        }
+
+        columns
+    }
+
+    /// Check, that the fields of the projected `expression` of the aggregate query are either `GROUP BY`
+    /// expressions or arguments of the aggregates, as they have a single value for the group.
+    fn check_grouped(&mut self, expression: &Expression, group_by: &[Expression]) {
+        if group_by.contains(expression) {
+            return;
+        }
+        match expression {
+            Expression::Identifier(identifier) => self.problems.push(Problem::Failed {
+                expression: identifier.0.clone(),
+                reason: "fields of the query with aggregates or GROUP BY must be grouped by or aggregated".to_string(),
+            }),
+            Expression::Operation(operation) => match &**operation {
+                Operation::Unary(operation) => self.check_grouped(&operation.expression, group_by),
+                Operation::Binary(operation) => {
+                    self.check_grouped(&operation.left_expression, group_by);
+                    self.check_grouped(&operation.right_expression, group_by);
+                }
+            },
+            Expression::FunctionCall(call) if Aggregate::lookup(&call.name.0).is_none() => {
+                for argument in &call.arguments {
+                    self.check_grouped(argument, group_by);
+                }
+            }
+            Expression::In(in_subquery) => self.check_grouped(&in_subquery.expression, group_by),
+            Expression::List(items) => {
+                for item in items {
+                    self.check_grouped(item, group_by);
+                }
+            }
+            Expression::FunctionCall(_) | Expression::Literal(_) | Expression::RelativeDate(_) => {}
+        }
+    }
+
+    /// Check the comparison of the field with the constant, that fails for any value of the field by its type,
+    /// e.g. `date > 'soon'`. String fields are not checked, as their values may be converted to any type.
+    fn check_comparison(&mut self, expression: &Expression, operation: &BinaryOperation, is_left_constant: bool, is_right_constant: bool) {
+        if !matches!(operation.op, BinaryOp::Gt | BinaryOp::Lt | BinaryOp::Gte | BinaryOp::Lte | BinaryOp::Eq) {
+            return;
+        }
+        let field = match (&operation.left_expression, &operation.right_expression) {
+            (Expression::Identifier(field), _) if is_right_constant => &field.0,
+            (_, Expression::Identifier(field)) if is_left_constant => &field.0,
+            _ => return,
+        };
+        let sample = match self.types.iter().find(|(name, _)| name == field) {
+            Some((_, Type::DateTime)) => Value::DateTime(DateTime::UNIX_EPOCH),
+            Some((_, Type::Number)) => Value::Number(0.into()),
+            Some((_, Type::Duration)) => Value::Duration(TimeDelta::zero()),
+            Some((_, Type::Bool)) => Value::Bool(false),
+            _ => return,
+        };
+
+        if let Err(err) = operation.apply(&HashMap::from([(field.clone(), sample)])) {
+            self.problems.push(Problem::Failed { expression: expression.to_string(), reason: err.to_string() });
+        }
+    }
+
+    /// Check expressions of the subquery over the same fields, and that it selects single column.
+    fn check_subquery(&mut self, query: &Query) {
+        let columns = self.check_projection(query);
+        let aggregates = std::mem::replace(&mut self.aggregates, false);
+        if let Some(predicate) = &query.predicate {
+            self.check(&predicate.expr);
+        }
+        for expression in &query.group_by {
+            self.check(expression);
+        }
+        self.aggregates = aggregates;
+        if let Some(list) = &query.from {
+            self.problems.push(Problem::Failed {
+                expression: format!("({query})"),
+                reason: format!("subquery selects from the same list as the query, but it selects from '{list}'"),
+            });
+        }
+        if columns != 1 {
+            self.problems.push(Problem::Failed {
+                expression: format!("({query})"),
+                reason: format!("subquery must select exactly one column, but it selects {columns}"),
+            });
+        }
+    }
+
+    fn finish(self) -> Result<(), ValidationError> {
+        if self.problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError(self.problems))
+        }
+    }
+}
+
+/// Context of the constant expressions, that has no fields.
+pub(crate) struct Constant;
+
+impl Reflectable for Constant {
+    fn get_field(&self, field: &str) -> Result<Value<'_>, ReflectError> {
+        Err(ReflectError::NoField(field.to_string()))
+    }
+
+    fn fields(&self) -> FieldsIterator<'_> {
+        Box::new(std::iter::empty())
+    }
+
+    fn field_names() -> Cow<'static, [Cow<'static, str>]> {
+        Cow::Borrowed(&[])
+    }
+}
+
+/// Returns the candidate, that the misspelled `name` most likely means: the one, that differs only in case,
+/// starts with the `name` or is within two edits of it.
+fn suggest<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Option<String> {
+    let name = name.to_lowercase();
+    candidates
+        .filter_map(|candidate| {
+            let lowercase = candidate.to_lowercase();
+            let distance = match edit_distance(&name, &lowercase) {
+                0 => 0,
+                _ if lowercase.starts_with(&name) => 1,
+                distance if distance <= 2 => distance + 1,
+                _ => return None,
+            };
+            Some((distance, candidate))
+        })
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.to_string())
+}
+
+/// Levenshtein distance between `left` and `right`.
+pub(crate) fn edit_distance(left: &str, right: &str) -> usize {
+    let right = right.chars().collect::<Vec<_>>();
+    let mut previous = (0..=right.len()).collect::<Vec<_>>();
+    for (i, left_char) in left.chars().enumerate() {
+        let mut current = Vec::with_capacity(previous.len());
+        current.push(i + 1);
+        for (j, right_char) in right.iter().enumerate() {
+            let substitution = previous[j] + usize::from(left_char != *right_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[right.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::reflect::tests::TestReflect;
+    use crate::task::Task;
+    use std::str::FromStr;
+
+    #[test]
+    fn validate_query() {
+        let validate = |query: &str| Query::from_str(query).unwrap().validate::<TestReflect>().map_err(|err| err.0);
+
+        assert!(validate("SELECT string, LOWER(string) WHERE number > 10 + 5 AND date_time < TODAY").is_ok());
+        assert!(validate("SELECT string WHERE string > 0").is_ok());
+        assert!(validate("SELECT nested.field").is_ok());
+        assert!(validate("SELECT string, COUNT(*), AVG(number + 1) + 1 WHERE number IN (SELECT MAX(number)) GROUP BY string").is_ok());
+        assert_eq!(validate("SELECT *, SUM(MAX(number)), COUNT(string, number) WHERE SUM(number) > 1").unwrap_err().len(), 4);
+        assert!(validate("SELECT UPPER(string), LOWER(string) = 'a', COUNT(*) GROUP BY UPPER(string), string").is_ok());
+        assert_eq!(
+            validate("SELECT string, number + 1, COUNT(*) GROUP BY string").unwrap_err(),
+            [Problem::Failed {
+                expression: "number".to_string(),
+                reason: "fields of the query with aggregates or GROUP BY must be grouped by or aggregated".to_string()
+            }]
+        );
+        assert_eq!(validate("SELECT string, COUNT(*)").unwrap_err().len(), 1);
+        assert_eq!(
+            validate("SELECT num, STRING WHERE lowr(strng) = 'a' AND SUBSTR(string) AND 'text' > 10 + 5").unwrap_err(),
+            [
+                Problem::NoField { name: "num".to_string(), suggestion: Some("number".to_string()) },
+                Problem::NoField { name: "STRING".to_string(), suggestion: Some("string".to_string()) },
+                Problem::NoField { name: "strng".to_string(), suggestion: Some("string".to_string()) },
+                Problem::NoFunction { name: "lowr".to_string(), suggestion: Some("LOWER".to_string()) },
+                Problem::Arity { name: "SUBSTR", min: 2, max: 3, received: 1 },
+                Problem::Failed {
+                    expression: "'text' > 10 + 5".to_string(),
+                    reason: "Failed to convert value 'text' to type 'Number'. \nReason: invalid float literal".to_string()
+                },
+            ]
+        );
+        assert_eq!(
+            Query::from_str("SELECT prio").unwrap().validate::<TestReflect>().unwrap_err().to_string(),
+            "Query is invalid:\n  no field 'prio'"
+        );
+    }
+
+    #[test]
+    fn validate_field_types() {
+        let validate = |query: &str| Query::from_str(query).unwrap().validate::<Task>().map_err(|err| err.0);
+
+        assert!(validate("SELECT * WHERE date > '2024-12-12' AND name > 10 AND status = 'on'").is_ok());
+        assert!(matches!(
+            &validate("SELECT * WHERE 'soon' < date").unwrap_err()[..],
+            [Problem::Failed { expression, .. }] if expression == "'soon' < date"
+        ));
+    }
+}
@@ -1,55 +1,211 @@
 pub mod conversion;
+pub mod decimal;
 pub mod operations;
 
-use std::borrow::Cow;
-use crate::query::ast::expression::Literal;
-use chrono::{DateTime, Utc};
+use std::borrow::{Borrow, Cow};
+use std::sync::Arc;
+use crate::query::ast::expression::{Expression, Literal};
+use chrono::{DateTime, TimeDelta, Utc};
+use decimal::Decimal;
+use crate::query::evaluator::timezone::{timezone, to_local};
+use crate::query::evaluator::context::ExecutionContext;
 use std::cmp::Ordering;
 use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
 use std::num::ParseFloatError;
 use std::str::FromStr;
 
 /// Represents possible values of [`Query`] expression execution.
+///
+/// Strings are borrowed from the reflected item for the lifetime `'a`, so reading the fields doesn't allocate.
+/// Values, that outlive the item, e.g. in the result set or in the errors, are `Value<'static>`.
+///
+/// Date times are displayed in the user's [`timezone`].
 #[derive(Debug, PartialEq, PartialOrd, Clone)]
-pub enum Value {
+pub enum Value<'a> {
     Null,
     Bool(bool),
     Number(Number),
-    String(String),
+    String(Text<'a>),
     DateTime(DateTime<Utc>),
+    Duration(TimeDelta),
+    List(Vec<Value<'a>>),
 }
 
-impl Display for Value {
+impl Value<'_> {
+    /// Returns the value, that doesn't borrow from the item, copying the borrowed strings.
+    pub fn into_static(self) -> Value<'static> {
+        match self {
+            Value::Null => Value::Null,
+            Value::Bool(bool) => Value::Bool(bool),
+            Value::Number(number) => Value::Number(number),
+            Value::String(string) => Value::String(string.into_static()),
+            Value::DateTime(date_time) => Value::DateTime(date_time),
+            Value::Duration(duration) => Value::Duration(duration),
+            Value::List(values) => Value::List(values.into_iter().map(Value::into_static).collect()),
+        }
+    }
+
+    /// Returns the copy of the value, that doesn't borrow from the item.
+    pub fn to_static(&self) -> Value<'static> {
+        self.clone().into_static()
+    }
+}
+
+impl Display for Value<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Value::Null => Display::fmt("NULL", f),
             Value::Bool(bool) => Display::fmt(bool, f),
             Value::String(string) => Display::fmt(string, f),
             Value::Number(number) => Display::fmt(number, f),
-            Value::DateTime(date_time) => Display::fmt(&date_time.format("%Y-%m-%d %H:%M"), f),
+            Value::DateTime(date_time) => Display::fmt(&to_local(timezone(), *date_time).format("%Y-%m-%d %H:%M"), f),
+            Value::Duration(duration) => Display::fmt(&conversion::format_duration(*duration), f),
+            Value::List(values) => {
+                let values = values.iter().map(ToString::to_string).collect::<Vec<_>>();
+                write!(f, "[{}]", values.join(", "))
+            }
         }
     }
 }
 
-impl From<&Literal> for Value {
-    fn from(val: &Literal) -> Value {
+/// String of the [`Value`], that is either borrowed from the reflected item, or shared between the values.
+#[derive(Debug, Clone)]
+pub enum Text<'a> {
+    Borrowed(&'a str),
+    Shared(Arc<str>),
+}
+
+impl Text<'_> {
+    /// Returns the text, that doesn't borrow from the item, copying the borrowed string.
+    pub fn into_static(self) -> Text<'static> {
+        Text::Shared(self.into_shared())
+    }
+
+    /// Returns the shared string, copying the borrowed one.
+    pub fn into_shared(self) -> Arc<str> {
+        match self {
+            Text::Borrowed(string) => string.into(),
+            Text::Shared(string) => string,
+        }
+    }
+}
+
+impl Deref for Text<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match self {
+            Text::Borrowed(string) => string,
+            Text::Shared(string) => string,
+        }
+    }
+}
+
+impl Borrow<str> for Text<'_> {
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+impl PartialEq for Text<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for Text<'_> {}
+
+impl PartialOrd for Text<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Text<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl Hash for Text<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl Display for Text<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+
+impl<'a> From<&'a str> for Text<'a> {
+    fn from(value: &'a str) -> Self {
+        Text::Borrowed(value)
+    }
+}
+
+impl From<String> for Text<'_> {
+    fn from(value: String) -> Self {
+        Text::Shared(value.into())
+    }
+}
+
+impl From<Arc<str>> for Text<'_> {
+    fn from(value: Arc<str>) -> Self {
+        Text::Shared(value)
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for Text<'a> {
+    fn from(value: Cow<'a, str>) -> Self {
+        match value {
+            Cow::Borrowed(string) => Text::Borrowed(string),
+            Cow::Owned(string) => string.into(),
+        }
+    }
+}
+
+impl<'a> From<&'a Literal> for Value<'a> {
+    fn from(val: &'a Literal) -> Value<'a> {
         match val {
             Literal::Null => Value::Null,
             Literal::Bool(bool) => Value::Bool(*bool),
             Literal::Number(number) => Value::Number(*number),
-            Literal::String(string) => Value::String(string.to_string()),
+            Literal::String(string) => Value::String(string.as_str().into()),
+            Literal::DateTime(date_time) => Value::DateTime(*date_time),
+            Literal::Duration(duration) => Value::Duration(*duration),
         }
     }
 }
 
-impl From<Value> for Cow<'static, Value> {
-    fn from(value: Value) -> Self {
+/// Value is converted to the literal, or to the list of literals.
+impl From<&Value<'_>> for Expression {
+    fn from(val: &Value) -> Expression {
+        let literal = match val {
+            Value::Null => Literal::Null,
+            Value::Bool(bool) => Literal::Bool(*bool),
+            Value::Number(number) => Literal::Number(*number),
+            Value::String(string) => Literal::String(string.to_string()),
+            Value::DateTime(date_time) => Literal::DateTime(*date_time),
+            Value::Duration(duration) => Literal::Duration(*duration),
+            Value::List(values) => return Expression::List(values.iter().map(Expression::from).collect()),
+        };
+
+        Expression::Literal(literal)
+    }
+}
+
+impl<'a> From<Value<'a>> for Cow<'_, Value<'a>> {
+    fn from(value: Value<'a>) -> Self {
         Cow::Owned(value)
     }
 }
 
-impl<'a> From<&'a Value> for Cow<'a, Value> {
-    fn from(value: &'a Value) -> Self {
+impl<'a, 'b> From<&'b Value<'a>> for Cow<'b, Value<'a>> {
+    fn from(value: &'b Value<'a>) -> Self {
         Cow::Borrowed(value)
     }
 }
@@ -57,6 +213,14 @@ impl<'a> From<&'a Value> for Cow<'a, Value> {
 pub enum Number {
     Int(i64),
     Float(f64),
+    /// Exact decimal, that is not subject to the floating point rounding.
+    Decimal(Decimal),
+}
+
+/// Returns tolerance of the query comparison operators with floats of the current [`ExecutionContext`]. Floats are
+/// compared exactly by default.
+pub fn float_epsilon() -> f64 {
+    ExecutionContext::with(|context| context.float_epsilon)
 }
 
 impl Number {
@@ -64,6 +228,7 @@ impl Number {
         match self {
             Number::Int(i64) => i64,
             Number::Float(f64) => f64 as i64,
+            Number::Decimal(decimal) => decimal.as_i64(),
         }
     }
 
@@ -71,6 +236,18 @@ impl Number {
         match self {
             Number::Int(i64) => i64 as f64,
             Number::Float(f64) => f64,
+            Number::Decimal(decimal) => decimal.as_f64(),
+        }
+    }
+
+    /// Compares the numbers like [`PartialOrd`], except that the comparison with floats is done within `epsilon`:
+    /// numbers, that differ by no more than it, are equal.
+    pub fn cmp_within(&self, other: &Number, epsilon: f64) -> Option<Ordering> {
+        match (self, other) {
+            (Number::Float(_), _) | (_, Number::Float(_)) if (self.as_f64() - other.as_f64()).abs() <= epsilon => {
+                Some(Ordering::Equal)
+            }
+            _ => self.partial_cmp(other),
         }
     }
 }
@@ -80,28 +257,26 @@ impl Display for Number {
         match self {
             Number::Int(int) => Display::fmt(int, f),
             Number::Float(float) => Display::fmt(float, f),
+            Number::Decimal(decimal) => Display::fmt(decimal, f),
         }
     }
 }
 
 impl PartialEq for Number {
     fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (Number::Float(first), Number::Int(second))
-            | (Number::Int(second), Number::Float(first)) => first.eq(&(*second as f64)),
-            (Number::Int(first), Number::Int(second)) => first.eq(second),
-            (Number::Float(first), Number::Float(second)) => first.eq(second),
-        }
+        self.partial_cmp(other) == Some(Ordering::Equal)
     }
 }
 
+/// Integers and decimals are compared exactly, comparison with floats is done in `f64`.
 impl PartialOrd for Number {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         match (self, other) {
-            (Number::Float(first), Number::Int(second)) => first.partial_cmp(&(*second as f64)),
-            (Number::Int(first), Number::Float(second)) => (*first as f64).partial_cmp(second),
             (Number::Int(first), Number::Int(second)) => first.partial_cmp(second),
-            (Number::Float(first), Number::Float(second)) => first.partial_cmp(second),
+            (Number::Decimal(first), Number::Decimal(second)) => first.partial_cmp(second),
+            (Number::Decimal(first), Number::Int(second)) => first.partial_cmp(&Decimal::from(*second)),
+            (Number::Int(first), Number::Decimal(second)) => Decimal::from(*first).partial_cmp(second),
+            (first, second) => first.as_f64().partial_cmp(&second.as_f64()),
         }
     }
 }
@@ -118,6 +293,12 @@ impl From<i64> for Number {
     }
 }
 
+impl From<Decimal> for Number {
+    fn from(value: Decimal) -> Self {
+        Number::Decimal(value)
+    }
+}
+
 impl FromStr for Number {
     type Err = ParseFloatError;
 
@@ -128,9 +309,9 @@ impl FromStr for Number {
     }
 }
 
-impl Into<String> for &Value {
-    fn into(self) -> String {
-        self.to_string()
+impl From<&Value<'_>> for String {
+    fn from(value: &Value) -> String {
+        value.to_string()
     }
 }
 
@@ -161,4 +342,5 @@ mod tests {
 
         assert!(left < right)
     }
+
 }
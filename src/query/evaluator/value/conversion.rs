@@ -1,19 +1,27 @@
 use super::{Number, Value};
-use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Utc};
 use std::borrow::Cow;
 use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
+use std::iter::once;
+use std::ops::Range;
+use std::sync::Arc;
+use crate::query::evaluator::context::ExecutionContext;
+use chrono::format::{Item, StrftimeItems};
 use thiserror::Error;
+use crate::query::evaluator::timezone::{from_local, timezone, to_local};
 
 /// Represents possible types of [`Value`].
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[repr(u8)]
 pub enum Type {
     DateTime = 0,
     Number = 1,
+    Duration = 2,
     Bool = 3,
     String = 4,
-    Null = 5,
+    List = 5,
+    Null = 6,
 }
 
 impl Type {
@@ -25,7 +33,7 @@ impl Type {
     }
 }
 
-impl Value {
+impl<'v> Value<'v> {
     /// Returns the type of current [`Value`]
     pub fn r#type(&self) -> Type {
         match self {
@@ -34,14 +42,16 @@ impl Value {
             Value::Number(_) => Type::Number,
             Value::String(_) => Type::String,
             Value::DateTime(_) => Type::DateTime,
+            Value::Duration(_) => Type::Duration,
+            Value::List(_) => Type::List,
         }
     }
     /// Unify types so they are now the same type and can be used in binary operations.
     ///
     /// When an operator combines expressions of different data types, the data type with the lower precedence is first converted to the data type with the higher precedence.
     pub fn unify_types<'a, 'b>(
-        left: &'a Value,
-        right: &'b Value,
+        left: &'a Self,
+        right: &'b Self,
     ) -> Result<(Cow<'a, Self>, Cow<'b, Self>), ConversionError> {
         let left_type = left.r#type();
         let right_type = right.r#type();
@@ -52,23 +62,58 @@ impl Value {
             Ordering::Greater => Ok((left.cast_to(right_type)?.into(), right.into())),
         }
     }
+    /// Compares `left` and `right` when one of them is a [`DateTime`] and another is a date-only value.
+    ///
+    /// The date-only value is either a day (`%Y-%m-%d`) or an ISO week (`%G-W%V`, e.g. `2024-W50`)
+    /// and is treated as the half-open period `[00:00 of the first day, 00:00 of the day after the last)`,
+    /// so `date = '2024-12-12'` matches any time of that day, `date < '2024-12-12'` matches times before it
+    /// and `date > '2024-12-12'` matches times after it. Returns `None` if values have the same granularity.
+    pub fn cmp_mixed_granularity(left: &Value, right: &Value) -> Option<Ordering> {
+        match (left, right) {
+            (Value::DateTime(date_time), period) => Some(cmp_to_period(date_time, period.as_period()?)),
+            (period, Value::DateTime(date_time)) => Some(cmp_to_period(date_time, period.as_period()?).reverse()),
+            _ => None,
+        }
+    }
+    /// Returns the half-open range of date times covering the period, if current [`Value`] is a date-only string.
+    ///
+    /// Supported periods are days (`%Y-%m-%d`) and ISO weeks (`%G-W%V`) in the user's [`timezone`].
+    pub fn as_period(&self) -> Option<Range<DateTime<Utc>>> {
+        let Value::String(string) = self else {
+            return None;
+        };
+        let (start, length) = match NaiveDate::parse_from_str(string, "%Y-%m-%d") {
+            Ok(day) => (day, TimeDelta::days(1)),
+            Err(_) => (NaiveDate::parse_from_str(&format!("{string}-1"), "%G-W%V-%u").ok()?, TimeDelta::weeks(1)),
+        };
+        let start = start.and_time(NaiveTime::MIN);
+        let tz = timezone();
+
+        Some(from_local(tz, start)..from_local(tz, start + length))
+    }
     /// Try to cast current [`Value`] to provided [`Type`].
     ///
     /// If conversion to the provided type fails or is not possible, an error will be returned.
     pub fn cast_to(&self, r#type: Type) -> Result<Self, ConversionError> {
-        return match r#type {
+        match r#type {
             Type::DateTime => self.cast_to_datetime().map(Value::DateTime),
             Type::Number => self.cast_to_number().map(Value::Number),
+            Type::Duration => self.cast_to_duration().map(Value::Duration),
             Type::Bool => self.cast_to_bool().map(Value::Bool),
-            Type::String => self.cast_to_string().map(|x| Value::String(x.to_string())),
+            Type::String => match self {
+                Value::String(string) => Ok(Value::String(string.clone())),
+                value => value.cast_to_string().map(|x| Value::String(x.into_owned().into())),
+            },
+            Type::List => self.cast_to_list().map(Value::List),
             Type::Null => Err(ConversionError::NotAllowed {
                 from: self.r#type(),
                 to: Type::Null,
             }),
-        };
+        }
     }
     /// Try to cast current [`Value`] to [`DateTime`].
     ///
+    /// Numbers are unix timestamps, strings are parsed by [`parse_datetime`].
     /// If conversion to [`DateTime`] fails or is not possible, an error will be returned.
     pub fn cast_to_datetime(&self) -> Result<DateTime<Utc>, ConversionError> {
         let value = match self {
@@ -82,13 +127,11 @@ impl Value {
                     }
                 })?
             }
-            Value::String(string) => NaiveDateTime::parse_from_str(string, "%Y-%m-%d %H:%M")
-                .map_err(|err| ConversionError::Failed {
-                    value: Value::String(string.to_string()),
-                    dest_type: Type::DateTime,
-                    reason: err.to_string(),
-                })?
-                .and_utc(),
+            Value::String(string) => parse_datetime(string).map_err(|reason| ConversionError::Failed {
+                value: Value::String(string.clone().into_static()),
+                dest_type: Type::DateTime,
+                reason,
+            })?,
             value => {
                 return Err(ConversionError::NotAllowed {
                     from: value.r#type(),
@@ -111,7 +154,7 @@ impl Value {
                 string
                     .parse::<Number>()
                     .map_err(|err| ConversionError::Failed {
-                        value: Value::String(string.to_string()),
+                        value: Value::String(string.clone().into_static()),
                         dest_type: Type::Number,
                         reason: err.to_string(),
                     })?
@@ -126,15 +169,38 @@ impl Value {
 
         Ok(value)
     }
+    /// Try to cast current [`Value`] to [`TimeDelta`].
+    ///
+    /// Strings are parsed as duration text, e.g. `3 days` or `2 hours 30 minutes`.
+    /// If conversion to [`TimeDelta`] fails or is not possible, an error will be returned.
+    pub fn cast_to_duration(&self) -> Result<TimeDelta, ConversionError> {
+        let value = match self {
+            Value::Duration(duration) => *duration,
+            Value::String(string) => parse_duration(string).map_err(|reason| ConversionError::Failed {
+                value: Value::String(string.clone().into_static()),
+                dest_type: Type::Duration,
+                reason,
+            })?,
+            value => {
+                return Err(ConversionError::NotAllowed {
+                    from: value.r#type(),
+                    to: Type::Duration,
+                })
+            }
+        };
+
+        Ok(value)
+    }
     /// Try to cast current [`Value`] to [`String`].
     ///
     /// If conversion to [`String`] fails or is not possible, an error will be returned.
-    pub fn cast_to_string(&self) -> Result<Cow<str>, ConversionError> {
+    pub fn cast_to_string(&self) -> Result<Cow<'_, str>, ConversionError> {
         let value = match self {
-            Value::String(string) => string.into(),
+            Value::String(string) => Cow::Borrowed(&**string),
             Value::Bool(bool) => bool.to_string().into(),
             Value::Number(number) => number.to_string().into(),
-            Value::DateTime(datetime) => datetime.format("%Y-%m-%d %H:%M").to_string().into(),
+            Value::DateTime(datetime) => to_local(timezone(), *datetime).format("%Y-%m-%d %H:%M").to_string().into(),
+            Value::Duration(duration) => format_duration(*duration).into(),
             value => {
                 return Err(ConversionError::NotAllowed {
                     from: value.r#type(),
@@ -145,24 +211,30 @@ impl Value {
 
         Ok(value)
     }
+    /// Try to cast current [`Value`] to the list of values.
+    ///
+    /// Only lists are lists, other values are not converted.
+    pub fn cast_to_list(&self) -> Result<Vec<Self>, ConversionError> {
+        match self {
+            Value::List(values) => Ok(values.clone()),
+            value => Err(ConversionError::NotAllowed {
+                from: value.r#type(),
+                to: Type::List,
+            }),
+        }
+    }
     /// Try to cast current [`Value`] to [`bool`].
     ///
     /// If conversion to [`bool`] fails or is not possible, an error will be returned.
     pub fn cast_to_bool(&self) -> Result<bool, ConversionError> {
         let value = match self {
             Value::Bool(bool) => *bool,
-            Value::Number(number) => {
-                if number.as_i64() == 0 {
-                    false
-                } else {
-                    true
-                }
-            }
+            Value::Number(number) => number.as_i64() != 0,
             Value::String(string) => {
                 string
                     .parse::<bool>()
                     .map_err(|err| ConversionError::Failed {
-                        value: Value::String(string.to_string()),
+                        value: Value::String(string.clone().into_static()),
                         dest_type: Type::Bool,
                         reason: err.to_string(),
                     })?
@@ -179,6 +251,135 @@ impl Value {
     }
 }
 
+/// Formats of the date time text, that are tried in order by [`parse_datetime`] after the registered ones.
+pub const DATETIME_FORMATS: [&str; 7] = [
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%d",
+    "%+",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M:%S",
+    "%d.%m.%Y %H:%M",
+    "%d.%m.%Y",
+];
+
+impl ExecutionContext {
+    /// Register date time `format`, e.g. `%m/%d/%Y`, that is tried by [`parse_datetime`] before the built-in
+    /// [`DATETIME_FORMATS`] and the formats registered earlier.
+    ///
+    /// Returns an error, if `format` is not a valid [`chrono::format::strftime`] format.
+    pub fn register_datetime_format(&mut self, format: &str) -> Result<(), String> {
+        if StrftimeItems::new(format).any(|item| item == Item::Error) {
+            return Err(format!("Invalid date time format '{format}'"));
+        }
+        if !self.datetime_formats.iter().any(|registered| registered == format) {
+            self.datetime_formats = once(format.to_string()).chain(self.datetime_formats.iter().cloned()).collect();
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns date time formats registered in the current [`ExecutionContext`], the latest registered first. They are
+/// tried before [`DATETIME_FORMATS`]. Formats are shared with the context, not copied.
+pub fn datetime_formats() -> Arc<[String]> {
+    ExecutionContext::with(|context| context.datetime_formats.clone())
+}
+
+/// Parse date time text by the first matching format of [`datetime_formats`], then of [`DATETIME_FORMATS`].
+///
+/// Time with the offset, e.g. RFC 3339 `2024-12-12T20:20:00+02:00`, is converted to UTC, time without the offset is
+/// taken in the user's [`timezone`], and date without the time is taken at midnight.
+pub fn parse_datetime(text: &str) -> Result<DateTime<Utc>, String> {
+    let registered = datetime_formats();
+    let formats = || registered.iter().map(String::as_str).chain(DATETIME_FORMATS.iter().copied());
+    let tz = timezone();
+    formats()
+        .find_map(|format| {
+            DateTime::parse_from_str(text, format)
+                .map(|date_time| date_time.to_utc())
+                .or_else(|_| NaiveDateTime::parse_from_str(text, format).map(|date_time| from_local(tz, date_time)))
+                .or_else(|_| NaiveDate::parse_from_str(text, format).map(|date| from_local(tz, date.and_time(NaiveTime::MIN))))
+                .ok()
+        })
+        .ok_or_else(|| format!("Date time '{text}' matches none of the formats: {}", formats().collect::<Vec<_>>().join(", ")))
+}
+
+/// Units of the duration text with their length in milliseconds.
+const DURATION_UNITS: &[(&str, &str, i64)] = &[
+    ("week", "weeks", 604_800_000),
+    ("day", "days", 86_400_000),
+    ("hour", "hours", 3_600_000),
+    ("minute", "minutes", 60_000),
+    ("second", "seconds", 1_000),
+    ("millisecond", "milliseconds", 1),
+];
+
+/// Parse duration text consisting of `<amount> <unit>` pairs, e.g. `3 days`, `1 day 2 hours` or `-1 week`.
+///
+/// Supported units are weeks, days, hours, minutes, seconds and milliseconds, both in singular and plural form.
+pub fn parse_duration(text: &str) -> Result<TimeDelta, String> {
+    let mut parts = text.split_whitespace().peekable();
+    if parts.peek().is_none() {
+        return Err("Duration is empty".to_string());
+    }
+
+    let mut milliseconds = 0i64;
+    while let Some(amount) = parts.next() {
+        let amount = amount
+            .parse::<i64>()
+            .map_err(|_| format!("Invalid amount '{amount}'"))?;
+        let unit = parts
+            .next()
+            .ok_or_else(|| format!("Missing unit after '{amount}'"))?;
+        let (_, _, unit_milliseconds) = DURATION_UNITS
+            .iter()
+            .find(|(singular, plural, _)| unit.eq_ignore_ascii_case(singular) || unit.eq_ignore_ascii_case(plural))
+            .ok_or_else(|| format!("Unknown unit '{unit}'"))?;
+
+        milliseconds = amount
+            .checked_mul(*unit_milliseconds)
+            .and_then(|amount| milliseconds.checked_add(amount))
+            .ok_or_else(|| "Duration is out-of-range".to_string())?;
+    }
+
+    TimeDelta::try_milliseconds(milliseconds).ok_or_else(|| "Duration is out-of-range".to_string())
+}
+
+/// Format duration as text, that can be parsed back by [`parse_duration`], e.g. `1 day 2 hours` or
+/// `-1 second -500 milliseconds`.
+///
+/// Precision below a millisecond is truncated toward zero, so durations shorter than a millisecond print as
+/// `0 seconds`.
+pub fn format_duration(duration: TimeDelta) -> String {
+    let mut milliseconds = duration.num_milliseconds();
+    if milliseconds == 0 {
+        return "0 seconds".to_string();
+    }
+
+    let mut parts = Vec::new();
+    for (singular, plural, unit_milliseconds) in &DURATION_UNITS[1..] {
+        let amount = milliseconds / unit_milliseconds;
+        milliseconds %= unit_milliseconds;
+        match amount {
+            0 => {}
+            1 | -1 => parts.push(format!("{amount} {singular}")),
+            amount => parts.push(format!("{amount} {plural}")),
+        }
+    }
+
+    parts.join(" ")
+}
+
+fn cmp_to_period(date_time: &DateTime<Utc>, period: Range<DateTime<Utc>>) -> Ordering {
+    if *date_time < period.start {
+        Ordering::Less
+    } else if period.contains(date_time) {
+        Ordering::Equal
+    } else {
+        Ordering::Greater
+    }
+}
+
 /// Represents possible errors of type conversion
 #[derive(Error, Debug)]
 pub enum ConversionError {
@@ -186,7 +387,7 @@ pub enum ConversionError {
     NotAllowed { from: Type, to: Type },
     #[error("Failed to convert value '{value}' to type '{dest_type}'. \nReason: {reason}")]
     Failed {
-        value: Value,
+        value: Value<'static>,
         dest_type: Type,
         reason: String,
     },
@@ -197,8 +398,10 @@ impl Display for Type {
         let val = match self {
             Type::DateTime => "DateTime",
             Type::Number => "Number",
+            Type::Duration => "Duration",
             Type::Bool => "Bool",
             Type::String => "String",
+            Type::List => "List",
             Type::Null => "Null",
         };
 
@@ -212,7 +415,7 @@ mod tests {
 
     #[test]
     fn unify_types() {
-        let left = Value::String("2020-12-12 20:20".to_string());
+        let left = Value::String("2020-12-12 20:20".into());
         let right = Value::DateTime(Utc::now());
 
         assert_ne!(left.r#type(), right.r#type());
@@ -229,13 +432,13 @@ mod tests {
 
     #[test]
     fn cast_string_to_num() {
-        let value = Value::String("3.14".to_string());
+        let value = Value::String("2.5".into());
 
         assert_ne!(value.r#type(), Type::Number);
 
-        assert!(matches!(value.cast_to_number(), Ok(Number::Float(3.14))));
+        assert!(matches!(value.cast_to_number(), Ok(Number::Float(2.5))));
 
-        let incorrect = Value::String("IncorrectNumber".to_string());
+        let incorrect = Value::String("IncorrectNumber".into());
 
         assert!(matches!(
             incorrect.cast_to_number(),
@@ -245,7 +448,7 @@ mod tests {
 
     #[test]
     fn cast_string_to_datetime() {
-        let value = Value::String("2020-12-12 20:20".to_string());
+        let value = Value::String("2020-12-12 20:20".into());
 
         assert_ne!(value.r#type(), Type::DateTime);
 
@@ -255,7 +458,7 @@ mod tests {
                 .and_utc())
         );
 
-        let incorrect = Value::String("IncorrectDate".to_string());
+        let incorrect = Value::String("IncorrectDate".into());
 
         assert!(matches!(
             incorrect.cast_to_datetime(),
@@ -263,6 +466,78 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn datetime_text() {
+        let date_time = |date_time: &str| NaiveDateTime::parse_from_str(date_time, "%Y-%m-%d %H:%M").unwrap().and_utc();
+
+        assert_eq!(parse_datetime("2020-12-12"), Ok(date_time("2020-12-12 00:00")));
+        assert_eq!(parse_datetime("2020-12-12T20:20:00+02:00"), Ok(date_time("2020-12-12 18:20")));
+        assert_eq!(parse_datetime("2020-12-12T20:20:00Z"), Ok(date_time("2020-12-12 20:20")));
+        assert_eq!(parse_datetime("2020-12-12 20:20:30"), Ok(date_time("2020-12-12 20:20") + TimeDelta::seconds(30)));
+        assert_eq!(parse_datetime("12.12.2020"), Ok(date_time("2020-12-12 00:00")));
+        assert!(parse_datetime("12/31/2020").is_err());
+
+        let mut context = ExecutionContext::default();
+        assert!(context.register_datetime_format("%Q").is_err());
+        context.register_datetime_format("%m/%d/%Y").unwrap();
+        context.scope(|| {
+            assert_eq!(parse_datetime("12/31/2020"), Ok(date_time("2020-12-31 00:00")));
+            assert_eq!(datetime_formats().first().map(String::as_str), Some("%m/%d/%Y"));
+            assert!(Arc::ptr_eq(&datetime_formats(), &datetime_formats()));
+        });
+        assert!(parse_datetime("12/31/2020").is_err());
+    }
+
+    #[test]
+    fn duration_text() {
+        assert_eq!(parse_duration("3 days"), Ok(TimeDelta::days(3)));
+        assert_eq!(parse_duration("1 Week 2 hours"), Ok(TimeDelta::weeks(1) + TimeDelta::hours(2)));
+        assert_eq!(parse_duration("-1 day"), Ok(TimeDelta::days(-1)));
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("3").is_err());
+        assert!(parse_duration("3 fortnights").is_err());
+
+        assert_eq!(format_duration(TimeDelta::days(8) + TimeDelta::minutes(1)), "8 days 1 minute");
+        assert_eq!(format_duration(-TimeDelta::hours(25)), "-1 day -1 hour");
+        assert_eq!(parse_duration(&format_duration(-TimeDelta::hours(25))), Ok(-TimeDelta::hours(25)));
+        assert_eq!(format_duration(TimeDelta::milliseconds(1_500)), "1 second 500 milliseconds");
+        assert_eq!(format_duration(-TimeDelta::milliseconds(250)), "-250 milliseconds");
+        assert_eq!(parse_duration("-250 milliseconds"), Ok(-TimeDelta::milliseconds(250)));
+        assert_eq!(format_duration(TimeDelta::microseconds(999)), "0 seconds");
+        assert_eq!(format_duration(-TimeDelta::microseconds(1_999)), "-1 millisecond");
+
+        let value = Value::String("2 hours".into());
+
+        assert!(matches!(value.cast_to(Type::Duration), Ok(Value::Duration(duration)) if duration == TimeDelta::hours(2)));
+    }
+
+    #[test]
+    fn mixed_granularity() {
+        let day = Value::String("2020-12-12".into());
+        let date_time = |date_time: &str| Value::DateTime(
+            NaiveDateTime::parse_from_str(date_time, "%Y-%m-%d %H:%M")
+                .unwrap()
+                .and_utc()
+        );
+
+        assert_eq!(Value::cmp_mixed_granularity(&date_time("2020-12-12 00:00"), &day), Some(Ordering::Equal));
+        assert_eq!(Value::cmp_mixed_granularity(&date_time("2020-12-12 23:59"), &day), Some(Ordering::Equal));
+        assert_eq!(Value::cmp_mixed_granularity(&date_time("2020-12-11 23:59"), &day), Some(Ordering::Less));
+        assert_eq!(Value::cmp_mixed_granularity(&date_time("2020-12-13 00:00"), &day), Some(Ordering::Greater));
+        assert_eq!(Value::cmp_mixed_granularity(&day, &date_time("2020-12-13 00:00")), Some(Ordering::Less));
+
+        let same_granularity = Value::String("2020-12-12 20:20".into());
+
+        assert_eq!(Value::cmp_mixed_granularity(&date_time("2020-12-12 20:20"), &same_granularity), None);
+
+        let week = Value::String("2020-W50".into());
+
+        assert_eq!(Value::cmp_mixed_granularity(&date_time("2020-12-06 23:59"), &week), Some(Ordering::Less));
+        assert_eq!(Value::cmp_mixed_granularity(&date_time("2020-12-07 00:00"), &week), Some(Ordering::Equal));
+        assert_eq!(Value::cmp_mixed_granularity(&date_time("2020-12-13 23:59"), &week), Some(Ordering::Equal));
+        assert_eq!(Value::cmp_mixed_granularity(&date_time("2020-12-14 00:00"), &week), Some(Ordering::Greater));
+    }
+
     #[test]
     fn not_allowed_cast() {
         let value = Value::Bool(true);
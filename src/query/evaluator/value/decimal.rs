@@ -0,0 +1,180 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter, Write};
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Maximal number of the fractional digits of [`Decimal`].
+pub const MAX_SCALE: u32 = 18;
+
+/// Exact decimal number, that is `mantissa * 10^-scale`.
+///
+/// Unlike `f64`, decimal fractions, e.g. monetary values, are represented exactly, so `0.1 + 0.2` is `0.3`.
+/// Scale is kept by arithmetic and display, so `1.50` stays `1.50`, but is equal to `1.5`. Mantissa is 64-bit,
+/// so decimal has up to 18 significant digits, e.g. `9999999999999.99999`.
+#[derive(Clone, Copy, Debug)]
+pub struct Decimal {
+    mantissa: i64,
+    scale: u32,
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ParseDecimalError {
+    #[error("Invalid decimal number")]
+    Invalid,
+    #[error("Decimal number has more than {MAX_SCALE} fractional digits")]
+    Scale,
+    #[error("Decimal number is out-of-range")]
+    Overflow,
+}
+
+impl Decimal {
+    /// Create decimal `mantissa * 10^-scale`. Returns `None`, if `scale` is greater than [`MAX_SCALE`].
+    pub fn new(mantissa: i64, scale: u32) -> Option<Decimal> {
+        (scale <= MAX_SCALE).then_some(Decimal { mantissa, scale })
+    }
+
+    pub fn mantissa(self) -> i64 {
+        self.mantissa
+    }
+
+    pub fn scale(self) -> u32 {
+        self.scale
+    }
+
+    pub fn checked_add(self, other: Decimal) -> Option<Decimal> {
+        let (first, second, scale) = self.align(other)?;
+        Some(Decimal { mantissa: first.checked_add(second)?, scale })
+    }
+
+    pub fn checked_sub(self, other: Decimal) -> Option<Decimal> {
+        let (first, second, scale) = self.align(other)?;
+        Some(Decimal { mantissa: first.checked_sub(second)?, scale })
+    }
+
+    pub fn checked_neg(self) -> Option<Decimal> {
+        Some(Decimal { mantissa: self.mantissa.checked_neg()?, scale: self.scale })
+    }
+
+    /// Returns the integer part of the decimal.
+    pub fn as_i64(self) -> i64 {
+        self.mantissa / 10i64.pow(self.scale)
+    }
+
+    pub fn as_f64(self) -> f64 {
+        self.mantissa as f64 / 10f64.powi(self.scale as i32)
+    }
+
+    /// Returns mantissas of both decimals at the common scale.
+    fn align(self, other: Decimal) -> Option<(i64, i64, u32)> {
+        let scale = self.scale.max(other.scale);
+        let rescale = |decimal: Decimal| decimal.mantissa.checked_mul(10i64.pow(scale - decimal.scale));
+        Some((rescale(self)?, rescale(other)?, scale))
+    }
+}
+
+impl From<i64> for Decimal {
+    fn from(value: i64) -> Self {
+        Decimal { mantissa: value, scale: 0 }
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        let digits = format!("{digits:0>width$}", width = scale + 1);
+        let (int, fraction) = digits.split_at(digits.len() - scale);
+
+        if self.mantissa < 0 {
+            f.write_char('-')?;
+        }
+        f.write_str(int)?;
+        if scale > 0 {
+            f.write_char('.')?;
+            f.write_str(fraction)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = ParseDecimalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, s) = match s.trim().as_bytes().first() {
+            Some(b'-') => (true, &s.trim()[1..]),
+            Some(b'+') => (false, &s.trim()[1..]),
+            _ => (false, s.trim()),
+        };
+        let (int, fraction) = s.split_once('.').unwrap_or((s, ""));
+        let is_digits = |part: &str| part.bytes().all(|byte| byte.is_ascii_digit());
+        if int.len() + fraction.len() == 0 || !is_digits(int) || !is_digits(fraction) {
+            return Err(ParseDecimalError::Invalid);
+        }
+        let scale = u32::try_from(fraction.len()).ok().filter(|scale| *scale <= MAX_SCALE).ok_or(ParseDecimalError::Scale)?;
+
+        // Negative mantissa is accumulated with the sign, so `i64::MIN` is not out-of-range.
+        let mut mantissa = 0i64;
+        for digit in int.bytes().chain(fraction.bytes()) {
+            let digit = i64::from(digit - b'0');
+            mantissa = mantissa
+                .checked_mul(10)
+                .and_then(|mantissa| if negative { mantissa.checked_sub(digit) } else { mantissa.checked_add(digit) })
+                .ok_or(ParseDecimalError::Overflow)?;
+        }
+
+        Ok(Decimal { mantissa, scale })
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Decimals are compared by value regardless of the scale.
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Mantissas are aligned in 128 bits, so they never overflow.
+        let scale = self.scale.max(other.scale);
+        let rescale = |decimal: &Decimal| i128::from(decimal.mantissa) * 10i128.pow(scale - decimal.scale);
+        rescale(self).cmp(&rescale(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimal_arithmetic() {
+        let decimal = |s: &str| s.parse::<Decimal>().unwrap();
+
+        assert_eq!(decimal("0.1").checked_add(decimal("0.2")).unwrap().to_string(), "0.3");
+        assert_eq!(decimal("1.50").checked_sub(decimal("3")).unwrap().to_string(), "-1.50");
+        assert_eq!(decimal("-.05").to_string(), "-0.05");
+        assert_eq!(decimal("1.50"), decimal("1.5"));
+        assert!(decimal("-2.01") < decimal("-2"));
+        assert_eq!(decimal("12.99").as_i64(), 12);
+        assert_eq!(Decimal::new(i64::MAX, 0).unwrap().checked_add(decimal("1")), None);
+        assert_eq!(Decimal::new(i64::MAX, 0).unwrap().checked_add(decimal("0.1")), None);
+        assert!(Decimal::new(i64::MAX, 0).unwrap() > decimal("0.1"));
+        assert_eq!(decimal("-9223372036854775808").mantissa(), i64::MIN);
+        assert_eq!(decimal("-9223372036854775808").checked_neg(), None);
+        assert_eq!("9223372036854775808".parse::<Decimal>(), Err(ParseDecimalError::Overflow));
+
+        assert_eq!("1.2.3".parse::<Decimal>(), Err(ParseDecimalError::Invalid));
+        assert_eq!(".".parse::<Decimal>(), Err(ParseDecimalError::Invalid));
+        assert_eq!("0.0000000000000000001".parse::<Decimal>(), Err(ParseDecimalError::Scale));
+    }
+}
@@ -1,68 +1,79 @@
+use std::cmp::Ordering;
+use chrono::{DateTime, TimeDelta, Utc};
 use thiserror::Error;
 use crate::query::EvaluationError;
-use crate::query::ast::expression::{BinaryOp};
-use super::Value;
+use crate::query::ast::expression::{BinaryOp, UnaryOp};
+use crate::query::ast::Collation;
+use super::{float_epsilon, Number, Value};
 use super::conversion::Type;
+use super::decimal::Decimal;
 
 
-impl Value{
+impl Value<'_>{
 
     /// Tests that `left` and `right` are equal.
     ///
     /// if `left` and `right` are of different types, they will be unified.
-    pub fn eq(left: &Value, right: &Value) -> Result<Value, EvaluationError> {
+    pub fn eq(left: &Value, right: &Value) -> Result<Value<'static>, EvaluationError> {
         if let (Value::Null, value ) | (value, Value::Null) = (left, right){
             return Ok(Value::Bool(value.r#type() == Type::Null))
         };
-        let (left, right) = Value::unify_types(left, right)?;
 
-        Ok(Value::Bool(left == right))
+        Ok(Value::Bool(Value::compare(left, right)?.is_some_and(Ordering::is_eq)))
+    }
+    /// Tests that `left` and `right` are equal, where `NULL` is equal to `NULL` and not equal to any other value.
+    ///
+    /// Result is never `NULL`, whatever the `NULL` handling of `=` is.
+    /// if `left` and `right` are of different types, they will be unified.
+    pub fn null_safe_eq(left: &Value, right: &Value) -> Result<Value<'static>, EvaluationError> {
+        match (left, right) {
+            (Value::Null, Value::Null) => Ok(Value::Bool(true)),
+            (Value::Null, _) | (_, Value::Null) => Ok(Value::Bool(false)),
+            (left, right) => Ok(Value::Bool(Value::compare(left, right)?.is_some_and(Ordering::is_eq))),
+        }
     }
     /// Tests that `left` is less than or equals to `right`.
     ///
     /// if `left` and `right` are of different types, they will be unified.
-    pub fn lte(left: &Value, right: &Value) -> Result<Value, EvaluationError> {
+    pub fn lte(left: &Value, right: &Value) -> Result<Value<'static>, EvaluationError> {
         Value::unsupported_null(left,right, BinaryOp::Lte)?;
-        let (left, right) = Value::unify_types(left, right)?;
 
-        Ok(Value::Bool(left <= right))
+        Ok(Value::Bool(Value::compare(left, right)?.is_some_and(Ordering::is_le)))
     }
 
     /// Tests that `left` is less than `right`.
     ///
     /// if `left` and `right` are of different types, they will be unified.
-    pub fn lt(left: &Value, right: &Value) -> Result<Value, EvaluationError> {
+    pub fn lt(left: &Value, right: &Value) -> Result<Value<'static>, EvaluationError> {
         Value::unsupported_null(left,right, BinaryOp::Lt)?;
-        let (left, right) = Value::unify_types(left, right)?;
 
-        Ok(Value::Bool(left < right))
+        Ok(Value::Bool(Value::compare(left, right)?.is_some_and(Ordering::is_lt)))
     }
     /// Tests that `left` is greater than or equals to `right`.
     ///
     /// if `left` and `right` are of different types, they will be unified.
-    pub fn gte(left: &Value, right: &Value) -> Result<Value, EvaluationError> {
+    pub fn gte(left: &Value, right: &Value) -> Result<Value<'static>, EvaluationError> {
         Value::unsupported_null(left,right, BinaryOp::Gte)?;
-        let (left, right) = Value::unify_types(left, right)?;
 
-        Ok(Value::Bool(left >= right))
+        Ok(Value::Bool(Value::compare(left, right)?.is_some_and(Ordering::is_ge)))
     }
     /// Tests that `left` is greater than `right`.
     ///
     /// if `left` and `right` are of different types, they will be unified.
-    pub fn gt(left: &Value, right: &Value) -> Result<Value, EvaluationError> {
+    pub fn gt(left: &Value, right: &Value) -> Result<Value<'static>, EvaluationError> {
         Value::unsupported_null(left,right, BinaryOp::Gt)?;
-        let (left, right) = Value::unify_types(left, right)?;
 
-        Ok(Value::Bool(left > right))
+        Ok(Value::Bool(Value::compare(left, right)?.is_some_and(Ordering::is_gt)))
     }
     /// Performs a logical "and" operation between `left` and `right`.
     ///
-    /// One of the values must be a boolean. Another will be converted to bool.
-    pub fn and(left: &Value, right: &Value) -> Result<Value, EvaluationError> {
+    /// One of the values must be a boolean. Another will be converted to bool, even if the result is already known.
+    pub fn and(left: &Value, right: &Value) -> Result<Value<'static>, EvaluationError> {
         if let (Value::Bool(left), right ) | ( right , Value::Bool(left)) = (left, right){
-            Ok(Value::Bool(*left && right.cast_to_bool()?))
+            let right = right.cast_to_bool()?;
+            Ok(Value::Bool(*left && right))
         } else {
-            return Err(BinaryOperationError::Unsupported {
+            Err(BinaryOperationError::Unsupported {
                 left: left.r#type(),
                 right: right.r#type(),
                 operator: BinaryOp::And
@@ -71,12 +82,13 @@ impl Value{
     }
     /// Performs a logical "or" operation between `left` and `right`.
     ///
-    /// One of the values must be a boolean. Another will be converted to bool.
-    pub fn or(left: &Value, right: &Value) -> Result<Value, EvaluationError> {
+    /// One of the values must be a boolean. Another will be converted to bool, even if the result is already known.
+    pub fn or(left: &Value, right: &Value) -> Result<Value<'static>, EvaluationError> {
         if let (Value::Bool(left), right ) | ( right , Value::Bool(left)) = (left, right){
-            Ok(Value::Bool(*left || right.cast_to_bool()?))
+            let right = right.cast_to_bool()?;
+            Ok(Value::Bool(*left || right))
         } else {
-            return Err(BinaryOperationError::Unsupported {
+            Err(BinaryOperationError::Unsupported {
                 left: left.r#type(),
                 right: right.r#type(),
                 operator: BinaryOp::Or
@@ -86,25 +98,244 @@ impl Value{
 
     /// Performs a pattern matching between `left` and `pattern`.
     ///
-    /// `pattern` must be a string. `left` value will be converted to string.
-    pub fn like(left: &Value, pattern: &Value) -> Result<Value, EvaluationError> {
-        if let Value::String(pattern) = pattern {
-            Ok(Value::Bool(left.cast_to_string()?.contains(&*pattern)))
-        } else {
+    /// `pattern` must be a string. `left` value will be converted to string, list matches if any of its items match.
+    pub fn like(left: &Value, pattern: &Value) -> Result<Value<'static>, EvaluationError> {
+        Value::match_text(left, pattern, BinaryOp::Like, |text, pattern| text.contains(pattern))
+    }
+    /// Tests that `left` starts with `prefix`.
+    ///
+    /// `prefix` must be a string. `left` value will be converted to string, list matches if any of its items match.
+    pub fn starts_with(left: &Value, prefix: &Value) -> Result<Value<'static>, EvaluationError> {
+        Value::match_text(left, prefix, BinaryOp::StartsWith, |text, prefix| text.starts_with(prefix))
+    }
+
+    /// Tests that `left` ends with `suffix`.
+    ///
+    /// `suffix` must be a string. `left` value will be converted to string, list matches if any of its items match.
+    pub fn ends_with(left: &Value, suffix: &Value) -> Result<Value<'static>, EvaluationError> {
+        Value::match_text(left, suffix, BinaryOp::EndsWith, |text, suffix| text.ends_with(suffix))
+    }
+
+    /// Tests `left` text against `pattern` with `matches` in the current collation. `NULL` items of the lists
+    /// never match.
+    fn match_text(
+        left: &Value,
+        pattern: &Value,
+        operator: BinaryOp,
+        matches: fn(&str, &str) -> bool,
+    ) -> Result<Value<'static>, EvaluationError> {
+        let Value::String(pattern) = pattern else {
+            return Err(BinaryOperationError::Unsupported { left: left.r#type(), right: pattern.r#type(), operator }.into());
+        };
+        let collation = Collation::current();
+        let pattern = collation.fold(pattern);
+        fn is_match(value: &Value, pattern: &str, collation: Collation, matches: fn(&str, &str) -> bool) -> Result<bool, EvaluationError> {
+            match value {
+                Value::List(items) => {
+                    for item in items.iter().filter(|item| **item != Value::Null) {
+                        if is_match(item, pattern, collation, matches)? {
+                            return Ok(true);
+                        }
+                    }
+                    Ok(false)
+                }
+                value => Ok(matches(&collation.fold(&value.cast_to_string()?), pattern)),
+            }
+        }
+
+        Ok(Value::Bool(is_match(left, &pattern, collation, matches)?))
+    }
+    /// Adds `right` to `left`.
+    ///
+    /// Numbers and durations are summed, durations can be added to date times. Integer and decimal sum, that
+    /// overflows, fails the operation instead of wrapping around.
+    pub fn add(left: &Value, right: &Value) -> Result<Value<'static>, EvaluationError> {
+        Value::unsupported_null(left, right, BinaryOp::Add)?;
+
+        match (left, right) {
+            (Value::DateTime(date_time), Value::Duration(duration))
+            | (Value::Duration(duration), Value::DateTime(date_time)) => {
+                Value::shift(left, right, BinaryOp::Add, date_time.checked_add_signed(*duration))
+            }
+            (Value::Duration(first), Value::Duration(second)) => {
+                Value::duration(left, right, BinaryOp::Add, first.checked_add(second))
+            }
+            _ => Value::arithmetic(left, right, BinaryOp::Add, i64::checked_add, Decimal::checked_add, |left, right| left + right),
+        }
+    }
+
+    /// Subtracts `right` from `left`.
+    ///
+    /// Numbers and durations are subtracted, durations can be subtracted from date times,
+    /// and date times subtracted from each other give the duration between them. Integer and decimal difference,
+    /// that overflows, fails the operation instead of wrapping around.
+    pub fn sub(left: &Value, right: &Value) -> Result<Value<'static>, EvaluationError> {
+        Value::unsupported_null(left, right, BinaryOp::Sub)?;
+
+        match (left, right) {
+            (Value::DateTime(date_time), Value::Duration(duration)) => {
+                Value::shift(left, right, BinaryOp::Sub, date_time.checked_sub_signed(*duration))
+            }
+            (Value::DateTime(first), Value::DateTime(second)) => Ok(Value::Duration(first.signed_duration_since(*second))),
+            (Value::Duration(first), Value::Duration(second)) => {
+                Value::duration(left, right, BinaryOp::Sub, first.checked_sub(second))
+            }
+            _ => Value::arithmetic(left, right, BinaryOp::Sub, i64::checked_sub, Decimal::checked_sub, |left, right| left - right),
+        }
+    }
+
+    /// Performs an arithmetic operation on numbers, checking integers and decimals for overflow.
+    ///
+    /// One of the values must be a number. Another will be converted to number. Decimals and integers give exact
+    /// decimal, any float operand makes the result float.
+    fn arithmetic(
+        left: &Value,
+        right: &Value,
+        op: BinaryOp,
+        int: fn(i64, i64) -> Option<i64>,
+        decimal: fn(Decimal, Decimal) -> Option<Decimal>,
+        float: fn(f64, f64) -> f64,
+    ) -> Result<Value<'static>, EvaluationError> {
+        let numeric = |value: &Value| matches!(value.r#type(), Type::Number | Type::String | Type::Bool);
+        let has_number = left.r#type() == Type::Number || right.r#type() == Type::Number;
+        if !has_number || !numeric(left) || !numeric(right) {
             return Err(BinaryOperationError::Unsupported {
                 left: left.r#type(),
-                right: pattern.r#type(),
-                operator: BinaryOp::Like
-            }.into())
+                right: right.r#type(),
+                operator: op,
+            }.into());
         }
+
+        let overflow = |reason: &str| BinaryOperationError::Failed {
+            operation: op,
+            left: left.to_static(),
+            right: right.to_static(),
+            reason: reason.to_string(),
+        };
+        let number = match (left.cast_to_number()?, right.cast_to_number()?) {
+            (Number::Int(first), Number::Int(second)) => Number::Int(int(first, second).ok_or_else(|| overflow("Integer overflow"))?),
+            (Number::Decimal(first), Number::Decimal(second)) => Number::Decimal(decimal(first, second).ok_or_else(|| overflow("Decimal overflow"))?),
+            (Number::Decimal(first), Number::Int(second)) => Number::Decimal(decimal(first, second.into()).ok_or_else(|| overflow("Decimal overflow"))?),
+            (Number::Int(first), Number::Decimal(second)) => Number::Decimal(decimal(first.into(), second).ok_or_else(|| overflow("Decimal overflow"))?),
+            (first, second) => Number::Float(float(first.as_f64(), second.as_f64())),
+        };
+
+        Ok(Value::Number(number))
     }
+
+    /// Wraps the result of shifting a date time by a duration.
+    fn shift(left: &Value, right: &Value, op: BinaryOp, result: Option<DateTime<Utc>>) -> Result<Value<'static>, EvaluationError> {
+        result
+            .map(Value::DateTime)
+            .ok_or_else(|| BinaryOperationError::Failed {
+                operation: op,
+                left: left.to_static(),
+                right: right.to_static(),
+                reason: "Date is out-of-range".to_string(),
+            }.into())
+    }
+
+    /// Wraps the result of summing or subtracting durations.
+    fn duration(left: &Value, right: &Value, op: BinaryOp, result: Option<TimeDelta>) -> Result<Value<'static>, EvaluationError> {
+        result
+            .map(Value::Duration)
+            .ok_or_else(|| BinaryOperationError::Failed {
+                operation: op,
+                left: left.to_static(),
+                right: right.to_static(),
+                reason: "Duration is out-of-range".to_string(),
+            }.into())
+    }
+
     /// Performs a logical "not" operation on `value`.
     ///
     /// Value will be converted to bool.
-    pub fn not(value: &Value) -> Result<Value, EvaluationError> {
+    pub fn not(value: &Value) -> Result<Value<'static>, EvaluationError> {
         Ok(Value::Bool(!value.cast_to_bool()?))
     }
 
+    /// Compares `left` and `right`, taking into account mixed date granularity, the current [`Collation`] and the
+    /// [`float_epsilon`] of the numbers.
+    ///
+    /// if `left` and `right` are of different types, they will be unified.
+    fn compare(left: &Value, right: &Value) -> Result<Option<Ordering>, EvaluationError> {
+        if let Some(ordering) = Value::cmp_mixed_granularity(left, right) {
+            return Ok(Some(ordering));
+        }
+        let (left, right) = Value::unify_types(left, right)?;
+        if let (Value::List(left), Value::List(right)) = (&*left, &*right) {
+            return Value::compare_lists(left, right);
+        }
+        if let (Value::Number(left), Value::Number(right)) = (&*left, &*right) {
+            return Ok(left.cmp_within(right, float_epsilon()));
+        }
+        let collation = Collation::current();
+
+        Ok(left.collate(collation).partial_cmp(&right.collate(collation)))
+    }
+
+    /// Compares lists item by item, the shorter list is less, if it is the beginning of the longer one.
+    ///
+    /// Items are compared as values of the comparison, except that `NULL` item is equal only to `NULL`.
+    fn compare_lists(left: &[Value], right: &[Value]) -> Result<Option<Ordering>, EvaluationError> {
+        for (left, right) in left.iter().zip(right) {
+            let ordering = match (left, right) {
+                (Value::Null, Value::Null) => Some(Ordering::Equal),
+                (Value::Null, _) | (_, Value::Null) => None,
+                (left, right) => Value::compare(left, right)?,
+            };
+            if ordering != Some(Ordering::Equal) {
+                return Ok(ordering);
+            }
+        }
+
+        Ok(Some(left.len().cmp(&right.len())))
+    }
+
+    /// Tests that the list contains `value`, that is equal to one of its items. Value, that is not a list,
+    /// is a list of one item.
+    pub fn contains(&self, value: &Value) -> Result<bool, EvaluationError> {
+        match self {
+            Value::List(items) => Value::any_eq(items, value),
+            item => Value::any_eq(std::slice::from_ref(item), value),
+        }
+    }
+
+    /// Tests that `value` is equal to one of the `items`.
+    pub(crate) fn any_eq(items: &[Value], value: &Value) -> Result<bool, EvaluationError> {
+        for item in items {
+            if Value::eq(value, item)?.cast_to_bool()? {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Performs an arithmetic negation of `value`.
+    ///
+    /// Durations are negated, other values will be converted to number.
+    pub fn neg(value: &Value) -> Result<Value<'static>, EvaluationError> {
+        if let Value::Duration(duration) = value {
+            return Ok(Value::Duration(-*duration));
+        }
+        let number = match value.cast_to_number()? {
+            Number::Int(int) => Number::Int(int.checked_neg().ok_or_else(|| UnaryOperationError::Failed {
+                operation: UnaryOp::Neg,
+                value: value.to_static(),
+                reason: "Integer overflow".to_string(),
+            })?),
+            Number::Float(float) => Number::Float(-float),
+            Number::Decimal(decimal) => Number::Decimal(decimal.checked_neg().ok_or_else(|| UnaryOperationError::Failed {
+                operation: UnaryOp::Neg,
+                value: value.to_static(),
+                reason: "Decimal overflow".to_string(),
+            })?),
+        };
+
+        Ok(Value::Number(number))
+    }
+
     fn unsupported_null(left: &Value, right: &Value, op: BinaryOp) -> Result<(), EvaluationError> {
         if let (Value::Null, _ ) | (_, Value::Null) = (left, right){
             return Err(BinaryOperationError::Unsupported {
@@ -127,8 +358,8 @@ pub enum BinaryOperationError {
     #[error("Failed to perform operation '{operation}' between values '{left}' and '{right}'. \nReason: {reason}")]
     Failed {
         operation: BinaryOp,
-        left: Value,
-        right: Value,
+        left: Value<'static>,
+        right: Value<'static>,
         reason: String,
     },
 }
@@ -137,11 +368,11 @@ pub enum BinaryOperationError {
 #[derive(Error, Debug)]
 pub enum UnaryOperationError {
     #[error("Unsupported unary operation '{operation}' on type '{r#type}'")]
-    Unsupported { r#type: Type, operation: BinaryOp },
+    Unsupported { r#type: Type, operation: UnaryOp },
     #[error("Failed to perform unary operation '{operation}' on value '{value}'. \nReason: {reason}")]
     Failed {
-        operation: BinaryOp,
-        value: Value,
+        operation: UnaryOp,
+        value: Value<'static>,
         reason: String,
     },
 }
@@ -149,7 +380,7 @@ pub enum UnaryOperationError {
 #[cfg(test)]
 mod tests {
     use chrono::NaiveDateTime;
-    use crate::query::evaluator::value::Number;
+    use crate::query::evaluator::context::ExecutionContext;
     use super::*;
 
     #[test]
@@ -162,7 +393,7 @@ mod tests {
 
     #[test]
     fn gt_different_types() {
-        let left = Value::String("2024-12-12 20:20".to_string());
+        let left = Value::String("2024-12-12 20:20".into());
         let right = Value::DateTime(NaiveDateTime::parse_from_str("2020-12-12 20:20", "%Y-%m-%d %H:%M")
             .unwrap()
             .and_utc());
@@ -186,9 +417,64 @@ mod tests {
         assert!(matches!(Value::eq(&left, &right), Ok(Value::Bool(false))));
     }
 
+    #[test]
+    fn list_comparison() {
+        let list = |items: &[Value<'static>]| Value::List(items.to_vec());
+        let number = |number: i64| Value::Number(number.into());
+        let date = Value::DateTime(NaiveDateTime::parse_from_str("2024-12-12 20:20", "%Y-%m-%d %H:%M").unwrap().and_utc());
+
+        assert!(matches!(Value::eq(&list(&[number(1), date.clone()]), &list(&[Value::Number(1.0.into()), Value::String("2024-12-12".into())])), Ok(Value::Bool(true))));
+        assert!(matches!(Value::lt(&list(&[number(1), number(2)]), &list(&[number(1), number(3)])), Ok(Value::Bool(true))));
+        assert!(matches!(Value::lt(&list(&[number(1)]), &list(&[number(1), number(0)])), Ok(Value::Bool(true))));
+        assert!(matches!(Value::gt(&list(&[number(2)]), &list(&[number(1), number(0)])), Ok(Value::Bool(true))));
+        assert!(matches!(Value::eq(&list(&[Value::Null]), &list(&[Value::Null])), Ok(Value::Bool(true))));
+        assert!(matches!(Value::eq(&list(&[Value::Null]), &list(&[number(1)])), Ok(Value::Bool(false))));
+        assert!(matches!(Value::eq(&list(&[]), &number(1)), Err(EvaluationError::Conversion(_))));
+        assert!(list(&[number(1), Value::String("a".into())]).contains(&Value::String("1".into())).unwrap());
+        assert!(!list(&[]).contains(&Value::Null).unwrap());
+    }
+
+    #[test]
+    fn null_safe_eq() {
+        let number = Value::Number(Number::from(10));
+
+        assert!(matches!(Value::null_safe_eq(&Value::Null, &Value::Null), Ok(Value::Bool(true))));
+        assert!(matches!(Value::null_safe_eq(&number, &Value::Null), Ok(Value::Bool(false))));
+        assert!(matches!(Value::null_safe_eq(&Value::Null, &number), Ok(Value::Bool(false))));
+        assert!(matches!(Value::null_safe_eq(&number, &Value::String("10".into())), Ok(Value::Bool(true))));
+    }
+
+    #[test]
+    fn float_epsilon_cmp() {
+        let sum = Value::Number((0.1 + 0.2).into());
+        let third = Value::Number((0.3).into());
+        let context = ExecutionContext { float_epsilon: 1e-9, ..ExecutionContext::default() };
+
+        assert_eq!(Value::eq(&sum, &third).unwrap(), Value::Bool(false));
+        context.scope(|| {
+            assert_eq!(Value::eq(&sum, &third).unwrap(), Value::Bool(true));
+            assert_eq!(Value::lt(&third, &Value::Number((0.30001).into())).unwrap(), Value::Bool(true));
+            // Equality of the values themselves stays exact.
+            assert_ne!(sum, third);
+        });
+    }
+
+    #[test]
+    fn eq_date_only() {
+        let left = Value::DateTime(NaiveDateTime::parse_from_str("2024-12-12 20:20", "%Y-%m-%d %H:%M")
+            .unwrap()
+            .and_utc());
+
+        assert!(matches!(Value::eq(&left, &Value::String("2024-12-12".into())), Ok(Value::Bool(true))));
+        assert!(matches!(Value::eq(&left, &Value::String("2024-12-13".into())), Ok(Value::Bool(false))));
+        assert!(matches!(Value::lt(&left, &Value::String("2024-12-13".into())), Ok(Value::Bool(true))));
+        assert!(matches!(Value::gt(&left, &Value::String("2024-12-12".into())), Ok(Value::Bool(false))));
+        assert!(matches!(Value::gte(&left, &Value::String("2024-12-12".into())), Ok(Value::Bool(true))));
+    }
+
     #[test]
     fn and_no_bool() {
-        let left = Value::String("2024-12-12 20:20".to_string());
+        let left = Value::String("2024-12-12 20:20".into());
         let right = Value::DateTime(NaiveDateTime::parse_from_str("2020-12-12 20:20", "%Y-%m-%d %H:%M")
             .unwrap()
             .and_utc());
@@ -206,7 +492,7 @@ mod tests {
 
     #[test]
     fn like_not_string_pattern() {
-        let left = Value::String("string".to_string());
+        let left = Value::String("string".into());
         let pattern = Value::Bool(false);
 
         assert!(matches!(Value::like(&left, &pattern), Err(EvaluationError::BinaryOperation(BinaryOperationError::Unsupported { .. }))));
@@ -214,9 +500,113 @@ mod tests {
 
     #[test]
     fn like() {
-        let left = Value::String("string".to_string());
-        let pattern = Value::String("str".to_string());
+        let left = Value::String("string".into());
+        let pattern = Value::String("str".into());
 
         assert!(matches!(Value::like(&left, &pattern), Ok(Value::Bool(true))));
     }
+
+    #[test]
+    fn like_list() {
+        let tags = Value::List(vec![Value::String("home".into()), Value::Null, Value::String("urgent".into())]);
+
+        assert!(matches!(Value::like(&tags, &Value::String("gen".into())), Ok(Value::Bool(true))));
+        assert!(matches!(Value::starts_with(&tags, &Value::String("ho".into())), Ok(Value::Bool(true))));
+        assert!(matches!(Value::ends_with(&tags, &Value::String("work".into())), Ok(Value::Bool(false))));
+        assert!(matches!(Value::like(&Value::List(vec![]), &Value::String("".into())), Ok(Value::Bool(false))));
+    }
+
+    #[test]
+    fn starts_with() {
+        let left = Value::String("string".into());
+
+        assert!(matches!(Value::starts_with(&left, &Value::String("str".into())), Ok(Value::Bool(true))));
+        assert!(matches!(Value::starts_with(&left, &Value::String("ing".into())), Ok(Value::Bool(false))));
+        assert!(matches!(Value::starts_with(&left, &Value::Null), Err(EvaluationError::BinaryOperation(BinaryOperationError::Unsupported { .. }))));
+    }
+
+    #[test]
+    fn ends_with() {
+        let left = Value::String("string".into());
+
+        assert!(matches!(Value::ends_with(&left, &Value::String("ing".into())), Ok(Value::Bool(true))));
+        assert!(matches!(Value::ends_with(&left, &Value::String("str".into())), Ok(Value::Bool(false))));
+    }
+
+    #[test]
+    fn add_and_sub() {
+        let date_time = |date_time: &str| Value::DateTime(NaiveDateTime::parse_from_str(date_time, "%Y-%m-%d %H:%M")
+            .unwrap()
+            .and_utc());
+
+        assert!(matches!(Value::add(&Value::Number(Number::from(2)), &Value::String("3".into())), Ok(Value::Number(Number::Int(5)))));
+        assert!(matches!(Value::sub(&Value::Number(Number::from(2)), &Value::Number(Number::from(0.5))), Ok(Value::Number(Number::Float(1.5)))));
+        assert_eq!(Value::add(&date_time("2024-12-12 20:20"), &Value::Duration(TimeDelta::days(7))).unwrap(), date_time("2024-12-19 20:20"));
+        assert_eq!(Value::add(&Value::Duration(TimeDelta::hours(1)), &date_time("2024-12-12 20:20")).unwrap(), date_time("2024-12-12 21:20"));
+        assert_eq!(Value::sub(&date_time("2024-12-12 20:20"), &Value::Duration(TimeDelta::days(12))).unwrap(), date_time("2024-11-30 20:20"));
+        assert!(matches!(Value::sub(&Value::Duration(TimeDelta::days(1)), &date_time("2024-12-12 20:20")), Err(EvaluationError::BinaryOperation(BinaryOperationError::Unsupported { .. }))));
+        assert!(matches!(Value::add(&Value::Number(Number::from(i64::MAX)), &Value::Number(Number::from(1))), Err(EvaluationError::BinaryOperation(BinaryOperationError::Failed { .. }))));
+        assert!(matches!(Value::add(&Value::Number(Number::from(1)), &Value::Null), Err(EvaluationError::BinaryOperation(BinaryOperationError::Unsupported { .. }))));
+    }
+
+    #[test]
+    fn integer_overflow() {
+        let int = |int: i64| Value::Number(Number::Int(int));
+        let failed = |result: Result<Value, EvaluationError>| matches!(
+            result,
+            Err(EvaluationError::BinaryOperation(BinaryOperationError::Failed { reason, .. })) if reason == "Integer overflow"
+        );
+
+        assert_eq!(Value::add(&int(i64::MAX - 1), &int(1)).unwrap(), int(i64::MAX));
+        assert_eq!(Value::add(&int(i64::MAX), &int(-1)).unwrap(), int(i64::MAX - 1));
+        assert_eq!(Value::sub(&int(i64::MIN + 1), &int(1)).unwrap(), int(i64::MIN));
+        assert_eq!(Value::sub(&int(-1), &int(i64::MAX)).unwrap(), int(i64::MIN));
+        assert!(failed(Value::add(&int(i64::MAX), &int(1))));
+        assert!(failed(Value::add(&int(i64::MIN), &int(-1))));
+        assert!(failed(Value::sub(&int(i64::MIN), &int(1))));
+        assert!(failed(Value::sub(&int(0), &int(i64::MIN))));
+        assert!(failed(Value::add(&int(i64::MAX), &Value::Bool(true))));
+        assert!(failed(Value::add(&Value::String("9223372036854775807".into()), &int(1))));
+        assert!(matches!(Value::add(&int(i64::MAX), &Value::Number(Number::Float(1.0))), Ok(Value::Number(Number::Float(_)))));
+        assert_eq!(Value::neg(&int(i64::MIN + 1)).unwrap(), int(i64::MAX));
+    }
+
+    #[test]
+    fn duration_arithmetic() {
+        let date_time = |date_time: &str| Value::DateTime(NaiveDateTime::parse_from_str(date_time, "%Y-%m-%d %H:%M")
+            .unwrap()
+            .and_utc());
+        let duration = Value::Duration;
+
+        assert_eq!(Value::sub(&date_time("2024-12-12 20:20"), &date_time("2024-12-10 19:20")).unwrap(), duration(TimeDelta::hours(49)));
+        assert_eq!(Value::sub(&date_time("2024-12-10 20:20"), &date_time("2024-12-12 20:20")).unwrap(), duration(TimeDelta::days(-2)));
+        assert_eq!(Value::add(&duration(TimeDelta::days(1)), &duration(TimeDelta::hours(2))).unwrap(), duration(TimeDelta::hours(26)));
+        assert_eq!(Value::sub(&duration(TimeDelta::days(1)), &duration(TimeDelta::days(3))).unwrap(), duration(TimeDelta::days(-2)));
+        assert_eq!(Value::neg(&duration(TimeDelta::days(1))).unwrap(), duration(TimeDelta::days(-1)));
+        assert!(matches!(Value::add(&duration(TimeDelta::MAX), &duration(TimeDelta::days(1))), Err(EvaluationError::BinaryOperation(BinaryOperationError::Failed { .. }))));
+        assert!(matches!(Value::gt(&duration(TimeDelta::days(31)), &Value::String("30 days".into())), Ok(Value::Bool(true))));
+        assert!(matches!(Value::gt(&Value::sub(&date_time("2024-12-12 20:20"), &date_time("2024-11-01 00:00")).unwrap(), &duration(TimeDelta::days(30))), Ok(Value::Bool(true))));
+    }
+
+    #[test]
+    fn decimal_arithmetic() {
+        let decimal = |decimal: &str| Value::Number(Number::Decimal(decimal.parse().unwrap()));
+
+        assert_eq!(Value::add(&decimal("0.1"), &decimal("0.2")).unwrap(), decimal("0.3"));
+        assert_eq!(Value::add(&decimal("0.1"), &decimal("0.2")).unwrap().to_string(), "0.3");
+        assert_eq!(Value::sub(&decimal("10.00"), &Value::Number(Number::from(3))).unwrap().to_string(), "7.00");
+        assert!(matches!(Value::add(&decimal("1.5"), &Value::Number(Number::from(0.25))), Ok(Value::Number(Number::Float(1.75)))));
+        assert!(matches!(Value::eq(&decimal("2.50"), &Value::Number(Number::from(2.5))), Ok(Value::Bool(true))));
+        assert!(matches!(Value::gt(&decimal("2.000000000000000001"), &Value::Number(Number::from(2))), Ok(Value::Bool(true))));
+        assert_eq!(Value::neg(&decimal("1.5")).unwrap(), decimal("-1.5"));
+        let max = Value::Number(Number::Decimal(Decimal::new(i64::MAX, 2).unwrap()));
+        assert!(matches!(Value::add(&max, &decimal("0.01")), Err(EvaluationError::BinaryOperation(BinaryOperationError::Failed { .. }))));
+    }
+
+    #[test]
+    fn neg() {
+        assert!(matches!(Value::neg(&Value::Number(Number::from(10))), Ok(Value::Number(Number::Int(-10)))));
+        assert!(matches!(Value::neg(&Value::String("2.5".into())), Ok(Value::Number(Number::Float(-2.5)))));
+        assert!(matches!(Value::neg(&Value::Number(Number::from(i64::MIN))), Err(EvaluationError::UnaryOperation(UnaryOperationError::Failed { .. }))));
+    }
 }
\ No newline at end of file
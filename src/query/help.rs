@@ -0,0 +1,131 @@
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+use crate::query::ast::expression::{BinaryOp, RelativeDate, UnaryOp};
+use crate::query::evaluator::aggregate::Aggregate;
+use crate::query::evaluator::function::Function;
+use crate::query::evaluator::value::conversion::Type;
+
+/// Reference of the query language.
+///
+/// Operators, functions and relative dates are rendered from their definitions, so the reference stays in sync with
+/// the parser and the evaluator.
+pub struct SyntaxHelp<'a> {
+    fields: &'a [Cow<'static, str>],
+}
+
+impl<'a> SyntaxHelp<'a> {
+    /// Create reference of the queries over items with `fields`.
+    pub fn new(fields: &'a [Cow<'static, str>]) -> SyntaxHelp<'a> {
+        SyntaxHelp { fields }
+    }
+}
+
+impl Display for SyntaxHelp<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "SELECT <field>[, <field>...] [FROM <list>] [WHERE <predicate>] [GROUP BY <expression>[, <expression>...]] [COLLATE BINARY|NOCASE]")?;
+        writeln!(f)?;
+        writeln!(f, "Fields:")?;
+        writeln!(f, "  {}", self.fields.join(", "))?;
+        writeln!(f, "  * - all fields")?;
+        writeln!(f, "  <expression> [AS <alias>] - computed column")?;
+        writeln!(f)?;
+
+        writeln!(f, "Operators, from the tightest binding. Binary operators are used as <left> <operator> <right>:")?;
+        let max_precedence = BinaryOp::ALL.iter().map(BinaryOp::precedence).chain(UnaryOp::ALL.iter().map(UnaryOp::precedence)).max();
+        for precedence in 0..=max_precedence.unwrap_or_default() {
+            let operators = UnaryOp::ALL
+                .iter()
+                .filter(|op| op.precedence() == precedence)
+                .map(|op| format!("{op} <operand>"))
+                .chain(BinaryOp::ALL.iter().filter(|op| op.precedence() == precedence).map(ToString::to_string))
+                .collect::<Vec<_>>();
+            if !operators.is_empty() {
+                writeln!(f, "  {}", operators.join(", "))?;
+            }
+        }
+        writeln!(f, "  <operand> IN (SELECT <field> [WHERE <predicate>]) - operand is one of the subquery values")?;
+        writeln!(f, "  <operand> IN (<value>, ...) - operand is one of the list values")?;
+        writeln!(f)?;
+
+        writeln!(f, "Collations:")?;
+        writeln!(f, "  BINARY - strings are compared as they are")?;
+        writeln!(f, "  NOCASE - strings are compared ignoring case and accents")?;
+        writeln!(f)?;
+
+        writeln!(f, "Functions:")?;
+        let functions = Function::all();
+        let signatures = functions
+            .iter()
+            .map(|function| format!("{}({})", function.name, function.parameters))
+            .collect::<Vec<_>>();
+        let width = signatures.iter().map(String::len).max().unwrap_or_default();
+        for (signature, function) in signatures.iter().zip(functions) {
+            writeln!(f, "  {signature:width$}  {}", function.description)?;
+        }
+        writeln!(f)?;
+
+        writeln!(f, "Aggregates, that are selected for the group of items with the same GROUP BY values, or all items:")?;
+        let signatures = Aggregate::ALL.map(|aggregate| format!("{}({})", aggregate.name(), aggregate.parameters()));
+        let width = signatures.iter().map(String::len).max().unwrap_or_default();
+        for (signature, aggregate) in signatures.iter().zip(Aggregate::ALL) {
+            writeln!(f, "  {signature:width$}  {}", aggregate.description())?;
+        }
+        writeln!(f)?;
+
+        writeln!(f, "Literals:")?;
+        writeln!(f, "  1, 1.5, DECIMAL '12.30', 'string', \"string\", true, false, NULL")?;
+        writeln!(f, "  DATE '2024-12-12', DATETIME '2024-12-12 20:20', INTERVAL '1 day 2 hours'")?;
+        writeln!(f, "  {}", RelativeDate::ALL.map(|date| date.to_string()).join(", "))?;
+        writeln!(f, "  ('a', 'b'), [1, 2, 3], [1], [] - lists")?;
+        writeln!(f)?;
+
+        writeln!(f, "Comments:")?;
+        write!(f, "  -- line comment, /* block comment */")
+    }
+}
+
+/// Fields of the queried items along with their types.
+pub struct Schema<'a> {
+    fields: &'a [(Cow<'static, str>, Type)],
+}
+
+impl<'a> Schema<'a> {
+    /// Create schema of the items with typed `fields`, see [`Reflectable::field_types`](crate::query::reflect::Reflectable::field_types).
+    pub fn new(fields: &'a [(Cow<'static, str>, Type)]) -> Schema<'a> {
+        Schema { fields }
+    }
+}
+
+impl Display for Schema<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let width = self.fields.iter().map(|(name, _)| name.len()).max().unwrap_or_default();
+        let fields = self.fields.iter().map(|(name, r#type)| format!("{name:width$}  {type}"));
+
+        f.write_str(&fields.collect::<Vec<_>>().join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_help() {
+        let fields = [Cow::Borrowed("name"), Cow::Borrowed("date")];
+
+        let help = SyntaxHelp::new(&fields).to_string();
+
+        assert!(help.contains("  name, date\n"));
+        assert!(help.contains("  - <operand>\n  +, -\n"));
+        assert!(help.contains("SUBSTR(string, start[, length])"));
+        assert!(help.contains("COUNT(*|value)"));
+        assert!(help.contains("NEXT WEEK"));
+    }
+
+    #[test]
+    fn render_schema() {
+        let fields = [(Cow::Borrowed("name"), Type::String), (Cow::Borrowed("date"), Type::DateTime)];
+
+        assert_eq!(Schema::new(&fields).to_string(), "name  String\ndate  DateTime");
+    }
+}
@@ -1,14 +1,23 @@
 pub mod evaluator;
 pub mod ast;
+pub mod help;
+pub mod completion;
+pub mod cache;
 
 use thiserror::Error;
 use crate::query::evaluator::value::operations::{BinaryOperationError, UnaryOperationError};
 use crate::query::evaluator::value::conversion::ConversionError;
 use crate::query::reflect::ReflectError;
+use crate::query::evaluator::function::FunctionError;
+use crate::query::evaluator::validate::ValidationError;
+use crate::query::evaluator::trace::Trace;
+use crate::query::evaluator::limits::Limit;
 
 pub use evaluator::reflect;
 pub use evaluator::result_set::ResultSet;
 pub use ast::{Query};
+pub use completion::{complete, Suggestion, SuggestionKind};
+pub use cache::QueryCache;
 
 /// Represents possible errors of expression evaluation
 #[derive(Debug, Error)]
@@ -20,5 +29,20 @@ pub enum EvaluationError{
     #[error(transparent)]
     BinaryOperation(#[from] BinaryOperationError),
     #[error(transparent)]
-    UnaryOperation(#[from] UnaryOperationError)
+    UnaryOperation(#[from] UnaryOperationError),
+    #[error(transparent)]
+    Function(#[from] FunctionError),
+    #[error(transparent)]
+    Validation(#[from] ValidationError),
+    #[error("Subquery must select exactly one column, but it selects {0}")]
+    SubqueryColumns(usize),
+    #[error("Subqueries are evaluated only by queries over all items")]
+    NotMaterialized,
+    #[error("Query exceeded the limit of {0}")]
+    LimitExceeded(Limit),
+    #[error("Query is cancelled")]
+    Cancelled,
+    /// Evaluation failed for the item, that is identified by its first field, e.g. the name of the task.
+    #[error("{source}\nFailed to evaluate {location} for the item with {item}{}", .trace.as_ref().map(|trace| format!(":\n{trace}")).unwrap_or_default())]
+    Item { item: String, location: String, trace: Option<Trace>, source: Box<EvaluationError> },
 }
\ No newline at end of file
@@ -0,0 +1,164 @@
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+use ureq::{Agent, Request};
+use crate::store::{StoreError, TaskStore};
+use crate::task::Task;
+
+/// Store of tasks on the todo-list server, accessed over its HTTP API.
+#[derive(Debug)]
+pub struct RemoteStore {
+    url: String,
+    token: Option<String>,
+    agent: Agent,
+}
+
+impl RemoteStore {
+    /// Create store of the server at `url`, e.g. `http://host:8080`. `token` is sent as bearer token.
+    pub fn new(url: &str, token: Option<String>) -> RemoteStore {
+        RemoteStore {
+            url: url.trim_end_matches('/').to_string(),
+            token,
+            agent: Agent::new(),
+        }
+    }
+
+    fn request(&self, method: &str, path: &str) -> Request {
+        let request = self.agent.request(method, &format!("{}{path}", self.url));
+        match &self.token {
+            Some(token) => request.set("Authorization", &format!("Bearer {token}")),
+            None => request,
+        }
+    }
+
+    /// Returns path of the task resource.
+    fn task_path(name: &str) -> String {
+        format!("/tasks/{}", utf8_percent_encode(name, NON_ALPHANUMERIC))
+    }
+}
+
+impl TaskStore for RemoteStore {
+    fn get(&self, name: &str) -> Result<Option<Task>, StoreError> {
+        optional(self.request("GET", &Self::task_path(name)).call())
+    }
+
+    fn insert(&self, task: &Task) -> Result<Option<Task>, StoreError> {
+        json(self.request("POST", "/tasks").send_json(task))
+    }
+
+    fn update(&self, name: &str, task: &Task) -> Result<Option<Task>, StoreError> {
+        json(self.request("PUT", &Self::task_path(name)).send_json(task))
+    }
+
+    fn delete(&self, name: &str) -> Result<Option<Task>, StoreError> {
+        optional(self.request("DELETE", &Self::task_path(name)).call())
+    }
+
+    fn done(&self, name: &str) -> Result<bool, StoreError> {
+        match self.request("POST", &format!("{}/done", Self::task_path(name))).call() {
+            Ok(_) => Ok(true),
+            Err(ureq::Error::Status(404, _)) => Ok(false),
+            Err(err) => Err(RemoteError::from(err).into()),
+        }
+    }
+
+    fn tasks(&self) -> Result<Vec<Task>, StoreError> {
+        json(self.request("GET", "/tasks").call())
+    }
+}
+
+/// Read JSON body of the response.
+fn json<T: DeserializeOwned>(response: Result<ureq::Response, ureq::Error>) -> Result<T, StoreError> {
+    let value = response
+        .map_err(RemoteError::from)?
+        .into_json()
+        .map_err(RemoteError::Response)?;
+
+    Ok(value)
+}
+
+/// Read JSON body of the response, or `None` if the server responded with `404 Not Found`.
+fn optional<T: DeserializeOwned>(response: Result<ureq::Response, ureq::Error>) -> Result<Option<T>, StoreError> {
+    match response {
+        Err(ureq::Error::Status(404, _)) => Ok(None),
+        response => json(response).map(Some),
+    }
+}
+
+/// Represents possible errors of requests to the todo-list server.
+#[derive(Error, Debug)]
+pub enum RemoteError {
+    #[error("Request to the server failed. \nReason: {0}")]
+    Request(Box<ureq::Error>),
+    #[error("Server responded with {0}: {1}")]
+    Status(u16, String),
+    #[error("Failed to read response of the server. \nReason: {0}")]
+    Response(std::io::Error),
+}
+
+impl From<ureq::Error> for RemoteError {
+    fn from(err: ureq::Error) -> Self {
+        match err {
+            ureq::Error::Status(status, response) => {
+                RemoteError::Status(status, response.into_string().unwrap_or_default())
+            }
+            err => RemoteError::Request(Box::new(err)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use chrono::NaiveDateTime;
+    use tempfile::tempdir;
+    use crate::server::{parse_token, Serve};
+    use crate::retention::RetentionPolicy;
+    use crate::storage::Storage;
+    use crate::task::Status;
+    use super::*;
+
+    #[test]
+    fn remote_store() {
+        let http = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", http.server_addr());
+        thread::spawn(move || {
+            let tempdir = tempdir().unwrap();
+            let storage = Storage::<Task>::open(&tempdir).unwrap();
+            let settings = Serve {
+                address: String::new(),
+                tokens: vec![parse_token("secret:write").unwrap()],
+                rate_limit: None,
+                cors_origins: Vec::new(),
+                print_spec: false,
+                retention: false,
+                retention_policy: RetentionPolicy { archive_after: 30, purge_after: 365 },
+            };
+            settings.serve(&http, &storage).unwrap();
+        });
+        let store = RemoteStore::new(&url, Some("secret".to_string()));
+        let task = Task {
+            name: "some task".to_string(),
+            description: "description".to_string(),
+            date: NaiveDateTime::parse_from_str("2024-12-12 20:20", "%Y-%m-%d %H:%M").unwrap().and_utc(),
+            category: "category".to_string(),
+            status: Status::Off,
+            owner: None,
+            assignee: None,
+            external_refs: Vec::new(),
+        };
+
+        assert_eq!(store.insert(&task).unwrap(), None);
+        assert_eq!(store.get("some task").unwrap(), Some(task.clone()));
+        assert!(store.done("some task").unwrap());
+        assert!(!store.done("no task").unwrap());
+        let renamed = Task { name: "renamed".to_string(), ..task };
+        assert_eq!(store.update("some task", &renamed).unwrap(), None);
+        assert_eq!(store.tasks().unwrap(), [renamed]);
+        assert!(store.delete("renamed").unwrap().is_some());
+        assert_eq!(store.delete("renamed").unwrap(), None);
+
+        let unauthorized = RemoteStore::new(&url, None);
+        assert!(matches!(unauthorized.tasks(), Err(StoreError::Remote(RemoteError::Status(401, _)))));
+    }
+}
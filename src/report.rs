@@ -0,0 +1,275 @@
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+use clap::builder::PossibleValuesParser;
+use clap::{Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use crate::category::CategoryFilter;
+use crate::format::{exporter, ExportError, FormatError, EXPORTERS};
+use crate::notify::{Channel, NotifyError};
+use crate::query::ast::{ParseError, Predicate};
+use crate::query::reflect::{FieldsIterator, ReflectError, Reflectable, ToValue, Value};
+use crate::query::{EvaluationError, Query};
+use crate::storage::{Storage, StorageError};
+use crate::store::{list_tasks, StoreError};
+use crate::task::Task;
+use crate::view::{View, VIEWS_TREE};
+
+/// Name of the storage tree, where report definitions are kept.
+pub const REPORTS_TREE: &str = "reports";
+
+/// Name of the report format, that renders the query results as a table.
+pub const TABLE_FORMAT: &str = "table";
+
+/// Represents report definition: results of the query from `source` rendered in `format` and delivered
+/// to `destination`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Report {
+    pub name: String,
+    pub source: ReportSource,
+    pub format: String,
+    pub destination: Destination,
+}
+
+/// Query of the report.
+///
+/// * `ReportSource::View` - query of the saved view, so changes of the view apply to the report;
+/// * `ReportSource::Query` - query text;
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ReportSource {
+    View(String),
+    Query(String),
+}
+
+/// Where the report is delivered.
+///
+/// * `Destination::Stdout` - printed;
+/// * `Destination::File` - written to the file, replacing its content;
+/// * `Destination::Webhook` - posted as a message to the channel webhook;
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum Destination {
+    Stdout,
+    File(PathBuf),
+    Webhook { channel: Channel, url: String },
+}
+
+/// Commands to manage reports.
+///
+/// * `ReportCommand::Save` - Save report definition;
+/// * `ReportCommand::List` - List report definitions;
+/// * `ReportCommand::Run` - Run report or all reports, e.g. from cron;
+/// * `ReportCommand::Delete` - Delete report definition;
+#[derive(Debug, Subcommand, PartialEq)]
+pub enum ReportCommand {
+    #[command(about = "Save report, replacing the report with the same name")]
+    Save {
+        name: String,
+        #[arg(long, conflicts_with = "query", help = "Saved view, that selects tasks of the report")]
+        view: Option<String>,
+        #[arg(value_name = "QUERY", required_unless_present = "view", trailing_var_arg = true, allow_hyphen_values = true)]
+        query: Vec<String>,
+        #[arg(long, value_parser = report_formats(), default_value = TABLE_FORMAT, help = "Format of the report: table of the query results, or tasks, that satisfy the query, in the export format")]
+        format: String,
+        #[arg(long, value_name = "FILE", conflicts_with = "webhook", help = "Write report to the file instead of stdout")]
+        output: Option<PathBuf>,
+        #[arg(long, value_name = "URL", requires = "channel", help = "Post report to the channel webhook instead of stdout")]
+        webhook: Option<String>,
+        #[arg(long, value_enum, help = "Channel of the webhook")]
+        channel: Option<Channel>,
+    },
+    #[command(about = "List reports")]
+    List,
+    #[command(about = "Run report, or all reports if the name is omitted")]
+    Run {
+        name: Option<String>,
+        #[command(flatten)]
+        filter: CategoryFilter,
+    },
+    #[command(about = "Delete report")]
+    Delete { name: String },
+}
+
+/// Parser of the `--format` value of the report, that accepts `table` and names of the registered exporters.
+pub fn report_formats() -> PossibleValuesParser {
+    PossibleValuesParser::new([TABLE_FORMAT].into_iter().chain(EXPORTERS.iter().map(|exporter| exporter.name())))
+}
+
+impl Report {
+    /// Save report to the `reports`.
+    pub fn save(&self, reports: &Storage<Report>) -> Result<(), StorageError> {
+        reports.insert(&self.name, self)?;
+
+        Ok(())
+    }
+
+    /// Render the report over tasks of the `storage`, that also satisfy `filter`.
+    pub fn render(&self, storage: &Storage<Task>, filter: Option<&Predicate>) -> Result<String, ReportError> {
+        let mut query = match &self.source {
+            ReportSource::View(name) => storage
+                .open_tree::<View>(VIEWS_TREE)?
+                .get(name)?
+                .ok_or_else(|| ReportError::NoView(name.clone()))?
+                .parse()?,
+            ReportSource::Query(query) => Query::from_str(query)?,
+        };
+        if let Some(filter) = filter {
+            query.and_where(filter.clone());
+        }
+        let tasks = list_tasks(storage, query.from.as_ref())?;
+        if self.format == TABLE_FORMAT {
+            return Ok(query.execute(tasks.iter())?.to_string());
+        }
+
+        query.validate::<Task>().map_err(EvaluationError::from)?;
+        let tasks = match &query.predicate {
+            Some(predicate) => predicate.filter(tasks.iter())?.into_iter().cloned().collect(),
+            None => tasks,
+        };
+        let mut output = Vec::new();
+        exporter(&self.format)?.write_tasks(&tasks, &mut output)?;
+
+        Ok(String::from_utf8_lossy(&output).into_owned())
+    }
+
+    /// Render the report over tasks of the `storage`, that also satisfy `filter`, and deliver it to the destination.
+    pub fn run(&self, storage: &Storage<Task>, filter: Option<&Predicate>) -> Result<(), ReportError> {
+        let report = self.render(storage, filter)?;
+        match &self.destination {
+            Destination::Stdout => println!("{report}"),
+            Destination::File(path) => std::fs::File::create(path)?.write_all(report.as_bytes())?,
+            Destination::Webhook { channel, url } => channel.post_message(url, &report)?,
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for ReportSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReportSource::View(name) => write!(f, "view '{name}'"),
+            ReportSource::Query(query) => Display::fmt(query, f),
+        }
+    }
+}
+
+impl Display for Destination {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Destination::Stdout => f.write_str("stdout"),
+            Destination::File(path) => Display::fmt(&path.display(), f),
+            Destination::Webhook { channel, url } => {
+                let channel = channel.to_possible_value().expect("channels are not skipped");
+                write!(f, "{} {url}", channel.get_name())
+            }
+        }
+    }
+}
+
+/// Reflectable implementation to be able to list reports with select queries.
+impl Reflectable for Report {
+    fn get_field(&self, field: &str) -> Result<Value<'_>, ReflectError> {
+        let value = match field {
+            "name" => self.name.to_value(),
+            "source" => Value::String(self.source.to_string().into()),
+            "format" => self.format.to_value(),
+            "destination" => Value::String(self.destination.to_string().into()),
+            field => return Err(ReflectError::NoField(field.to_string())),
+        };
+
+        Ok(value)
+    }
+
+    fn fields(&self) -> FieldsIterator<'_> {
+        Box::new([
+            ("name".into(), self.name.to_value()),
+            ("source".into(), Value::String(self.source.to_string().into())),
+            ("format".into(), self.format.to_value()),
+            ("destination".into(), Value::String(self.destination.to_string().into())),
+        ].into_iter())
+    }
+
+    fn field_names() -> Cow<'static, [Cow<'static, str>]> {
+        (&[Cow::Borrowed("name"), Cow::Borrowed("source"), Cow::Borrowed("format"), Cow::Borrowed("destination")]).into()
+    }
+}
+
+/// Represents possible errors of running report.
+#[derive(Error, Debug)]
+pub enum ReportError {
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    #[error(transparent)]
+    Store(#[from] StoreError),
+    #[error("View '{0}' not found")]
+    NoView(String),
+    #[error("Invalid query. {0}")]
+    Query(#[from] ParseError),
+    #[error("Failed to execute query. {0}")]
+    QueryEvaluation(#[from] EvaluationError),
+    #[error(transparent)]
+    Format(#[from] FormatError),
+    #[error(transparent)]
+    Export(#[from] ExportError),
+    #[error("Failed to write report. \nReason: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Notify(#[from] NotifyError),
+    #[error("{0} reports failed")]
+    Failed(usize),
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+    use tempfile::tempdir;
+    use crate::store::TaskStore;
+    use crate::task::Status;
+    use super::*;
+
+    #[test]
+    fn render_report() {
+        let tempdir = tempdir().unwrap();
+        let storage = Storage::<Task>::open(&tempdir).unwrap();
+        let task = |name: &str, category: &str| Task {
+            name: name.to_string(),
+            description: "description".to_string(),
+            date: NaiveDateTime::parse_from_str("2024-12-12 20:20", "%Y-%m-%d %H:%M").unwrap().and_utc(),
+            category: category.to_string(),
+            status: Status::Off,
+            owner: None,
+            assignee: None,
+            external_refs: Vec::new(),
+        };
+        TaskStore::insert_batch(&storage, &[task("first", "work"), task("second", "home")]).unwrap();
+        let words = ["name", "where", "category", "=", "'work'"].map(ToString::to_string);
+        View::new("work", &words).unwrap().save(&storage.open_tree(VIEWS_TREE).unwrap()).unwrap();
+        let report = |source: ReportSource, format: &str| Report {
+            name: "report".to_string(),
+            source,
+            format: format.to_string(),
+            destination: Destination::Stdout,
+        };
+
+        let table = report(ReportSource::View("work".to_string()), TABLE_FORMAT).render(&storage, None).unwrap();
+        assert!(table.contains("first") && !table.contains("second"));
+
+        let jsonl = report(ReportSource::Query("SELECT name WHERE category = 'home'".to_string()), "jsonl")
+            .render(&storage, None)
+            .unwrap();
+        assert_eq!(jsonl.lines().count(), 1);
+        assert!(jsonl.contains("\"second\""));
+
+        let filter = CategoryFilter { categories: vec!["home".to_string()] }.predicate();
+        let filtered = report(ReportSource::Query("SELECT name".to_string()), TABLE_FORMAT)
+            .render(&storage, filter.as_ref())
+            .unwrap();
+        assert!(filtered.contains("second") && !filtered.contains("first"));
+
+        let no_view = report(ReportSource::View("home".to_string()), TABLE_FORMAT).render(&storage, None);
+        assert!(matches!(no_view, Err(ReportError::NoView(_))));
+    }
+}
@@ -0,0 +1,157 @@
+use std::fmt::{Display, Formatter};
+use chrono::{DateTime, TimeDelta, Utc};
+use clap::Args;
+use crate::history::{Action, HistoryEntry, HISTORY_TREE};
+use crate::storage::{self, Storage, StorageError};
+use crate::task::{Status, Task};
+
+/// Name of the storage tree, where archived tasks are kept.
+pub const ARCHIVE_TREE: &str = "archive";
+
+/// Retention policy for completed tasks.
+///
+/// Completed tasks which date is older than `archive_after` days are moved to the archive.
+/// Archived tasks which date is older than `purge_after` days are deleted.
+#[derive(Debug, Clone, Args, PartialEq)]
+pub struct RetentionPolicy {
+    #[arg(long, value_name = "DAYS", default_value_t = 30, help = "Archive completed tasks older than DAYS")]
+    pub archive_after: i64,
+    #[arg(long, value_name = "DAYS", default_value_t = 365, help = "Purge archived tasks older than DAYS")]
+    pub purge_after: i64,
+}
+
+/// Action performed on a task by [`RetentionPolicy`].
+///
+/// * `RetentionAction::Archived` - task was moved to the archive;
+/// * `RetentionAction::Purged` - task was deleted from the archive;
+#[derive(Debug, PartialEq)]
+pub enum RetentionAction {
+    Archived(Task),
+    Purged(Task),
+}
+
+impl RetentionPolicy {
+    /// Apply policy to the tasks of the `storage` and its archive at the moment `now`, and record the actions to
+    /// the history.
+    ///
+    /// Returns the log of performed actions.
+    pub fn run(&self, storage: &Storage<Task>, now: DateTime<Utc>) -> Result<Vec<RetentionAction>, StorageError> {
+        let log = self.apply(storage, &storage.open_tree(ARCHIVE_TREE)?, now)?;
+        let history = storage.open_tree(HISTORY_TREE)?;
+        for action in &log {
+            match action {
+                RetentionAction::Archived(task) => HistoryEntry::record(&history, Action::Archive, &task.name)?,
+                RetentionAction::Purged(task) => HistoryEntry::record(&history, Action::Purge, &task.name)?,
+            }
+        }
+
+        Ok(log)
+    }
+
+    /// Apply policy to the tasks in `storage` and `archive` at the moment `now`.
+    ///
+    /// Returns the log of performed actions.
+    pub fn apply(
+        &self,
+        storage: &Storage<Task>,
+        archive: &Storage<Task>,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<RetentionAction>, StorageError> {
+        let mut log = Vec::new();
+
+        let purge_before = now - TimeDelta::days(self.purge_after);
+        for entry in archive.iter() {
+            let (key, task) = entry?;
+            if task.date < purge_before {
+                archive.delete(key)?;
+                log.push(RetentionAction::Purged(task));
+            }
+        }
+
+        let archive_before = now - TimeDelta::days(self.archive_after);
+        let mut archived = Vec::new();
+        for entry in storage.iter() {
+            let (key, task) = entry?;
+            if task.status == Status::On && task.date < archive_before {
+                archived.push((key, task));
+            }
+        }
+        // Tasks are moved in a single transaction, so they are never lost or kept in both trees.
+        storage::apply_batches(&[
+            archive.batch(archived.iter().map(|(key, task)| (key, task)), [])?,
+            storage.batch([], archived.iter().map(|(key, _)| key))?,
+        ])?;
+        log.extend(archived.into_iter().map(|(_, task)| RetentionAction::Archived(task)));
+
+        Ok(log)
+    }
+}
+
+impl Display for RetentionAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RetentionAction::Archived(task) => write!(f, "Archived task '{}'", task.name),
+            RetentionAction::Purged(task) => write!(f, "Purged task '{}'", task.name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::task::tests::{date, task};
+    use tempfile::tempdir;
+    use super::*;
+
+    #[test]
+    fn apply_policy() {
+        let tempdir = tempdir().unwrap();
+        let storage = Storage::<Task>::open(&tempdir).unwrap();
+        let archive = storage.open_tree::<Task>(ARCHIVE_TREE).unwrap();
+        let now = date("2024-12-12 20:20");
+
+        let recent = task("recent", "2024-12-01 20:20", Status::On);
+        let pending = task("pending", "2020-12-12 20:20", Status::Off);
+        let completed = task("completed", "2024-10-10 20:20", Status::On);
+        let archived = task("archived", "2024-01-01 20:20", Status::On);
+        let expired = task("expired", "2022-12-12 20:20", Status::On);
+        for task in [&recent, &pending, &completed] {
+            storage.insert(&task.name, task).unwrap();
+        }
+        for task in [&archived, &expired] {
+            archive.insert(&task.name, task).unwrap();
+        }
+
+        let policy = RetentionPolicy { archive_after: 30, purge_after: 365 };
+        let log = policy.apply(&storage, &archive, now).unwrap();
+
+        assert_eq!(log, [
+            RetentionAction::Purged(task("expired", "2022-12-12 20:20", Status::On)),
+            RetentionAction::Archived(task("completed", "2024-10-10 20:20", Status::On)),
+        ]);
+        assert_eq!(storage.get("completed").unwrap(), None);
+        assert_eq!(archive.get("completed").unwrap(), Some(completed));
+        assert_eq!(storage.get("pending").unwrap(), Some(pending));
+        assert_eq!(archive.get("expired").unwrap(), None);
+    }
+
+    #[test]
+    fn run_records_history() {
+        let tempdir = tempdir().unwrap();
+        let storage = Storage::<Task>::open(&tempdir).unwrap();
+        let archive = storage.open_tree::<Task>(ARCHIVE_TREE).unwrap();
+        storage.insert("completed", &task("completed", "2024-10-10 20:20", Status::On)).unwrap();
+        archive.insert("expired", &task("expired", "2022-12-12 20:20", Status::On)).unwrap();
+
+        let policy = RetentionPolicy { archive_after: 30, purge_after: 365 };
+        assert_eq!(policy.run(&storage, date("2024-12-12 20:20")).unwrap().len(), 2);
+
+        let history = storage
+            .open_tree::<HistoryEntry>(HISTORY_TREE)
+            .unwrap()
+            .iter()
+            .map(|entry| entry.map(|(_, entry)| (entry.action, entry.task)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(history, [(Action::Purge, "expired".to_string()), (Action::Archive, "completed".to_string())]);
+    }
+}
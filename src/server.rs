@@ -0,0 +1,1120 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use chrono::{TimeDelta, Utc};
+use clap::{Args, ValueEnum};
+use percent_encoding::percent_decode_str;
+use ring::digest;
+use serde_json::json;
+use thiserror::Error;
+use tiny_http::{Header, Method, Request, Response};
+use crate::audit::{AuditEntry, AUDIT_TREE};
+use crate::calendar::to_ics;
+use crate::query::Query;
+use crate::retention::RetentionPolicy;
+use crate::share::{to_html, ShareClaims, ShareError, ShareKey};
+use crate::view::{View, VIEWS_TREE};
+use crate::storage::{Storage, StorageError};
+use crate::store::{self, StoreError, TaskStore};
+use crate::task::{Status, Task};
+
+/// Settings of the HTTP server.
+///
+/// If no tokens are specified, the API is open for everyone who can reach the address.
+#[derive(Debug, Clone, Args, PartialEq)]
+pub struct Serve {
+    #[arg(long, default_value = "127.0.0.1:8080", help = "Address to listen on")]
+    pub address: String,
+    #[arg(long = "token", value_name = "TOKEN:SCOPE[:USER]", value_parser = parse_token, help = "Access token with 'read' or 'write' scope of the optional user. May be repeated")]
+    pub tokens: Vec<Token>,
+    #[arg(long, value_name = "REQUESTS", help = "Maximum number of requests per minute from a single client")]
+    pub rate_limit: Option<u32>,
+    #[arg(long = "cors-origin", value_name = "ORIGIN", help = "Origin allowed to make cross-origin requests, '*' for any. May be repeated")]
+    pub cors_origins: Vec<String>,
+    #[arg(long, help = "Print OpenAPI specification of the HTTP API and exit")]
+    pub print_spec: bool,
+    #[arg(long, help = "Archive and purge old completed tasks by the retention policy at startup and then daily")]
+    pub retention: bool,
+    #[command(flatten)]
+    pub retention_policy: RetentionPolicy,
+}
+
+/// Access token of the HTTP API. Tasks added with the token of the `user` are owned by the user.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub secret: String,
+    pub scope: Scope,
+    pub user: Option<String>,
+}
+
+/// Access scopes. `Scope::Write` includes `Scope::Read`.
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, PartialOrd)]
+pub enum Scope {
+    Read,
+    Write,
+}
+
+/// Request to the HTTP API.
+#[derive(Debug)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: String,
+    pub authorization: Option<String>,
+    pub origin: Option<String>,
+    pub client: Option<IpAddr>,
+    pub body: String,
+}
+
+/// Response to the HTTP request.
+#[derive(Debug, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: String,
+}
+
+/// Maximal size of the request body in bytes. Bodies are tasks in JSON, so real requests are far below it.
+const MAX_BODY: u64 = 1024 * 1024;
+
+/// Interval between the runs of the retention policy by the server.
+const RETENTION_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Limits the number of requests from a single client within the fixed time window.
+///
+/// Clients, which window has expired, are forgotten once per window, so the limiter doesn't grow with every client
+/// it has ever seen.
+#[derive(Debug)]
+pub struct RateLimiter {
+    limit: u32,
+    window: Duration,
+    clients: HashMap<Option<IpAddr>, (Instant, u32)>,
+    pruned: Option<Instant>,
+}
+
+/// Route of the HTTP API. Routes are used both to dispatch requests and to generate OpenAPI specification.
+pub struct Route {
+    pub method: Method,
+    /// Path template, where `{name}` segments are path parameters.
+    pub path: &'static str,
+    /// Scope required to access the route, `None` if the route is public.
+    /// Routes that require [`Scope::Write`] are recorded to the audit log.
+    pub scope: Option<Scope>,
+    pub summary: &'static str,
+    pub parameters: &'static [Parameter],
+    /// Possible responses as pairs of status code and description.
+    pub responses: &'static [(u16, &'static str)],
+    pub content_type: &'static str,
+    handler: fn(&Server, &Parameters) -> HttpResponse,
+}
+
+/// Parameter of the [`Route`].
+pub struct Parameter {
+    pub name: &'static str,
+    pub location: ParameterLocation,
+    pub description: &'static str,
+}
+
+/// Location of the [`Parameter`] in the request.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParameterLocation {
+    Path,
+    Query,
+}
+
+/// Values of the path and query parameters, the body of the request and the user of the access token.
+pub struct Parameters {
+    values: HashMap<String, String>,
+    body: String,
+    user: Option<String>,
+}
+
+static NAME_PARAMETER: &[Parameter] = &[Parameter {
+    name: "name",
+    location: ParameterLocation::Path,
+    description: "Name of the task",
+}];
+
+/// Routes of the HTTP API.
+pub static ROUTES: &[Route] = &[
+    Route {
+        method: Method::Get,
+        path: "/calendar.ics",
+        scope: Some(Scope::Read),
+        summary: "iCalendar feed of pending tasks",
+        parameters: &[Parameter {
+            name: "where",
+            location: ParameterLocation::Query,
+            description: "Query predicate to filter tasks, e.g. category = 'work'",
+        }],
+        responses: &[(200, "iCalendar feed"), (400, "Invalid predicate")],
+        content_type: "text/calendar",
+        handler: |server, parameters| server.calendar(parameters),
+    },
+    Route {
+        method: Method::Get,
+        path: "/tasks",
+        scope: Some(Scope::Read),
+        summary: "List tasks ordered by name",
+        parameters: &[],
+        responses: &[(200, "Array of tasks")],
+        content_type: "application/json",
+        handler: |server, _| server.tasks(),
+    },
+    Route {
+        method: Method::Post,
+        path: "/tasks",
+        scope: Some(Scope::Write),
+        summary: "Add task or replace the task with the same name",
+        parameters: &[],
+        responses: &[(200, "Replaced task or null"), (400, "Invalid task")],
+        content_type: "application/json",
+        handler: |server, parameters| server.insert(parameters),
+    },
+    Route {
+        method: Method::Get,
+        path: "/tasks/{name}",
+        scope: Some(Scope::Read),
+        summary: "Get task",
+        parameters: NAME_PARAMETER,
+        responses: &[(200, "Task"), (404, "Task not found")],
+        content_type: "application/json",
+        handler: |server, parameters| server.get(parameters),
+    },
+    Route {
+        method: Method::Put,
+        path: "/tasks/{name}",
+        scope: Some(Scope::Write),
+        summary: "Update task, possibly renaming it",
+        parameters: NAME_PARAMETER,
+        responses: &[(200, "Task replaced by renaming or null"), (400, "Invalid task"), (404, "Task not found")],
+        content_type: "application/json",
+        handler: |server, parameters| server.update(parameters),
+    },
+    Route {
+        method: Method::Delete,
+        path: "/tasks/{name}",
+        scope: Some(Scope::Write),
+        summary: "Delete task",
+        parameters: NAME_PARAMETER,
+        responses: &[(200, "Deleted task"), (404, "Task not found")],
+        content_type: "application/json",
+        handler: |server, parameters| server.delete(parameters),
+    },
+    Route {
+        method: Method::Post,
+        path: "/tasks/{name}/done",
+        scope: Some(Scope::Write),
+        summary: "Mark task as completed",
+        parameters: NAME_PARAMETER,
+        responses: &[(200, "Task marked as completed"), (404, "Task not found")],
+        content_type: "text/plain",
+        handler: |server, parameters| server.done(parameters),
+    },
+    Route {
+        method: Method::Post,
+        path: "/views/{name}/share",
+        scope: Some(Scope::Write),
+        summary: "Mint read-only share token of the saved view",
+        parameters: &[
+            Parameter {
+                name: "name",
+                location: ParameterLocation::Path,
+                description: "Name of the saved view",
+            },
+            Parameter {
+                name: "expires_in",
+                location: ParameterLocation::Query,
+                description: "Positive number of days the token is valid. Token never expires if not specified",
+            },
+        ],
+        responses: &[(200, "Share token and path of the shared view"), (400, "Invalid expiration"), (404, "View not found")],
+        content_type: "application/json",
+        handler: |server, parameters| server.share(parameters),
+    },
+    Route {
+        method: Method::Get,
+        path: "/shared/{token}",
+        scope: None,
+        summary: "Tasks of the shared view. Share token grants read-only access to the view",
+        parameters: &[
+            Parameter {
+                name: "token",
+                location: ParameterLocation::Path,
+                description: "Share token",
+            },
+            Parameter {
+                name: "format",
+                location: ParameterLocation::Query,
+                description: "Format of the page, 'html' or 'json'. Defaults to 'html'",
+            },
+        ],
+        responses: &[(200, "Shared view"), (400, "Invalid format"), (403, "Invalid or expired share token, or the view was changed"), (404, "View not found")],
+        content_type: "text/html",
+        handler: |server, parameters| server.shared(parameters),
+    },
+    Route {
+        method: Method::Get,
+        path: "/openapi.json",
+        scope: None,
+        summary: "OpenAPI specification of the HTTP API",
+        parameters: &[],
+        responses: &[(200, "OpenAPI specification")],
+        content_type: "application/json",
+        handler: |server, parameters| server.spec(parameters),
+    },
+];
+
+/// HTTP API over the tasks in storage.
+pub struct Server<'a> {
+    settings: &'a Serve,
+    storage: &'a Storage<Task>,
+    audit: Storage<AuditEntry>,
+    views: Storage<View>,
+    share_key: ShareKey,
+    rate_limiter: Option<RateLimiter>,
+}
+
+impl Serve {
+    /// Serve requests to the tasks in `storage` until the process is stopped.
+    pub fn run(&self, storage: &Storage<Task>) -> Result<(), ServerError> {
+        if self.print_spec {
+            println!("{:#}", openapi_spec());
+            return Ok(());
+        }
+        let http = tiny_http::Server::http(&self.address).map_err(ServerError::Bind)?;
+        println!("Listening on http://{}", self.address);
+
+        self.serve(&http, storage)
+    }
+
+    /// Serve requests received by `http` to the tasks in `storage`.
+    ///
+    /// With `retention` the retention policy is applied before the first request and then every day.
+    pub fn serve(&self, http: &tiny_http::Server, storage: &Storage<Task>) -> Result<(), ServerError> {
+        let mut server = Server::new(self, storage)?;
+        let mut retention_due = Instant::now();
+        loop {
+            if self.retention && Instant::now() >= retention_due {
+                self.apply_retention(storage);
+                retention_due = Instant::now() + RETENTION_INTERVAL;
+            }
+            let timeout = retention_due.saturating_duration_since(Instant::now()).max(Duration::from_secs(1));
+            let mut request = match http.recv_timeout(timeout) {
+                Ok(Some(request)) => request,
+                Ok(None) => continue,
+                Err(_) => break,
+            };
+            let body = match read_body(&mut request) {
+                Ok(body) => body,
+                Err(response) => {
+                    respond(request, response);
+                    continue;
+                }
+            };
+            let header = |name: &'static str| request
+                .headers()
+                .iter()
+                .find(|header| header.field.equiv(name))
+                .map(|header| header.value.to_string());
+            let http_request = HttpRequest {
+                method: request.method().clone(),
+                url: request.url().to_string(),
+                authorization: header("Authorization"),
+                origin: header("Origin"),
+                client: request.remote_addr().map(|address| address.ip()),
+                body,
+            };
+            let response = server.handle(&http_request, Instant::now());
+            respond(request, response);
+        }
+
+        Ok(())
+    }
+
+    /// Apply the retention policy to the tasks in `storage`. Failure is reported, but doesn't stop the server.
+    fn apply_retention(&self, storage: &Storage<Task>) {
+        match self.retention_policy.run(storage, Utc::now()) {
+            Ok(log) => log.iter().for_each(|action| println!("{action}")),
+            Err(err) => eprintln!("Failed to apply retention policy. \nReason: {err}"),
+        }
+    }
+}
+
+impl<'a> Server<'a> {
+    pub fn new(settings: &'a Serve, storage: &'a Storage<Task>) -> Result<Server<'a>, ServerError> {
+        Ok(Server {
+            settings,
+            storage,
+            audit: storage.open_tree(AUDIT_TREE)?,
+            views: storage.open_tree(VIEWS_TREE)?,
+            share_key: ShareKey::load_or_create(storage)?,
+            rate_limiter: settings.rate_limit.map(|limit| RateLimiter::new(limit, Duration::from_secs(60))),
+        })
+    }
+
+    /// Handle `request` received at the moment `now`.
+    ///
+    /// Request is dispatched to the matching route from [`ROUTES`].
+    /// Token is accepted either as `Authorization: Bearer <token>` header or `token` query parameter,
+    /// as calendar clients can not set headers. Mutations are recorded to the audit log.
+    pub fn handle(&mut self, request: &HttpRequest, now: Instant) -> HttpResponse {
+        let mut response = if self.rate_limiter.as_mut().is_some_and(|limiter| !limiter.check(request.client, now)) {
+            HttpResponse::text(429, "Too many requests")
+        } else if request.method == Method::Options {
+            self.preflight()
+        } else {
+            self.route(request)
+        };
+
+        if let Some(origin) = self.allowed_origin(request) {
+            response.headers.push(("Access-Control-Allow-Origin", origin));
+            response.headers.push(("Vary", "Origin".to_string()));
+        }
+
+        response
+    }
+
+    fn route(&self, request: &HttpRequest) -> HttpResponse {
+        let (path, query) = request.url.split_once('?').unwrap_or((&request.url, ""));
+        let mut parameters = url::form_urlencoded::parse(query.as_bytes())
+            .map(|(name, value)| (name.into_owned(), value.into_owned()))
+            .collect::<HashMap<_, _>>();
+        let token = request
+            .authorization
+            .as_deref()
+            .and_then(|authorization| authorization.strip_prefix("Bearer "))
+            .or(parameters.get("token").map(String::as_str))
+            .map(ToString::to_string);
+
+        let Some((route, path_parameters)) = ROUTES
+            .iter()
+            .filter(|route| route.method == request.method)
+            .find_map(|route| route.match_path(path).map(|parameters| (route, parameters)))
+        else {
+            return HttpResponse::text(404, "Not found");
+        };
+        parameters.extend(path_parameters);
+        let parameters = Parameters {
+            values: parameters,
+            body: request.body.clone(),
+            user: token
+                .as_deref()
+                .and_then(|token| self.find_token(token))
+                .and_then(|token| token.user.clone()),
+        };
+
+        let response = route
+            .scope
+            .and_then(|scope| self.authorize(token.as_deref(), scope))
+            .unwrap_or_else(|| (route.handler)(self, &parameters));
+        if route.scope == Some(Scope::Write) {
+            self.record_audit(request, token.as_deref(), &response);
+        }
+
+        response
+    }
+
+    /// Returns error response if `token` is not allowed to access `scope`.
+    fn authorize(&self, token: Option<&str>, scope: Scope) -> Option<HttpResponse> {
+        if self.settings.tokens.is_empty() {
+            return None;
+        }
+        let Some(token) = token.and_then(|token| self.find_token(token)) else {
+            return Some(HttpResponse::text(401, "Unauthorized"));
+        };
+        if token.scope < scope {
+            return Some(HttpResponse::text(403, "Forbidden"));
+        }
+
+        None
+    }
+
+    /// Returns the configured token with the `secret`.
+    ///
+    /// Secrets are compared by their SHA-256 digests in constant time, so neither the content nor the length of the
+    /// configured secrets leak through the response time.
+    fn find_token(&self, secret: &str) -> Option<&Token> {
+        let digest = digest::digest(&digest::SHA256, secret.as_bytes());
+
+        self.settings.tokens.iter().find(|known| {
+            let known = digest::digest(&digest::SHA256, known.secret.as_bytes());
+            known.as_ref().iter().zip(digest.as_ref()).fold(0, |difference, (left, right)| difference | (left ^ right)) == 0
+        })
+    }
+
+    fn calendar(&self, parameters: &Parameters) -> HttpResponse {
+        let predicate = parameters.get("where");
+        let query = match predicate {
+            Some(predicate) => match Query::from_str(&format!("SELECT * WHERE {predicate}")) {
+                Ok(query) => query.predicate,
+                Err(err) => return HttpResponse::text(400, err.to_string()),
+            },
+            None => None,
+        };
+
+        let mut tasks = Vec::new();
+        for entry in self.storage.iter() {
+            let task = match entry {
+                Ok((_, task)) => task,
+                Err(err) => return HttpResponse::text(500, err.to_string()),
+            };
+            if task.status == Status::On {
+                continue;
+            }
+            match query.as_ref().map_or(Ok(true), |predicate| predicate.test(&task)) {
+                Ok(true) => tasks.push(task),
+                Ok(false) => {}
+                Err(err) => return HttpResponse::text(400, err.to_string()),
+            }
+        }
+
+        HttpResponse {
+            status: 200,
+            content_type: "text/calendar; charset=utf-8",
+            headers: Vec::new(),
+            body: to_ics(&tasks, Utc::now()),
+        }
+    }
+
+    fn tasks(&self) -> HttpResponse {
+        match TaskStore::tasks(self.storage) {
+            Ok(tasks) => HttpResponse::json(200, &tasks),
+            Err(err) => HttpResponse::text(500, err.to_string()),
+        }
+    }
+
+    fn get(&self, parameters: &Parameters) -> HttpResponse {
+        let name = parameters.get("name").unwrap_or_default();
+
+        found(TaskStore::get(self.storage, name))
+    }
+
+    fn insert(&self, parameters: &Parameters) -> HttpResponse {
+        let mut task = match serde_json::from_str::<Task>(&parameters.body) {
+            Ok(task) => task,
+            Err(err) => return HttpResponse::text(400, err.to_string()),
+        };
+        if task.owner.is_none() {
+            task.owner = parameters.user.clone();
+        }
+
+        match TaskStore::insert(self.storage, &task) {
+            Ok(prev_task) => HttpResponse::json(200, &prev_task),
+            Err(err) => HttpResponse::text(500, err.to_string()),
+        }
+    }
+
+    fn update(&self, parameters: &Parameters) -> HttpResponse {
+        let name = parameters.get("name").unwrap_or_default();
+        let task = match serde_json::from_str::<Task>(&parameters.body) {
+            Ok(task) => task,
+            Err(err) => return HttpResponse::text(400, err.to_string()),
+        };
+
+        match TaskStore::get(self.storage, name) {
+            Ok(Some(_)) => {}
+            Ok(None) => return HttpResponse::text(404, "Task not found"),
+            Err(err) => return HttpResponse::text(500, err.to_string()),
+        }
+        match TaskStore::update(self.storage, name, &task) {
+            Ok(replaced) => HttpResponse::json(200, &replaced),
+            Err(err) => HttpResponse::text(500, err.to_string()),
+        }
+    }
+
+    fn delete(&self, parameters: &Parameters) -> HttpResponse {
+        let name = parameters.get("name").unwrap_or_default();
+
+        found(TaskStore::delete(self.storage, name))
+    }
+
+    fn done(&self, parameters: &Parameters) -> HttpResponse {
+        let name = parameters.get("name").unwrap_or_default();
+
+        match TaskStore::done(self.storage, name) {
+            Ok(true) => HttpResponse::text(200, "Task marked as completed"),
+            Ok(false) => HttpResponse::text(404, "Task not found"),
+            Err(err) => HttpResponse::text(500, err.to_string()),
+        }
+    }
+
+    fn share(&self, parameters: &Parameters) -> HttpResponse {
+        let name = parameters.get("name").unwrap_or_default();
+        let expires = match parameters.get("expires_in").map(str::parse::<i64>) {
+            Some(Ok(days)) if days <= 0 => return HttpResponse::text(400, "Expiration must be a positive number of days"),
+            Some(Ok(days)) => match TimeDelta::try_days(days).and_then(|days| Utc::now().checked_add_signed(days)) {
+                Some(expires) => Some(expires),
+                None => return HttpResponse::text(400, "Expiration is out of range"),
+            },
+            Some(Err(err)) => return HttpResponse::text(400, err.to_string()),
+            None => None,
+        };
+        let view = match self.views.get(name) {
+            Ok(Some(view)) => view,
+            Ok(None) => return HttpResponse::text(404, "View not found"),
+            Err(err) => return HttpResponse::text(500, err.to_string()),
+        };
+
+        let token = self.share_key.mint(&ShareClaims::new(&view, expires));
+        HttpResponse::json(200, &json!({ "path": format!("/shared/{token}"), "token": token }))
+    }
+
+    fn shared(&self, parameters: &Parameters) -> HttpResponse {
+        let token = parameters.get("token").unwrap_or_default();
+        let claims = match self.share_key.verify(token, Utc::now()) {
+            Ok(claims) => claims,
+            Err(err) => return HttpResponse::text(403, err.to_string()),
+        };
+        let view = match self.views.get(&claims.view) {
+            Ok(Some(view)) => view,
+            Ok(None) => return HttpResponse::text(404, "View not found"),
+            Err(err) => return HttpResponse::text(500, err.to_string()),
+        };
+        if let Err(err) = claims.check(&view) {
+            return HttpResponse::text(403, err.to_string());
+        }
+        let result_set = view
+            .parse()
+            .map_err(|err| err.to_string())
+            .and_then(|query| {
+                let tasks = store::list_tasks(self.storage, query.from.as_ref()).map_err(|err| err.to_string())?;
+                query.execute(tasks.iter()).map_err(|err| err.to_string())
+            });
+        let result_set = match result_set {
+            Ok(result_set) => result_set,
+            Err(err) => return HttpResponse::text(500, err),
+        };
+
+        match parameters.get("format").unwrap_or("html") {
+            "html" => HttpResponse {
+                status: 200,
+                content_type: "text/html; charset=utf-8",
+                headers: Vec::new(),
+                body: to_html(&view.name, &result_set),
+            },
+            "json" => HttpResponse::json(200, &result_set.to_json()),
+            format => HttpResponse::text(400, format!("Unknown format '{format}'")),
+        }
+    }
+
+    fn spec(&self, _: &Parameters) -> HttpResponse {
+        HttpResponse {
+            status: 200,
+            content_type: "application/json",
+            headers: Vec::new(),
+            body: openapi_spec().to_string(),
+        }
+    }
+
+    fn preflight(&self) -> HttpResponse {
+        let mut response = HttpResponse::text(204, "");
+        response.headers.push(("Access-Control-Allow-Methods", "GET, POST, PUT, DELETE".to_string()));
+        response.headers.push(("Access-Control-Allow-Headers", "Authorization, Content-Type".to_string()));
+
+        response
+    }
+
+    fn allowed_origin(&self, request: &HttpRequest) -> Option<String> {
+        let origin = request.origin.as_ref()?;
+
+        self.settings
+            .cors_origins
+            .iter()
+            .any(|allowed| allowed == "*" || allowed == origin)
+            .then(|| origin.to_string())
+    }
+
+    fn record_audit(&self, request: &HttpRequest, token: Option<&str>, response: &HttpResponse) {
+        let entry = AuditEntry {
+            time: Utc::now(),
+            client: request.client.map(|client| client.to_string()).unwrap_or_default(),
+            token: token.map(|token| self.token_label(token)).unwrap_or_default(),
+            request: format!("{} {}", request.method, request.url.split('?').next().unwrap_or_default()),
+            status: response.status,
+        };
+        if let Err(err) = AuditEntry::record(&self.audit, &entry) {
+            eprintln!("Failed to record audit entry. \nReason: {err}");
+        }
+    }
+
+    /// Label of the `token` in the audit log. It is the user of the token, if the token is configured with one,
+    /// otherwise the fingerprint of the token, so the secret is never written to the log.
+    fn token_label(&self, token: &str) -> String {
+        if let Some(user) = self.find_token(token).and_then(|token| token.user.as_ref()) {
+            return user.clone();
+        }
+        let digest = digest::digest(&digest::SHA256, token.as_bytes());
+
+        digest.as_ref()[..8].iter().fold("sha256:".to_string(), |mut label, byte| {
+            label.push_str(&format!("{byte:02x}"));
+            label
+        })
+    }
+}
+
+impl Route {
+    /// Match `path` against the route path template. Returns values of the path parameters, if path matches.
+    fn match_path(&self, path: &str) -> Option<Vec<(String, String)>> {
+        let mut segments = path.trim_matches('/').split('/');
+        let mut parameters = Vec::new();
+        for template in self.path.trim_matches('/').split('/') {
+            let segment = segments.next()?;
+            match template.strip_prefix('{').and_then(|name| name.strip_suffix('}')) {
+                Some(name) => parameters.push((name.to_string(), percent_decode_str(segment).decode_utf8_lossy().into_owned())),
+                None if template == segment => {}
+                None => return None,
+            }
+        }
+
+        segments.next().is_none().then_some(parameters)
+    }
+}
+
+impl Parameters {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+}
+
+/// Respond with the task as JSON, or `404 Not Found` if there is no task.
+fn found(task: Result<Option<Task>, StoreError>) -> HttpResponse {
+    match task {
+        Ok(Some(task)) => HttpResponse::json(200, &task),
+        Ok(None) => HttpResponse::text(404, "Task not found"),
+        Err(err) => HttpResponse::text(500, err.to_string()),
+    }
+}
+
+/// Generate OpenAPI specification from [`ROUTES`].
+pub fn openapi_spec() -> serde_json::Value {
+    let mut paths = serde_json::Map::new();
+    for route in ROUTES {
+        let parameters = route.parameters.iter().map(|parameter| {
+            let (location, required) = match parameter.location {
+                ParameterLocation::Path => ("path", true),
+                ParameterLocation::Query => ("query", false),
+            };
+            json!({
+                "name": parameter.name,
+                "in": location,
+                "description": parameter.description,
+                "required": required,
+                "schema": { "type": "string" },
+            })
+        }).collect::<Vec<_>>();
+        let mut responses = route.responses.iter().map(|(status, description)| {
+            let mut response = json!({ "description": description });
+            if (200..300).contains(status) {
+                response["content"] = json!({ route.content_type: { "schema": { "type": "string" } } });
+            }
+            (status.to_string(), response)
+        }).collect::<serde_json::Map<_, _>>();
+        let mut operation = json!({
+            "summary": route.summary,
+            "operationId": operation_id(route),
+            "parameters": parameters,
+        });
+        if route.scope.is_some() {
+            responses.insert("401".to_string(), json!({ "description": "Missing or unknown token" }));
+            responses.insert("403".to_string(), json!({ "description": "Token scope is insufficient" }));
+            operation["security"] = json!([{ "bearer": [] }, { "token": [] }]);
+        }
+        if matches!(route.method, Method::Post | Method::Put) {
+            responses.insert("413".to_string(), json!({ "description": "Request body is too large" }));
+        }
+        responses.insert("429".to_string(), json!({ "description": "Rate limit exceeded" }));
+        operation["responses"] = responses.into();
+
+        let path = paths.entry(route.path).or_insert_with(|| json!({}));
+        path[route.method.as_str().to_lowercase()] = operation;
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "todo-list",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": paths,
+        "components": {
+            "securitySchemes": {
+                "bearer": { "type": "http", "scheme": "bearer" },
+                "token": { "type": "apiKey", "in": "query", "name": "token" },
+            },
+        },
+    })
+}
+
+/// Returns operation id of the route, e.g. `postTasksNameDone` for `POST /tasks/{name}/done`.
+fn operation_id(route: &Route) -> String {
+    route
+        .path
+        .split(|char: char| !char.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .fold(route.method.as_str().to_lowercase(), |mut id, word| {
+            let mut chars = word.chars();
+            id.extend(chars.next().map(|char| char.to_ascii_uppercase()));
+            id.push_str(chars.as_str());
+            id
+        })
+}
+
+impl RateLimiter {
+    pub fn new(limit: u32, window: Duration) -> RateLimiter {
+        RateLimiter {
+            limit,
+            window,
+            clients: HashMap::new(),
+            pruned: None,
+        }
+    }
+
+    /// Count request of the `client` at the moment `now`. Returns `false` if the client exceeded the limit.
+    pub fn check(&mut self, client: Option<IpAddr>, now: Instant) -> bool {
+        if self.pruned.is_none_or(|pruned| now.duration_since(pruned) >= self.window) {
+            let window = self.window;
+            self.clients.retain(|_, (window_start, _)| now.duration_since(*window_start) < window);
+            self.pruned = Some(now);
+        }
+        let (window_start, count) = self.clients.entry(client).or_insert((now, 0));
+        if now.duration_since(*window_start) >= self.window {
+            *window_start = now;
+            *count = 0;
+        }
+        *count += 1;
+
+        *count <= self.limit
+    }
+}
+
+impl HttpResponse {
+    fn text(status: u16, body: impl Into<String>) -> HttpResponse {
+        HttpResponse {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    fn json(status: u16, body: &impl serde::Serialize) -> HttpResponse {
+        HttpResponse {
+            status,
+            content_type: "application/json",
+            headers: Vec::new(),
+            body: serde_json::to_string(body).unwrap_or_default(),
+        }
+    }
+}
+
+/// Parse token in format `TOKEN:SCOPE` or `TOKEN:SCOPE:USER`.
+pub fn parse_token(token: &str) -> Result<Token, String> {
+    let (rest, last) = token
+        .rsplit_once(':')
+        .ok_or_else(|| "Token must be in format 'TOKEN:SCOPE[:USER]'".to_string())?;
+    let (secret, scope, user) = match Scope::from_str(last, true) {
+        Ok(scope) => (rest, scope, None),
+        Err(err) => {
+            let (secret, scope) = rest.rsplit_once(':').ok_or(err)?;
+            (secret, Scope::from_str(scope, true)?, Some(last.to_string()))
+        }
+    };
+    if secret.is_empty() {
+        return Err("Token must not be empty".to_string());
+    }
+
+    Ok(Token {
+        secret: secret.to_string(),
+        scope,
+        user,
+    })
+}
+
+/// Read the body of the `request`. Bodies over [`MAX_BODY`] are rejected with 413, so a single request does not
+/// exhaust the memory of the server.
+fn read_body(request: &mut Request) -> Result<String, HttpResponse> {
+    let too_large = || HttpResponse::text(413, format!("Request body exceeds {MAX_BODY} bytes"));
+    if request.body_length().is_some_and(|length| length as u64 > MAX_BODY) {
+        return Err(too_large());
+    }
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY + 1)
+        .read_to_string(&mut body)
+        .map_err(|err| HttpResponse::text(400, err.to_string()))?;
+    if body.len() as u64 > MAX_BODY {
+        return Err(too_large());
+    }
+
+    Ok(body)
+}
+
+fn respond(request: Request, response: HttpResponse) {
+    let mut http_response = Response::from_string(response.body).with_status_code(response.status);
+    let headers = [("Content-Type", response.content_type.to_string())]
+        .into_iter()
+        .chain(response.headers);
+    for (name, value) in headers {
+        if let Ok(header) = Header::from_bytes(name, value) {
+            http_response.add_header(header);
+        }
+    }
+
+    if let Err(err) = request.respond(http_response) {
+        eprintln!("Failed to send response. \nReason: {err}");
+    }
+}
+
+/// Represents possible errors of running server.
+#[derive(Error, Debug)]
+pub enum ServerError {
+    #[error("Failed to start server. \nReason: {0}")]
+    Bind(Box<dyn std::error::Error + Send + Sync>),
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    #[error(transparent)]
+    Share(#[from] ShareError),
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+    use tempfile::tempdir;
+    use crate::retention::ARCHIVE_TREE;
+    use super::*;
+
+    #[test]
+    fn calendar_feed() {
+        let tempdir = tempdir().unwrap();
+        let storage = test_storage(&tempdir);
+        let settings = settings(Vec::new());
+        let mut server = Server::new(&settings, &storage).unwrap();
+
+        let response = server.handle(&request(Method::Get, "/calendar.ics?where=category+%3D+%27work%27", None), Instant::now());
+
+        assert_eq!(response.status, 200);
+        assert!(response.body.contains("SUMMARY:first"));
+        assert!(!response.body.contains("SUMMARY:second"));
+        assert!(!response.body.contains("SUMMARY:third"));
+
+        assert_eq!(server.handle(&request(Method::Get, "/calendar.ics?where=category+%3D", None), Instant::now()).status, 400);
+        assert_eq!(server.handle(&request(Method::Post, "/calendar.ics", None), Instant::now()).status, 404);
+    }
+
+    #[test]
+    fn authorization() {
+        let tempdir = tempdir().unwrap();
+        let storage = test_storage(&tempdir);
+        let settings = settings(vec![parse_token("reader:read").unwrap(), parse_token("writer:WRITE:alice").unwrap()]);
+        let mut server = Server::new(&settings, &storage).unwrap();
+        let mut status = |method, url, authorization| server.handle(&request(method, url, authorization), Instant::now()).status;
+
+        assert_eq!(status(Method::Get, "/calendar.ics", None), 401);
+        assert_eq!(status(Method::Get, "/calendar.ics?token=unknown", None), 401);
+        assert_eq!(status(Method::Get, "/calendar.ics?token=reader", None), 200);
+        assert_eq!(status(Method::Post, "/tasks/first/done", Some("Bearer reader")), 403);
+        assert_eq!(status(Method::Post, "/tasks/first/done", Some("Bearer writer")), 200);
+        assert_eq!(status(Method::Post, "/tasks/no%20task/done", Some("Bearer writer")), 404);
+
+        assert_eq!(storage.get("first").unwrap().unwrap().status, Status::On);
+        let audit = storage.open_tree::<AuditEntry>(AUDIT_TREE).unwrap()
+            .iter()
+            .map(|entry| entry.map(|(_, entry)| (entry.token, entry.request, entry.status)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(audit, [
+            ("sha256:3d0941964aa3ebdc".to_string(), "POST /tasks/first/done".to_string(), 403),
+            ("alice".to_string(), "POST /tasks/first/done".to_string(), 200),
+            ("alice".to_string(), "POST /tasks/no%20task/done".to_string(), 404),
+        ]);
+    }
+
+    #[test]
+    fn owner_from_token() {
+        let tempdir = tempdir().unwrap();
+        let storage = test_storage(&tempdir);
+        let settings = settings(vec![parse_token("writer:write:alice").unwrap(), parse_token("a:b:write").unwrap()]);
+        let mut server = Server::new(&settings, &storage).unwrap();
+        let mut add = request(Method::Post, "/tasks", Some("Bearer writer"));
+        add.body = r#"{"name":"new","description":"description","date":"2024-12-12T20:20:00Z","category":"work","status":"Off"}"#.to_string();
+
+        assert_eq!(settings.tokens[1], Token { secret: "a:b".to_string(), scope: Scope::Write, user: None });
+        assert_eq!(server.handle(&add, Instant::now()).status, 200);
+        assert_eq!(storage.get("new").unwrap().unwrap().owner.as_deref(), Some("alice"));
+        assert!(parse_token("token:owner").is_err());
+    }
+
+    #[test]
+    fn share_view() {
+        let tempdir = tempdir().unwrap();
+        let storage = test_storage(&tempdir);
+        let settings = settings(vec![parse_token("writer:write").unwrap()]);
+        let views = storage.open_tree::<View>(VIEWS_TREE).unwrap();
+        View::new("work", &["name where category = 'work'".to_string()]).unwrap().save(&views).unwrap();
+        let mut server = Server::new(&settings, &storage).unwrap();
+
+        assert_eq!(server.handle(&request(Method::Post, "/views/home/share", Some("Bearer writer")), Instant::now()).status, 404);
+        assert_eq!(server.handle(&request(Method::Post, "/views/work/share?expires_in=-1", Some("Bearer writer")), Instant::now()).status, 400);
+        assert_eq!(server.handle(&request(Method::Post, "/views/work/share?expires_in=0", Some("Bearer writer")), Instant::now()).status, 400);
+        let response = server.handle(&request(Method::Post, "/views/work/share?expires_in=1", Some("Bearer writer")), Instant::now());
+        let path = serde_json::from_str::<serde_json::Value>(&response.body).unwrap()["path"].as_str().unwrap().to_string();
+
+        let response = server.handle(&request(Method::Get, &format!("{path}?format=json"), None), Instant::now());
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body, r#"[{"name":"first"},{"name":"third"}]"#);
+        let response = server.handle(&request(Method::Get, &path, None), Instant::now());
+        assert!(response.body.contains("<td>first</td>"));
+        assert_eq!(server.handle(&request(Method::Get, &format!("{path}x"), None), Instant::now()).status, 403);
+
+        View::new("work", &["*".to_string()]).unwrap().save(&views).unwrap();
+        assert_eq!(server.handle(&request(Method::Get, &path, None), Instant::now()).status, 403);
+        views.delete("work").unwrap();
+        assert_eq!(server.handle(&request(Method::Get, &path, None), Instant::now()).status, 404);
+    }
+
+    #[test]
+    fn rate_limit_and_cors() {
+        let tempdir = tempdir().unwrap();
+        let storage = test_storage(&tempdir);
+        let mut settings = settings(Vec::new());
+        settings.rate_limit = Some(2);
+        settings.cors_origins = vec!["https://example.com".to_string()];
+        let mut server = Server::new(&settings, &storage).unwrap();
+        let now = Instant::now();
+
+        let mut cross_origin = request(Method::Get, "/calendar.ics", None);
+        cross_origin.origin = Some("https://example.com".to_string());
+        let response = server.handle(&cross_origin, now);
+        assert!(response.headers.contains(&("Access-Control-Allow-Origin", "https://example.com".to_string())));
+
+        cross_origin.origin = Some("https://other.com".to_string());
+        let response = server.handle(&cross_origin, now);
+        assert_eq!(response.status, 200);
+        assert!(response.headers.iter().all(|(name, _)| *name != "Access-Control-Allow-Origin"));
+
+        assert_eq!(server.handle(&request(Method::Get, "/calendar.ics", None), now).status, 429);
+        assert_eq!(server.handle(&request(Method::Options, "/calendar.ics", None), now).status, 429);
+        assert_eq!(server.handle(&request(Method::Get, "/calendar.ics", None), now + Duration::from_secs(60)).status, 200);
+    }
+
+    #[test]
+    fn rate_limiter_forgets_expired_clients() {
+        let mut limiter = RateLimiter::new(1, Duration::from_secs(60));
+        let now = Instant::now();
+        let client = |octet| Some(IpAddr::from([127, 0, 0, octet]));
+
+        assert!(limiter.check(client(1), now));
+        assert!(limiter.check(client(2), now + Duration::from_secs(30)));
+        assert!(!limiter.check(client(2), now + Duration::from_secs(31)));
+        assert_eq!(limiter.clients.len(), 2);
+        assert!(limiter.check(client(3), now + Duration::from_secs(61)));
+        assert_eq!(limiter.clients.len(), 2);
+        assert!(!limiter.clients.contains_key(&client(1)));
+    }
+
+    #[test]
+    fn serve_rejects_large_body() {
+        let tempdir = tempdir().unwrap();
+        let storage = test_storage(&tempdir);
+        let settings = settings(vec![parse_token("secret:write").unwrap()]);
+        let http = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/tasks", http.server_addr());
+        std::thread::spawn(move || settings.serve(&http, &storage));
+        let status = |result: Result<ureq::Response, ureq::Error>| match result {
+            Ok(response) => response.status(),
+            Err(ureq::Error::Status(status, _)) => status,
+            Err(err) => panic!("{err}"),
+        };
+        let post = || ureq::post(&url).set("Authorization", "Bearer secret");
+
+        assert_eq!(status(post().send_string(&"x".repeat(MAX_BODY as usize + 1))), 413);
+        // Body without the length is sent in chunks, and is cut at the limit.
+        assert_eq!(status(post().send(std::io::repeat(b'x').take(MAX_BODY + 1))), 413);
+        assert_eq!(status(post().send_string("not a task")), 400);
+    }
+
+    #[test]
+    fn serve_applies_retention() {
+        let tempdir = tempdir().unwrap();
+        let storage = test_storage(&tempdir);
+        let mut settings = settings(Vec::new());
+        settings.retention = true;
+        let http = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/calendar.ics", http.server_addr());
+        std::thread::spawn({
+            let storage = storage.clone();
+            move || settings.serve(&http, &storage)
+        });
+
+        // Policy is applied before the first request is served.
+        ureq::get(&url).call().unwrap();
+        assert_eq!(storage.get("third").unwrap(), None);
+        assert!(storage.open_tree::<Task>(ARCHIVE_TREE).unwrap().get("third").unwrap().is_some());
+        assert!(storage.get("first").unwrap().is_some());
+    }
+
+    #[test]
+    fn openapi() {
+        let spec = openapi_spec();
+
+        assert_eq!(spec["paths"]["/calendar.ics"]["get"]["operationId"], "getCalendarIcs");
+        assert_eq!(spec["paths"]["/tasks/{name}/done"]["post"]["parameters"][0]["in"], "path");
+        assert!(spec["paths"]["/tasks/{name}/done"]["post"]["responses"]["403"].is_object());
+        assert!(spec["paths"]["/openapi.json"]["get"]["security"].is_null());
+
+        let tempdir = tempdir().unwrap();
+        let storage = test_storage(&tempdir);
+        let settings = settings(vec![parse_token("reader:read").unwrap()]);
+        let mut server = Server::new(&settings, &storage).unwrap();
+        let response = server.handle(&request(Method::Get, "/openapi.json", None), Instant::now());
+
+        assert_eq!(response.status, 200);
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&response.body).unwrap(), spec);
+    }
+
+    fn test_storage(path: &tempfile::TempDir) -> Storage<Task> {
+        let storage = Storage::<Task>::open(path).unwrap();
+        for (name, category, status) in [("first", "work", Status::Off), ("second", "home", Status::Off), ("third", "work", Status::On)] {
+            let task = Task {
+                name: name.to_string(),
+                description: "description".to_string(),
+                date: NaiveDateTime::parse_from_str("2024-12-12 20:20", "%Y-%m-%d %H:%M").unwrap().and_utc(),
+                category: category.to_string(),
+                status,
+                owner: None,
+                assignee: None,
+                external_refs: Vec::new(),
+            };
+            storage.insert(&task.name, &task).unwrap();
+        }
+
+        storage
+    }
+
+    fn settings(tokens: Vec<Token>) -> Serve {
+        Serve {
+            address: "127.0.0.1:8080".to_string(),
+            tokens,
+            rate_limit: None,
+            cors_origins: Vec::new(),
+            print_spec: false,
+            retention: false,
+            retention_policy: RetentionPolicy { archive_after: 30, purge_after: 365 },
+        }
+    }
+
+    fn request(method: Method, url: &str, authorization: Option<&str>) -> HttpRequest {
+        HttpRequest {
+            method,
+            url: url.to_string(),
+            authorization: authorization.map(ToString::to_string),
+            origin: None,
+            client: None,
+            body: String::new(),
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use ring::{digest, hmac};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use crate::query::ResultSet;
+use crate::storage::{Storage, StorageError};
+use crate::task::Task;
+use crate::view::View;
+
+/// Name of the storage tree, where signing keys are kept.
+pub const KEYS_TREE: &str = "keys";
+
+const SHARE_KEY: &str = "share";
+
+/// Key, that signs read-only share tokens.
+///
+/// Key is generated on the first use and kept in the storage, so issued tokens stay valid across restarts.
+pub struct ShareKey(hmac::Key);
+
+/// Claims of the share token. Token grants read-only access to the saved `view` until `expires`.
+///
+/// `query` is the digest of the view's query at the moment of sharing, so the token stops working, once the view is
+/// saved with another query.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ShareClaims {
+    pub view: String,
+    pub query: String,
+    pub expires: Option<DateTime<Utc>>,
+}
+
+impl ShareClaims {
+    /// Claims, that share `view` until `expires`.
+    pub fn new(view: &View, expires: Option<DateTime<Utc>>) -> ShareClaims {
+        ShareClaims { view: view.name.clone(), query: query_digest(view), expires }
+    }
+
+    /// Check, that the claims were issued for the current version of the `view`.
+    pub fn check(&self, view: &View) -> Result<(), ShareError> {
+        if self.view == view.name && self.query == query_digest(view) {
+            Ok(())
+        } else {
+            Err(ShareError::ViewChanged)
+        }
+    }
+}
+
+fn query_digest(view: &View) -> String {
+    URL_SAFE_NO_PAD.encode(digest::digest(&digest::SHA256, view.query.as_bytes()))
+}
+
+impl ShareKey {
+    /// Load the key from `storage` or generate a new one.
+    pub fn load_or_create(storage: &Storage<Task>) -> Result<ShareKey, ShareError> {
+        let keys = storage.open_tree::<Vec<u8>>(KEYS_TREE)?;
+        let secret = match keys.get(SHARE_KEY)? {
+            Some(secret) => secret,
+            None => {
+                let mut secret = vec![0; 32];
+                SystemRandom::new().fill(&mut secret).map_err(|_| ShareError::KeyGeneration)?;
+                keys.insert(SHARE_KEY, &secret)?;
+                secret
+            }
+        };
+
+        Ok(ShareKey(hmac::Key::new(hmac::HMAC_SHA256, &secret)))
+    }
+
+    /// Mint token in format `<claims>.<signature>`, both encoded as URL-safe base64.
+    pub fn mint(&self, claims: &ShareClaims) -> String {
+        let claims = URL_SAFE_NO_PAD.encode(serde_json::to_vec(claims).unwrap_or_default());
+        let signature = URL_SAFE_NO_PAD.encode(hmac::sign(&self.0, claims.as_bytes()));
+
+        format!("{claims}.{signature}")
+    }
+
+    /// Verify signature and expiration of the `token` at the moment `now`. Returns claims of the token.
+    pub fn verify(&self, token: &str, now: DateTime<Utc>) -> Result<ShareClaims, ShareError> {
+        let (claims, signature) = token.split_once('.').ok_or(ShareError::InvalidToken)?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| ShareError::InvalidToken)?;
+        hmac::verify(&self.0, claims.as_bytes(), &signature).map_err(|_| ShareError::InvalidToken)?;
+        let claims = URL_SAFE_NO_PAD
+            .decode(claims)
+            .ok()
+            .and_then(|claims| serde_json::from_slice::<ShareClaims>(&claims).ok())
+            .ok_or(ShareError::InvalidToken)?;
+        if claims.expires.is_some_and(|expires| expires <= now) {
+            return Err(ShareError::Expired);
+        }
+
+        Ok(claims)
+    }
+}
+
+/// Render `result_set` as HTML page with the `title`.
+pub fn to_html(title: &str, result_set: &ResultSet) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>{0}</title></head>\n<body>\n<h1>{0}</h1>\n<table>\n<tr>",
+        escape_html(title)
+    );
+    for column in result_set.columns() {
+        html.push_str(&format!("<th>{}</th>", escape_html(column)));
+    }
+    html.push_str("</tr>\n");
+    for row in result_set.rows() {
+        html.push_str("<tr>");
+        for value in row {
+            html.push_str(&format!("<td>{}</td>", escape_html(&value.to_string())));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+
+    html
+}
+
+fn escape_html(text: &str) -> String {
+    text.chars().fold(String::with_capacity(text.len()), |mut escaped, char| {
+        match char {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            char => escaped.push(char),
+        }
+        escaped
+    })
+}
+
+/// Represents possible errors of sharing views.
+#[derive(Error, Debug)]
+pub enum ShareError {
+    #[error("Invalid share token")]
+    InvalidToken,
+    #[error("Share token is expired")]
+    Expired,
+    #[error("Shared view was changed")]
+    ViewChanged,
+    #[error("Failed to generate share key")]
+    KeyGeneration,
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+    use tempfile::tempdir;
+    use crate::query::reflect::Value;
+    use super::*;
+
+    #[test]
+    fn mint_and_verify() {
+        let tempdir = tempdir().unwrap();
+        let storage = Storage::<Task>::open(&tempdir).unwrap();
+        let key = ShareKey::load_or_create(&storage).unwrap();
+        let now = Utc::now();
+        let view = View { name: "work".to_string(), query: "SELECT name".to_string() };
+        let claims = ShareClaims::new(&view, Some(now + TimeDelta::days(1)));
+
+        let token = key.mint(&claims);
+
+        assert_eq!(key.verify(&token, now).unwrap(), claims);
+        assert_eq!(ShareKey::load_or_create(&storage).unwrap().verify(&token, now).unwrap(), claims);
+        assert!(matches!(key.verify(&token, now + TimeDelta::days(2)), Err(ShareError::Expired)));
+
+        let (_, signature) = token.split_once('.').unwrap();
+        let forged = ShareClaims { view: "home".to_string(), ..claims.clone() };
+        let forged = format!("{}.{signature}", URL_SAFE_NO_PAD.encode(serde_json::to_vec(&forged).unwrap()));
+        assert!(matches!(key.verify(&forged, now), Err(ShareError::InvalidToken)));
+    }
+
+    #[test]
+    fn check_view() {
+        let view = View { name: "work".to_string(), query: "SELECT name".to_string() };
+        let claims = ShareClaims::new(&view, None);
+
+        assert!(claims.check(&view).is_ok());
+        let changed = View { query: "SELECT *".to_string(), ..view.clone() };
+        assert!(matches!(claims.check(&changed), Err(ShareError::ViewChanged)));
+        let renamed = View { name: "home".to_string(), ..view };
+        assert!(matches!(claims.check(&renamed), Err(ShareError::ViewChanged)));
+    }
+
+    #[test]
+    fn render() {
+        let mut result_set = ResultSet::new();
+        result_set.add_row([("name", Value::String("<b>task</b>".into())), ("done", Value::Bool(true))]);
+
+        assert!(to_html("View", &result_set).contains("<tr><td>&lt;b&gt;task&lt;/b&gt;</td><td>true</td></tr>"));
+    }
+}
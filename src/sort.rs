@@ -0,0 +1,222 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use crate::query::reflect::{Reflectable, Value};
+use crate::task::{Status, Task};
+
+/// Ordering of the listed tasks, that is composed of the sort keys.
+///
+/// Tasks are compared by the first key, ties are broken by the next ones. Spec is written as comma-separated keys,
+/// e.g. `overdue,date,-name`:
+///
+/// * `overdue` - pending tasks, that are past due, first;
+/// * `<field>` - ascending by the task field;
+/// * `-<field>` - descending by the task field;
+///
+/// `NULL` values of the field, e.g. tasks without assignee, are placed last in both directions, unless the field is
+/// followed by `nulls first`, e.g. `assignee nulls first`. `nulls last` states the default explicitly.
+///
+/// Default spec `overdue,date` places overdue tasks first, then the others by due date.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SortSpec(pub Vec<SortKey>);
+
+/// Key of the [`SortSpec`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SortKey {
+    Overdue,
+    Field { name: String, descending: bool, nulls_first: bool },
+}
+
+impl SortSpec {
+    /// Append `key`, that breaks ties of the keys before it.
+    pub fn then(mut self, key: SortKey) -> SortSpec {
+        self.0.push(key);
+        self
+    }
+
+    /// Returns `true` if tasks ordered by name are ordered by the spec, so they don't need to be sorted. Names are
+    /// unique, so the keys after ascending `name` never break ties.
+    pub fn is_name_order(&self) -> bool {
+        matches!(self.0.first(), Some(SortKey::Field { name, descending: false, .. }) if name == "name")
+    }
+
+    /// Compare tasks `left` and `right` at the time `now`.
+    pub fn compare(&self, left: &Task, right: &Task, now: DateTime<Utc>) -> Ordering {
+        self.0
+            .iter()
+            .map(|key| key.compare(left, right, now))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+
+    /// Sort `tasks` at the time `now`. Sort is stable, so the tasks, that are equal by all keys, keep their order.
+    pub fn sort(&self, tasks: &mut [Task], now: DateTime<Utc>) {
+        tasks.sort_by(|left, right| self.compare(left, right, now));
+    }
+}
+
+impl SortKey {
+    fn compare(&self, left: &Task, right: &Task, now: DateTime<Utc>) -> Ordering {
+        match self {
+            SortKey::Overdue => {
+                let is_overdue = |task: &Task| task.status == Status::Off && task.date < now;
+                is_overdue(right).cmp(&is_overdue(left))
+            }
+            SortKey::Field { name, descending, nulls_first } => {
+                let null = if *nulls_first { Ordering::Less } else { Ordering::Greater };
+                match (left.get_field(name).unwrap_or(Value::Null), right.get_field(name).unwrap_or(Value::Null)) {
+                    (Value::Null, Value::Null) => Ordering::Equal,
+                    (Value::Null, _) => null,
+                    (_, Value::Null) => null.reverse(),
+                    (left, right) if *descending => right.partial_cmp(&left).unwrap_or(Ordering::Equal),
+                    (left, right) => left.partial_cmp(&right).unwrap_or(Ordering::Equal),
+                }
+            }
+        }
+    }
+}
+
+impl Default for SortSpec {
+    fn default() -> Self {
+        SortSpec(Vec::new())
+            .then(SortKey::Overdue)
+            .then(SortKey::Field { name: "date".to_string(), descending: false, nulls_first: false })
+    }
+}
+
+impl FromStr for SortSpec {
+    type Err = SortSpecError;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let keys = spec
+            .split(',')
+            .map(str::trim)
+            .map(|key| match key {
+                "overdue" => Ok(SortKey::Overdue),
+                key => {
+                    let (field, nulls_first) = match key.rsplit_once(char::is_whitespace) {
+                        Some((rest, placement)) if placement.eq_ignore_ascii_case("first") => (nulls(rest), true),
+                        Some((rest, placement)) if placement.eq_ignore_ascii_case("last") => (nulls(rest), false),
+                        _ => (Some(key), false),
+                    };
+                    let field = field.ok_or_else(|| SortSpecError(key.to_string()))?;
+                    let (name, descending) = field.strip_prefix('-').map_or((field, false), |name| (name, true));
+                    if Task::field_names().iter().any(|field| field == name) {
+                        Ok(SortKey::Field { name: name.to_string(), descending, nulls_first })
+                    } else {
+                        Err(SortSpecError(key.to_string()))
+                    }
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(SortSpec(keys))
+    }
+}
+
+impl Display for SortSpec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let keys = self
+            .0
+            .iter()
+            .map(|key| match key {
+                SortKey::Overdue => "overdue".to_string(),
+                SortKey::Field { name, descending, nulls_first } => format!(
+                    "{}{name}{}",
+                    if *descending { "-" } else { "" },
+                    if *nulls_first { " nulls first" } else { "" }
+                ),
+            })
+            .collect::<Vec<_>>();
+
+        Display::fmt(&keys.join(","), f)
+    }
+}
+
+/// Returns the field of the key, that ends with `nulls`, e.g. `assignee` of `assignee nulls`.
+fn nulls(key: &str) -> Option<&str> {
+    let (field, nulls) = key.trim_end().rsplit_once(char::is_whitespace)?;
+
+    nulls.eq_ignore_ascii_case("nulls").then(|| field.trim_end())
+}
+
+/// Sort key is neither `overdue` nor the task field.
+#[derive(Error, Debug)]
+#[error("Unknown sort key '{0}'. Expected 'overdue' or the task field, optionally prefixed by '-' and followed by 'nulls first' or 'nulls last'")]
+pub struct SortSpecError(pub String);
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDateTime, TimeDelta};
+    use super::*;
+
+    #[test]
+    fn sort_spec() {
+        let now = NaiveDateTime::parse_from_str("2024-12-12 20:20", "%Y-%m-%d %H:%M").unwrap().and_utc();
+        let task = |name: &str, days: i64, status: Status| Task {
+            name: name.to_string(),
+            description: "description".to_string(),
+            date: now + TimeDelta::days(days),
+            category: "category".to_string(),
+            status,
+            owner: None,
+            assignee: (name == "assigned").then(|| "bob".to_string()),
+            external_refs: Vec::new(),
+        };
+        let mut tasks = [
+            task("done", -3, Status::On),
+            task("soon", 1, Status::Off),
+            task("overdue", -1, Status::Off),
+            task("assigned", 2, Status::Off),
+        ];
+        let names = |tasks: &[Task]| tasks.iter().map(|task| task.name.clone()).collect::<Vec<_>>();
+
+        SortSpec::default().sort(&mut tasks, now);
+
+        assert_eq!(names(&tasks), ["overdue", "done", "soon", "assigned"]);
+
+        SortSpec::from_str("assignee, -date").unwrap().sort(&mut tasks, now);
+
+        assert_eq!(names(&tasks), ["assigned", "soon", "overdue", "done"]);
+        assert_eq!(SortSpec::default().to_string(), "overdue,date");
+        assert!(SortSpec::from_str("overdue,prio").is_err());
+        assert!(SortSpec::from_str("name,-date").unwrap().is_name_order());
+        assert!(!SortSpec::from_str("-name").unwrap().is_name_order());
+        assert!(!SortSpec::default().is_name_order());
+    }
+
+    #[test]
+    fn nulls_placement() {
+        let task = |name: &str, assignee: Option<&str>| Task {
+            name: name.to_string(),
+            description: "description".to_string(),
+            date: Utc::now(),
+            category: "category".to_string(),
+            status: Status::Off,
+            owner: None,
+            assignee: assignee.map(ToString::to_string),
+            external_refs: Vec::new(),
+        };
+        let mut tasks = [task("first", Some("alice")), task("second", None), task("third", Some("bob"))];
+        let names = |tasks: &[Task]| tasks.iter().map(|task| task.name.clone()).collect::<Vec<_>>();
+        let now = Utc::now();
+
+        SortSpec::from_str("-assignee").unwrap().sort(&mut tasks, now);
+        assert_eq!(names(&tasks), ["third", "first", "second"]);
+
+        SortSpec::from_str("assignee NULLS FIRST").unwrap().sort(&mut tasks, now);
+        assert_eq!(names(&tasks), ["second", "first", "third"]);
+
+        SortSpec::from_str("-assignee nulls first, name").unwrap().sort(&mut tasks, now);
+        assert_eq!(names(&tasks), ["second", "third", "first"]);
+
+        SortSpec::from_str("assignee  nulls last").unwrap().sort(&mut tasks, now);
+        assert_eq!(names(&tasks), ["first", "third", "second"]);
+
+        assert_eq!(SortSpec::from_str("-assignee nulls first,date nulls last").unwrap().to_string(), "-assignee nulls first,date");
+        assert!(SortSpec::from_str("assignee first").is_err());
+        assert!(SortSpec::from_str("assignee nulls").is_err());
+    }
+}
@@ -0,0 +1,159 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use crate::key::KeyStrategy;
+use crate::migration::{self, SCHEMA_VERSION};
+use crate::retention::ARCHIVE_TREE;
+use crate::storage::{Storage, StorageError};
+use crate::store::{ARCHIVE_LIST, CURRENT_LIST};
+use crate::task::Task;
+
+/// Version of the binary.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Environment variables, that configure the app. Their values are not reported, as they may be secrets.
+pub const CONFIG_VARIABLES: [&str; 21] = [
+    "TODO_LIST_REMOTE",
+    "TODO_LIST_TOKEN",
+    "TODO_LIST_USER",
+    "TODO_LIST_KEYS",
+    "TODO_LIST_DATE_FORMATS",
+    "TODO_LIST_TIMEZONE",
+    "TODO_LIST_FIELDS",
+    "TODO_LIST_MAX_SCANNED_ROWS",
+    "TODO_LIST_MAX_RESULT_ROWS",
+    "TODO_LIST_MAX_QUERY_TIME",
+    "TODO_LIST_FLOAT_EPSILON",
+    "TODO_LIST_PAGE_SIZE",
+    "TODO_LIST_MAX_COLUMN_WIDTH",
+    "TODO_LIST_COLUMN_WIDTHS",
+    "TODO_LIST_WRAP",
+    "TODO_LIST_TOTALS",
+    "TODO_LIST_SORT",
+    "TODO_LIST_CATEGORIES",
+    "TODO_LIST_FUTURE_DATES",
+    "TODO_LIST_MAX_NAME_LENGTH",
+    "TODO_LIST_WEBHOOK",
+];
+
+/// Status of the binary and the local storage.
+///
+/// Status is collected before the storage is migrated, so pending migrations are reported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusReport {
+    pub version: &'static str,
+    pub schema_version: u32,
+    pub path: PathBuf,
+    pub size: u64,
+    pub key_strategy: KeyStrategy,
+    pub lists: Vec<(&'static str, usize)>,
+    /// Configuration variables, that are set in the environment.
+    pub config: Vec<&'static str>,
+}
+
+impl StatusReport {
+    /// Collect status of the `storage` opened at `path`.
+    pub fn collect(storage: &Storage<Task>, path: &Path) -> Result<StatusReport, StorageError> {
+        Ok(StatusReport {
+            version: VERSION,
+            schema_version: migration::schema_version(storage)?,
+            path: path.canonicalize().unwrap_or_else(|_| path.to_path_buf()),
+            size: storage.size_on_disk()?,
+            key_strategy: KeyStrategy::load(storage)?,
+            lists: vec![
+                (CURRENT_LIST, storage.iter().count()),
+                (ARCHIVE_LIST, storage.open_tree::<Task>(ARCHIVE_TREE)?.iter().count()),
+            ],
+            config: CONFIG_VARIABLES.into_iter().filter(|variable| std::env::var_os(variable).is_some()).collect(),
+        })
+    }
+
+    /// Returns the number of migrations, that the next command applies.
+    pub fn pending_migrations(&self) -> u32 {
+        SCHEMA_VERSION.saturating_sub(self.schema_version)
+    }
+}
+
+impl Display for StatusReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Version: {}", self.version)?;
+        match self.pending_migrations() {
+            0 => writeln!(f, "Schema version: {}", self.schema_version)?,
+            pending => writeln!(
+                f,
+                "Schema version: {} ({pending} pending migrations to {SCHEMA_VERSION}, applied by the next command)",
+                self.schema_version
+            )?,
+        }
+        writeln!(f, "Storage: {} ({} bytes)", self.path.display(), self.size)?;
+        writeln!(f, "Keys: {}", self.key_strategy)?;
+        let lists = self.lists.iter().map(|(list, count)| format!("{list} ({count} tasks)")).collect::<Vec<_>>();
+        writeln!(f, "Lists: {}", lists.join(", "))?;
+        if self.config.is_empty() {
+            write!(f, "Config: defaults and command-line options")
+        } else {
+            write!(f, "Config: environment variables {}", self.config.join(", "))
+        }
+    }
+}
+
+/// Latest release of the app on GitHub.
+#[cfg(feature = "self-update")]
+pub const RELEASES_URL: &str = "https://api.github.com/repos/Rastler3D/todo-list/releases/latest";
+
+/// Returns the version of the latest release, if it differs from the version of the binary.
+#[cfg(feature = "self-update")]
+pub fn check_update() -> Result<Option<String>, StatusError> {
+    #[derive(serde::Deserialize)]
+    struct Release {
+        tag_name: String,
+    }
+
+    let release: Release = ureq::get(RELEASES_URL)
+        .set("Accept", "application/vnd.github+json")
+        .set("User-Agent", "todo-list")
+        .call()
+        .map_err(|err| StatusError::Request(Box::new(err)))?
+        .into_json()
+        .map_err(StatusError::Response)?;
+    let latest = release.tag_name.trim_start_matches('v');
+
+    Ok((latest != VERSION).then(|| latest.to_string()))
+}
+
+/// Represents possible errors of the status.
+#[derive(Error, Debug)]
+pub enum StatusError {
+    #[error("Failed to read storage status. \nReason: {0}")]
+    Storage(#[from] StorageError),
+    #[cfg(feature = "self-update")]
+    #[error("Failed to check for updates. \nReason: {0}")]
+    Request(Box<ureq::Error>),
+    #[cfg(feature = "self-update")]
+    #[error("Failed to read the latest release. \nReason: {0}")]
+    Response(std::io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+    use crate::migration::migrate;
+    use super::*;
+
+    #[test]
+    fn collect_status() {
+        let tempdir = tempdir().unwrap();
+        let storage = Storage::<Task>::open(&tempdir).unwrap();
+
+        let status = StatusReport::collect(&storage, tempdir.path()).unwrap();
+        assert_eq!(status.schema_version, 0);
+        assert_eq!(status.pending_migrations(), SCHEMA_VERSION);
+        assert_eq!(status.lists, [(CURRENT_LIST, 0), (ARCHIVE_LIST, 0)]);
+        assert!(status.to_string().contains("pending migrations"));
+
+        migrate(&storage).unwrap();
+        let status = StatusReport::collect(&storage, tempdir.path()).unwrap();
+        assert_eq!(status.pending_migrations(), 0);
+        assert!(status.to_string().starts_with(&format!("Version: {VERSION}\nSchema version: {SCHEMA_VERSION}\n")));
+    }
+}
@@ -1,19 +1,39 @@
-use crate::command::CommandError;
-use crate::query::{Query, ResultSet};
 use bincode::error::{DecodeError, EncodeError};
 use serde::{Deserialize, Serialize};
-use sled::Db;
+use sled::transaction::{ConflictableTransactionError, TransactionError, Transactional};
+use sled::{Batch, Db, IVec, Tree};
 use std::marker::PhantomData;
+use std::ops::RangeBounds;
 use std::path::Path;
 use thiserror::Error;
-use crate::query::reflect::Reflectable;
+#[cfg(feature = "cli")]
+use crate::{command::CommandError, query::reflect::Reflectable, query::{Query, ResultSet}, store::StoreError};
 
 /// Persistent key-value storage.
 pub struct Storage<V: Serialize + for<'a> Deserialize<'a>> {
     db: Db,
+    tree: Tree,
     phantom_data: PhantomData<V>,
 }
 
+/// Writes to a tree of the storage, that are applied atomically together with the writes to other trees of the same
+/// storage by [`apply_batches`].
+pub struct StorageBatch {
+    tree: Tree,
+    batch: Batch,
+}
+
+/// Handle of the same storage tree.
+impl<V: Serialize + for<'a> Deserialize<'a>> Clone for Storage<V> {
+    fn clone(&self) -> Self {
+        Self {
+            db: self.db.clone(),
+            tree: self.tree.clone(),
+            phantom_data: PhantomData,
+        }
+    }
+}
+
 impl<V: Serialize + for<'a> Deserialize<'a>> Storage<V> {
     /// Open storage with specified path.
     pub fn open(path: impl AsRef<Path>) -> Result<Self, StorageError> {
@@ -21,13 +41,22 @@ impl<V: Serialize + for<'a> Deserialize<'a>> Storage<V> {
 
         Ok(Self {
             phantom_data: PhantomData,
+            tree: (*db).clone(),
             db,
         })
     }
+    /// Open storage backed by the tree with specified `name` in the same database.
+    pub fn open_tree<U: Serialize + for<'a> Deserialize<'a>>(&self, name: impl AsRef<[u8]>) -> Result<Storage<U>, StorageError> {
+        Ok(Storage {
+            phantom_data: PhantomData,
+            tree: self.db.open_tree(name)?,
+            db: self.db.clone(),
+        })
+    }
     /// Get value by key. Value will be deserialized by bincode.
     pub fn get<K: AsRef<[u8]>>(&self, key: K) -> Result<Option<V>, StorageError> {
         Ok(self
-            .db
+            .tree
             .get(key)?
             .map(|data| {
                 bincode::serde::decode_from_std_read(&mut &*data, bincode::config::standard())
@@ -45,7 +74,7 @@ impl<V: Serialize + for<'a> Deserialize<'a>> Storage<V> {
         if let Some(mut value) = value {
             update_fn(&mut value);
             let updated_value = bincode::serde::encode_to_vec(value, bincode::config::standard())?;
-            self.db.insert(key, updated_value)?;
+            self.tree.insert(key, updated_value)?;
 
             return Ok(true);
         }
@@ -55,41 +84,185 @@ impl<V: Serialize + for<'a> Deserialize<'a>> Storage<V> {
     /// Insert value. Value will be serialized by bincode.
     pub fn insert<K: AsRef<[u8]>>(&self, key: K, value: &V) -> Result<Option<V>, StorageError> {
         let value = bincode::serde::encode_to_vec(value, bincode::config::standard())?;
-        let old_value = self.db.insert(key, value)?;
+        let old_value = self.tree.insert(key, value)?;
 
         Ok(old_value
             .map(|x| bincode::serde::decode_from_std_read(&mut &*x, bincode::config::standard()))
             .transpose()?)
     }
+    /// Insert multiple values atomically. Values will be serialized by bincode.
+    pub fn insert_batch<'a, K: AsRef<[u8]>>(&self, items: impl IntoIterator<Item = (K, &'a V)>) -> Result<(), StorageError>
+    where
+        V: 'a,
+    {
+        let mut batch = Batch::default();
+        for (key, value) in items {
+            let value = bincode::serde::encode_to_vec(value, bincode::config::standard())?;
+            batch.insert(key.as_ref(), value);
+        }
+        self.tree.apply_batch(batch)?;
+
+        Ok(())
+    }
+
+    /// Insert `items` and delete values with `keys` atomically. Values will be serialized by bincode.
+    pub fn apply_batch<'a, K: AsRef<[u8]>>(
+        &self,
+        items: impl IntoIterator<Item = (K, &'a V)>,
+        keys: impl IntoIterator<Item = K>,
+    ) -> Result<(), StorageError>
+    where
+        V: 'a,
+    {
+        self.tree.apply_batch(self.batch(items, keys)?.batch)?;
+
+        Ok(())
+    }
+    /// Returns the batch, that inserts `items` and deletes values with `keys`, to be applied by [`apply_batches`]
+    /// with the batches of the other trees. Values will be serialized by bincode.
+    pub fn batch<'a, K: AsRef<[u8]>>(
+        &self,
+        items: impl IntoIterator<Item = (K, &'a V)>,
+        keys: impl IntoIterator<Item = K>,
+    ) -> Result<StorageBatch, StorageError>
+    where
+        V: 'a,
+    {
+        let mut batch = Batch::default();
+        for key in keys {
+            batch.remove(key.as_ref());
+        }
+        for (key, value) in items {
+            let value = bincode::serde::encode_to_vec(value, bincode::config::standard())?;
+            batch.insert(key.as_ref(), value);
+        }
+
+        Ok(StorageBatch { tree: self.tree.clone(), batch })
+    }
+    /// Update all values, that satisfy `predicate`, atomically. Returns the values before the update.
+    pub fn update_where(
+        &self,
+        predicate: impl Fn(&V) -> bool,
+        update_fn: impl Fn(&mut V),
+    ) -> Result<Vec<V>, StorageError>
+    where
+        V: Clone,
+    {
+        let mut batch = Batch::default();
+        let mut prev_values = Vec::new();
+        for entry in self.iter() {
+            let (key, mut value) = entry?;
+            if predicate(&value) {
+                prev_values.push(value.clone());
+                update_fn(&mut value);
+                batch.insert(key, bincode::serde::encode_to_vec(&value, bincode::config::standard())?);
+            }
+        }
+        self.tree.apply_batch(batch)?;
+
+        Ok(prev_values)
+    }
 
     pub fn delete<K: AsRef<[u8]>>(&self, key: K) -> Result<Option<V>, StorageError> {
-        let old_value = self.db.remove(key)?;
+        let old_value = self.tree.remove(key)?;
 
         Ok(old_value
             .map(|x| bincode::serde::decode_from_std_read(&mut &*x, bincode::config::standard()))
             .transpose()?)
     }
+    /// Remove and return the value with the greatest key, e.g. the latest value keyed by [`Storage::generate_id`].
+    pub fn pop_last(&self) -> Result<Option<V>, StorageError> {
+        Ok(self
+            .tree
+            .pop_max()?
+            .map(|(_, x)| bincode::serde::decode_from_std_read(&mut &*x, bincode::config::standard()))
+            .transpose()?)
+    }
+    /// Write buffered changes of the database to disk. Returns the number of flushed bytes.
+    pub fn flush(&self) -> Result<usize, StorageError> {
+        Ok(self.db.flush()?)
+    }
+    /// Returns the size of the database files in bytes.
+    pub fn size_on_disk(&self) -> Result<u64, StorageError> {
+        Ok(self.db.size_on_disk()?)
+    }
+    /// Generate a monotonic ID, unique within the database.
+    pub fn generate_id(&self) -> Result<u64, StorageError> {
+        Ok(self.db.generate_id()?)
+    }
+    /// Re-encode all values, that were stored as `U`, to `V` with `convert`. Returns the number of converted values.
+    pub fn migrate<U: for<'a> Deserialize<'a>>(&self, convert: impl Fn(U) -> V) -> Result<usize, StorageError> {
+        let mut batch = Batch::default();
+        let mut count = 0;
+        for entry in self.tree.iter() {
+            let (key, data) = entry?;
+            let value: U = bincode::serde::decode_from_std_read(&mut &*data, bincode::config::standard())?;
+            batch.insert(key, bincode::serde::encode_to_vec(convert(value), bincode::config::standard())?);
+            count += 1;
+        }
+        self.tree.apply_batch(batch)?;
+
+        Ok(count)
+    }
+    /// Returns an iterator over values in ascending order of their keys. Keys are compared as bytes, e.g. name keys
+    /// are in the order of the names, so values, that are required in the key order, don't need to be sorted.
+    pub fn iter_sorted(&self) -> impl Iterator<Item = Result<V, StorageError>> {
+        self.iter().map(|entry| entry.map(|(_, value)| value))
+    }
+    /// Returns an iterator over values, which keys are within the `range`, in ascending order of their keys.
+    pub fn range<K: AsRef<[u8]>>(&self, range: impl RangeBounds<K>) -> impl Iterator<Item = Result<V, StorageError>> {
+        self.tree.range(range).map(|entry| {
+            let (_, data) = entry?;
+
+            Ok(bincode::serde::decode_from_std_read(&mut &*data, bincode::config::standard())?)
+        })
+    }
+    /// Returns an iterator over keys and values in key order. Values will be deserialized by bincode.
+    pub fn iter(&self) -> impl Iterator<Item = Result<(IVec, V), StorageError>> {
+        self.tree.iter().map(|entry| {
+            let (key, data) = entry?;
+            let value = bincode::serde::decode_from_std_read(&mut &*data, bincode::config::standard())?;
+
+            Ok((key, value))
+        })
+    }
 }
 
+#[cfg(feature = "cli")]
 impl<V: Reflectable + for<'a> Deserialize<'a> + Serialize> Storage<V> {
-    /// Select values that satisfy query.
+    /// Select values that satisfy query. Query must not select from other list, as values are not tasks.
+    ///
+    /// Query is cancelled by the token of the enclosing [`CancellationToken::scope`](crate::query::evaluator::cancel::CancellationToken::scope).
     pub fn select(&self, query: Query) -> Result<ResultSet, CommandError> {
+        if let Some(list) = &query.from {
+            return Err(StoreError::NoList(list.0.clone()).into());
+        }
         let items = self
-            .db
             .iter()
-            .values()
-            .map(|x| {
-                x.map_err(Into::into).and_then(|data| {
-                    bincode::serde::decode_from_std_read(&mut &*data, bincode::config::standard())
-                        .map_err(Into::into)
-                })
-            })
+            .map(|entry| entry.map(|(_, value)| value))
             .collect::<Result<Vec<V>, StorageError>>()?;
 
         Ok(query.execute(items.iter())?)
     }
 }
 
+/// Apply `batches` to their trees in a single transaction, so either all of them are applied or none.
+pub fn apply_batches(batches: &[StorageBatch]) -> Result<(), StorageError> {
+    let trees = batches.iter().map(|batch| batch.tree.clone()).collect::<Vec<_>>();
+    trees[..]
+        .transaction(|trees| {
+            for (tree, batch) in trees.iter().zip(batches) {
+                tree.apply_batch(&batch.batch)?;
+            }
+            Ok::<_, ConflictableTransactionError<sled::Error>>(())
+        })
+        .map_err(|err| match err {
+            TransactionError::Abort(err) | TransactionError::Storage(err) => err,
+        })?;
+
+    Ok(())
+}
+
 /// Represents possible errors of running command.
 #[derive(Error, Debug)]
 pub enum StorageError {
@@ -103,10 +276,8 @@ pub enum StorageError {
 
 #[cfg(test)]
 mod tests {
-    use std::str::FromStr;
     use tempfile::tempdir;
     use crate::query::evaluator::query::tests::test_dataset;
-    use crate::query::reflect::Value;
     use super::*;
 
     #[test]
@@ -119,7 +290,7 @@ mod tests {
         }
         let hello = storage.get("Hello").unwrap();
 
-        assert_eq!(hello.as_ref(), test_dataset.get(0))
+        assert_eq!(hello.as_ref(), test_dataset.first())
     }
 
     #[test]
@@ -150,11 +321,40 @@ mod tests {
 
         let hello = storage.get("Hello").unwrap();
 
-        assert_ne!(hello.as_ref(), test_dataset.get(0))
+        assert_ne!(hello.as_ref(), test_dataset.first())
+    }
+
+    #[test]
+    fn insert_batch() {
+        let storage = get_test_storage();
+        let test_dataset = test_dataset();
+
+        storage.insert_batch(test_dataset.iter().map(|test| (test.string.to_string(), test))).unwrap();
+
+        for test in &test_dataset{
+            assert_eq!(storage.get(&test.string).unwrap().as_ref(), Some(test));
+        }
+    }
+
+    #[test]
+    fn update_where() {
+        let storage = get_test_storage();
+        let test_dataset = test_dataset();
+
+        storage.insert_batch(test_dataset.iter().map(|test| (test.string.to_string(), test))).unwrap();
+        let prev_values = storage.update_where(|test| test.number > 10, |test| test.number = 0).unwrap();
+
+        assert_eq!(prev_values, [test_dataset[3].clone(), test_dataset[4].clone()]);
+        assert_eq!(storage.get("Hi").unwrap().unwrap().number, 0);
+        assert_eq!(storage.get("Hello").unwrap().unwrap().number, 1);
     }
 
     #[test]
+    #[cfg(feature = "cli")]
     fn select_item() {
+        use std::str::FromStr;
+        use crate::query::reflect::Value;
+
         let storage = get_test_storage();
         let test_dataset = test_dataset();
 
@@ -163,11 +363,11 @@ mod tests {
         }
 
         let hello = storage.select(Query::from_str("SELECT * WHERE number = 10").unwrap()).unwrap();
-        let expected = test_dataset.get(1).unwrap();
+        let expected = &test_dataset[1];
 
         assert!(hello.rows().eq([[
+            Value::String(expected.string.clone().into()),
             Value::Number(expected.number.into()),
-            Value::String(expected.string.to_string()),
             Value::DateTime(expected.date_time)
         ]]));
 
@@ -0,0 +1,467 @@
+use thiserror::Error;
+use crate::history::{Action, HistoryEntry, HISTORY_TREE};
+use crate::remote::{RemoteError, RemoteStore};
+use crate::storage::{Storage, StorageError};
+use crate::task::{Status, Task};
+use crate::validator::TaskValidationError;
+use crate::key::{KeyError, KeyStrategy, TaskKeys};
+use std::ops::Bound;
+use crate::query::ast::expression::Identifier;
+use crate::query::evaluator::plan::Plan;
+use crate::query::reflect::Value;
+use crate::query::Query;
+use crate::retention::ARCHIVE_TREE;
+use crate::interrupt;
+
+/// Name of the list of the current tasks, that queries select from by default.
+pub const CURRENT_LIST: &str = "tasks";
+
+/// Name of the list of the archived tasks.
+pub const ARCHIVE_LIST: &str = "archive";
+
+/// Store of tasks, that task commands operate on.
+///
+/// Implemented by the local [`Storage`] and by the [`RemoteStore`] over the HTTP API of the todo-list server.
+pub trait TaskStore {
+    /// Get task by name.
+    fn get(&self, name: &str) -> Result<Option<Task>, StoreError>;
+    /// Insert task or replace the task with the same name. Returns the replaced task.
+    fn insert(&self, task: &Task) -> Result<Option<Task>, StoreError>;
+    /// Insert multiple tasks.
+    fn insert_batch(&self, tasks: &[Task]) -> Result<(), StoreError> {
+        for task in tasks {
+            self.insert(task)?;
+        }
+
+        Ok(())
+    }
+    /// Replace the task `name` with `task`, that may have a different name.
+    /// Returns the other task, that was replaced because of renaming.
+    fn update(&self, name: &str, task: &Task) -> Result<Option<Task>, StoreError>;
+    /// Delete task by name. Returns the deleted task.
+    fn delete(&self, name: &str) -> Result<Option<Task>, StoreError>;
+    /// Mark task as completed. Returns `false` if there is no such task.
+    fn done(&self, name: &str) -> Result<bool, StoreError>;
+    /// Returns all tasks ordered by name.
+    fn tasks(&self) -> Result<Vec<Task>, StoreError>;
+    /// Returns the tasks ordered by name, that may satisfy the query. All tasks are returned, unless the store
+    /// finds them by the query [`Plan`].
+    fn candidates(&self, _query: &Query) -> Result<Vec<Task>, StoreError> {
+        self.tasks()
+    }
+    /// Import `tasks`. Task, that references the same external item as the existing task, updates it
+    /// keeping the completion status, so re-import does not duplicate tasks. Other tasks are inserted.
+    fn import(&self, tasks: Vec<Task>) -> Result<Imported, StoreError> {
+        let existing = self.tasks()?;
+        let mut added = Vec::new();
+        let mut updated = 0;
+        for mut task in tasks {
+            let prev_task = existing.iter().find(|existing| {
+                existing
+                    .external_refs
+                    .iter()
+                    .any(|existing_ref| task.external_refs.iter().any(|external_ref| external_ref.same_as(existing_ref)))
+            });
+            match prev_task {
+                Some(prev_task) => {
+                    task.status = prev_task.status;
+                    self.update(&prev_task.name, &task)?;
+                    updated += 1;
+                }
+                None => added.push(task),
+            }
+        }
+        self.insert_batch(&added)?;
+
+        Ok(Imported { added: added.len(), updated })
+    }
+}
+
+/// Numbers of imported tasks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Imported {
+    pub added: usize,
+    pub updated: usize,
+}
+
+/// Returns tasks of the `list` of the local `storage` ordered by name. Current tasks are returned, if the list
+/// is omitted.
+pub fn list_tasks(storage: &Storage<Task>, list: Option<&Identifier>) -> Result<Vec<Task>, StoreError> {
+    match list.map(|list| list.0.as_str()) {
+        None | Some(CURRENT_LIST) => storage.tasks(),
+        Some(ARCHIVE_LIST) => tasks_by_name(&storage.open_tree(ARCHIVE_TREE)?, KeyStrategy::load(storage)?),
+        Some(list) => Err(StoreError::NoList(list.to_string())),
+    }
+}
+
+/// Returns tasks of the `tree`, that are stored by keys of the `strategy`, ordered by name.
+///
+/// Name keys are iterated in the order of the names, so the tasks are sorted only if keys are not names.
+fn tasks_by_name(tree: &Storage<Task>, strategy: KeyStrategy) -> Result<Vec<Task>, StoreError> {
+    let mut tasks = tree.iter_sorted().collect::<Result<Vec<_>, _>>()?;
+    if strategy != KeyStrategy::Name {
+        tasks.sort_by(|left, right| left.name.cmp(&right.name));
+    }
+
+    Ok(tasks)
+}
+
+/// Local store. Changes of the tasks are recorded to the history.
+///
+/// Tasks are stored by the keys of the storage [`KeyStrategy`](crate::key::KeyStrategy) and are found by name
+/// with [`TaskKeys`].
+impl TaskStore for Storage<Task> {
+    fn get(&self, name: &str) -> Result<Option<Task>, StoreError> {
+        match TaskKeys::load(self)?.get(name) {
+            Some(key) => Ok(Storage::get(self, key)?),
+            None => Ok(None),
+        }
+    }
+
+    fn insert(&self, task: &Task) -> Result<Option<Task>, StoreError> {
+        let key = TaskKeys::load(self)?.get_or_generate(self, &task.name)?;
+        let prev_task = Storage::insert(self, key, task)?;
+        HistoryEntry::record(&self.open_tree(HISTORY_TREE)?, Action::Add, &task.name)?;
+
+        Ok(prev_task)
+    }
+
+    fn insert_batch(&self, tasks: &[Task]) -> Result<(), StoreError> {
+        let mut keys = TaskKeys::load(self)?;
+        let items = tasks
+            .iter()
+            .map(|task| Ok((keys.get_or_generate(self, &task.name)?, task)))
+            .collect::<Result<Vec<_>, KeyError>>()?;
+        Storage::insert_batch(self, items)?;
+        let history = self.open_tree(HISTORY_TREE)?;
+        for task in tasks {
+            HistoryEntry::record(&history, Action::Add, &task.name)?;
+        }
+
+        Ok(())
+    }
+
+    fn update(&self, name: &str, task: &Task) -> Result<Option<Task>, StoreError> {
+        // Task and its history entry are written one after another, that must not be interrupted in between.
+        let _section = interrupt::critical_section();
+        let mut keys = TaskKeys::load(self)?;
+        let mut replaced = None;
+        let mut deleted = Vec::new();
+        if task.name != name {
+            if let Some(key) = keys.get(&task.name) {
+                replaced = Storage::get(self, &key)?;
+                deleted.push(key);
+            }
+            if keys.strategy() == KeyStrategy::Name {
+                deleted.push(name.as_bytes().to_vec());
+            }
+        }
+        let key = keys.renamed(self, name, &task.name)?;
+        // Renamed task is inserted and the previous one is deleted atomically.
+        Storage::apply_batch(self, [(key, task)], deleted)?;
+        HistoryEntry::record(&self.open_tree(HISTORY_TREE)?, Action::Update, &task.name)?;
+
+        Ok(replaced)
+    }
+
+    fn delete(&self, name: &str) -> Result<Option<Task>, StoreError> {
+        let Some(key) = TaskKeys::load(self)?.get(name) else {
+            return Ok(None);
+        };
+        let task = Storage::delete(self, key)?;
+        if task.is_some() {
+            HistoryEntry::record(&self.open_tree(HISTORY_TREE)?, Action::Delete, name)?;
+        }
+
+        Ok(task)
+    }
+
+    fn done(&self, name: &str) -> Result<bool, StoreError> {
+        let Some(key) = TaskKeys::load(self)?.get(name) else {
+            return Ok(false);
+        };
+        let is_updated = Storage::update(self, key, |task| task.status = Status::On)?;
+        if is_updated {
+            HistoryEntry::record(&self.open_tree(HISTORY_TREE)?, Action::Done, name)?;
+        }
+
+        Ok(is_updated)
+    }
+
+    fn tasks(&self) -> Result<Vec<Task>, StoreError> {
+        tasks_by_name(self, KeyStrategy::load(self)?)
+    }
+
+    /// Tasks are looked up by the names of the query, or taken from the range of the names, if they are the keys.
+    fn candidates(&self, query: &Query) -> Result<Vec<Task>, StoreError> {
+        let name = |value: &Value| match value {
+            Value::String(name) => Some(name.to_string()),
+            _ => None,
+        };
+        let bound = |bound: &Bound<Value>| match bound {
+            Bound::Included(value) => name(value).map(Bound::Included),
+            Bound::Excluded(value) => name(value).map(Bound::Excluded),
+            Bound::Unbounded => Some(Bound::Unbounded),
+        };
+
+        match query.plan(&["name"]) {
+            Plan::Lookup { values, .. } => {
+                let Some(mut names) = values.iter().map(name).collect::<Option<Vec<_>>>() else {
+                    return self.tasks();
+                };
+                names.sort();
+                names.dedup();
+                let keys = TaskKeys::load(self)?;
+                let mut tasks = Vec::with_capacity(names.len());
+                for name in names {
+                    if let Some(task) = keys.get(&name).map(|key| Storage::get(self, key)).transpose()?.flatten() {
+                        tasks.push(task);
+                    }
+                }
+
+                Ok(tasks)
+            }
+            Plan::Range { lower, upper, .. } if KeyStrategy::load(self)? == KeyStrategy::Name => {
+                match (bound(&lower), bound(&upper)) {
+                    (Some(lower), Some(upper)) => Ok(self.range::<String>((lower, upper)).collect::<Result<_, _>>()?),
+                    _ => self.tasks(),
+                }
+            }
+            _ => self.tasks(),
+        }
+    }
+}
+
+/// Backend, that commands are running against.
+///
+/// * `Backend::Local` - local storage;
+/// * `Backend::Remote` - todo-list server;
+pub enum Backend {
+    Local(Storage<Task>),
+    Remote(RemoteStore),
+}
+
+impl Backend {
+    /// Returns the store of tasks.
+    pub fn store(&self) -> &dyn TaskStore {
+        match self {
+            Backend::Local(storage) => storage,
+            Backend::Remote(remote) => remote,
+        }
+    }
+
+    /// Returns the local storage, or `None` if running against the server.
+    pub fn local(&self) -> Option<&Storage<Task>> {
+        match self {
+            Backend::Local(storage) => Some(storage),
+            Backend::Remote(_) => None,
+        }
+    }
+}
+
+/// Represents possible errors of task store.
+#[derive(Error, Debug)]
+pub enum StoreError {
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    #[error(transparent)]
+    Remote(#[from] RemoteError),
+    #[error(transparent)]
+    Invalid(#[from] TaskValidationError),
+    #[error(transparent)]
+    Key(#[from] KeyError),
+    #[error("No list '{0}'. Lists are '{CURRENT_LIST}' and '{ARCHIVE_LIST}'")]
+    NoList(String),
+    #[error("Task '{0}' not found. It was renamed or deleted meanwhile")]
+    NoTask(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use chrono::NaiveDateTime;
+    use crate::query::Query;
+    use tempfile::tempdir;
+    use crate::task::ExternalRef;
+    use super::*;
+
+    #[test]
+    fn local_store_records_history() {
+        let tempdir = tempdir().unwrap();
+        let storage = Storage::<Task>::open(&tempdir).unwrap();
+        let store: &dyn TaskStore = &storage;
+        let task = Task {
+            name: "task".to_string(),
+            description: "description".to_string(),
+            date: NaiveDateTime::parse_from_str("2024-12-12 20:20", "%Y-%m-%d %H:%M").unwrap().and_utc(),
+            category: "category".to_string(),
+            status: Status::Off,
+            owner: None,
+            assignee: None,
+            external_refs: Vec::new(),
+        };
+
+        assert_eq!(store.insert(&task).unwrap(), None);
+        assert!(store.done("task").unwrap());
+        assert_eq!(store.update("task", &Task { name: "renamed".to_string(), ..task.clone() }).unwrap(), None);
+        assert_eq!(store.get("task").unwrap(), None);
+        assert!(store.update("renamed", &task).unwrap().is_none());
+        assert!(!store.done("no task").unwrap());
+        assert_eq!(store.tasks().unwrap(), [task]);
+        assert!(store.delete("task").unwrap().is_some());
+        assert_eq!(store.get("task").unwrap(), None);
+
+        let actions = storage
+            .open_tree::<HistoryEntry>(HISTORY_TREE)
+            .unwrap()
+            .iter()
+            .map(|entry| entry.unwrap().1.action)
+            .collect::<Vec<_>>();
+        assert_eq!(actions, [Action::Add, Action::Done, Action::Update, Action::Update, Action::Delete]);
+    }
+
+    #[test]
+    fn rename_replaces_task() {
+        let tempdir = tempdir().unwrap();
+        let storage = Storage::<Task>::open(&tempdir).unwrap();
+        let store: &dyn TaskStore = &storage;
+        let task = |name: &str, description: &str| Task {
+            name: name.to_string(),
+            description: description.to_string(),
+            date: NaiveDateTime::parse_from_str("2024-12-12 20:20", "%Y-%m-%d %H:%M").unwrap().and_utc(),
+            category: "category".to_string(),
+            status: Status::Off,
+            owner: None,
+            assignee: None,
+            external_refs: Vec::new(),
+        };
+        store.insert(&task("first", "first")).unwrap();
+        store.insert(&task("second", "second")).unwrap();
+
+        assert_eq!(store.update("first", &task("second", "first")).unwrap(), Some(task("second", "second")));
+        assert_eq!(store.tasks().unwrap(), [task("second", "first")]);
+    }
+
+    #[test]
+    fn import_updates_referenced_tasks() {
+        let tempdir = tempdir().unwrap();
+        let storage = Storage::<Task>::open(&tempdir).unwrap();
+        let store: &dyn TaskStore = &storage;
+        let issue = |name: &str, id: &str| Task {
+            name: name.to_string(),
+            description: "description".to_string(),
+            date: NaiveDateTime::parse_from_str("2024-12-12 20:20", "%Y-%m-%d %H:%M").unwrap().and_utc(),
+            category: "category".to_string(),
+            status: Status::Off,
+            owner: None,
+            assignee: None,
+            external_refs: vec![ExternalRef {
+                source: "github".to_string(),
+                id: id.to_string(),
+                url: format!("https://github.com/{id}"),
+            }],
+        };
+
+        assert_eq!(store.import(vec![issue("first", "1"), issue("second", "2")]).unwrap(), Imported { added: 2, updated: 0 });
+        store.done("first").unwrap();
+        assert_eq!(store.import(vec![issue("renamed", "1"), issue("third", "3")]).unwrap(), Imported { added: 1, updated: 1 });
+
+        let tasks = store.tasks().unwrap();
+        assert_eq!(tasks.iter().map(|task| task.name.as_str()).collect::<Vec<_>>(), ["renamed", "second", "third"]);
+        assert_eq!(tasks[0].status, Status::On);
+    }
+
+    #[test]
+    fn select_in_name_order() {
+        let task = |name: &str| Task {
+            name: name.to_string(),
+            description: "description".to_string(),
+            date: NaiveDateTime::parse_from_str("2024-12-12 20:20", "%Y-%m-%d %H:%M").unwrap().and_utc(),
+            category: "category".to_string(),
+            status: Status::Off,
+            owner: None,
+            assignee: None,
+            external_refs: Vec::new(),
+        };
+
+        for strategy in [KeyStrategy::Name, KeyStrategy::Ulid, KeyStrategy::Uuid, KeyStrategy::Int] {
+            let tempdir = tempdir().unwrap();
+            let storage = Storage::<Task>::open(&tempdir).unwrap();
+            KeyStrategy::configure(&storage, strategy).unwrap();
+            let store: &dyn TaskStore = &storage;
+            for name in ["b", "c", "a", "B", "ab"] {
+                store.insert(&task(name)).unwrap();
+            }
+
+            let result_set = Query::from_str("SELECT name").unwrap().execute(&store.tasks().unwrap()).unwrap();
+            let names = result_set.get_column("name").map(ToString::to_string).collect::<Vec<_>>();
+            assert_eq!(names, ["B", "a", "ab", "b", "c"], "{strategy:?} keys");
+        }
+    }
+
+    #[test]
+    fn candidates_by_name() {
+        let task = |name: &str| Task {
+            name: name.to_string(),
+            description: "description".to_string(),
+            date: NaiveDateTime::parse_from_str("2024-12-12 20:20", "%Y-%m-%d %H:%M").unwrap().and_utc(),
+            category: "category".to_string(),
+            status: Status::Off,
+            owner: None,
+            assignee: None,
+            external_refs: Vec::new(),
+        };
+
+        for strategy in [KeyStrategy::Name, KeyStrategy::Ulid] {
+            let tempdir = tempdir().unwrap();
+            let storage = Storage::<Task>::open(&tempdir).unwrap();
+            KeyStrategy::configure(&storage, strategy).unwrap();
+            let store: &dyn TaskStore = &storage;
+            for name in ["b", "c", "a", "B", "ab"] {
+                store.insert(&task(name)).unwrap();
+            }
+            let candidates = |query: &str| {
+                let query = Query::from_str(query).unwrap();
+                let candidates = store.candidates(&query).unwrap();
+                let names = |tasks: &[Task]| {
+                    let result_set = query.execute(tasks).unwrap();
+                    result_set.get_column("name").map(ToString::to_string).collect::<Vec<_>>()
+                };
+                assert_eq!(names(&candidates), names(&store.tasks().unwrap()), "{strategy:?} keys");
+                candidates.into_iter().map(|task| task.name).collect::<Vec<_>>()
+            };
+
+            assert_eq!(candidates("SELECT name WHERE name IN ('c', 'x', 'a') OR name = 'a'"), ["a", "c"]);
+            assert_eq!(candidates("SELECT name WHERE name = 'a' OR category = 'x'").len(), 5);
+            let range = candidates("SELECT name WHERE name > 'a' AND name <= 'b'");
+            match strategy {
+                KeyStrategy::Name => assert_eq!(range, ["ab", "b"]),
+                _ => assert_eq!(range.len(), 5),
+            }
+        }
+    }
+
+    #[test]
+    fn tasks_of_lists() {
+        let tempdir = tempdir().unwrap();
+        let storage = Storage::<Task>::open(&tempdir).unwrap();
+        let task = |name: &str| Task {
+            name: name.to_string(),
+            description: "description".to_string(),
+            date: NaiveDateTime::parse_from_str("2024-12-12 20:20", "%Y-%m-%d %H:%M").unwrap().and_utc(),
+            category: "category".to_string(),
+            status: Status::On,
+            owner: None,
+            assignee: None,
+            external_refs: Vec::new(),
+        };
+        TaskStore::insert(&storage, &task("current")).unwrap();
+        storage.open_tree::<Task>(ARCHIVE_TREE).unwrap().insert("archived", &task("archived")).unwrap();
+        let list = |name: &str| list_tasks(&storage, Some(&Identifier(name.to_string())));
+
+        assert_eq!(list_tasks(&storage, None).unwrap(), [task("current")]);
+        assert_eq!(list(CURRENT_LIST).unwrap(), [task("current")]);
+        assert_eq!(list(ARCHIVE_LIST).unwrap(), [task("archived")]);
+        assert!(matches!(list("work"), Err(StoreError::NoList(list)) if list == "work"));
+    }
+}
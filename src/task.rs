@@ -1,67 +1,260 @@
 use std::borrow::Cow;
 use std::fmt::{Display, Formatter};
+#[cfg(feature = "table")]
 use std::iter::once;
 use std::str::FromStr;
-use crate::query::reflect::{FieldsIterator, ReflectError, Reflectable, Value};
+use crate::query::evaluator::computed::{computed_fields, computed_values, get_computed, with_computed_names};
+use crate::query::reflect::{impl_value_via_string, FieldsIterator, FromFields, FromValue, ReflectError, Reflectable, ReflectableMut, ToValue, Type, Value};
+use std::collections::HashMap;
 use chrono::{DateTime, NaiveDateTime, Utc};
+#[cfg(feature = "cli")]
 use clap::{Args, ValueEnum};
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "table")]
 use tabled::{Table, Tabled};
+#[cfg(feature = "table")]
 use tabled::settings::Style;
 
 /// Represents task.
-#[derive(Debug, Serialize, Deserialize, Args, Tabled, PartialEq)]
+///
+/// Task arguments are not required when tasks are read from stdin.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "cli", derive(Args))]
+#[cfg_attr(feature = "table", derive(Tabled))]
 pub struct Task {
+    #[cfg_attr(feature = "cli", arg(required = false, required_unless_present = "stdin"))]
     pub name: String,
+    #[cfg_attr(feature = "cli", arg(required = false, required_unless_present = "stdin"))]
     pub description: String,
-    #[arg(value_parser = parse_date_time)]
+    #[cfg_attr(feature = "cli", arg(value_parser = parse_date_time, required = false, required_unless_present = "stdin"))]
     pub date: DateTime<Utc>,
+    #[cfg_attr(feature = "cli", arg(required = false, required_unless_present = "stdin"))]
     pub category: String,
-    pub status: Status
+    #[cfg_attr(feature = "cli", arg(required = false, required_unless_present = "stdin"))]
+    pub status: Status,
+    #[cfg_attr(feature = "cli", arg(long, help = "User who owns the task. Defaults to the current user"))]
+    #[cfg_attr(feature = "table", tabled(display_with = "display_user"))]
+    pub owner: Option<String>,
+    #[cfg_attr(feature = "cli", arg(long, help = "User the task is assigned to"))]
+    #[cfg_attr(feature = "table", tabled(display_with = "display_user"))]
+    pub assignee: Option<String>,
+    /// References to the task in external systems, such as GitHub issue the task was imported from.
+    #[cfg_attr(feature = "cli", arg(skip))]
+    #[serde(default)]
+    #[cfg_attr(feature = "table", tabled(display_with = "display_external_refs"))]
+    pub external_refs: Vec<ExternalRef>,
+}
+
+/// Reference to the task in external system.
+///
+/// Task is identified by `id` within the `source` system, e.g. `owner/repo#1` within `github`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExternalRef {
+    pub source: String,
+    pub id: String,
+    pub url: String,
 }
 
 /// Represents task status.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, ValueEnum, PartialOrd, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
 pub enum Status{
     On,
     Off
 }
 
-fn parse_date_time(date: &str) -> Result<DateTime<Utc>, chrono::ParseError>{
+#[cfg(feature = "table")]
+fn display_user(user: &Option<String>) -> String {
+    user.clone().unwrap_or_default()
+}
+
+fn display_external_refs(external_refs: &[ExternalRef]) -> String {
+    external_refs.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n")
+}
+
+/// Parse date time in format `%Y-%m-%d %H:%M`.
+pub fn parse_date_time(date: &str) -> Result<DateTime<Utc>, chrono::ParseError>{
     NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M")
         .map(|date| date.and_utc())
 }
 
 /// Reflectable implementation to be able to use task in select queries.
+///
+/// Fields, that are registered by [`register_field`](crate::query::evaluator::context::ExecutionContext::register_field),
+/// follow the stored fields.
 impl Reflectable for Task {
-    fn get_field(&self, field: &str) -> Result<Value, ReflectError> {
+    fn get_field(&self, field: &str) -> Result<Value<'_>, ReflectError> {
         let value = match field {
-            "name" => Value::String(self.name.to_string()),
-            "description" => Value::String(self.description.to_string()),
-            "date" => Value::DateTime(self.date),
-            "category" => Value::String(self.category.to_string()),
-            "status" => Value::String(self.status.to_string()),
-            field => return Err(ReflectError::NoField(field.to_string())),
+            "name" => self.name.to_value(),
+            "description" => self.description.to_value(),
+            "date" => self.date.to_value(),
+            "category" => self.category.to_value(),
+            "status" => self.status.to_value(),
+            "owner" => self.owner.to_value(),
+            "assignee" => self.assignee.to_value(),
+            "external_refs" => external_refs_value(&self.external_refs),
+            field => return get_computed(self, field),
         };
 
-        return Ok(value);
+        Ok(value)
     }
 
-    fn fields(&self) -> FieldsIterator {
+    fn fields(&self) -> FieldsIterator<'_> {
         Box::new([
-            ("name".into(), Value::String(self.name.to_string())),
-            ("description".into(), Value::String(self.description.to_string())),
-            ("date".into(), Value::DateTime(self.date)),
-            ("category".into(), Value::String(self.category.to_string())),
-            ("status".into(), Value::String(self.status.to_string())),
-        ].into_iter())
+            ("name".into(), self.name.to_value()),
+            ("description".into(), self.description.to_value()),
+            ("date".into(), self.date.to_value()),
+            ("category".into(), self.category.to_value()),
+            ("status".into(), self.status.to_value()),
+            ("owner".into(), self.owner.to_value()),
+            ("assignee".into(), self.assignee.to_value()),
+            ("external_refs".into(), external_refs_value(&self.external_refs)),
+        ].into_iter().chain(computed_values(self)))
+    }
+
+    fn field_names() -> Cow<'static, [Cow<'static, str>]> {
+        with_computed_names::<Task>(&[
+            Cow::Borrowed("name"),
+            Cow::Borrowed("description"),
+            Cow::Borrowed("date"),
+            Cow::Borrowed("category"),
+            Cow::Borrowed("status"),
+            Cow::Borrowed("owner"),
+            Cow::Borrowed("assignee"),
+            Cow::Borrowed("external_refs"),
+        ])
+    }
+
+    fn field_types() -> Cow<'static, [(Cow<'static, str>, Type)]> {
+        (&[
+            (Cow::Borrowed("name"), String::TYPE),
+            (Cow::Borrowed("description"), String::TYPE),
+            (Cow::Borrowed("date"), DateTime::<Utc>::TYPE),
+            (Cow::Borrowed("category"), String::TYPE),
+            (Cow::Borrowed("status"), Status::TYPE),
+            (Cow::Borrowed("owner"), Option::<String>::TYPE),
+            (Cow::Borrowed("assignee"), Option::<String>::TYPE),
+            (Cow::Borrowed("external_refs"), Type::String),
+        ]).into()
+    }
+
+    fn get_nested(&self, field: &str) -> Result<&dyn Reflectable, ReflectError> {
+        match field {
+            "external_refs" => Ok(&self.external_refs),
+            field => Err(ReflectError::NoField(field.to_string())),
+        }
+    }
+}
+
+/// Task is built from the fields by `INSERT` statement. Name, description, date, category and status are required,
+/// owner and assignee are optional. External references cannot be inserted.
+impl FromFields for Task {
+    fn from_fields(fields: &HashMap<String, Value>) -> Result<Self, ReflectError> {
+        const INSERTABLE: [&str; 7] = ["name", "description", "date", "category", "status", "owner", "assignee"];
+        if let Some(field) = fields.keys().find(|field| !INSERTABLE.contains(&field.as_str())) {
+            return Err(ReflectError::NoField(field.clone()));
+        }
+        fn convert<T: FromValue>(field: &str, value: &Value) -> Result<T, ReflectError> {
+            T::from_value(value).map_err(|source| ReflectError::InvalidValue { field: field.to_string(), source })
+        }
+        fn required<T: FromValue>(fields: &HashMap<String, Value>, field: &str) -> Result<T, ReflectError> {
+            match fields.get(field) {
+                Some(Value::Null) | None => Err(ReflectError::Required(field.to_string())),
+                Some(value) => convert(field, value),
+            }
+        }
+
+        Ok(Task {
+            name: required(fields, "name")?,
+            description: required(fields, "description")?,
+            date: required(fields, "date")?,
+            category: required(fields, "category")?,
+            status: required(fields, "status")?,
+            owner: fields.get("owner").map_or(Ok(None), |value| convert("owner", value))?,
+            assignee: fields.get("assignee").map_or(Ok(None), |value| convert("assignee", value))?,
+            external_refs: Vec::new(),
+        })
+    }
+}
+
+/// Name, description, date, category and status are required, owner and assignee are cleared by `NULL`.
+/// External references and computed fields are read-only.
+impl ReflectableMut for Task {
+    fn set_field(&mut self, field: &str, value: Value) -> Result<(), ReflectError> {
+        fn convert<T: FromValue>(field: &str, value: &Value) -> Result<T, ReflectError> {
+            match value {
+                Value::Null => Err(ReflectError::Required(field.to_string())),
+                value => T::from_value(value).map_err(|source| ReflectError::InvalidValue { field: field.to_string(), source }),
+            }
+        }
+        fn optional<T: FromValue>(field: &str, value: &Value) -> Result<Option<T>, ReflectError> {
+            Option::<T>::from_value(value).map_err(|source| ReflectError::InvalidValue { field: field.to_string(), source })
+        }
+
+        match field {
+            "name" => self.name = convert(field, &value)?,
+            "description" => self.description = convert(field, &value)?,
+            "date" => self.date = convert(field, &value)?,
+            "category" => self.category = convert(field, &value)?,
+            "status" => self.status = convert(field, &value)?,
+            "owner" => self.owner = optional(field, &value)?,
+            "assignee" => self.assignee = optional(field, &value)?,
+            "external_refs" => return Err(ReflectError::ReadOnly(field.to_string())),
+            field if computed_fields::<Task>().iter().any(|computed| computed.name == field) => {
+                return Err(ReflectError::ReadOnly(field.to_string()))
+            }
+            field => return Err(ReflectError::NoField(field.to_string())),
+        }
+
+        Ok(())
+    }
+}
+
+/// Reflectable implementation to be able to read ids of the external references by source, e.g. `external_refs.github`.
+///
+/// Id of the source, that task has no reference in, is `NULL`.
+impl Reflectable for Vec<ExternalRef> {
+    fn get_field(&self, field: &str) -> Result<Value<'_>, ReflectError> {
+        Ok(self
+            .iter()
+            .find(|external_ref| external_ref.source == field)
+            .map_or(Value::Null, |external_ref| external_ref.id.to_value()))
+    }
+
+    fn fields(&self) -> FieldsIterator<'_> {
+        Box::new(
+            self.iter()
+                .map(|external_ref| (external_ref.source.clone().into(), external_ref.id.to_value()))
+                .collect::<Vec<_>>()
+                .into_iter(),
+        )
     }
 
     fn field_names() -> Cow<'static, [Cow<'static, str>]> {
-        (&[Cow::Borrowed("name"), Cow::Borrowed("description"), Cow::Borrowed("date"), Cow::Borrowed("category"), Cow::Borrowed("status")]).into()
+        (&[]).into()
     }
 }
 
+/// External references are reflected as comma-separated `source:id` pairs, or `NULL` if there are none.
+fn external_refs_value(external_refs: &[ExternalRef]) -> Value<'_> {
+    if external_refs.is_empty() {
+        Value::Null
+    } else {
+        Value::String(display_external_refs(external_refs).replace('\n', ", ").into())
+    }
+}
+
+impl ExternalRef {
+    /// Returns `true` if both references identify the same item of the same system.
+    pub fn same_as(&self, other: &ExternalRef) -> bool {
+        self.source == other.source && self.id == other.id
+    }
+}
+
+impl_value_via_string!(Status);
+
+/// Task is displayed as the table with one row.
+#[cfg(feature = "table")]
 impl Display for Task{
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut table = Table::new(once(self));
@@ -71,6 +264,12 @@ impl Display for Task{
     }
 }
 
+impl Display for ExternalRef{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.source, self.id)
+    }
+}
+
 impl Display for Status{
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -93,8 +292,32 @@ impl FromStr for Status{
 }
 
 #[cfg(test)]
-mod tests {
+pub mod tests {
+    use crate::query::ast::expression::Identifier;
+    use crate::query::reflect::{FromValue, Text};
     use super::*;
+
+    /// Pending or completed task with `name`, that is due at `date_time` in format `%Y-%m-%d %H:%M`.
+    pub fn task(name: &str, date_time: &str, status: Status) -> Task {
+        Task {
+            name: name.to_string(),
+            description: "description".to_string(),
+            date: date(date_time),
+            category: "category".to_string(),
+            status,
+            owner: None,
+            assignee: None,
+            external_refs: Vec::new(),
+        }
+    }
+
+    /// Date time in format `%Y-%m-%d %H:%M` as UTC.
+    pub fn date(date_time: &str) -> DateTime<Utc> {
+        NaiveDateTime::parse_from_str(date_time, "%Y-%m-%d %H:%M")
+            .unwrap()
+            .and_utc()
+    }
+
     fn test_task() -> Task{
         Task{
             name: "RandomName".to_string(),
@@ -103,7 +326,10 @@ mod tests {
                 .unwrap()
                 .and_utc(),
             category: "RandomCategory".to_string(),
-            status: Status::On
+            status: Status::On,
+            owner: Some("RandomOwner".to_string()),
+            assignee: None,
+            external_refs: Vec::new(),
         }
     }
     #[test]
@@ -111,14 +337,72 @@ mod tests {
         let task = test_task();
 
         let name = task.get_field("name").unwrap();
-        assert_eq!(name, Value::String(task.name.to_string()));
+        assert_eq!(name, Value::String(task.name.as_str().into()));
+        assert!(matches!(name, Value::String(Text::Borrowed(_))));
 
         let date = task.get_field("date").unwrap();
         assert_eq!(date, Value::DateTime(task.date));
 
         let status = task.get_field("status").unwrap();
-        assert_eq!(status, Value::String(task.status.to_string()));
+        assert_eq!(status, Value::String(task.status.to_string().into()));
+
+        let owner = task.get_field("owner").unwrap();
+        assert_eq!(owner, Value::String("RandomOwner".into()));
+
+        let assignee = task.get_field("assignee").unwrap();
+        assert_eq!(assignee, Value::Null);
+
+    }
+
+    #[test]
+    fn field_types() {
+        let types = Task::field_types();
+
+        assert!(types.iter().map(|(name, _)| name).eq(Task::field_names().iter()));
+        assert!(types.contains(&(Cow::Borrowed("date"), Type::DateTime)));
+        assert!(types.contains(&(Cow::Borrowed("status"), Type::String)));
+    }
+
+    #[test]
+    fn set_field_reflectable() {
+        let mut task = test_task();
+
+        task.set_field("name", Value::String("Renamed".into())).unwrap();
+        task.set_field("status", Value::String("Off".into())).unwrap();
+        task.set_field("owner", Value::Null).unwrap();
+        assert_eq!(task.name, "Renamed");
+        assert_eq!(task.status, Status::Off);
+        assert_eq!(task.owner, None);
+
+        assert!(matches!(task.set_field("date", Value::Null), Err(ReflectError::Required(_))));
+        assert!(matches!(task.set_field("date", Value::String("soon".into())), Err(ReflectError::InvalidValue { .. })));
+        assert!(matches!(task.set_field("external_refs", Value::Null), Err(ReflectError::ReadOnly(_))));
+        assert!(matches!(task.set_field("unknown", Value::Null), Err(ReflectError::NoField(_))));
+        assert_eq!(task, Task { name: "Renamed".to_string(), status: Status::Off, owner: None, ..test_task() });
+    }
+
+    #[test]
+    fn external_refs_reflectable() {
+        let mut task = test_task();
+        task.external_refs.push(ExternalRef {
+            source: "github".to_string(),
+            id: "owner/repo#1".to_string(),
+            url: "https://github.com/owner/repo/issues/1".to_string(),
+        });
+        let read = |path: &str| Identifier(path.to_string()).read(&task).unwrap();
+
+        assert_eq!(read("external_refs"), Value::String("github:owner/repo#1".into()));
+        assert_eq!(read("external_refs.github"), Value::String("owner/repo#1".into()));
+        assert_eq!(read("external_refs.todoist"), Value::Null);
+    }
+
+    #[test]
+    fn status_round_trip() {
+        let value = Status::Off.to_value();
 
+        assert_eq!(value, Value::String("off".into()));
+        assert_eq!(Status::from_value(&value).unwrap(), Status::Off);
+        assert!(Status::from_value(&Value::String("unknown".into())).is_err());
     }
 
     #[test]
@@ -128,11 +412,14 @@ mod tests {
         let fields = <Task as Reflectable>::fields(&task);
 
         assert!(fields.eq([
-            ("name".into(), Value::String(task.name.to_string())),
-            ("description".into(), Value::String(task.description.to_string())),
+            ("name".into(), Value::String(task.name.as_str().into())),
+            ("description".into(), Value::String(task.description.as_str().into())),
             ("date".into(), Value::DateTime(task.date)),
-            ("category".into(), Value::String(task.category.to_string())),
-            ("status".into(), Value::String(task.status.to_string()))
+            ("category".into(), Value::String(task.category.as_str().into())),
+            ("status".into(), Value::String(task.status.to_string().into())),
+            ("owner".into(), Value::String("RandomOwner".into())),
+            ("assignee".into(), Value::Null),
+            ("external_refs".into(), Value::Null)
         ]));
 
     }
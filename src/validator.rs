@@ -0,0 +1,182 @@
+use std::fmt::{Display, Formatter};
+use chrono::{DateTime, Utc};
+use clap::Args;
+use thiserror::Error;
+use crate::store::{Imported, StoreError, TaskStore};
+use crate::task::Task;
+
+/// Validation rules of the added, updated and imported tasks.
+///
+/// Rules are configured by the global options or the environment variables, tasks are not restricted by default.
+#[derive(Debug, Clone, Default, Args, PartialEq)]
+pub struct TaskValidator {
+    #[arg(long, global = true, env = "TODO_LIST_MAX_NAME_LENGTH", value_name = "CHARS", help = "Maximal length of the task name")]
+    pub max_name_length: Option<usize>,
+    #[arg(long = "allowed-category", global = true, env = "TODO_LIST_CATEGORIES", value_name = "CATEGORY", value_delimiter = ',', help = "Category, that tasks may have. May be repeated or comma-separated")]
+    pub categories: Vec<String>,
+    #[arg(long, global = true, env = "TODO_LIST_FUTURE_DATES", help = "Require due dates of the tasks to be in the future")]
+    pub future_dates: bool,
+    #[arg(long, global = true, help = "Skip validation of the tasks")]
+    pub no_validate: bool,
+}
+
+impl TaskValidator {
+    /// Validate `task` at the moment `now`. All invalid fields of the task are reported at once.
+    pub fn validate(&self, task: &Task, now: DateTime<Utc>) -> Result<(), TaskValidationError> {
+        if self.no_validate {
+            return Ok(());
+        }
+        let errors = [
+            self.max_name_length
+                .filter(|max| task.name.chars().count() > *max)
+                .map(|max| FieldError::new("name", format!("must be at most {max} characters long"))),
+            self.validate_category(&task.category),
+            (self.future_dates && task.date <= now).then(|| FieldError::new("date", "must be in the future".to_string())),
+        ];
+        let errors = errors.into_iter().flatten().collect::<Vec<_>>();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(TaskValidationError { task: task.name.clone(), errors })
+        }
+    }
+
+    /// Returns an error if the `category` is not allowed.
+    pub fn validate_category(&self, category: &str) -> Option<FieldError> {
+        if self.no_validate || self.categories.is_empty() || self.categories.iter().any(|allowed| allowed == category) {
+            return None;
+        }
+        let allowed = self.categories.iter().map(|allowed| format!("'{allowed}'")).collect::<Vec<_>>();
+
+        Some(FieldError::new("category", format!("must be one of {}", allowed.join(", "))))
+    }
+}
+
+/// Task store, that validates tasks before they are added or updated in the inner `store`.
+pub struct ValidatingStore<'a> {
+    pub store: &'a dyn TaskStore,
+    pub validator: &'a TaskValidator,
+}
+
+impl ValidatingStore<'_> {
+    fn validate(&self, task: &Task) -> Result<(), StoreError> {
+        Ok(self.validator.validate(task, Utc::now())?)
+    }
+}
+
+impl TaskStore for ValidatingStore<'_> {
+    fn get(&self, name: &str) -> Result<Option<Task>, StoreError> {
+        self.store.get(name)
+    }
+
+    fn insert(&self, task: &Task) -> Result<Option<Task>, StoreError> {
+        self.validate(task)?;
+        self.store.insert(task)
+    }
+
+    fn insert_batch(&self, tasks: &[Task]) -> Result<(), StoreError> {
+        tasks.iter().try_for_each(|task| self.validate(task))?;
+        self.store.insert_batch(tasks)
+    }
+
+    fn update(&self, name: &str, task: &Task) -> Result<Option<Task>, StoreError> {
+        self.validate(task)?;
+        self.store.update(name, task)
+    }
+
+    fn delete(&self, name: &str) -> Result<Option<Task>, StoreError> {
+        self.store.delete(name)
+    }
+
+    fn done(&self, name: &str) -> Result<bool, StoreError> {
+        self.store.done(name)
+    }
+
+    fn tasks(&self) -> Result<Vec<Task>, StoreError> {
+        self.store.tasks()
+    }
+
+    /// All tasks are validated before any of them is imported.
+    fn import(&self, tasks: Vec<Task>) -> Result<Imported, StoreError> {
+        tasks.iter().try_for_each(|task| self.validate(task))?;
+        self.store.import(tasks)
+    }
+}
+
+/// Invalid field of the task.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl FieldError {
+    fn new(field: &'static str, message: String) -> Self {
+        FieldError { field, message }
+    }
+}
+
+impl Display for FieldError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// Task is not valid.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub struct TaskValidationError {
+    pub task: String,
+    pub errors: Vec<FieldError>,
+}
+
+impl Display for TaskValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Task '{}' is invalid:", self.task)?;
+        for error in &self.errors {
+            write!(f, "\n  {error}")?;
+        }
+        f.write_str("\nUse '--no-validate' to skip validation")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDateTime, TimeDelta};
+    use crate::task::Status;
+    use super::*;
+
+    #[test]
+    fn validate_task() {
+        let now = NaiveDateTime::parse_from_str("2024-12-12 20:20", "%Y-%m-%d %H:%M").unwrap().and_utc();
+        let task = Task {
+            name: "Long task name".to_string(),
+            description: "description".to_string(),
+            date: now - TimeDelta::days(1),
+            category: "hobby".to_string(),
+            status: Status::Off,
+            owner: None,
+            assignee: None,
+            external_refs: Vec::new(),
+        };
+        let validator = TaskValidator {
+            max_name_length: Some(10),
+            categories: vec!["work".to_string(), "home".to_string()],
+            future_dates: true,
+            no_validate: false,
+        };
+
+        let err = validator.validate(&task, now).unwrap_err();
+
+        assert_eq!(err.errors.iter().map(ToString::to_string).collect::<Vec<_>>(), [
+            "name: must be at most 10 characters long",
+            "category: must be one of 'work', 'home'",
+            "date: must be in the future",
+        ]);
+        assert!(TaskValidator::default().validate(&task, now).is_ok());
+        assert!(TaskValidator { no_validate: true, ..validator.clone() }.validate(&task, now).is_ok());
+
+        let valid = Task { name: "Task".to_string(), category: "work".to_string(), date: now + TimeDelta::days(1), ..task };
+        assert!(validator.validate(&valid, now).is_ok());
+    }
+}
@@ -0,0 +1,110 @@
+use std::borrow::Cow;
+use std::str::FromStr;
+use clap::Subcommand;
+use serde::{Deserialize, Serialize};
+use crate::query::ast::ParseError;
+use crate::query::reflect::{FieldsIterator, ReflectError, Reflectable, ToValue, Value};
+use crate::query::Query;
+use crate::storage::{Storage, StorageError};
+
+/// Name of the storage tree, where saved views are kept.
+pub const VIEWS_TREE: &str = "views";
+
+/// Represents saved view, that is a named select query over tasks.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct View {
+    pub name: String,
+    pub query: String,
+}
+
+/// Commands to manage saved views.
+///
+/// * `ViewCommand::Save` - Save query as view;
+/// * `ViewCommand::List` - List saved views;
+/// * `ViewCommand::Show` - Select tasks with saved view;
+/// * `ViewCommand::Delete` - Delete view. Links, that share the view, stop working;
+#[derive(Debug, Subcommand, PartialEq)]
+pub enum ViewCommand {
+    #[command(about = "Save query as view, replacing the view with the same name")]
+    Save {
+        name: String,
+        #[arg(value_name = "QUERY", required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        query: Vec<String>,
+    },
+    #[command(about = "List saved views")]
+    List,
+    #[command(about = "Select tasks with saved view")]
+    Show { name: String },
+    #[command(about = "Delete view")]
+    Delete { name: String },
+}
+
+impl View {
+    /// Create view with `name` from the query words, as they are passed to `select` command.
+    ///
+    /// Query is validated, so only parsable queries are saved. Query is saved in the canonical form.
+    pub fn new(name: &str, query: &[String]) -> Result<View, ParseError> {
+        let query = Query::from_str(&format!("SELECT {}", query.join(" ")))?;
+
+        Ok(View {
+            name: name.to_string(),
+            query: query.to_string(),
+        })
+    }
+
+    /// Returns parsed query of the view.
+    pub fn parse(&self) -> Result<Query, ParseError> {
+        Query::parse_cached(&self.query)
+    }
+
+    /// Save view to the `views`.
+    pub fn save(&self, views: &Storage<View>) -> Result<(), StorageError> {
+        views.insert(&self.name, self)?;
+
+        Ok(())
+    }
+}
+
+/// Reflectable implementation to be able to list views with select queries.
+impl Reflectable for View {
+    fn get_field(&self, field: &str) -> Result<Value<'_>, ReflectError> {
+        let value = match field {
+            "name" => self.name.to_value(),
+            "query" => self.query.to_value(),
+            field => return Err(ReflectError::NoField(field.to_string())),
+        };
+
+        Ok(value)
+    }
+
+    fn fields(&self) -> FieldsIterator<'_> {
+        Box::new([
+            ("name".into(), self.name.to_value()),
+            ("query".into(), self.query.to_value()),
+        ].into_iter())
+    }
+
+    fn field_names() -> Cow<'static, [Cow<'static, str>]> {
+        (&[Cow::Borrowed("name"), Cow::Borrowed("query")]).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+    use super::*;
+
+    #[test]
+    fn save_view() {
+        let tempdir = tempdir().unwrap();
+        let views = Storage::<View>::open(&tempdir).unwrap();
+        let words = |query: &str| query.split_whitespace().map(ToString::to_string).collect::<Vec<_>>();
+
+        let view = View::new("work", &words("name where category = 'work'")).unwrap();
+        view.save(&views).unwrap();
+
+        assert_eq!(views.get("work").unwrap().unwrap().query, "SELECT name WHERE category = 'work'");
+        assert_eq!(view.parse().unwrap(), Query::from_str("SELECT name WHERE category = 'work'").unwrap());
+        assert!(View::new("invalid", &words("name where")).is_err());
+    }
+}
@@ -0,0 +1,29 @@
+//! Runs of the command-line app, each in its own process, over the storage in a temporary directory.
+
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::tempdir;
+
+fn todo_list(dir: &Path, args: &[&str]) -> Output {
+    let output = Command::new(env!("CARGO_BIN_EXE_todo-list"))
+        .args(args)
+        .current_dir(dir)
+        .env_clear()
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    output
+}
+
+#[test]
+fn added_task_is_persisted() {
+    let dir = tempdir().unwrap();
+
+    todo_list(dir.path(), &["add", "t1", "desc", "2024-01-01 10:00", "work", "off"]);
+    let shown = todo_list(dir.path(), &["show", "t1"]);
+    let exported = todo_list(dir.path(), &["export"]);
+
+    assert!(!String::from_utf8_lossy(&shown.stdout).contains("Task not found"));
+    assert!(String::from_utf8_lossy(&exported.stdout).contains("\"t1\""));
+}
@@ -0,0 +1,104 @@
+//! Snapshots of the output, that users see or parse in their scripts: tables, exported tasks and error messages.
+//!
+//! Changed output fails the tests, and the new snapshots are reviewed with `cargo insta review`.
+
+use std::str::FromStr;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use insta::assert_snapshot;
+use todo_list::command::CommandError;
+use todo_list::format;
+use todo_list::query::reflect::Value;
+use todo_list::query::{Query, ResultSet};
+use todo_list::task::{ExternalRef, Status, Task};
+
+fn date(date: &str) -> DateTime<Utc> {
+    NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M").unwrap().and_utc()
+}
+
+fn tasks() -> Vec<Task> {
+    vec![
+        Task {
+            name: "Pay rent".to_string(),
+            description: "Monthly".to_string(),
+            date: date("2024-10-10 20:10"),
+            category: "home".to_string(),
+            status: Status::On,
+            owner: Some("alice".to_string()),
+            assignee: None,
+            external_refs: Vec::new(),
+        },
+        Task {
+            name: "Fix login".to_string(),
+            description: "Users can't log in\nwith SSO".to_string(),
+            date: date("2024-12-01 09:00"),
+            category: "work".to_string(),
+            status: Status::Off,
+            owner: Some("bob".to_string()),
+            assignee: Some("alice".to_string()),
+            external_refs: vec![ExternalRef {
+                source: "github".to_string(),
+                id: "owner/repo#1".to_string(),
+                url: "https://github.com/owner/repo/issues/1".to_string(),
+            }],
+        },
+    ]
+}
+
+fn select(query: &str) -> ResultSet {
+    Query::from_str(query).unwrap().execute(&tasks()).unwrap()
+}
+
+#[test]
+fn result_set_table() {
+    assert_snapshot!("select_all", select("SELECT *"));
+    assert_snapshot!("select_expressions", select("SELECT name, date + INTERVAL '1 day' AS tomorrow, [status, owner] AS list, assignee WHERE date > DATE '2024-01-01'"));
+    assert_snapshot!("select_nothing", select("SELECT name WHERE false"));
+
+    let mut result_set = ResultSet::new();
+    result_set.add_row([("string", Value::String("ünïcödé 件名".into())), ("number", Value::Number(1.5.into()))]);
+    result_set.add_row([("number", Value::Number((-1).into())), ("duration", Value::Duration(chrono::TimeDelta::hours(26)))]);
+    assert_snapshot!("mixed_values", result_set);
+}
+
+#[test]
+fn task_display() {
+    let tasks = tasks();
+
+    assert_snapshot!("task", tasks[0]);
+    assert_snapshot!("task_with_refs", tasks[1]);
+}
+
+#[test]
+fn exported_tasks() {
+    for name in ["jsonl", "org"] {
+        let mut output = Vec::new();
+        format::exporter(name).unwrap().write_tasks(&tasks(), &mut output).unwrap();
+
+        assert_snapshot!(format!("export_{name}"), String::from_utf8(output).unwrap());
+    }
+}
+
+#[test]
+fn error_messages() {
+    let parse_error = Query::from_str("SELECT name WHERE date >").unwrap_err();
+    assert_snapshot!("parse_error", parse_error);
+
+    let Err(validation_error) = Query::from_str("SELECT nme WHERE lowr(category) = 'work' AND 'text' > 10")
+        .unwrap()
+        .execute(&tasks())
+    else {
+        panic!("query is invalid");
+    };
+    assert_snapshot!("validation_error", CommandError::from(validation_error));
+
+    let Err(evaluation_error) = Query::from_str("SELECT name WHERE description + 1 > 0").unwrap().execute(&tasks()) else {
+        panic!("query fails");
+    };
+    assert_snapshot!("evaluation_error", CommandError::from(evaluation_error));
+
+    let import_error = format::importer("jsonl")
+        .unwrap()
+        .read_tasks(&mut "{\"name\": \"task\"}\n\nnot json\n".as_bytes())
+        .unwrap_err();
+    assert_snapshot!("import_error", CommandError::from(import_error));
+}